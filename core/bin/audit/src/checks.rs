@@ -0,0 +1,404 @@
+//! Consistency checks of the blocks against each other, the witness and the L1 state.
+//!
+//! Checks never stop on the first error: every found inconsistency is recorded in the audit
+//! of the corresponding block, so a single run shows the full picture.
+
+// External deps
+use ethabi::{ParamType, Token};
+// Workspace deps
+use zksync_circuit::{serialization::ProverData, witness::utils::fr_from_bytes};
+use zksync_crypto::Fr;
+use zksync_types::{aggregated_operations::BlocksCommitOperation, block::Block, BlockNumber, H256};
+// Local deps
+use crate::l1::{BlockCounters, L1Client};
+
+/// Names of the `CommitBlockInfo` fields in the order they're encoded.
+const COMMIT_BLOCK_INFO_FIELDS: [&str; 6] = [
+    "newStateHash",
+    "publicData",
+    "timestamp",
+    "onchainOperations",
+    "blockNumber",
+    "feeAccount",
+];
+
+/// Result of the audit of a single block.
+#[derive(Debug)]
+pub struct BlockAudit {
+    pub block_number: BlockNumber,
+    /// Inconsistencies found in the block.
+    pub mismatches: Vec<String>,
+    /// Checks that couldn't be performed, e.g. because the block isn't committed yet.
+    pub skipped: Vec<String>,
+}
+
+impl BlockAudit {
+    pub fn new(block_number: BlockNumber) -> Self {
+        Self {
+            block_number,
+            mismatches: Vec::new(),
+            skipped: Vec::new(),
+        }
+    }
+
+    pub fn mismatch(&mut self, message: impl Into<String>) {
+        self.mismatches.push(message.into());
+    }
+
+    pub fn skip(&mut self, message: impl Into<String>) {
+        self.skipped.push(message.into());
+    }
+}
+
+fn commit_blocks_params() -> [ParamType; 2] {
+    let stored_block_info = ParamType::Tuple(vec![
+        ParamType::Uint(32),       // uint32 blockNumber
+        ParamType::Uint(64),       // uint64 priorityOperations
+        ParamType::FixedBytes(32), // bytes32 pendingOnchainOperationsHash
+        ParamType::Uint(256),      // uint256 timestamp
+        ParamType::FixedBytes(32), // bytes32 stateHash
+        ParamType::FixedBytes(32), // bytes32 commitment
+    ]);
+    let commit_block_info = ParamType::Tuple(vec![
+        ParamType::FixedBytes(32), // bytes32 newStateHash
+        ParamType::Bytes,          // bytes publicData
+        ParamType::Uint(256),      // uint256 timestamp
+        ParamType::Array(Box::new(ParamType::Tuple(vec![
+            ParamType::Bytes,    // bytes ethWitness
+            ParamType::Uint(32), // uint32 publicDataOffset
+        ]))),
+        ParamType::Uint(32), // uint32 blockNumber
+        ParamType::Uint(32), // uint32 feeAccount
+    ]);
+    [
+        stored_block_info,
+        ParamType::Array(Box::new(commit_block_info)),
+    ]
+}
+
+/// Compares the root hash recalculated from the stored account diffs with the block root hash.
+pub fn check_root_hash(audit: &mut BlockAudit, block: &Block, replayed_root: Fr) {
+    if replayed_root != block.new_root_hash {
+        audit.mismatch(format!(
+            "recalculated root hash {:?} doesn't match the block root hash {:?}",
+            Block::encode_fr_for_eth(replayed_root),
+            block.get_eth_encoded_root()
+        ));
+    }
+}
+
+/// Recalculates the block commitment from the public data derived from the executed operations.
+pub fn check_commitment(audit: &mut BlockAudit, block: &Block, previous_root: Fr) {
+    let commitment = Block::get_commitment(
+        block.block_number,
+        block.fee_account,
+        Block::encode_fr_for_eth(previous_root),
+        block.get_eth_encoded_root(),
+        block.timestamp,
+        &block.get_onchain_op_commitment(),
+        &block.get_eth_public_data(),
+    );
+    if commitment != block.block_commitment {
+        audit.mismatch(format!(
+            "recalculated commitment {:?} doesn't match the stored commitment {:?}",
+            commitment, block.block_commitment
+        ));
+    }
+}
+
+/// Compares the block with its copy stored in the `commitBlocks` aggregated operation.
+pub fn check_committed_block(audit: &mut BlockAudit, block: &Block, committed_block: &Block) {
+    if block.get_eth_public_data() != committed_block.get_eth_public_data() {
+        audit.mismatch("public data differs from the one in the commit operation");
+    }
+    if block.new_root_hash != committed_block.new_root_hash {
+        audit.mismatch("root hash differs from the one in the commit operation");
+    }
+    if block.block_commitment != committed_block.block_commitment {
+        audit.mismatch("commitment differs from the one in the commit operation");
+    }
+    if block.timestamp != committed_block.timestamp {
+        audit.mismatch("timestamp differs from the one in the commit operation");
+    }
+}
+
+/// Checks that the stored witness was generated for this exact block.
+pub fn check_witness(
+    audit: &mut BlockAudit,
+    block: &Block,
+    previous_root: Fr,
+    prover_data: &ProverData,
+) {
+    // The circuit works with the commitment truncated to fit into the field element.
+    let mut block_commitment = block.block_commitment.as_bytes().to_vec();
+    block_commitment[0] &= 0xffu8 >> 3;
+    if prover_data.public_data_commitment != fr_from_bytes(block_commitment) {
+        audit.mismatch("witness public data commitment doesn't match the block commitment");
+    }
+    if prover_data.old_root != previous_root {
+        audit.mismatch(format!(
+            "witness old root {:?} doesn't match the previous block root {:?}",
+            Block::encode_fr_for_eth(prover_data.old_root),
+            Block::encode_fr_for_eth(previous_root)
+        ));
+    }
+    if prover_data.new_root != block.new_root_hash {
+        audit.mismatch(format!(
+            "witness new root {:?} doesn't match the block root {:?}",
+            Block::encode_fr_for_eth(prover_data.new_root),
+            block.get_eth_encoded_root()
+        ));
+    }
+}
+
+/// Compares the calldata of the `commitBlocks` transaction with the one expected for the block.
+///
+/// The expected calldata is built from the block as it's stored in the database, so any difference
+/// means that the data sent to L1 doesn't correspond to the executed operations.
+pub fn check_commit_calldata(
+    audit: &mut BlockAudit,
+    block: &Block,
+    commit_op: &BlocksCommitOperation,
+    calldata: &[u8],
+) {
+    let params = commit_blocks_params();
+    let selector = ethabi::short_signature("commitBlocks", &params);
+    if calldata.len() < 4 || calldata[..4] != selector {
+        audit.mismatch("commit transaction is not a `commitBlocks` call");
+        return;
+    }
+    let tokens = match ethabi::decode(&params, &calldata[4..]) {
+        Ok(tokens) => tokens,
+        Err(err) => {
+            audit.mismatch(format!("can't decode `commitBlocks` calldata: {}", err));
+            return;
+        }
+    };
+
+    let expected = BlocksCommitOperation {
+        last_committed_block: commit_op.last_committed_block.clone(),
+        blocks: vec![block.clone()],
+    }
+    .get_eth_tx_args();
+    if tokens[0] != expected[0] {
+        audit.mismatch("last committed block passed to `commitBlocks` differs from the stored one");
+    }
+
+    let committed_blocks = tokens[1].clone().into_array().unwrap_or_default();
+    let expected_block = expected[1]
+        .clone()
+        .into_array()
+        .unwrap_or_default()
+        .remove(0);
+    let block_index = commit_op
+        .blocks
+        .iter()
+        .position(|committed_block| committed_block.block_number == block.block_number);
+    let actual_block = match block_index.and_then(|index| committed_blocks.get(index)) {
+        Some(actual_block) => actual_block.clone(),
+        None => {
+            audit.mismatch("block is not passed to `commitBlocks`");
+            return;
+        }
+    };
+
+    let fields = |token: Token| token.into_tuple().unwrap_or_default();
+    for ((name, actual), expected) in COMMIT_BLOCK_INFO_FIELDS
+        .iter()
+        .zip(fields(actual_block))
+        .zip(fields(expected_block))
+    {
+        if actual != expected {
+            audit.mismatch(format!(
+                "`{}` in the `commitBlocks` calldata differs from the stored block",
+                name
+            ));
+        }
+    }
+}
+
+/// Loads the `commitBlocks` transaction from L1 and checks its calldata.
+pub async fn check_commit_tx(
+    audit: &mut BlockAudit,
+    l1: &(dyn L1Client + Sync),
+    block: &Block,
+    commit_op: &BlocksCommitOperation,
+    tx_hash: H256,
+) -> anyhow::Result<()> {
+    match l1.tx_input(tx_hash).await? {
+        Some(calldata) => check_commit_calldata(audit, block, commit_op, &calldata),
+        None => audit.mismatch(format!(
+            "commit transaction {:?} is not found on L1",
+            tx_hash
+        )),
+    }
+    Ok(())
+}
+
+/// Compares the last blocks with confirmed operations in the database with the contract counters.
+pub async fn check_block_counters(
+    l1: &(dyn L1Client + Sync),
+    database: BlockCounters,
+) -> anyhow::Result<Vec<String>> {
+    let contract = l1.block_counters().await?;
+    let mut mismatches = Vec::new();
+    for (name, database, contract) in [
+        ("committed", database.committed, contract.committed),
+        ("proven", database.proven, contract.proven),
+        ("executed", database.executed, contract.executed),
+    ] {
+        if database != contract {
+            mismatches.push(format!(
+                "last {} block is {} in the database, but {} on the contract",
+                name, *database, *contract
+            ));
+        }
+    }
+    Ok(mismatches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use zksync_crypto::ff::PrimeField;
+    use zksync_types::{AccountId, U256};
+
+    struct MockL1 {
+        txs: HashMap<H256, Vec<u8>>,
+        counters: BlockCounters,
+    }
+
+    #[async_trait::async_trait]
+    impl L1Client for MockL1 {
+        async fn tx_input(&self, hash: H256) -> anyhow::Result<Option<Vec<u8>>> {
+            Ok(self.txs.get(&hash).cloned())
+        }
+
+        async fn block_counters(&self) -> anyhow::Result<BlockCounters> {
+            Ok(self.counters)
+        }
+    }
+
+    fn fr(value: u32) -> Fr {
+        Fr::from_str(&value.to_string()).unwrap()
+    }
+
+    fn block(number: u32, previous_root: Fr) -> Block {
+        Block::new_from_available_block_sizes(
+            BlockNumber(number),
+            fr(number),
+            AccountId(0),
+            Vec::new(),
+            (0, 0),
+            &[10],
+            U256::zero(),
+            U256::zero(),
+            Block::encode_fr_for_eth(previous_root),
+            1_600_000_000 + u64::from(number),
+        )
+    }
+
+    fn commit_calldata(commit_op: &BlocksCommitOperation) -> Vec<u8> {
+        let mut calldata =
+            ethabi::short_signature("commitBlocks", &commit_blocks_params()).to_vec();
+        calldata.extend(ethabi::encode(&commit_op.get_eth_tx_args()));
+        calldata
+    }
+
+    #[test]
+    fn commitment_is_recalculated() {
+        let block = block(2, fr(1));
+        let mut audit = BlockAudit::new(block.block_number);
+        check_commitment(&mut audit, &block, fr(1));
+        assert!(audit.mismatches.is_empty());
+
+        check_commitment(&mut audit, &block, fr(5));
+        assert_eq!(audit.mismatches.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn commit_calldata_is_checked_against_l1() {
+        let commit_op = BlocksCommitOperation {
+            last_committed_block: block(1, fr(0)),
+            blocks: vec![block(2, fr(1)), block(3, fr(2))],
+        };
+        let tx_hash = H256::repeat_byte(0x01);
+        let l1 = MockL1 {
+            txs: vec![(tx_hash, commit_calldata(&commit_op))]
+                .into_iter()
+                .collect(),
+            counters: BlockCounters::default(),
+        };
+
+        for block in &commit_op.blocks {
+            let mut audit = BlockAudit::new(block.block_number);
+            check_commit_tx(&mut audit, &l1, block, &commit_op, tx_hash)
+                .await
+                .unwrap();
+            assert!(audit.mismatches.is_empty(), "{:?}", audit.mismatches);
+        }
+
+        // The database has a different state for the block than the one sent to L1.
+        let mut tampered_block = commit_op.blocks[1].clone();
+        tampered_block.new_root_hash = fr(100);
+        tampered_block.timestamp += 1;
+        let mut audit = BlockAudit::new(tampered_block.block_number);
+        check_commit_tx(&mut audit, &l1, &tampered_block, &commit_op, tx_hash)
+            .await
+            .unwrap();
+        assert_eq!(audit.mismatches.len(), 2, "{:?}", audit.mismatches);
+        assert!(audit.mismatches[0].contains("newStateHash"));
+        assert!(audit.mismatches[1].contains("timestamp"));
+
+        let mut audit = BlockAudit::new(BlockNumber(2));
+        check_commit_tx(
+            &mut audit,
+            &l1,
+            &commit_op.blocks[0],
+            &commit_op,
+            H256::repeat_byte(0x02),
+        )
+        .await
+        .unwrap();
+        assert_eq!(audit.mismatches.len(), 1);
+    }
+
+    #[test]
+    fn committed_block_copy_is_compared() {
+        let block = block(2, fr(1));
+        let mut committed_block = block.clone();
+        let mut audit = BlockAudit::new(block.block_number);
+        check_committed_block(&mut audit, &block, &committed_block);
+        assert!(audit.mismatches.is_empty());
+
+        committed_block.block_commitment = H256::repeat_byte(0xaa);
+        check_committed_block(&mut audit, &block, &committed_block);
+        assert_eq!(audit.mismatches.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn block_counters_are_compared() {
+        let counters = BlockCounters {
+            committed: BlockNumber(10),
+            proven: BlockNumber(8),
+            executed: BlockNumber(7),
+        };
+        let l1 = MockL1 {
+            txs: HashMap::new(),
+            counters,
+        };
+        assert!(check_block_counters(&l1, counters)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let database = BlockCounters {
+            executed: BlockNumber(6),
+            ..counters
+        };
+        let mismatches = check_block_counters(&l1, database).await.unwrap();
+        assert_eq!(mismatches.len(), 1);
+        assert!(mismatches[0].contains("executed"));
+    }
+}
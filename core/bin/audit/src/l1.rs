@@ -0,0 +1,48 @@
+//! Access to the zkSync contract state on L1.
+
+// External deps
+use anyhow::format_err;
+use web3::{contract::Options, types::U256};
+// Workspace deps
+use zksync_eth_client::EthereumGateway;
+use zksync_types::{BlockNumber, H256};
+
+/// Number of blocks that passed each stage of processing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlockCounters {
+    pub committed: BlockNumber,
+    pub proven: BlockNumber,
+    pub executed: BlockNumber,
+}
+
+#[async_trait::async_trait]
+pub trait L1Client {
+    /// Returns the input of the Ethereum transaction, or `None` if the transaction is unknown.
+    async fn tx_input(&self, hash: H256) -> anyhow::Result<Option<Vec<u8>>>;
+    /// Returns the `totalBlocksCommitted`, `totalBlocksProven` and `totalBlocksExecuted`
+    /// counters of the contract.
+    async fn block_counters(&self) -> anyhow::Result<BlockCounters>;
+}
+
+async fn query_counter(client: &EthereumGateway, name: &str) -> anyhow::Result<BlockNumber> {
+    let value: U256 = client
+        .call_main_contract_function(name, (), None, Options::default(), None)
+        .await
+        .map_err(|err| format_err!("Failed to query contract {}: {}", name, err))?;
+    Ok(BlockNumber(value.as_u32()))
+}
+
+#[async_trait::async_trait]
+impl L1Client for EthereumGateway {
+    async fn tx_input(&self, hash: H256) -> anyhow::Result<Option<Vec<u8>>> {
+        Ok(self.get_tx(hash).await?.map(|tx| tx.input.0))
+    }
+
+    async fn block_counters(&self) -> anyhow::Result<BlockCounters> {
+        Ok(BlockCounters {
+            committed: query_counter(self, "totalBlocksCommitted").await?,
+            proven: query_counter(self, "totalBlocksProven").await?,
+            executed: query_counter(self, "totalBlocksExecuted").await?,
+        })
+    }
+}
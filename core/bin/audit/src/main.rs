@@ -0,0 +1,223 @@
+//! End-to-end consistency audit of the blocks.
+//!
+//! For every block in the range the tool recalculates the root hash from the stored account
+//! diffs, re-derives the public data and the commitment from the executed operations and compares
+//! them with the commit operation, the `commitBlocks` calldata sent to L1 and the stored witness.
+//! Finally, the blocks with confirmed Ethereum operations are compared with the block counters
+//! of the contract.
+
+// External deps
+use anyhow::{ensure, format_err};
+use structopt::StructOpt;
+// Workspace deps
+use zksync_circuit::serialization::ProverData;
+use zksync_config::{ContractsConfig, ETHClientConfig, ETHSenderConfig};
+use zksync_crypto::Fr;
+use zksync_eth_client::EthereumGateway;
+use zksync_storage::{blob_store, StorageProcessor};
+use zksync_types::{
+    aggregated_operations::{AggregatedActionType, AggregatedOperation},
+    BlockNumber,
+};
+// Local deps
+use crate::checks::{
+    check_block_counters, check_commit_tx, check_commitment, check_committed_block,
+    check_root_hash, check_witness, BlockAudit,
+};
+use crate::l1::{BlockCounters, L1Client};
+use crate::replay::StateReplay;
+
+mod checks;
+mod l1;
+mod replay;
+
+#[derive(StructOpt)]
+#[structopt(name = "zkSync block audit tool", author = "Matter Labs")]
+#[structopt(
+    about = "Tool to check the consistency of the blocks in the database, the witness and L1"
+)]
+struct Opt {
+    /// First block to audit
+    #[structopt(long, default_value = "1")]
+    from: u32,
+    /// Last block to audit, the last block stored in the database by default
+    #[structopt(long)]
+    to: Option<u32>,
+    /// Skip the checks that require access to L1
+    #[structopt(long)]
+    no_l1: bool,
+}
+
+async fn audit_block(
+    storage: &mut StorageProcessor<'_>,
+    l1: Option<&(dyn L1Client + Sync)>,
+    replay: &mut StateReplay,
+    previous_root: Fr,
+    block_number: BlockNumber,
+) -> anyhow::Result<(BlockAudit, Fr)> {
+    let mut audit = BlockAudit::new(block_number);
+    let block = storage
+        .chain()
+        .block_schema()
+        .get_block(block_number)
+        .await?
+        .ok_or_else(|| format_err!("block {} is not found in the database", *block_number))?;
+
+    let updates = storage
+        .chain()
+        .state_schema()
+        .load_state_diff_for_block(block_number)
+        .await?;
+    replay.apply_block_diff(updates);
+    check_root_hash(&mut audit, &block, replay.root_hash());
+    check_commitment(&mut audit, &block, previous_root);
+
+    let commit_op = storage
+        .chain()
+        .operations_schema()
+        .get_aggregated_op_that_affects_block(AggregatedActionType::CommitBlocks, block_number)
+        .await?;
+    match commit_op {
+        Some((_, AggregatedOperation::CommitBlocks(commit_op))) => {
+            match commit_op
+                .blocks
+                .iter()
+                .find(|committed_block| committed_block.block_number == block_number)
+            {
+                Some(committed_block) => check_committed_block(&mut audit, &block, committed_block),
+                None => audit.mismatch("block is missing in its commit operation"),
+            }
+
+            if let Some(l1) = l1 {
+                let tx_hash = storage
+                    .ethereum_schema()
+                    .aggregated_op_final_hash(AggregatedActionType::CommitBlocks, block_number)
+                    .await?;
+                match tx_hash {
+                    Some(tx_hash) => {
+                        check_commit_tx(&mut audit, l1, &block, &commit_op, tx_hash).await?
+                    }
+                    None => audit.skip("commit transaction is not confirmed"),
+                }
+            }
+        }
+        Some((id, _)) => audit.mismatch(format!(
+            "aggregated operation {} is not a commit operation",
+            id
+        )),
+        None => audit.skip("block is not committed"),
+    }
+
+    match storage.prover_schema().get_witness(block_number).await? {
+        Some(witness) => match ProverData::decode(&witness) {
+            Ok(prover_data) => check_witness(&mut audit, &block, previous_root, &prover_data),
+            Err(err) => audit.mismatch(format!("can't decode the witness: {}", err)),
+        },
+        None => audit.skip("witness is not stored"),
+    }
+
+    Ok((audit, block.new_root_hash))
+}
+
+async fn load_database_counters(
+    storage: &mut StorageProcessor<'_>,
+) -> anyhow::Result<BlockCounters> {
+    let mut block_schema = storage.chain().block_schema();
+    Ok(BlockCounters {
+        committed: block_schema.get_last_committed_confirmed_block().await?,
+        proven: block_schema.get_last_proven_confirmed_block().await?,
+        executed: block_schema.get_last_verified_confirmed_block().await?,
+    })
+}
+
+fn print_block_audit(audit: &BlockAudit) {
+    if audit.mismatches.is_empty() {
+        println!("Block {}: ok", *audit.block_number);
+    } else {
+        println!(
+            "Block {}: {} mismatch(es)",
+            *audit.block_number,
+            audit.mismatches.len()
+        );
+    }
+    for mismatch in &audit.mismatches {
+        println!("  mismatch: {}", mismatch);
+    }
+    for skipped in &audit.skipped {
+        println!("  skipped: {}", skipped);
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opt = Opt::from_args();
+    let mut storage = StorageProcessor::establish_connection().await?;
+    if let Some(blob_store) = blob_store::from_env() {
+        storage.set_blob_store(blob_store);
+    }
+    let l1 = if opt.no_l1 {
+        None
+    } else {
+        Some(EthereumGateway::from_config(
+            &ETHClientConfig::from_env(),
+            &ETHSenderConfig::from_env(),
+            ContractsConfig::from_env().contract_addr,
+        ))
+    };
+    let l1 = l1.as_ref().map(|l1| l1 as &(dyn L1Client + Sync));
+
+    let to = match opt.to {
+        Some(to) => BlockNumber(to),
+        None => {
+            storage
+                .chain()
+                .block_schema()
+                .get_last_saved_block()
+                .await?
+        }
+    };
+    ensure!(
+        opt.from >= 1 && opt.from <= *to,
+        "block range {}..={} is empty",
+        opt.from,
+        *to
+    );
+
+    let (_, accounts) = storage
+        .chain()
+        .state_schema()
+        .load_committed_state(Some(BlockNumber(opt.from - 1)))
+        .await?;
+    let mut replay = StateReplay::new(accounts);
+    let mut previous_root = replay.root_hash();
+
+    let mut mismatches = 0;
+    for block_number in opt.from..=*to {
+        let (audit, root) = audit_block(
+            &mut storage,
+            l1,
+            &mut replay,
+            previous_root,
+            BlockNumber(block_number),
+        )
+        .await?;
+        print_block_audit(&audit);
+        mismatches += audit.mismatches.len();
+        previous_root = root;
+    }
+
+    if let Some(l1) = l1 {
+        let database_counters = load_database_counters(&mut storage).await?;
+        let counter_mismatches = check_block_counters(l1, database_counters).await?;
+        if counter_mismatches.is_empty() {
+            println!("Block counters: ok");
+        }
+        for mismatch in &counter_mismatches {
+            println!("  mismatch: {}", mismatch);
+        }
+        mismatches += counter_mismatches.len();
+    }
+
+    ensure!(mismatches == 0, "audit found {} mismatch(es)", mismatches);
+    Ok(())
+}
@@ -9,7 +9,7 @@ use web3::{
 };
 use zksync_config::{ContractsConfig, ETHClientConfig, ETHSenderConfig};
 use zksync_eth_client::EthereumGateway;
-use zksync_storage::StorageProcessor;
+use zksync_storage::{blob_store, StorageProcessor};
 use zksync_types::{aggregated_operations::stored_block_info, block::Block, BlockNumber, H256};
 
 // TODO: don't use anyhow (ZKS-588)
@@ -240,6 +240,9 @@ async fn main() -> anyhow::Result<()> {
         H256::from_str(key_without_prefix).expect("Cannot deserialize private key");
 
     let mut storage = StorageProcessor::establish_connection().await?;
+    if let Some(blob_store) = blob_store::from_env() {
+        storage.set_blob_store(blob_store);
+    }
     let client = EthereumGateway::from_config(
         &eth_client_config,
         &eth_sender_config,
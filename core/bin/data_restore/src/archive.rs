@@ -0,0 +1,331 @@
+//! Offline archive of the Ethereum data required to restore the zkSync state.
+//!
+//! Archive is a newline-delimited JSON file produced by the `export` command: the first
+//! line is a header, followed by the zkSync and Governance contracts logs and the
+//! Ethereum transactions referenced by the block events.
+//! `ArchiveTransport` serves this data through the web3 interface, so the restore
+//! process doesn't need an Ethereum node.
+
+// Built-in deps
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::sync::Arc;
+// External deps
+use anyhow::format_err;
+use futures::future;
+use jsonrpc_core::{Call, Params};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use web3::contract::Contract;
+use web3::types::{Address, Log, Transaction, H256, U256};
+use web3::{RequestId, Transport, Web3};
+// Local deps
+use crate::contract::ZkSyncDeployedContract;
+use crate::eth_tx_helpers::{get_block_number_from_ethereum_transaction, get_ethereum_transaction};
+use crate::events_state::EventsState;
+
+/// Ethereum state covered by the archive.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveHeader {
+    /// The last Ethereum block covered by the archive.
+    pub last_block: u64,
+    /// Amount of the blocks verified on the contract up to the last block.
+    pub total_verified_blocks: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+enum ArchiveRecord {
+    Header(ArchiveHeader),
+    Log(Log),
+    Transaction(Transaction),
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Archive {
+    pub header: ArchiveHeader,
+    /// Contracts logs in the order they're emitted.
+    pub logs: Vec<Log>,
+    pub transactions: HashMap<H256, Transaction>,
+}
+
+impl Archive {
+    /// Reads the archive in the NDJSON format.
+    pub fn read(reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut archive = Self::default();
+        let mut has_header = false;
+        for (line_number, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record = serde_json::from_str(&line).map_err(|err| {
+                format_err!(
+                    "Invalid archive record at line {}: {}",
+                    line_number + 1,
+                    err
+                )
+            })?;
+            match record {
+                ArchiveRecord::Header(header) => {
+                    archive.header = header;
+                    has_header = true;
+                }
+                ArchiveRecord::Log(log) => archive.logs.push(log),
+                ArchiveRecord::Transaction(transaction) => {
+                    archive.transactions.insert(transaction.hash, transaction);
+                }
+            }
+        }
+        anyhow::ensure!(has_header, "Archive header is missing");
+        Ok(archive)
+    }
+
+    /// Writes the archive in the NDJSON format.
+    pub fn write(&self, mut writer: impl Write) -> anyhow::Result<()> {
+        let mut write_record = |record: ArchiveRecord| -> anyhow::Result<()> {
+            serde_json::to_writer(&mut writer, &record)?;
+            writeln!(writer)?;
+            Ok(())
+        };
+
+        write_record(ArchiveRecord::Header(self.header.clone()))?;
+        for log in &self.logs {
+            write_record(ArchiveRecord::Log(log.clone()))?;
+        }
+        // Transactions are sorted to make the archive reproducible.
+        let mut transactions: Vec<_> = self.transactions.values().collect();
+        transactions.sort_by_key(|transaction| {
+            (
+                transaction.block_number,
+                transaction.transaction_index,
+                transaction.hash,
+            )
+        });
+        for transaction in transactions {
+            write_record(ArchiveRecord::Transaction(transaction.clone()))?;
+        }
+        Ok(())
+    }
+
+    /// Returns the logs that match the `eth_getLogs` filter.
+    fn logs(&self, filter: &Value) -> anyhow::Result<Vec<Log>> {
+        let addresses: Option<Vec<Address>> = match filter.get("address") {
+            None | Some(Value::Null) => None,
+            Some(Value::Array(addresses)) => {
+                Some(serde_json::from_value(addresses.clone().into())?)
+            }
+            Some(address) => Some(vec![serde_json::from_value(address.clone())?]),
+        };
+        // Only the first topic is used by the data restore.
+        let topics: Option<Vec<H256>> = match filter.get("topics").and_then(|t| t.get(0)) {
+            None | Some(Value::Null) => None,
+            Some(Value::Array(topics)) => Some(serde_json::from_value(topics.clone().into())?),
+            Some(topic) => Some(vec![serde_json::from_value(topic.clone())?]),
+        };
+        let from_block = self.block_number(filter.get("fromBlock"))?.unwrap_or(0);
+        let to_block = self
+            .block_number(filter.get("toBlock"))?
+            .unwrap_or(self.header.last_block);
+
+        let logs = self
+            .logs
+            .iter()
+            .filter(|log| {
+                let block = log.block_number.map(|number| number.as_u64()).unwrap_or(0);
+                let address_matches = addresses
+                    .as_ref()
+                    .map(|addresses| addresses.contains(&log.address))
+                    .unwrap_or(true);
+                let topic_matches = topics
+                    .as_ref()
+                    .map(|topics| {
+                        log.topics
+                            .first()
+                            .map(|topic| topics.contains(topic))
+                            .unwrap_or(false)
+                    })
+                    .unwrap_or(true);
+                from_block <= block && block <= to_block && address_matches && topic_matches
+            })
+            .cloned()
+            .collect();
+        Ok(logs)
+    }
+
+    fn block_number(&self, block: Option<&Value>) -> anyhow::Result<Option<u64>> {
+        let block = match block.and_then(Value::as_str) {
+            Some(block) => block,
+            None => return Ok(None),
+        };
+        let number = match block {
+            "earliest" => 0,
+            "latest" | "pending" => self.header.last_block,
+            number => u64::from_str_radix(number.trim_start_matches("0x"), 16)
+                .map_err(|err| format_err!("Invalid block number {}: {}", number, err))?,
+        };
+        Ok(Some(number))
+    }
+}
+
+/// Web3 transport that serves the requests of the restore process from the archive.
+#[derive(Debug, Clone)]
+pub struct ArchiveTransport {
+    archive: Arc<Archive>,
+}
+
+impl ArchiveTransport {
+    pub fn new(archive: Archive) -> Self {
+        Self {
+            archive: Arc::new(archive),
+        }
+    }
+
+    fn handle(&self, method: &str, mut params: Vec<Value>) -> anyhow::Result<Value> {
+        match method {
+            "eth_blockNumber" => Ok(json!(format!("{:#x}", self.archive.header.last_block))),
+            "eth_getLogs" => {
+                let filter = params
+                    .pop()
+                    .ok_or_else(|| format_err!("Filter is not provided"))?;
+                Ok(json!(self.archive.logs(&filter)?))
+            }
+            "eth_getTransactionByHash" => {
+                let hash: H256 = serde_json::from_value(
+                    params
+                        .pop()
+                        .ok_or_else(|| format_err!("Transaction hash is not provided"))?,
+                )?;
+                Ok(json!(self.archive.transactions.get(&hash)))
+            }
+            // The only contract call made by the restore process is the query
+            // of the total verified blocks amount.
+            "eth_call" => {
+                let mut result = [0u8; 32];
+                U256::from(self.archive.header.total_verified_blocks).to_big_endian(&mut result);
+                Ok(json!(format!("0x{}", hex::encode(result))))
+            }
+            _ => Err(format_err!(
+                "Method {} is not available in the offline archive",
+                method
+            )),
+        }
+    }
+}
+
+impl Transport for ArchiveTransport {
+    type Out = future::Ready<Result<Value, web3::Error>>;
+
+    fn prepare(&self, method: &str, params: Vec<Value>) -> (RequestId, Call) {
+        (0, web3::helpers::build_request(0, method, params))
+    }
+
+    fn send(&self, _id: RequestId, request: Call) -> Self::Out {
+        let result = match request {
+            Call::MethodCall(call) => {
+                let params = match call.params {
+                    Params::Array(params) => params,
+                    Params::None => Vec::new(),
+                    Params::Map(_) => {
+                        return future::ready(Err(web3::Error::InvalidResponse(
+                            "Named parameters are not supported".to_string(),
+                        )))
+                    }
+                };
+                self.handle(&call.method, params)
+                    .map_err(|err| web3::Error::InvalidResponse(err.to_string()))
+            }
+            _ => Err(web3::Error::Unreachable),
+        };
+        future::ready(result)
+    }
+}
+
+/// Fetches the logs and the commit transactions from the Ethereum node
+/// starting from the zkSync genesis and returns them as an archive.
+///
+/// # Arguments
+///
+/// * `web3` - Web3 provider url
+/// * `zksync_contract` - Rollup contract
+/// * `governance_contract` - Governance contract
+/// * `genesis_tx_hash` - zkSync contract creation tx hash
+/// * `eth_blocks_step` - Ethereum blocks delta step
+/// * `parallel_eth_logs_ranges` - Max amount of the ranges that are fetched in parallel
+/// * `end_eth_blocks_offset` - last block delta
+///
+pub async fn export_archive<T: Transport>(
+    web3: &Web3<T>,
+    zksync_contract: &ZkSyncDeployedContract<T>,
+    governance_contract: &(ethabi::Contract, Contract<T>),
+    genesis_tx_hash: H256,
+    eth_blocks_step: u64,
+    parallel_eth_logs_ranges: usize,
+    end_eth_blocks_offset: u64,
+) -> anyhow::Result<Archive> {
+    let genesis_transaction = get_ethereum_transaction(web3, &genesis_tx_hash).await?;
+    let mut last_watched_block = get_block_number_from_ethereum_transaction(&genesis_transaction)?;
+    let last_block = EventsState::get_last_block_number(web3)
+        .await?
+        .saturating_sub(end_eth_blocks_offset);
+
+    let mut archive = Archive::default();
+    archive
+        .transactions
+        .insert(genesis_transaction.hash, genesis_transaction);
+
+    while last_watched_block < last_block {
+        let ranges = EventsState::plan_logs_ranges(
+            last_watched_block,
+            last_block,
+            eth_blocks_step,
+            parallel_eth_logs_ranges,
+            &[],
+        );
+        for range in
+            EventsState::get_logs_ranges(web3, zksync_contract, governance_contract, &ranges).await
+        {
+            let range = range?;
+            last_watched_block = range.to_block;
+            archive.logs.extend(range.logs);
+        }
+        vlog::info!(
+            "Exported logs up to the ethereum block {} of {}",
+            last_watched_block,
+            last_block
+        );
+    }
+
+    let block_commit_topic = zksync_contract
+        .abi
+        .event("BlockCommit")
+        .expect("Main contract abi error")
+        .signature();
+    let block_verified_topic = zksync_contract
+        .abi
+        .event("BlockVerification")
+        .expect("Main contract abi error")
+        .signature();
+    for log in &archive.logs {
+        if log.topics[0] == block_verified_topic {
+            let block = U256::from(log.topics[1].as_bytes()).as_u32();
+            archive.header.total_verified_blocks = archive.header.total_verified_blocks.max(block);
+        }
+    }
+    let commit_tx_hashes: Vec<H256> = archive
+        .logs
+        .iter()
+        .filter(|log| log.topics[0] == block_commit_topic)
+        .filter_map(|log| log.transaction_hash)
+        .collect();
+    for tx_hash in commit_tx_hashes {
+        if !archive.transactions.contains_key(&tx_hash) {
+            let transaction = get_ethereum_transaction(web3, &tx_hash).await?;
+            archive.transactions.insert(tx_hash, transaction);
+        }
+    }
+    archive.header.last_block = last_block;
+
+    Ok(archive)
+}
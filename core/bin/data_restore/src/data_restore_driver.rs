@@ -69,6 +69,8 @@ pub struct DataRestoreDriver<T: Transport> {
     pub tree_state: TreeState,
     /// The step distance of viewing events in the ethereum blocks
     pub eth_blocks_step: u64,
+    /// Max amount of the ethereum blocks ranges which logs are fetched concurrently
+    pub parallel_eth_logs_ranges: usize,
     /// The distance to the last ethereum block
     pub end_eth_blocks_offset: u64,
     /// Finite mode flag. In finite mode, driver will only work until
@@ -93,6 +95,7 @@ impl<T: Transport> DataRestoreDriver<T> {
     /// * `upgrade_eth_blocks` - Ethereum blocks that include correct UpgradeComplete events
     /// * `init_contract_version` - The initial version of the deployed zkSync contract
     /// * `eth_blocks_step` - The step distance of viewing events in the ethereum blocks
+    /// * `parallel_eth_logs_ranges` - Max amount of the ethereum blocks ranges fetched concurrently
     /// * `end_eth_blocks_offset` - The distance to the last ethereum block
     /// * `finite_mode` - Finite mode flag.
     /// * `final_hash` - Hash of the last block which we want to restore
@@ -105,6 +108,7 @@ impl<T: Transport> DataRestoreDriver<T> {
         contract_upgrade_eth_blocks: Vec<u64>,
         init_contract_version: u32,
         eth_blocks_step: u64,
+        parallel_eth_logs_ranges: usize,
         end_eth_blocks_offset: u64,
         finite_mode: bool,
         final_hash: Option<Fr>,
@@ -130,6 +134,7 @@ impl<T: Transport> DataRestoreDriver<T> {
             events_state,
             tree_state,
             eth_blocks_step,
+            parallel_eth_logs_ranges,
             end_eth_blocks_offset,
             finite_mode,
             final_hash,
@@ -413,7 +418,61 @@ impl<T: Transport> DataRestoreDriver<T> {
 
     /// Updates events state, saves new blocks, tokens events and the last watched eth block number in storage
     /// Returns bool flag, true if there are new block events
-    async fn update_events_state(&mut self, interactor: &mut StorageInteractor<'_>) -> bool {
+    ///
+    /// Logs for several ethereum blocks ranges are fetched concurrently. If some of the ranges
+    /// fail, the ones following them are saved as checkpoints and are not requested again.
+    pub(crate) async fn update_events_state(
+        &mut self,
+        interactor: &mut StorageInteractor<'_>,
+    ) -> bool {
+        let last_watched_block = self.events_state.last_watched_eth_block_number;
+        let last_block = match EventsState::get_last_block_number(&self.web3).await {
+            Ok(block_number) => block_number.saturating_sub(self.end_eth_blocks_offset),
+            Err(err) => {
+                vlog::warn!("Failed to get the last ethereum block number: {}", err);
+                return false;
+            }
+        };
+
+        let checkpoints = interactor.load_eth_logs_checkpoints().await;
+        let ranges_to_fetch = EventsState::plan_logs_ranges(
+            last_watched_block,
+            last_block,
+            self.eth_blocks_step,
+            self.parallel_eth_logs_ranges,
+            &checkpoints,
+        );
+        let mut logs_ranges = checkpoints;
+        let fetched_ranges = EventsState::get_logs_ranges(
+            &self.web3,
+            &self.zksync_contract,
+            &self.governance_contract,
+            &ranges_to_fetch,
+        )
+        .await;
+        for (&(from_block, to_block), result) in ranges_to_fetch.iter().zip(fetched_ranges) {
+            match result {
+                Ok(range) => logs_ranges.push(range),
+                Err(err) => vlog::warn!(
+                    "Failed to fetch logs for ethereum blocks {}..={}: {}",
+                    from_block,
+                    to_block,
+                    err
+                ),
+            }
+        }
+
+        let (logs_ranges, pending_ranges) =
+            EventsState::take_contiguous_ranges(last_watched_block, logs_ranges);
+        // These ranges can't be processed until the missing ones are fetched,
+        // keep them in storage so they won't be requested again.
+        for range in pending_ranges
+            .iter()
+            .filter(|range| ranges_to_fetch.contains(&(range.from_block, range.to_block)))
+        {
+            interactor.save_eth_logs_checkpoint(range).await;
+        }
+
         let (
             block_events,
             token_events,
@@ -424,16 +483,14 @@ impl<T: Transport> DataRestoreDriver<T> {
         ) = self
             .events_state
             .update_events_state(
-                &self.web3,
                 &self.zksync_contract,
                 &self.governance_contract,
                 &self.contract_upgrade_eth_blocks,
-                self.eth_blocks_step,
-                self.end_eth_blocks_offset,
                 self.init_contract_version,
+                logs_ranges,
             )
-            .await
             .expect("Updating events state: cant update events state");
+        // Checkpoints covered by the last watched block are removed here as well.
         interactor
             .save_events_state(
                 &block_events,
@@ -18,7 +18,7 @@ use crate::storage_interactor::StoredTreeState;
 use crate::{
     data_restore_driver::StorageUpdateState,
     events::BlockEvent,
-    events_state::EventsState,
+    events_state::{EthLogsRange, EventsState},
     rollup_ops::RollupOpsBlock,
     storage_interactor::{
         block_event_into_stored_block_event, stored_block_event_into_block_event,
@@ -212,6 +212,31 @@ impl<'a> DatabaseStorageInteractor<'a> {
             .expect("Cant update events state");
     }
 
+    pub async fn load_eth_logs_checkpoints(&mut self) -> Vec<EthLogsRange> {
+        self.storage
+            .data_restore_schema()
+            .load_eth_logs_checkpoints()
+            .await
+            .expect("Cant load ethereum logs checkpoints")
+            .into_iter()
+            .map(|checkpoint| EthLogsRange {
+                from_block: checkpoint.from_block as u64,
+                to_block: checkpoint.to_block as u64,
+                logs: serde_json::from_value(checkpoint.logs)
+                    .expect("Cant deserialize ethereum logs checkpoint"),
+            })
+            .collect()
+    }
+
+    pub async fn save_eth_logs_checkpoint(&mut self, range: &EthLogsRange) {
+        let logs = serde_json::to_value(&range.logs).expect("Cant serialize ethereum logs");
+        self.storage
+            .data_restore_schema()
+            .save_eth_logs_checkpoint(range.from_block, range.to_block, logs)
+            .await
+            .expect("Cant save ethereum logs checkpoint");
+    }
+
     pub async fn save_genesis_tree_state(
         &mut self,
         genesis_updates: &[(AccountId, AccountUpdate)],
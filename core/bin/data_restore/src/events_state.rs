@@ -1,13 +1,11 @@
 // Built-in deps
 use std::collections::HashMap;
 use std::convert::TryFrom;
-use std::fmt::Debug;
 // External deps
 use anyhow::format_err;
+use futures::future::join_all;
 use web3::contract::Contract;
-use web3::types::{
-    BlockNumber as Web3BlockNumber, FilterBuilder, Log, Transaction, H256, U256, U64,
-};
+use web3::types::{BlockNumber as Web3BlockNumber, FilterBuilder, Log, Transaction, H256, U256};
 use web3::{Transport, Web3};
 // Workspace deps
 use zksync_contracts::upgrade_gatekeeper;
@@ -34,13 +32,24 @@ pub struct EventsState {
     pub priority_op_data: HashMap<SerialId, PriorityOp>,
 }
 
-pub struct NewEvents<'a, T: Transport + Debug> {
-    pub block_logs: Vec<(&'a ZkSyncDeployedContract<T>, Vec<Log>)>,
-    pub new_tokens: Vec<NewTokenEvent>,
-    pub priority_ops: Vec<PriorityOp>,
-    pub withdrawal_events: Vec<WithdrawalEvent>,
-    pub withdrawal_pending_events: Vec<WithdrawalPendingEvent>,
-    pub last_block: u64,
+struct NewEvents {
+    block_logs: Vec<Log>,
+    new_tokens: Vec<NewTokenEvent>,
+    priority_ops: Vec<PriorityOp>,
+    withdrawal_events: Vec<WithdrawalEvent>,
+    withdrawal_pending_events: Vec<WithdrawalPendingEvent>,
+    last_block: u64,
+}
+
+/// Logs emitted by the zkSync and Governance contracts in the Ethereum blocks range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EthLogsRange {
+    /// First block of the range.
+    pub from_block: u64,
+    /// Last block of the range (inclusive).
+    pub to_block: u64,
+    /// Logs of both contracts in the order they're emitted.
+    pub logs: Vec<Log>,
 }
 
 impl std::default::Default for EventsState {
@@ -87,29 +96,25 @@ impl EventsState {
         self.priority_op_data = priority_op_data;
     }
 
-    /// Update past events state from last watched ethereum block with delta between last eth block and last watched block.
+    /// Updates past events state with the logs fetched for the Ethereum block ranges
+    /// following the last watched block.
     /// Returns new verified committed blocks evens, added tokens events and the last watched eth block number
     ///
     /// # Arguments
     ///
-    /// * `web3` - Web3 provider url
     /// * `zksync_contract` - Rollup contract
     /// * `governance_contract` - Governance contract
     /// * `contract_upgrade_eth_blocks` - Ethereum blocks that include correct UpgradeComplete events
-    /// * `eth_blocks_step` - Blocks step for watching
-    /// * `end_eth_blocks_offset` - Delta between last eth block and last watched block
     /// * `init_contract_version` - The initial version of the deployed zkSync contract
+    /// * `logs_ranges` - Contiguous fetched log ranges, starting right after the last watched block
     ///
-    #[allow(clippy::too_many_arguments)]
-    pub async fn update_events_state<T: Transport>(
+    pub fn update_events_state<T: Transport>(
         &mut self,
-        web3: &Web3<T>,
         zksync_contract: &ZkSyncDeployedContract<T>,
         governance_contract: &(ethabi::Contract, Contract<T>),
         contract_upgrade_eth_blocks: &[u64],
-        eth_blocks_step: u64,
-        end_eth_blocks_offset: u64,
         init_contract_version: u32,
+        logs_ranges: Vec<EthLogsRange>,
     ) -> Result<
         (
             Vec<BlockEvent>,
@@ -130,15 +135,12 @@ impl EventsState {
             withdrawal_events,
             withdrawal_pending_events,
             last_block,
-        } = EventsState::get_new_events_and_last_watched_block(
-            web3,
+        } = EventsState::parse_new_events(
             zksync_contract,
             governance_contract,
             self.last_watched_eth_block_number,
-            eth_blocks_step,
-            end_eth_blocks_offset,
-        )
-        .await?;
+            logs_ranges,
+        )?;
         // Parse the initial contract version.
         let init_contract_version = ZkSyncContractVersion::try_from(init_contract_version)
             .expect("invalid initial contract version provided");
@@ -146,14 +148,12 @@ impl EventsState {
         // events emitted by the Upgrade GateKeeper. Should be provided by the
         // config.
         self.last_watched_eth_block_number = last_block;
-        for (zksync_contract, block_events) in block_logs {
-            self.update_blocks_state(
-                zksync_contract,
-                &block_events,
-                contract_upgrade_eth_blocks,
-                init_contract_version,
-            );
-        }
+        self.update_blocks_state(
+            zksync_contract,
+            &block_logs,
+            contract_upgrade_eth_blocks,
+            init_contract_version,
+        );
 
         let mut events_to_return = self.committed_events.clone();
         events_to_return.extend(self.verified_events.clone());
@@ -185,94 +185,6 @@ impl EventsState {
         Ok(web3.eth().block_number().await.map(|n| n.as_u64())?)
     }
 
-    /// Returns blocks logs, added token logs and the new last watched block number
-    ///
-    /// # Arguments
-    ///
-    /// * `web3` - Web3 provider url
-    /// * `zksync_contract` - Rollup contract
-    /// * `governance_contract` - Governance contract
-    /// * `last_watched_block_number` - the current last watched eth block
-    /// * `eth_blocks_step` - Ethereum blocks delta step
-    /// * `end_eth_blocks_offset` - last block delta
-    ///
-    #[allow(clippy::needless_lifetimes)] // Cargo clippy gives a false positive warning on needless_lifetimes there, so can be allowed.
-    async fn get_new_events_and_last_watched_block<'a, T: Transport>(
-        web3: &Web3<T>,
-        zksync_contract: &'a ZkSyncDeployedContract<T>,
-        governance_contract: &(ethabi::Contract, Contract<T>),
-        last_watched_block_number: u64,
-        eth_blocks_step: u64,
-        end_eth_blocks_offset: u64,
-    ) -> anyhow::Result<NewEvents<'a, T>> {
-        let latest_eth_block_minus_delta =
-            EventsState::get_last_block_number(web3).await? - end_eth_blocks_offset;
-
-        if latest_eth_block_minus_delta == last_watched_block_number {
-            return Ok(NewEvents {
-                block_logs: vec![],
-                new_tokens: vec![],
-                priority_ops: vec![],
-                withdrawal_events: vec![],
-                withdrawal_pending_events: vec![],
-                last_block: last_watched_block_number,
-            });
-            // No new eth blocks
-        }
-
-        let from_block_number_u64 = last_watched_block_number + 1;
-
-        let to_block_number_u64 =
-        // if (latest eth block < last watched + delta) then choose it
-        if from_block_number_u64 + eth_blocks_step > latest_eth_block_minus_delta {
-            latest_eth_block_minus_delta
-        } else {
-            from_block_number_u64 + eth_blocks_step
-        };
-
-        let new_tokens = EventsState::get_token_added_logs(
-            web3,
-            governance_contract,
-            Web3BlockNumber::Number(from_block_number_u64.into()),
-            Web3BlockNumber::Number(to_block_number_u64.into()),
-        )
-        .await?;
-        let mut logs = vec![];
-        let block_logs = EventsState::get_block_logs(
-            web3,
-            zksync_contract,
-            Web3BlockNumber::Number(from_block_number_u64.into()),
-            Web3BlockNumber::Number(to_block_number_u64.into()),
-        )
-        .await?;
-        logs.push((zksync_contract, block_logs));
-
-        let priority_op_data = EventsState::get_priority_operations_logs(
-            web3,
-            zksync_contract,
-            from_block_number_u64.into(),
-            to_block_number_u64.into(),
-        )
-        .await?;
-
-        let (withdrawal_events, withdrawal_pending_events) = EventsState::get_withdrawal_logs(
-            web3,
-            zksync_contract,
-            from_block_number_u64.into(),
-            to_block_number_u64.into(),
-        )
-        .await?;
-
-        Ok(NewEvents {
-            block_logs: logs,
-            new_tokens,
-            priority_ops: priority_op_data,
-            withdrawal_events,
-            withdrawal_pending_events,
-            last_block: to_block_number_u64,
-        })
-    }
-
     /// Returns logs about complete contract upgrades.
     ///
     /// # Arguments
@@ -305,247 +217,282 @@ impl EventsState {
         Ok(result)
     }
 
-    async fn get_priority_operations_logs_inner<T: Transport>(
-        web3: &Web3<T>,
-        contract: &ZkSyncDeployedContract<T>,
-        from: U64,
-        to: U64,
-    ) -> anyhow::Result<Vec<PriorityOp>> {
-        let priority_op_topic = contract
-            .abi
-            .event("NewPriorityRequest")
-            .expect("main contract abi error")
-            .signature();
-        let filter = FilterBuilder::default()
-            .address(vec![contract.web3_contract.address()])
-            .from_block(Web3BlockNumber::Number(from))
-            .to_block(Web3BlockNumber::Number(to))
-            .topics(Some(vec![priority_op_topic]), None, None, None)
-            .build();
-
-        let logs = web3.eth().logs(filter).await?;
-        logs.into_iter()
-            .map(|event| {
-                PriorityOp::try_from(event)
-                    .map_err(|e| format_err!("Failed to parse event log from ETH: {:?}", e))
-            })
-            .collect()
-    }
-
-    /// Returns priority operations logs emitted by the zkSync contract.
+    /// Splits the Ethereum blocks following the last watched one into the ranges
+    /// that have to be fetched. Ranges that are already fetched (checkpoints) are skipped.
+    /// Returns no more than `max_ranges` ranges.
     ///
     /// # Arguments
     ///
-    /// * `web3` - Web3 provider.
-    /// * `contract` - zkSync contract.
-    /// * `start` - start of the block range
-    /// * `end` - end of the block range (inclusive).
+    /// * `last_watched_block_number` - the current last watched eth block
+    /// * `last_block_number` - the last eth block that may be watched
+    /// * `eth_blocks_step` - Ethereum blocks delta step
+    /// * `max_ranges` - Max amount of the ranges that are fetched in parallel
+    /// * `checkpoints` - Already fetched ranges
     ///
-    async fn get_priority_operations_logs<T: Transport>(
-        web3: &Web3<T>,
-        contract: &ZkSyncDeployedContract<T>,
-        start: U64,
-        end: U64,
-    ) -> Result<Vec<PriorityOp>, anyhow::Error> {
-        const LIMIT_ERR: &str = "query returned more than";
-        let mut from_number = start;
-        let mut to_number = end;
-
-        let mut priority_operations = Vec::new();
-
-        loop {
-            if from_number > end {
-                return Ok(priority_operations);
-            }
-
-            let result = EventsState::get_priority_operations_logs_inner(
-                web3,
-                contract,
-                from_number,
-                to_number,
-            )
-            .await;
-            let range_diff = to_number - from_number;
-
-            match result {
-                Ok(mut operations) => {
-                    // Successfully processed block range.
-                    priority_operations.append(&mut operations);
-
-                    from_number = to_number + 1;
-                    to_number = (from_number + range_diff).min(end);
-
-                    continue;
-                }
-                Err(err) => {
-                    if err.to_string().contains(LIMIT_ERR) {
-                        if to_number <= from_number || to_number - from_number == 1.into() {
-                            return Err(format_err!(
-                                "Ethereum node failed to return logs for a single block: {}",
-                                err
-                            ));
-                        }
-
-                        // Shorten the block range.
-                        to_number = from_number + (range_diff / 2u64);
-
-                        continue;
-                    } else {
-                        // Non-recoverable error.
-                        return Err(err);
-                    }
-                }
+    pub fn plan_logs_ranges(
+        last_watched_block_number: u64,
+        last_block_number: u64,
+        eth_blocks_step: u64,
+        max_ranges: usize,
+        checkpoints: &[EthLogsRange],
+    ) -> Vec<(u64, u64)> {
+        let eth_blocks_step = eth_blocks_step.max(1);
+        let mut ranges = Vec::new();
+        let mut from_block = last_watched_block_number + 1;
+
+        while from_block <= last_block_number && ranges.len() < max_ranges {
+            if let Some(checkpoint) = checkpoints.iter().find(|c| c.from_block == from_block) {
+                from_block = checkpoint.to_block + 1;
+                continue;
             }
+            // Do not fetch the blocks which are covered by the next checkpoint.
+            let next_checkpoint = checkpoints
+                .iter()
+                .map(|c| c.from_block)
+                .filter(|block| *block > from_block)
+                .min()
+                .unwrap_or(u64::MAX);
+            let to_block = (from_block + eth_blocks_step - 1)
+                .min(last_block_number)
+                .min(next_checkpoint - 1);
+
+            ranges.push((from_block, to_block));
+            from_block = to_block + 1;
         }
+        ranges
     }
 
-    /// Returns new withdrawal logs
+    /// Takes the ranges that form a contiguous sequence following the last watched block.
+    /// The rest of the ranges are returned as well, they're expected to be processed later.
     ///
     /// # Arguments
     ///
-    /// * `web3` - Web3 provider url
-    /// * `contract` - Governance contract
-    /// * `from` - From ethereum block number
-    /// * `to` - To ethereum block number
+    /// * `last_watched_block_number` - the current last watched eth block
+    /// * `ranges` - Fetched log ranges in any order
     ///
-    async fn get_withdrawal_logs<T: Transport>(
-        web3: &Web3<T>,
-        contract: &ZkSyncDeployedContract<T>,
-        from: Web3BlockNumber,
-        to: Web3BlockNumber,
-    ) -> Result<(Vec<WithdrawalEvent>, Vec<WithdrawalPendingEvent>), anyhow::Error> {
-        let withdrawal_event_topic = contract
-            .abi
-            .event("Withdrawal")
-            .expect("ZkSync contract abi error")
-            .signature();
-
-        let pending_withdrawal_event_topic = contract
-            .abi
-            .event("WithdrawalPending")
-            .expect("ZkSync contract abi error")
-            .signature();
-
-        let filter = FilterBuilder::default()
-            .address(vec![contract.web3_contract.address()])
-            .from_block(from)
-            .to_block(to)
-            .topics(
-                Some(vec![withdrawal_event_topic, pending_withdrawal_event_topic]),
-                None,
-                None,
-                None,
-            )
-            .build();
-
-        let logs = web3.eth().logs(filter).await?;
-        let mut pending_withdrawals = vec![];
-        let mut withdrawals = vec![];
-        for log in logs {
-            if log.topics[0] == pending_withdrawal_event_topic {
-                pending_withdrawals.push(WithdrawalPendingEvent::try_from(log).map_err(|e| {
-                    format_err!(
-                        "Failed to parse WithdrawalPendingEvent event log from ETH: {}",
-                        e
-                    )
-                })?)
+    pub fn take_contiguous_ranges(
+        last_watched_block_number: u64,
+        mut ranges: Vec<EthLogsRange>,
+    ) -> (Vec<EthLogsRange>, Vec<EthLogsRange>) {
+        ranges.sort_by_key(|range| range.from_block);
+
+        let mut next_block = last_watched_block_number + 1;
+        let mut contiguous = Vec::new();
+        let mut rest = Vec::new();
+        for range in ranges {
+            if range.from_block == next_block {
+                next_block = range.to_block + 1;
+                contiguous.push(range);
             } else {
-                withdrawals.push(WithdrawalEvent::try_from(log).map_err(|e| {
-                    format_err!("Failed to parse Withdrawal event log from ETH: {}", e)
-                })?)
+                rest.push(range);
             }
         }
-        Ok((withdrawals, pending_withdrawals))
+        (contiguous, rest)
     }
 
-    /// Returns new added token logs
+    /// Fetches the logs for the specified block ranges concurrently.
+    /// Every range is fetched independently, so the failure of one of them
+    /// doesn't affect the other ones.
     ///
     /// # Arguments
     ///
     /// * `web3` - Web3 provider url
-    /// * `contract` - Governance contract
-    /// * `from` - From ethereum block number
-    /// * `to` - To ethereum block number
+    /// * `zksync_contract` - Rollup contract
+    /// * `governance_contract` - Governance contract
+    /// * `ranges` - Inclusive block ranges to fetch
     ///
-    async fn get_token_added_logs<T: Transport>(
+    pub async fn get_logs_ranges<T: Transport>(
         web3: &Web3<T>,
-        contract: &(ethabi::Contract, Contract<T>),
-        from: Web3BlockNumber,
-        to: Web3BlockNumber,
-    ) -> Result<Vec<NewTokenEvent>, anyhow::Error> {
-        let new_token_event_topic = contract
-            .0
-            .event("NewToken")
-            .expect("Governance contract abi error")
-            .signature();
-        let filter = FilterBuilder::default()
-            .address(vec![contract.1.address()])
-            .from_block(from)
-            .to_block(to)
-            .topics(Some(vec![new_token_event_topic]), None, None, None)
-            .build();
-
-        web3.eth()
-            .logs(filter)
-            .await?
-            .into_iter()
-            .map(|event| {
-                NewTokenEvent::try_from(event)
-                    .map_err(|e| format_err!("Failed to parse NewToken event log from ETH: {}", e))
-            })
-            .collect()
+        zksync_contract: &ZkSyncDeployedContract<T>,
+        governance_contract: &(ethabi::Contract, Contract<T>),
+        ranges: &[(u64, u64)],
+    ) -> Vec<anyhow::Result<EthLogsRange>> {
+        let zksync_topics = vec![
+            EventsState::event_topic(&zksync_contract.abi, "BlockVerification"),
+            EventsState::event_topic(&zksync_contract.abi, "BlockCommit"),
+            EventsState::event_topic(&zksync_contract.abi, "BlocksRevert"),
+            EventsState::event_topic(&zksync_contract.abi, "NewPriorityRequest"),
+            EventsState::event_topic(&zksync_contract.abi, "Withdrawal"),
+            EventsState::event_topic(&zksync_contract.abi, "WithdrawalPending"),
+        ];
+        let governance_topics = vec![EventsState::event_topic(&governance_contract.0, "NewToken")];
+
+        let requests = ranges.iter().map(|&(from_block, to_block)| {
+            let zksync_topics = zksync_topics.clone();
+            let governance_topics = governance_topics.clone();
+            async move {
+                let mut logs = EventsState::get_logs_with_range_splitting(
+                    web3,
+                    zksync_contract.web3_contract.address(),
+                    zksync_topics,
+                    from_block,
+                    to_block,
+                )
+                .await?;
+                logs.extend(
+                    EventsState::get_logs_with_range_splitting(
+                        web3,
+                        governance_contract.1.address(),
+                        governance_topics,
+                        from_block,
+                        to_block,
+                    )
+                    .await?,
+                );
+                // Logs of the contracts are fetched separately, so they have to be merged in the emission order.
+                logs.sort_by_key(|log| (log.block_number, log.log_index));
+                Ok(EthLogsRange {
+                    from_block,
+                    to_block,
+                    logs,
+                })
+            }
+        });
+        join_all(requests).await
     }
 
-    /// Returns the contract logs that occurred on the specified blocks
+    /// Returns the contract logs with the specified topics for the blocks range.
+    /// If the Ethereum node refuses to return too many logs at once,
+    /// the range is split in halves until it's small enough.
     ///
     /// # Arguments
     ///
     /// * `web3` - Web3 provider url
-    /// * `contract` - Specified contract
-    /// * `from_block_number` - Start ethereum block number
-    /// * `to_block_number` - End ethereum block number
+    /// * `address` - Contract address
+    /// * `topics` - Accepted event topics
+    /// * `from_block` - Start ethereum block number
+    /// * `to_block` - End ethereum block number (inclusive)
     ///
-    async fn get_block_logs<T: Transport>(
+    async fn get_logs_with_range_splitting<T: Transport>(
         web3: &Web3<T>,
-        contract: &ZkSyncDeployedContract<T>,
-        from_block_number: Web3BlockNumber,
-        to_block_number: Web3BlockNumber,
-    ) -> Result<Vec<Log>, anyhow::Error> {
-        let block_verified_topic = contract
-            .abi
-            .event("BlockVerification")
-            .expect("Main contract abi error")
-            .signature();
-
-        let block_comitted_topic = contract
-            .abi
-            .event("BlockCommit")
-            .expect("Main contract abi error")
-            .signature();
-
-        let reverted_topic = contract
-            .abi
-            .event("BlocksRevert")
-            .expect("Main contract abi error")
-            .signature();
-
-        let topics_vec: Vec<H256> =
-            vec![block_verified_topic, block_comitted_topic, reverted_topic];
+        address: Address,
+        topics: Vec<H256>,
+        from_block: u64,
+        to_block: u64,
+    ) -> anyhow::Result<Vec<Log>> {
+        const LIMIT_ERR: &str = "query returned more than";
 
-        let filter = FilterBuilder::default()
-            .address(vec![contract.web3_contract.address()])
-            .from_block(from_block_number)
-            .to_block(to_block_number)
-            .topics(Some(topics_vec), None, None, None)
-            .build();
+        let mut logs = Vec::new();
+        // Ranges are taken from the end of the stack, so the logs are kept sorted.
+        let mut pending_ranges = vec![(from_block, to_block)];
+        while let Some((from, to)) = pending_ranges.pop() {
+            let filter = FilterBuilder::default()
+                .address(vec![address])
+                .from_block(Web3BlockNumber::Number(from.into()))
+                .to_block(Web3BlockNumber::Number(to.into()))
+                .topics(Some(topics.clone()), None, None, None)
+                .build();
+
+            match web3.eth().logs(filter).await {
+                Ok(mut range_logs) => logs.append(&mut range_logs),
+                Err(err) if err.to_string().contains(LIMIT_ERR) => {
+                    if from == to {
+                        return Err(format_err!(
+                            "Ethereum node failed to return logs for a single block: {}",
+                            err
+                        ));
+                    }
+                    // Shorten the block range.
+                    let middle = from + (to - from) / 2;
+                    pending_ranges.push((middle + 1, to));
+                    pending_ranges.push((from, middle));
+                }
+                Err(err) => return Err(format_err!("No new logs: {}", err)),
+            }
+        }
+        Ok(logs)
+    }
 
-        let result = web3
-            .eth()
-            .logs(filter)
-            .await
-            .map_err(|e| format_err!("No new logs: {}", e))?;
+    /// Parses the fetched logs into the blocks logs, added tokens, priority operations
+    /// and withdrawals, returning the new last watched block number.
+    ///
+    /// # Arguments
+    ///
+    /// * `zksync_contract` - Rollup contract
+    /// * `governance_contract` - Governance contract
+    /// * `last_watched_block_number` - the current last watched eth block
+    /// * `logs_ranges` - Contiguous fetched log ranges
+    ///
+    fn parse_new_events<T: Transport>(
+        zksync_contract: &ZkSyncDeployedContract<T>,
+        governance_contract: &(ethabi::Contract, Contract<T>),
+        last_watched_block_number: u64,
+        logs_ranges: Vec<EthLogsRange>,
+    ) -> anyhow::Result<NewEvents> {
+        let block_topics = [
+            EventsState::event_topic(&zksync_contract.abi, "BlockVerification"),
+            EventsState::event_topic(&zksync_contract.abi, "BlockCommit"),
+            EventsState::event_topic(&zksync_contract.abi, "BlocksRevert"),
+        ];
+        let priority_op_topic =
+            EventsState::event_topic(&zksync_contract.abi, "NewPriorityRequest");
+        let withdrawal_topic = EventsState::event_topic(&zksync_contract.abi, "Withdrawal");
+        let pending_withdrawal_topic =
+            EventsState::event_topic(&zksync_contract.abi, "WithdrawalPending");
+        let new_token_topic = EventsState::event_topic(&governance_contract.0, "NewToken");
+
+        let mut new_events = NewEvents {
+            block_logs: vec![],
+            new_tokens: vec![],
+            priority_ops: vec![],
+            withdrawal_events: vec![],
+            withdrawal_pending_events: vec![],
+            last_block: last_watched_block_number,
+        };
+        for range in logs_ranges {
+            anyhow::ensure!(
+                range.from_block == new_events.last_block + 1,
+                "Log ranges must be contiguous, expected range starting at {}, got {}",
+                new_events.last_block + 1,
+                range.from_block
+            );
+            new_events.last_block = range.to_block;
+
+            for log in range.logs {
+                let topic = match log.topics.first() {
+                    Some(topic) => *topic,
+                    None => continue,
+                };
+                if block_topics.contains(&topic) {
+                    new_events.block_logs.push(log);
+                } else if topic == priority_op_topic {
+                    new_events.priority_ops.push(
+                        PriorityOp::try_from(log).map_err(|e| {
+                            format_err!("Failed to parse event log from ETH: {:?}", e)
+                        })?,
+                    );
+                } else if topic == pending_withdrawal_topic {
+                    new_events.withdrawal_pending_events.push(
+                        WithdrawalPendingEvent::try_from(log).map_err(|e| {
+                            format_err!(
+                                "Failed to parse WithdrawalPendingEvent event log from ETH: {}",
+                                e
+                            )
+                        })?,
+                    );
+                } else if topic == withdrawal_topic {
+                    new_events
+                        .withdrawal_events
+                        .push(WithdrawalEvent::try_from(log).map_err(|e| {
+                            format_err!("Failed to parse Withdrawal event log from ETH: {}", e)
+                        })?);
+                } else if topic == new_token_topic {
+                    new_events
+                        .new_tokens
+                        .push(NewTokenEvent::try_from(log).map_err(|e| {
+                            format_err!("Failed to parse NewToken event log from ETH: {}", e)
+                        })?);
+                }
+            }
+        }
+        Ok(new_events)
+    }
 
-        Ok(result)
+    fn event_topic(contract: &ethabi::Contract, name: &str) -> H256 {
+        contract
+            .event(name)
+            .expect("Contract abi error")
+            .signature()
     }
 
     /// Updates committed and verified blocks state by extending their arrays
@@ -673,7 +620,7 @@ impl EventsState {
 
 #[cfg(test)]
 mod test {
-    use super::EventsState;
+    use super::{EthLogsRange, EventsState};
     use web3::{
         api::{Eth, Namespace},
         types::{Bytes, H160},
@@ -748,4 +695,47 @@ mod test {
         assert_eq!(events_state.committed_events.len(), 16);
         assert_eq!(events_state.verified_events.len(), 11);
     }
+
+    fn checkpoint(from_block: u64, to_block: u64) -> EthLogsRange {
+        EthLogsRange {
+            from_block,
+            to_block,
+            logs: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn plan_logs_ranges() {
+        // Ranges are limited by the step, the last block and the max amount of ranges.
+        let ranges = EventsState::plan_logs_ranges(10, 45, 10, 8, &[]);
+        assert_eq!(ranges, vec![(11, 20), (21, 30), (31, 40), (41, 45)]);
+        let ranges = EventsState::plan_logs_ranges(10, 45, 10, 2, &[]);
+        assert_eq!(ranges, vec![(11, 20), (21, 30)]);
+        assert!(EventsState::plan_logs_ranges(45, 45, 10, 8, &[]).is_empty());
+
+        // Checkpoints are skipped and don't overlap with the planned ranges.
+        let checkpoints = [checkpoint(21, 30), checkpoint(35, 44)];
+        let ranges = EventsState::plan_logs_ranges(10, 60, 10, 8, &checkpoints);
+        assert_eq!(ranges, vec![(11, 20), (31, 34), (45, 54), (55, 60)]);
+    }
+
+    #[test]
+    fn take_contiguous_ranges() {
+        let ranges = vec![
+            checkpoint(31, 40),
+            checkpoint(11, 20),
+            checkpoint(51, 60),
+            checkpoint(21, 30),
+        ];
+        let (contiguous, rest) = EventsState::take_contiguous_ranges(10, ranges);
+        assert_eq!(
+            contiguous,
+            vec![checkpoint(11, 20), checkpoint(21, 30), checkpoint(31, 40)]
+        );
+        assert_eq!(rest, vec![checkpoint(51, 60)]);
+
+        let (contiguous, rest) = EventsState::take_contiguous_ranges(20, vec![checkpoint(11, 20)]);
+        assert!(contiguous.is_empty());
+        assert_eq!(rest.len(), 1);
+    }
 }
@@ -15,7 +15,7 @@ use zksync_types::{
 use crate::{
     data_restore_driver::StorageUpdateState,
     events::{BlockEvent, EventType},
-    events_state::EventsState,
+    events_state::{EthLogsRange, EventsState},
     rollup_ops::RollupOpsBlock,
     storage_interactor::{CachedTreeState, StoredTreeState},
 };
@@ -27,6 +27,7 @@ struct Inner {
     tokens: HashMap<TokenId, Token>,
     events_state: Vec<BlockEvent>,
     last_watched_block: u64,
+    eth_logs_checkpoints: Vec<EthLogsRange>,
     #[allow(dead_code)]
     last_committed_block: BlockNumber,
     last_verified_block: BlockNumber,
@@ -41,6 +42,7 @@ impl Default for Inner {
             tokens: Default::default(),
             events_state: vec![],
             last_watched_block: 0,
+            eth_logs_checkpoints: vec![],
             last_committed_block: BlockNumber(0),
             last_verified_block: BlockNumber(0),
             accounts: Default::default(),
@@ -269,9 +271,27 @@ impl InMemoryStorageInteractor {
         }
 
         inner.last_watched_block = last_watched_eth_block_number;
+        inner
+            .eth_logs_checkpoints
+            .retain(|range| range.to_block > last_watched_eth_block_number);
         inner.storage_state = StorageUpdateState::Events;
     }
 
+    pub async fn load_eth_logs_checkpoints(&mut self) -> Vec<EthLogsRange> {
+        let inner = self.inner.borrow();
+        let mut checkpoints = inner.eth_logs_checkpoints.clone();
+        checkpoints.sort_by_key(|range| range.from_block);
+        checkpoints
+    }
+
+    pub async fn save_eth_logs_checkpoint(&mut self, range: &EthLogsRange) {
+        let mut inner = self.inner.borrow_mut();
+        inner
+            .eth_logs_checkpoints
+            .retain(|checkpoint| checkpoint.from_block != range.from_block);
+        inner.eth_logs_checkpoints.push(range.clone());
+    }
+
     pub async fn save_genesis_tree_state(
         &mut self,
         genesis_updates: &[(AccountId, AccountUpdate)],
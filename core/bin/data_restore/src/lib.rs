@@ -1,3 +1,4 @@
+pub mod archive;
 pub mod contract;
 pub mod data_restore_driver;
 pub mod database_storage_interactor;
@@ -17,6 +18,8 @@ use zksync_types::{tokens::get_genesis_token_list, TokenId};
 
 // How many blocks we will process at once.
 pub const ETH_BLOCKS_STEP: u64 = 100;
+// How many blocks ranges we will fetch concurrently.
+pub const PARALLEL_ETH_LOGS_RANGES: usize = 8;
 pub const END_ETH_BLOCKS_OFFSET: u64 = 40;
 
 pub async fn add_tokens_to_storage(interactor: &mut StorageInteractor<'_>, eth_network: &str) {
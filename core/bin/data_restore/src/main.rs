@@ -1,17 +1,23 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::PathBuf;
+
 use serde::Deserialize;
 use structopt::StructOpt;
-use web3::transports::Http;
+use web3::{contract::Contract, transports::Http, Transport};
 use zksync_config::configs::{ChainConfig, ContractsConfig as EnvContractsConfig, ETHClientConfig};
+use zksync_contracts::governance_contract;
 use zksync_crypto::convert::FeConvert;
 use zksync_storage::ConnectionPool;
 use zksync_types::{Address, H256};
 
 use web3::Web3;
+use zksync_data_restore::archive::{export_archive, Archive, ArchiveTransport};
 use zksync_data_restore::contract::ZkSyncDeployedContract;
 use zksync_data_restore::{
     add_tokens_to_storage, data_restore_driver::DataRestoreDriver,
     database_storage_interactor::DatabaseStorageInteractor, storage_interactor::StorageInteractor,
-    END_ETH_BLOCKS_OFFSET, ETH_BLOCKS_STEP,
+    END_ETH_BLOCKS_OFFSET, ETH_BLOCKS_STEP, PARALLEL_ETH_LOGS_RANGES,
 };
 use zksync_types::network::Network;
 
@@ -45,6 +51,26 @@ struct Opt {
     /// Provides a path to the configuration file for data restore
     #[structopt(long = "config", name = "config")]
     config_path: Option<String>,
+
+    /// Restores data from the archive created by the `export` command instead of the Ethereum node.
+    /// Implies the `finite` mode
+    #[structopt(long)]
+    archive: Option<PathBuf>,
+
+    #[structopt(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "snake_case")]
+enum Command {
+    /// Exports the contracts logs and the commit transactions into an archive,
+    /// which can be used to restore the state without an Ethereum node
+    Export {
+        /// Path to the archive file
+        #[structopt(long)]
+        output: PathBuf,
+    },
 }
 
 #[derive(Debug, Deserialize)]
@@ -83,25 +109,82 @@ impl ContractsConfig {
 async fn main() {
     vlog::info!("Restoring zkSync state from the contract");
     let _vlog_guard = vlog::init();
-    let connection_pool = ConnectionPool::new(Some(1));
 
     let opt = Opt::from_args();
 
-    let web3_url = opt.web3_url.unwrap_or_else(|| {
-        let config_opts = ETHClientConfig::from_env();
-        config_opts.web3_url()
-    });
-
-    let transport = Http::new(&web3_url).expect("failed to start web3 transport");
-
     let config = opt
         .config_path
+        .clone()
         .map(|path| ContractsConfig::from_file(&path))
         .unwrap_or_else(ContractsConfig::from_env);
 
     vlog::info!("Using the following config: {:#?}", config);
 
+    if let Some(archive_path) = opt.archive.clone() {
+        let file = File::open(&archive_path).expect("Unable to open the archive");
+        let archive = Archive::read(BufReader::new(file)).expect("Unable to read the archive");
+        vlog::info!(
+            "Restoring from the archive up to the ethereum block {}",
+            archive.header.last_block
+        );
+        // The archive doesn't grow, so the restoring process must stop once it's processed.
+        // All the archived blocks are considered final.
+        let web3 = Web3::new(ArchiveTransport::new(archive));
+        run_restore(web3, opt, config, 0, true).await;
+        return;
+    }
+
+    let web3_url = opt.web3_url.clone().unwrap_or_else(|| {
+        let config_opts = ETHClientConfig::from_env();
+        config_opts.web3_url()
+    });
+
+    let transport = Http::new(&web3_url).expect("failed to start web3 transport");
+    let web3 = Web3::new(transport);
+
+    if let Some(Command::Export { output }) = &opt.command {
+        let contract = ZkSyncDeployedContract::version4(web3.eth(), config.contract_addr);
+        let governance = (
+            governance_contract(),
+            Contract::new(web3.eth(), config.governance_addr, governance_contract()),
+        );
+        let archive = export_archive(
+            &web3,
+            &contract,
+            &governance,
+            config.genesis_tx_hash,
+            ETH_BLOCKS_STEP,
+            PARALLEL_ETH_LOGS_RANGES,
+            END_ETH_BLOCKS_OFFSET,
+        )
+        .await
+        .expect("Unable to export the archive");
+        let file = File::create(output).expect("Unable to create the archive file");
+        archive
+            .write(BufWriter::new(file))
+            .expect("Unable to write the archive");
+        vlog::info!(
+            "Exported {} logs and {} transactions up to the ethereum block {}",
+            archive.logs.len(),
+            archive.transactions.len(),
+            archive.header.last_block
+        );
+        return;
+    }
+
     let finite_mode = opt.finite;
+    run_restore(web3, opt, config, END_ETH_BLOCKS_OFFSET, finite_mode).await;
+}
+
+async fn run_restore<T: Transport>(
+    web3: Web3<T>,
+    opt: Opt,
+    config: ContractsConfig,
+    end_eth_blocks_offset: u64,
+    finite_mode: bool,
+) {
+    let connection_pool = ConnectionPool::new(Some(1));
+
     let final_hash = if finite_mode {
         opt.final_hash
             .map(|value| FeConvert::from_hex(&value).expect("Can't parse the final hash"))
@@ -109,7 +192,6 @@ async fn main() {
         None
     };
     let storage = connection_pool.access_storage().await.unwrap();
-    let web3 = Web3::new(transport);
     let contract = ZkSyncDeployedContract::version4(web3.eth(), config.contract_addr);
     let mut driver = DataRestoreDriver::new(
         web3,
@@ -117,7 +199,8 @@ async fn main() {
         config.upgrade_eth_blocks,
         config.init_contract_version,
         ETH_BLOCKS_STEP,
-        END_ETH_BLOCKS_OFFSET,
+        PARALLEL_ETH_LOGS_RANGES,
+        end_eth_blocks_offset,
         finite_mode,
         final_hash,
         contract,
@@ -16,7 +16,7 @@ use crate::{
     data_restore_driver::StorageUpdateState,
     database_storage_interactor::DatabaseStorageInteractor,
     events::{BlockEvent, EventType},
-    events_state::EventsState,
+    events_state::{EthLogsRange, EventsState},
     inmemory_storage_interactor::InMemoryStorageInteractor,
     rollup_ops::RollupOpsBlock,
 };
@@ -141,6 +141,22 @@ impl StorageInteractor<'_> {
         ))
     }
 
+    /// Returns the ethereum logs ranges that were fetched, but not processed yet
+    pub async fn load_eth_logs_checkpoints(&mut self) -> Vec<EthLogsRange> {
+        storage_interact!(self.load_eth_logs_checkpoints())
+    }
+
+    /// Saves the fetched ethereum logs range, so it won't be requested again.
+    /// Saved ranges are removed once the last watched ethereum block passes them.
+    ///
+    /// # Arguments
+    ///
+    /// * `range` - Fetched logs range
+    ///
+    pub async fn save_eth_logs_checkpoint(&mut self, range: &EthLogsRange) {
+        storage_interact!(self.save_eth_logs_checkpoint(range))
+    }
+
     pub async fn save_withdrawals(
         &mut self,
         withdrawals: &[WithdrawalEvent],
@@ -1,7 +1,11 @@
 pub(crate) mod utils;
 
 use std::cmp::max;
-use std::{collections::HashMap, future::Future};
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex},
+};
 
 use chrono::Utc;
 use futures::future;
@@ -9,7 +13,7 @@ use jsonrpc_core::Params;
 use num::BigUint;
 use serde_json::{json, Value};
 use web3::{
-    contract::tokens::Tokenize,
+    contract::{tokens::Tokenize, Contract},
     types::{Bytes, Transaction, H160},
     RequestId, Transport, Web3,
 };
@@ -28,12 +32,14 @@ use zksync_types::{
 
 use crate::contract::ZkSyncDeployedContract;
 use crate::{
+    archive::{export_archive, Archive, ArchiveTransport},
     data_restore_driver::DataRestoreDriver,
     database_storage_interactor::DatabaseStorageInteractor,
+    events_state::EventsState,
     inmemory_storage_interactor::InMemoryStorageInteractor,
     storage_interactor::StorageInteractor,
     tests::utils::{create_log, u32_to_32bytes},
-    END_ETH_BLOCKS_OFFSET, ETH_BLOCKS_STEP,
+    END_ETH_BLOCKS_OFFSET, ETH_BLOCKS_STEP, PARALLEL_ETH_LOGS_RANGES,
 };
 use web3::api::{Eth, Namespace};
 use zksync_types::aggregated_operations::BlocksCommitOperation;
@@ -196,6 +202,12 @@ pub(crate) struct Web3Transport {
     transactions: HashMap<String, Transaction>,
     logs: HashMap<String, Vec<Log>>,
     last_block: u32,
+    /// Emulates the limit of the logs that Ethereum node returns for a single request.
+    max_logs_per_request: Option<usize>,
+    /// Logs requests starting from this block fail.
+    failing_from_block: Arc<Mutex<Option<u64>>>,
+    /// Block ranges of all the received logs requests.
+    logs_requests: Arc<Mutex<Vec<(u64, u64)>>>,
 }
 
 impl Web3Transport {
@@ -204,6 +216,9 @@ impl Web3Transport {
             transactions: HashMap::default(),
             logs: HashMap::default(),
             last_block: 0,
+            max_logs_per_request: None,
+            failing_from_block: Default::default(),
+            logs_requests: Default::default(),
         }
     }
     fn push_transactions(&mut self, transactions: Vec<Transaction>) {
@@ -218,7 +233,7 @@ impl Web3Transport {
         self.logs.insert(topic, logs);
     }
 
-    fn get_logs(&self, filter: Value) -> Vec<Log> {
+    fn get_logs(&self, filter: Value) -> Result<Vec<Log>, web3::Error> {
         let topics = if let Ok(topics) =
             serde_json::from_value::<Vec<Vec<String>>>(filter.get("topics").unwrap().clone())
         {
@@ -226,15 +241,43 @@ impl Web3Transport {
         } else {
             serde_json::from_value::<Vec<String>>(filter.get("topics").unwrap().clone()).unwrap()
         };
+        // Block tags (e.g. `latest`) are treated as an unbounded range.
+        let block_number = |key| {
+            filter
+                .get(key)
+                .and_then(Value::as_str)
+                .and_then(|number| number.strip_prefix("0x"))
+                .map(|number| u64::from_str_radix(number, 16).unwrap())
+        };
+        let from_block = block_number("fromBlock").unwrap_or(0);
+        let to_block = block_number("toBlock").unwrap_or(u64::MAX);
+        self.logs_requests
+            .lock()
+            .unwrap()
+            .push((from_block, to_block));
+        if *self.failing_from_block.lock().unwrap() == Some(from_block) {
+            return Err(web3::Error::Unreachable);
+        }
+
         let mut logs = vec![];
 
         for topic in &topics {
             if let Some(topic_logs) = self.logs.get(topic) {
-                logs.extend_from_slice(topic_logs)
+                logs.extend(topic_logs.iter().cloned().filter(|log| {
+                    let block = log.block_number.unwrap().as_u64();
+                    from_block <= block && block <= to_block
+                }));
             }
         }
 
-        logs
+        match self.max_logs_per_request {
+            Some(max_logs) if logs.len() > max_logs => Err(web3::Error::Rpc(jsonrpc_core::Error {
+                code: jsonrpc_core::ErrorCode::ServerError(-32005),
+                message: format!("query returned more than {} results", max_logs),
+                data: None,
+            })),
+            _ => Ok(logs),
+        }
     }
 }
 
@@ -269,7 +312,7 @@ impl Transport for Web3Transport {
                     "eth_blockNumber" => Ok(json!("0x80")),
                     "eth_getLogs" => {
                         let filter = params.pop().unwrap();
-                        Ok(json!(self.get_logs(filter)))
+                        self.get_logs(filter).map(|logs| json!(logs))
                     }
                     "eth_getTransactionByHash" => {
                         // TODO Cut `"` from start and end of the string
@@ -294,17 +337,10 @@ impl Transport for Web3Transport {
     }
 }
 
-#[db_test]
-async fn test_run_state_update(mut storage: StorageProcessor<'_>) {
-    let contract_addr = H160::from([1u8; 20]);
-    // No contract upgrades.
-    let contract_upgrade_eth_blocks = Vec::new();
-    // Use old contract version.
-    let init_contract_version: u32 = 3;
-
+/// Creates a transport with two committed and verified blocks: a deposit and a withdrawal,
+/// and one added token.
+fn create_test_transport(contract_addr: H160) -> Web3Transport {
     let mut transport = Web3Transport::new();
-
-    let mut interactor = StorageInteractor::Database(DatabaseStorageInteractor::new(storage));
     let contract = zksync_contract();
     let gov_contract = governance_contract();
 
@@ -407,6 +443,21 @@ async fn test_run_state_update(mut storage: StorageProcessor<'_>) {
         ),
     ]);
 
+    transport
+}
+
+#[db_test]
+async fn test_run_state_update(mut storage: StorageProcessor<'_>) {
+    let contract_addr = H160::from([1u8; 20]);
+    // No contract upgrades.
+    let contract_upgrade_eth_blocks = Vec::new();
+    // Use old contract version.
+    let init_contract_version: u32 = 3;
+
+    let transport = create_test_transport(contract_addr);
+
+    let mut interactor = StorageInteractor::Database(DatabaseStorageInteractor::new(storage));
+
     let eth = Eth::new(transport.clone());
     let mut driver = DataRestoreDriver::new(
         Web3::new(transport.clone()),
@@ -414,6 +465,7 @@ async fn test_run_state_update(mut storage: StorageProcessor<'_>) {
         contract_upgrade_eth_blocks.clone(),
         init_contract_version,
         ETH_BLOCKS_STEP,
+        PARALLEL_ETH_LOGS_RANGES,
         END_ETH_BLOCKS_OFFSET,
         true,
         None,
@@ -454,6 +506,7 @@ async fn test_run_state_update(mut storage: StorageProcessor<'_>) {
         contract_upgrade_eth_blocks,
         init_contract_version,
         ETH_BLOCKS_STEP,
+        PARALLEL_ETH_LOGS_RANGES,
         END_ETH_BLOCKS_OFFSET,
         true,
         None,
@@ -644,6 +697,7 @@ async fn test_with_inmemory_storage() {
         contract_upgrade_eth_blocks.clone(),
         init_contract_version,
         ETH_BLOCKS_STEP,
+        PARALLEL_ETH_LOGS_RANGES,
         END_ETH_BLOCKS_OFFSET,
         true,
         None,
@@ -677,6 +731,7 @@ async fn test_with_inmemory_storage() {
         contract_upgrade_eth_blocks,
         init_contract_version,
         ETH_BLOCKS_STEP,
+        PARALLEL_ETH_LOGS_RANGES,
         END_ETH_BLOCKS_OFFSET,
         true,
         None,
@@ -688,3 +743,179 @@ async fn test_with_inmemory_storage() {
     assert_eq!(driver.events_state.committed_events.len(), events.len());
     assert_eq!(*driver.tree_state.block_number, 4)
 }
+
+/// Creates `BlockCommit` logs for the rollup blocks `1..=eth_blocks.len()`,
+/// the block `i` is committed in the Ethereum block `eth_blocks[i - 1]`.
+fn create_commit_logs(contract_addr: H160, eth_blocks: &[u32]) -> (String, Vec<Log>) {
+    let block_committed_topic = zksync_contract()
+        .event("BlockCommit")
+        .expect("Main contract abi error")
+        .signature();
+    let logs = (1..)
+        .zip(eth_blocks)
+        .map(|(block, eth_block)| {
+            create_log(
+                contract_addr,
+                block_committed_topic,
+                vec![u32_to_32bytes(block).into()],
+                Bytes(vec![]),
+                *eth_block,
+                u32_to_32bytes(block).into(),
+            )
+        })
+        .collect();
+    (format!("{:?}", block_committed_topic), logs)
+}
+
+/// Checks that the block range is split when the Ethereum node refuses
+/// to return too many logs at once.
+#[tokio::test]
+async fn test_logs_range_splitting() {
+    let contract_addr = H160::from([1u8; 20]);
+    let mut transport = Web3Transport::new();
+    let (topic, commit_logs) = create_commit_logs(contract_addr, &[1, 2, 3, 3, 6, 8]);
+    transport.insert_logs(topic, commit_logs.clone());
+    transport.max_logs_per_request = Some(2);
+
+    let web3 = Web3::new(transport.clone());
+    let contract = ZkSyncDeployedContract::version4(web3.eth(), contract_addr);
+    let governance = (
+        governance_contract(),
+        Contract::new(web3.eth(), contract_addr, governance_contract()),
+    );
+
+    let mut ranges = EventsState::get_logs_ranges(&web3, &contract, &governance, &[(1, 8)]).await;
+    assert_eq!(ranges.len(), 1);
+    let range = ranges.pop().unwrap().unwrap();
+    assert_eq!((range.from_block, range.to_block), (1, 8));
+    // All the logs are returned in the original order.
+    assert_eq!(range.logs, commit_logs);
+
+    // Range that can't be split is reported as an error.
+    transport.max_logs_per_request = Some(1);
+    let web3 = Web3::new(transport.clone());
+    let contract = ZkSyncDeployedContract::version4(web3.eth(), contract_addr);
+    let mut ranges = EventsState::get_logs_ranges(&web3, &contract, &governance, &[(3, 3)]).await;
+    assert!(ranges.pop().unwrap().is_err());
+}
+
+/// Checks that if fetching some of the concurrent ranges fails, the successful ones
+/// are kept as checkpoints and are not requested again.
+#[tokio::test]
+async fn test_resume_from_logs_checkpoints() {
+    let contract_addr = H160::from([1u8; 20]);
+    let mut transport = Web3Transport::new();
+    let (topic, commit_logs) = create_commit_logs(contract_addr, &[5, 15, 25, 35]);
+    transport.insert_logs(topic, commit_logs);
+
+    let mut interactor = StorageInteractor::InMemory(InMemoryStorageInteractor::new());
+    let eth = Eth::new(transport.clone());
+    // The last Ethereum block is `0x80`, so the offset leaves 40 blocks to be fetched
+    // in 4 ranges of 10 blocks.
+    let mut driver = DataRestoreDriver::new(
+        Web3::new(transport.clone()),
+        contract_addr,
+        Vec::new(),
+        4,
+        10,
+        4,
+        0x80 - 40,
+        true,
+        None,
+        ZkSyncDeployedContract::version4(eth, contract_addr),
+    );
+
+    *transport.failing_from_block.lock().unwrap() = Some(11);
+    assert!(driver.update_events_state(&mut interactor).await);
+    // Only the range preceding the failed one is processed.
+    assert_eq!(driver.events_state.last_watched_eth_block_number, 10);
+    assert_eq!(driver.events_state.committed_events.len(), 1);
+    let checkpoints = interactor.load_eth_logs_checkpoints().await;
+    let checkpoints: Vec<_> = checkpoints
+        .iter()
+        .map(|range| (range.from_block, range.to_block))
+        .collect();
+    assert_eq!(checkpoints, vec![(21, 30), (31, 40)]);
+
+    *transport.failing_from_block.lock().unwrap() = None;
+    transport.logs_requests.lock().unwrap().clear();
+    assert!(driver.update_events_state(&mut interactor).await);
+    // Only the failed range is requested again: for the zkSync and the Governance contracts.
+    assert_eq!(
+        *transport.logs_requests.lock().unwrap(),
+        vec![(11, 20), (11, 20)]
+    );
+    assert_eq!(driver.events_state.last_watched_eth_block_number, 40);
+    assert_eq!(driver.events_state.committed_events.len(), 4);
+    assert!(interactor.load_eth_logs_checkpoints().await.is_empty());
+}
+
+/// Checks that the state restored from the exported archive matches the one
+/// restored from the Ethereum node.
+#[db_test]
+async fn test_restore_from_archive(storage: StorageProcessor<'_>) {
+    let contract_addr = H160::from([1u8; 20]);
+    let mut transport = create_test_transport(contract_addr);
+    let genesis_transaction = create_transaction(0, create_block(BlockNumber(0), vec![]));
+    let genesis_tx_hash = genesis_transaction.hash;
+    transport.push_transactions(vec![genesis_transaction]);
+
+    let web3 = Web3::new(transport);
+    let contract = ZkSyncDeployedContract::version4(web3.eth(), contract_addr);
+    let governance = (
+        governance_contract(),
+        Contract::new(web3.eth(), contract_addr, governance_contract()),
+    );
+    let archive = export_archive(
+        &web3,
+        &contract,
+        &governance,
+        genesis_tx_hash,
+        ETH_BLOCKS_STEP,
+        PARALLEL_ETH_LOGS_RANGES,
+        END_ETH_BLOCKS_OFFSET,
+    )
+    .await
+    .unwrap();
+    assert_eq!(archive.header.last_block, 0x80 - END_ETH_BLOCKS_OFFSET);
+    assert_eq!(archive.header.total_verified_blocks, 2);
+    // Two commits, two verifications and one added token.
+    assert_eq!(archive.logs.len(), 5);
+    // The genesis and two commit transactions.
+    assert_eq!(archive.transactions.len(), 3);
+
+    let mut serialized = Vec::new();
+    archive.write(&mut serialized).unwrap();
+    assert_eq!(Archive::read(serialized.as_slice()).unwrap(), archive);
+
+    // Restore the state without the Ethereum node.
+    let web3 = Web3::new(ArchiveTransport::new(archive));
+    let eth = web3.eth();
+    let mut driver = DataRestoreDriver::new(
+        web3,
+        contract_addr,
+        Vec::new(),
+        3,
+        ETH_BLOCKS_STEP,
+        PARALLEL_ETH_LOGS_RANGES,
+        0,
+        true,
+        None,
+        ZkSyncDeployedContract::version4(eth, contract_addr),
+    );
+    let mut interactor = StorageInteractor::Database(DatabaseStorageInteractor::new(storage));
+    driver.run_state_update(&mut interactor).await;
+
+    let db = match &mut interactor {
+        StorageInteractor::Database(db) => db,
+        _ => unreachable!(),
+    };
+    let (_, account) = AccountSchema(db.storage())
+        .account_state_by_address(Address::default())
+        .await
+        .unwrap()
+        .verified
+        .unwrap();
+    assert_eq!(BigUint::from(40u32), account.get_balance(TokenId(0)));
+    assert_eq!(*driver.tree_state.block_number, 2);
+}
@@ -0,0 +1,257 @@
+//! Exit proofs and the `performExodus` calldata built from them.
+
+// External deps
+use anyhow::{ensure, format_err};
+use num::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+// Workspace deps
+use zksync_crypto::bellman::plonk::{
+    better_cs::verifier::verify,
+    commitments::transcript::keccak_transcript::RollingKeccakTranscript,
+};
+use zksync_crypto::params::MIN_NFT_TOKEN_ID;
+use zksync_crypto::proof::EncodedSingleProof;
+use zksync_crypto::serialization::deserialize_single_proof;
+use zksync_crypto::Fr;
+use zksync_prover_utils::exit_proof::{create_exit_proof_with_tree, exit_proof_account_tree};
+use zksync_prover_utils::PlonkVerificationKey;
+use zksync_types::{AccountId, Address, TokenId, TokenLike, H256, U256};
+use zksync_utils::BigUintSerdeWrapper;
+// Local deps
+use crate::state::{ExitState, StoredBlockInfo};
+
+/// Exit to be proven: the owner of the account and the token to withdraw.
+/// All the tokens with the non-zero balance are withdrawn if the token is not specified.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExitRequest {
+    pub owner: Address,
+    pub token: Option<TokenLike>,
+}
+
+impl std::str::FromStr for ExitRequest {
+    type Err = anyhow::Error;
+
+    /// Parses the `<address>[:<token>]` string.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (owner, token) = match s.split_once(':') {
+            Some((owner, token)) => (owner, Some(TokenLike::parse(token))),
+            None => (s, None),
+        };
+        let owner = owner
+            .trim_start_matches("0x")
+            .parse()
+            .map_err(|e| format_err!("Invalid account address {}: {}", owner, e))?;
+        Ok(Self { owner, token })
+    }
+}
+
+/// Data required to withdraw the funds from the zkSync contract in the exodus mode.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExitProofData {
+    pub stored_block_info: StoredBlockInfo,
+    pub owner: Address,
+    pub account_id: AccountId,
+    pub token_id: TokenId,
+    pub amount: BigUintSerdeWrapper,
+    pub nft_creator_id: AccountId,
+    pub nft_creator_address: Address,
+    pub nft_serial_id: u32,
+    pub nft_content_hash: H256,
+    pub proof: EncodedSingleProof,
+    pub token_address: Address,
+    /// Hex-encoded input for the `performExodus` call of the zkSync contract.
+    pub calldata: String,
+}
+
+impl ExitProofData {
+    fn amount_u128(&self) -> anyhow::Result<u128> {
+        self.amount
+            .0
+            .to_u128()
+            .ok_or_else(|| format_err!("Amount {} doesn't fit into uint128", self.amount.0))
+    }
+
+    /// Encodes the `performExodus` call of the zkSync contract.
+    pub fn perform_exodus_calldata(&self) -> anyhow::Result<Vec<u8>> {
+        let amount = self.amount_u128()?;
+        let proof = self
+            .proof
+            .proof
+            .iter()
+            .map(|value| ethabi::Token::Uint(*value))
+            .collect();
+        let calldata = zksync_contracts::zksync_contract()
+            .function("performExodus")?
+            .encode_input(&[
+                self.stored_block_info.to_eth_token(),
+                ethabi::Token::Address(self.owner),
+                ethabi::Token::Uint(U256::from(*self.account_id)),
+                ethabi::Token::Uint(U256::from(*self.token_id)),
+                ethabi::Token::Uint(U256::from(amount)),
+                ethabi::Token::Uint(U256::from(*self.nft_creator_id)),
+                ethabi::Token::Address(self.nft_creator_address),
+                ethabi::Token::Uint(U256::from(self.nft_serial_id)),
+                ethabi::Token::FixedBytes(self.nft_content_hash.as_bytes().to_vec()),
+                ethabi::Token::Array(proof),
+            ])?;
+        Ok(calldata)
+    }
+
+    /// Computes the public input of the exit proof the same way as `verifyExitProof` of the Verifier contract.
+    pub fn contract_commitment(&self) -> anyhow::Result<U256> {
+        let amount = self.amount_u128()?;
+        let mut packed = Vec::new();
+        packed.extend_from_slice(self.stored_block_info.state_hash.as_bytes());
+        packed.extend_from_slice(&self.account_id.to_be_bytes());
+        packed.extend_from_slice(self.owner.as_bytes());
+        packed.extend_from_slice(&self.token_id.to_be_bytes());
+        packed.extend_from_slice(&amount.to_be_bytes());
+        packed.extend_from_slice(&self.nft_creator_id.to_be_bytes());
+        packed.extend_from_slice(self.nft_creator_address.as_bytes());
+        packed.extend_from_slice(&self.nft_serial_id.to_be_bytes());
+        packed.extend_from_slice(self.nft_content_hash.as_bytes());
+
+        let mut commitment = Sha256::digest(&packed);
+        // Contract keeps only the lower 253 bits, so the commitment fits into the field element.
+        commitment[0] &= 0x1f;
+        Ok(U256::from_big_endian(&commitment))
+    }
+
+    /// Checks that the stored calldata matches the exit, and that the contract would accept the proof.
+    pub fn verify(&self, vk: &PlonkVerificationKey) -> anyhow::Result<()> {
+        let calldata = hex::decode(self.calldata.trim_start_matches("0x"))?;
+        ensure!(
+            calldata == self.perform_exodus_calldata()?,
+            "Calldata doesn't match the exit data"
+        );
+
+        let commitment = self.contract_commitment()?;
+        ensure!(
+            self.proof.inputs == vec![commitment],
+            "Proof public input doesn't match the exit data, expected commitment: {:#x}",
+            commitment
+        );
+
+        let proof = deserialize_single_proof(&self.proof, vk.0.n)?;
+        let valid = verify::<_, _, RollingKeccakTranscript<Fr>>(&proof, &vk.0, None)
+            .map_err(|e| format_err!("Failed to verify the proof: {}", e))?;
+        ensure!(valid, "Proof is rejected by the exit verification key");
+        Ok(())
+    }
+}
+
+/// Proves the requested exits.
+/// Proofs are not verified here, the caller has to check them with `ExitProofData::verify`.
+pub fn prove_exits(
+    state: &ExitState,
+    requests: &[ExitRequest],
+) -> anyhow::Result<Vec<ExitProofData>> {
+    let accounts = state.account_map();
+    let mut circuit_account_tree = exit_proof_account_tree(&accounts);
+    // The placeholder NFT creator for the fungible tokens is the account with id 0.
+    let placeholder_creator_address = state
+        .account_address(AccountId(0))
+        .ok_or_else(|| format_err!("Account with id 0 does not exist"))?;
+
+    let mut exits = Vec::new();
+    for request in requests {
+        let account_id = state.account_id_by_address(request.owner).ok_or_else(|| {
+            format_err!(
+                "Unable to find account ID for address: {:#x}",
+                request.owner
+            )
+        })?;
+        let token_ids = match &request.token {
+            Some(token) => vec![state.resolve_token(token)?],
+            None => {
+                let mut token_ids: Vec<_> = accounts[&account_id]
+                    .get_nonzero_balances()
+                    .into_iter()
+                    .map(|(token_id, _)| token_id)
+                    .collect();
+                token_ids.sort();
+                token_ids
+            }
+        };
+        if token_ids.is_empty() {
+            vlog::warn!("Account {:#x} has no funds to withdraw", request.owner);
+        }
+
+        for token_id in token_ids {
+            vlog::info!(
+                "Proving exit for account {} ({:#x}), token {}",
+                *account_id,
+                request.owner,
+                *token_id
+            );
+            let (nft_creator_id, nft_creator_address, nft_serial_id, nft_content_hash) =
+                if *token_id >= MIN_NFT_TOKEN_ID {
+                    let nft = state
+                        .nft(token_id)
+                        .ok_or_else(|| format_err!("NFT {} is not found", *token_id))?;
+                    (
+                        nft.creator_id,
+                        nft.creator_address,
+                        nft.serial_id,
+                        nft.content_hash,
+                    )
+                } else {
+                    (AccountId(0), placeholder_creator_address, 0, H256::zero())
+                };
+            let token_address = state
+                .token_address(token_id)
+                .ok_or_else(|| format_err!("Token {} is not found", *token_id))?;
+
+            let (proof, amount) = create_exit_proof_with_tree(
+                &mut circuit_account_tree,
+                &accounts,
+                account_id,
+                request.owner,
+                token_id,
+                nft_creator_id,
+                nft_serial_id,
+                nft_content_hash,
+            )?;
+            let mut exit = ExitProofData {
+                stored_block_info: state.stored_block_info.clone(),
+                owner: request.owner,
+                account_id,
+                token_id,
+                amount: amount.into(),
+                nft_creator_id,
+                nft_creator_address,
+                nft_serial_id,
+                nft_content_hash,
+                proof,
+                token_address,
+                calldata: String::new(),
+            };
+            exit.calldata = format!("0x{}", hex::encode(exit.perform_exodus_calldata()?));
+            exits.push(exit);
+        }
+    }
+    Ok(exits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_request_parse() {
+        let owner = Address::from_low_u64_be(0xabcd);
+        let request: ExitRequest = format!("{:#x}", owner).parse().unwrap();
+        assert_eq!(request, ExitRequest { owner, token: None });
+
+        let request: ExitRequest = format!("{:x}:ETH", owner).parse().unwrap();
+        assert_eq!(request.owner, owner);
+        assert_eq!(request.token, Some(TokenLike::Symbol("ETH".to_string())));
+
+        let request: ExitRequest = format!("{:#x}:65536", owner).parse().unwrap();
+        assert_eq!(request.token, Some(TokenLike::Id(TokenId(65536))));
+
+        assert!("0x1234:ETH".parse::<ExitRequest>().is_err());
+    }
+}
@@ -0,0 +1,144 @@
+//! Tool generating the exit proofs for the exodus mode.
+//!
+//! The state is restored either from the database (e.g. filled by `data_restore`), or from the snapshot
+//! created by this tool. The output contains the calldata for the `performExodus` call of the zkSync contract.
+
+// Built-in deps
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+// External deps
+use anyhow::{ensure, format_err};
+use structopt::StructOpt;
+// Workspace deps
+use zksync_prover_utils::PlonkVerificationKey;
+use zksync_storage::ConnectionPool;
+// Local deps
+use crate::exodus::{prove_exits, ExitProofData, ExitRequest};
+use crate::state::ExitState;
+
+mod exodus;
+mod state;
+
+#[derive(StructOpt)]
+#[structopt(
+    name = "zkSync exit tool",
+    author = "Matter Labs",
+    rename_all = "snake_case"
+)]
+enum Opt {
+    /// Saves the last verified state from the database to the snapshot file.
+    Snapshot {
+        /// Path to the snapshot file
+        #[structopt(long)]
+        output: PathBuf,
+    },
+    /// Generates the exit proofs along with the `performExodus` calldata and verifies them.
+    Prove {
+        /// Snapshot to restore the state from, the database is used if it's not set
+        #[structopt(long)]
+        snapshot: Option<PathBuf>,
+        /// Exit to prove in the `<address>[:<token>]` form, token is "ETH", token address or token id.
+        /// All the funds of the account are withdrawn if the token is not set
+        #[structopt(long = "account")]
+        accounts: Vec<ExitRequest>,
+        /// File with the exits to prove, one `<address>[:<token>]` per line
+        #[structopt(long)]
+        accounts_file: Option<PathBuf>,
+        /// Path to the output file with the exits data
+        #[structopt(long)]
+        output: PathBuf,
+    },
+    /// Checks the exits data without sending any transaction: the calldata has to match the exits
+    /// and the proofs have to be accepted by the exit verification key.
+    Verify {
+        /// Path to the file with the exits data
+        #[structopt(long)]
+        input: PathBuf,
+    },
+}
+
+async fn load_state_from_db() -> anyhow::Result<ExitState> {
+    let connection_pool = ConnectionPool::new(Some(1));
+    let mut storage = connection_pool.access_storage().await?;
+    ExitState::load_from_db(&mut storage).await
+}
+
+fn read_exit_requests(path: &Path) -> anyhow::Result<Vec<ExitRequest>> {
+    std::fs::read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::parse)
+        .collect()
+}
+
+fn verify_exits(exits: &[ExitProofData]) -> anyhow::Result<()> {
+    let vk = PlonkVerificationKey::read_verification_key_for_exit_circuit()?;
+    for exit in exits {
+        exit.verify(&vk).map_err(|e| {
+            format_err!(
+                "Exit of token {} for account {:#x} is invalid: {}",
+                *exit.token_id,
+                exit.owner,
+                e
+            )
+        })?;
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    vlog::init();
+
+    match Opt::from_args() {
+        Opt::Snapshot { output } => {
+            let state = load_state_from_db().await?;
+            state.save_to_file(&output)?;
+            println!(
+                "Snapshot of block {} with {} accounts is saved to {}",
+                state.stored_block_info.block_number,
+                state.accounts.len(),
+                output.display()
+            );
+        }
+        Opt::Prove {
+            snapshot,
+            mut accounts,
+            accounts_file,
+            output,
+        } => {
+            if let Some(accounts_file) = accounts_file {
+                accounts.extend(read_exit_requests(&accounts_file)?);
+            }
+            ensure!(!accounts.is_empty(), "No accounts to prove the exits for");
+
+            let timer = Instant::now();
+            let state = match snapshot {
+                Some(snapshot) => ExitState::load_from_file(&snapshot)?,
+                None => load_state_from_db().await?,
+            };
+            vlog::info!(
+                "Restored state of block {}: {} s",
+                state.stored_block_info.block_number,
+                timer.elapsed().as_secs()
+            );
+
+            let exits = prove_exits(&state, &accounts)?;
+            verify_exits(&exits)?;
+            std::fs::write(&output, serde_json::to_string_pretty(&exits)?)?;
+            println!(
+                "{} exits are proven and verified, the `performExodus` calldata is saved to {}",
+                exits.len(),
+                output.display()
+            );
+        }
+        Opt::Verify { input } => {
+            let exits: Vec<ExitProofData> =
+                serde_json::from_str(&std::fs::read_to_string(&input)?)?;
+            verify_exits(&exits)?;
+            println!("All {} exits are valid", exits.len());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,190 @@
+//! Last verified state of the network the exit proofs are created for.
+
+// Built-in deps
+use std::path::Path;
+// External deps
+use anyhow::{ensure, format_err};
+use serde::{Deserialize, Serialize};
+// Workspace deps
+use zksync_crypto::params::MIN_NFT_TOKEN_ID;
+use zksync_storage::StorageProcessor;
+use zksync_types::{
+    block::Block, Account, AccountId, AccountMap, Address, BlockNumber, Token, TokenId, TokenLike,
+    H256, NFT, U256,
+};
+
+/// Block info stored in the zkSync contract, the exits are proven against its state hash.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredBlockInfo {
+    pub block_number: BlockNumber,
+    pub priority_operations: u64,
+    pub pending_onchain_operations_hash: H256,
+    pub timestamp: u64,
+    pub state_hash: H256,
+    pub commitment: H256,
+}
+
+impl StoredBlockInfo {
+    pub fn from_block(block: &Block) -> Self {
+        Self {
+            block_number: block.block_number,
+            priority_operations: block.number_of_processed_prior_ops(),
+            pending_onchain_operations_hash: block.get_onchain_operations_block_info().1,
+            timestamp: block.timestamp,
+            state_hash: block.get_eth_encoded_root(),
+            commitment: block.block_commitment,
+        }
+    }
+
+    /// Encodes the block info the same way as `zksync_types::aggregated_operations::stored_block_info`.
+    pub fn to_eth_token(&self) -> ethabi::Token {
+        ethabi::Token::Tuple(vec![
+            ethabi::Token::Uint(U256::from(*self.block_number)),
+            ethabi::Token::Uint(U256::from(self.priority_operations)),
+            ethabi::Token::FixedBytes(self.pending_onchain_operations_hash.as_bytes().to_vec()),
+            ethabi::Token::Uint(U256::from(self.timestamp)),
+            ethabi::Token::FixedBytes(self.state_hash.as_bytes().to_vec()),
+            ethabi::Token::FixedBytes(self.commitment.as_bytes().to_vec()),
+        ])
+    }
+}
+
+/// State of the accounts at the last verified block along with the known tokens.
+/// It's either loaded from the database (e.g. restored by `data_restore`) or from the snapshot file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExitState {
+    pub stored_block_info: StoredBlockInfo,
+    pub accounts: Vec<(AccountId, Account)>,
+    pub tokens: Vec<Token>,
+    pub nfts: Vec<NFT>,
+}
+
+impl ExitState {
+    /// Loads the last verified state, the block it corresponds to has to be confirmed on Ethereum.
+    pub async fn load_from_db(storage: &mut StorageProcessor<'_>) -> anyhow::Result<Self> {
+        let (state_block, accounts) = storage.chain().state_schema().load_verified_state().await?;
+        let last_verified_block = storage
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await?;
+        ensure!(
+            state_block == last_verified_block,
+            "Verified state is stored for block {}, but the last verified confirmed block is {}",
+            state_block,
+            last_verified_block
+        );
+        let block = storage
+            .chain()
+            .block_schema()
+            .get_block(last_verified_block)
+            .await?
+            .ok_or_else(|| format_err!("Block {} is not stored", last_verified_block))?;
+
+        let mut accounts: Vec<_> = accounts.into_iter().collect();
+        accounts.sort_by_key(|(id, _)| *id);
+        let mut tokens: Vec<_> = storage
+            .tokens_schema()
+            .load_tokens()
+            .await?
+            .into_iter()
+            .map(|(_, token)| token)
+            .collect();
+        tokens.sort_by_key(|token| token.id);
+        let mut nfts: Vec<_> = storage
+            .tokens_schema()
+            .load_nfts()
+            .await?
+            .into_iter()
+            .map(|(_, nft)| nft)
+            .collect();
+        nfts.sort_by_key(|nft| nft.id);
+
+        Ok(Self {
+            stored_block_info: StoredBlockInfo::from_block(&block),
+            accounts,
+            tokens,
+            nfts,
+        })
+    }
+
+    pub fn load_from_file(path: &Path) -> anyhow::Result<Self> {
+        let file = std::fs::File::open(path)
+            .map_err(|e| format_err!("Cannot open snapshot {}: {}", path.display(), e))?;
+        Ok(serde_json::from_reader(std::io::BufReader::new(file))?)
+    }
+
+    pub fn save_to_file(&self, path: &Path) -> anyhow::Result<()> {
+        let file = std::fs::File::create(path)?;
+        serde_json::to_writer(std::io::BufWriter::new(file), self)?;
+        Ok(())
+    }
+
+    pub fn account_map(&self) -> AccountMap {
+        self.accounts.iter().cloned().collect()
+    }
+
+    pub fn account_id_by_address(&self, address: Address) -> Option<AccountId> {
+        self.accounts
+            .iter()
+            .find(|(_, account)| account.address == address)
+            .map(|(id, _)| *id)
+    }
+
+    pub fn account_address(&self, account_id: AccountId) -> Option<Address> {
+        self.accounts
+            .iter()
+            .find(|(id, _)| *id == account_id)
+            .map(|(_, account)| account.address)
+    }
+
+    pub fn nft(&self, token_id: TokenId) -> Option<&NFT> {
+        self.nfts.iter().find(|nft| nft.id == token_id)
+    }
+
+    /// Returns the id of the fungible token or the NFT.
+    /// Symbols are only known for the fungible tokens, NFTs have to be addressed by their id or address.
+    pub fn resolve_token(&self, token: &TokenLike) -> anyhow::Result<TokenId> {
+        let found = match token {
+            TokenLike::Id(id) if **id >= MIN_NFT_TOKEN_ID => self.nft(*id).map(|nft| nft.id),
+            TokenLike::Id(id) => self.tokens.iter().find(|t| t.id == *id).map(|t| t.id),
+            TokenLike::Address(address) => self
+                .tokens
+                .iter()
+                .find(|t| t.address == *address)
+                .map(|t| t.id)
+                .or_else(|| {
+                    self.nfts
+                        .iter()
+                        .find(|nft| nft.address == *address)
+                        .map(|nft| nft.id)
+                }),
+            TokenLike::Symbol(symbol) => self
+                .tokens
+                .iter()
+                .find(|t| t.symbol.eq_ignore_ascii_case(symbol))
+                .map(|t| t.id),
+        };
+        found.ok_or_else(|| {
+            format_err!(
+                "Token {:?} not found. Symbols may not be available after data restore, \
+                 try using the token address or id instead",
+                token
+            )
+        })
+    }
+
+    /// Returns the L1 address of the fungible token or the L2 address of the NFT.
+    pub fn token_address(&self, token_id: TokenId) -> Option<Address> {
+        if *token_id >= MIN_NFT_TOKEN_ID {
+            self.nft(token_id).map(|nft| nft.address)
+        } else {
+            self.tokens
+                .iter()
+                .find(|token| token.id == token_id)
+                .map(|token| token.address)
+        }
+    }
+}
@@ -0,0 +1,553 @@
+//! Constraint count report of the main zkSync circuit.
+//! Circuit is synthesized with the testing constraint system for every block size and the number of
+//! constraints is attributed to the block level part, the part of the chunk shared by all operations
+//! and to every operation branch synthesized in each chunk.
+//! Fee constants assume that the circuit cost of the operation is proportional to its chunks,
+//! so the report also contains constraints spent by each operation type.
+//! Report is compared with the committed baseline, so changes of the circuit size are visible in review.
+
+// Built-in deps
+use std::collections::BTreeMap;
+use std::fs::{create_dir_all, File};
+use std::path::{Path, PathBuf};
+// External deps
+use serde::{Deserialize, Serialize};
+// Workspace deps
+use zksync_circuit::witness::utils::WitnessBuilder;
+use zksync_crypto::bellman::{
+    Circuit, ConstraintSystem, LinearCombination, SynthesisError, Variable,
+};
+use zksync_crypto::circuit::{account::CircuitAccount, CircuitAccountTree};
+use zksync_crypto::franklin_crypto::circuit::test::TestConstraintSystem;
+use zksync_crypto::params;
+use zksync_crypto::{Engine, Fr};
+use zksync_types::operations::NoopOp;
+use zksync_types::{
+    AccountId, BlockNumber, ChangePubKeyOp, DepositOp, ForcedExitOp, FullExitOp, MintNFTOp, SwapOp,
+    TransferOp, TransferToNewOp, WithdrawNFTOp, WithdrawOp,
+};
+use zksync_utils::parse_env;
+
+/// Namespaces of the operation branches in the chunk of the circuit with the number of chunks of the operation.
+const OPERATION_BRANCHES: &[(&str, usize)] = &[
+    ("deposit", DepositOp::CHUNKS),
+    ("transfer", TransferOp::CHUNKS),
+    ("transfer_to_new", TransferToNewOp::CHUNKS),
+    ("withdraw", WithdrawOp::CHUNKS),
+    ("full_exit", FullExitOp::CHUNKS),
+    ("change_pubkey_offchain", ChangePubKeyOp::CHUNKS),
+    ("noop", NoopOp::CHUNKS),
+    ("forced_exit", ForcedExitOp::CHUNKS),
+    ("mint_nft", MintNFTOp::CHUNKS),
+    ("withdraw_nft", WithdrawNFTOp::CHUNKS),
+    ("swap", SwapOp::CHUNKS),
+];
+
+const CHUNK_NAMESPACE_PREFIX: &str = "chunk number ";
+
+/// Constraints of the circuit for the block of the given size.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct BlockSizeReport {
+    pub total: usize,
+    /// Constraints outside of the chunks, e.g. fee collection and public data commitment.
+    pub block: usize,
+    /// Constraints of one chunk, every operation branch is synthesized in each chunk.
+    pub per_chunk: usize,
+    /// Constraints of one chunk that are not specific to any operation branch.
+    pub per_chunk_common: usize,
+    /// Constraints of each operation branch in one chunk.
+    pub per_chunk_branches: BTreeMap<String, usize>,
+}
+
+/// Circuit cost of the operation type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct OperationReport {
+    pub chunks: usize,
+    /// Constraints of all chunks occupied by the operation.
+    pub constraints: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ConstraintReport {
+    pub block_sizes: BTreeMap<usize, BlockSizeReport>,
+    pub operations: BTreeMap<String, OperationReport>,
+}
+
+impl ConstraintReport {
+    /// Flattens report into the `path -> value` pairs, used to compare reports.
+    fn flatten(&self) -> BTreeMap<String, usize> {
+        let mut values = BTreeMap::new();
+        for (block_chunks, block) in &self.block_sizes {
+            let prefix = format!("block_sizes.{}", block_chunks);
+            values.insert(format!("{}.total", prefix), block.total);
+            values.insert(format!("{}.block", prefix), block.block);
+            values.insert(format!("{}.per_chunk", prefix), block.per_chunk);
+            values.insert(
+                format!("{}.per_chunk_common", prefix),
+                block.per_chunk_common,
+            );
+            for (branch, constraints) in &block.per_chunk_branches {
+                values.insert(
+                    format!("{}.per_chunk_branches.{}", prefix, branch),
+                    *constraints,
+                );
+            }
+        }
+        for (operation, report) in &self.operations {
+            let prefix = format!("operations.{}", operation);
+            values.insert(format!("{}.chunks", prefix), report.chunks);
+            values.insert(format!("{}.constraints", prefix), report.constraints);
+        }
+        values
+    }
+}
+
+/// Part of the circuit the constraint belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CircuitPart {
+    Block,
+    Chunk(usize),
+    Branch(usize, &'static str),
+}
+
+/// Testing constraint system that counts constraints by the part of the circuit, based on the namespaces.
+struct CountingConstraintSystem {
+    inner: TestConstraintSystem<Engine>,
+    namespaces: Vec<String>,
+    block: usize,
+    chunk_common: BTreeMap<usize, usize>,
+    chunk_branches: BTreeMap<usize, BTreeMap<&'static str, usize>>,
+}
+
+impl CountingConstraintSystem {
+    fn new() -> Self {
+        Self {
+            inner: TestConstraintSystem::new(),
+            namespaces: Vec::new(),
+            block: 0,
+            chunk_common: BTreeMap::new(),
+            chunk_branches: BTreeMap::new(),
+        }
+    }
+
+    /// Only the top level `chunk number N` namespace belongs to the chunk: block level gadgets
+    /// (e.g. hashing of the fee tree) open namespaces with the same names for their own cycles.
+    fn current_part(&self) -> CircuitPart {
+        let mut namespaces = self.namespaces.iter();
+        let chunk = namespaces
+            .next()
+            .and_then(|namespace| namespace.strip_prefix(CHUNK_NAMESPACE_PREFIX)?.parse().ok());
+        let chunk = match chunk {
+            Some(chunk) => chunk,
+            None => return CircuitPart::Block,
+        };
+        let branch = namespaces.find_map(|namespace| {
+            OPERATION_BRANCHES
+                .iter()
+                .find(|(branch, _)| *branch == namespace.as_str())
+        });
+        match branch {
+            Some((branch, _)) => CircuitPart::Branch(chunk, *branch),
+            None => CircuitPart::Chunk(chunk),
+        }
+    }
+}
+
+impl ConstraintSystem<Engine> for CountingConstraintSystem {
+    type Root = Self;
+
+    fn alloc<F, A, AR>(&mut self, annotation: A, f: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<Fr, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.inner.alloc(annotation, f)
+    }
+
+    fn alloc_input<F, A, AR>(&mut self, annotation: A, f: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<Fr, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.inner.alloc_input(annotation, f)
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(&mut self, annotation: A, a: LA, b: LB, c: LC)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+        LA: FnOnce(LinearCombination<Engine>) -> LinearCombination<Engine>,
+        LB: FnOnce(LinearCombination<Engine>) -> LinearCombination<Engine>,
+        LC: FnOnce(LinearCombination<Engine>) -> LinearCombination<Engine>,
+    {
+        match self.current_part() {
+            CircuitPart::Block => self.block += 1,
+            CircuitPart::Chunk(chunk) => *self.chunk_common.entry(chunk).or_default() += 1,
+            CircuitPart::Branch(chunk, branch) => {
+                *self
+                    .chunk_branches
+                    .entry(chunk)
+                    .or_default()
+                    .entry(branch)
+                    .or_default() += 1
+            }
+        }
+        self.inner.enforce(annotation, a, b, c)
+    }
+
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        let name = name_fn().into();
+        self.namespaces.push(name.clone());
+        self.inner.push_namespace(|| name)
+    }
+
+    fn pop_namespace(&mut self) {
+        self.namespaces.pop();
+        self.inner.pop_namespace()
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+}
+
+/// Creates instance of the main zkSync circuit for the block of the given size filled with noop operations.
+fn noop_block_circuit(block_chunks: usize) -> impl Circuit<Engine> {
+    let fee_account_id = AccountId(0);
+    let mut account_tree = CircuitAccountTree::new(params::account_tree_depth());
+    account_tree.insert(*fee_account_id, CircuitAccount::default());
+
+    let mut witness_builder =
+        WitnessBuilder::new(&mut account_tree, fee_account_id, BlockNumber(1), 0);
+    witness_builder.extend_pubdata_with_noops(block_chunks);
+    witness_builder.collect_fees(&[]);
+    witness_builder.calculate_pubdata_commitment();
+    witness_builder.into_circuit_instance()
+}
+
+/// Synthesizes circuit for the block of the given size and counts its constraints.
+fn block_size_report(block_chunks: usize) -> anyhow::Result<BlockSizeReport> {
+    let mut cs = CountingConstraintSystem::new();
+    noop_block_circuit(block_chunks).synthesize(&mut cs)?;
+    if let Some(unsatisfied) = cs.inner.which_is_unsatisfied() {
+        anyhow::bail!(
+            "circuit for the block of {} chunks is not satisfied: {}",
+            block_chunks,
+            unsatisfied
+        );
+    }
+
+    // Chunks are synthesized by the same code, so all of them are expected to have the same constraints.
+    let first_chunk_common = cs.chunk_common.get(&0).copied().unwrap_or_default();
+    let first_chunk_branches = cs.chunk_branches.get(&0).cloned().unwrap_or_default();
+    for chunk in 1..block_chunks {
+        let common = cs.chunk_common.get(&chunk).copied().unwrap_or_default();
+        let branches = cs.chunk_branches.get(&chunk).cloned().unwrap_or_default();
+        anyhow::ensure!(
+            common == first_chunk_common && branches == first_chunk_branches,
+            "chunk {} of the block of {} chunks has different constraints than the first chunk",
+            chunk,
+            block_chunks
+        );
+    }
+
+    let per_chunk_branches: BTreeMap<String, usize> = OPERATION_BRANCHES
+        .iter()
+        .map(|(branch, _)| {
+            let constraints = first_chunk_branches
+                .get(branch)
+                .copied()
+                .unwrap_or_default();
+            (branch.to_string(), constraints)
+        })
+        .collect();
+    let per_chunk = first_chunk_common + per_chunk_branches.values().sum::<usize>();
+    let total = cs.inner.num_constraints();
+    anyhow::ensure!(
+        total == cs.block + per_chunk * block_chunks,
+        "constraints of the block of {} chunks are not fully attributed",
+        block_chunks
+    );
+
+    Ok(BlockSizeReport {
+        total,
+        block: cs.block,
+        per_chunk,
+        per_chunk_common: first_chunk_common,
+        per_chunk_branches,
+    })
+}
+
+/// Builds the constraint report for the given block sizes.
+pub(crate) fn make_constraint_report(block_sizes: &[usize]) -> anyhow::Result<ConstraintReport> {
+    let mut report = ConstraintReport {
+        block_sizes: BTreeMap::new(),
+        operations: BTreeMap::new(),
+    };
+    for &block_chunks in block_sizes {
+        vlog::info!(
+            "Synthesizing circuit for the block of {} chunks",
+            block_chunks
+        );
+        let block_report = block_size_report(block_chunks)?;
+        vlog::info!(
+            "Block of {} chunks: {} constraints, {} per chunk",
+            block_chunks,
+            block_report.total,
+            block_report.per_chunk
+        );
+        report.block_sizes.insert(block_chunks, block_report);
+    }
+
+    // Constraints of the chunk don't depend on the block size, so any block can be used here.
+    if let Some(block) = report.block_sizes.values().next() {
+        for (operation, chunks) in OPERATION_BRANCHES {
+            report.operations.insert(
+                operation.to_string(),
+                OperationReport {
+                    chunks: *chunks,
+                    constraints: chunks * block.per_chunk,
+                },
+            );
+        }
+    }
+    Ok(report)
+}
+
+/// Default path of the committed constraint report baseline.
+pub(crate) fn get_constraint_report_baseline_path() -> PathBuf {
+    let mut path = parse_env::<PathBuf>("ZKSYNC_HOME");
+    path.push("etc");
+    path.push("circuit");
+    path.push("constraint_report.json");
+    path
+}
+
+pub(crate) fn save_constraint_report(report: &ConstraintReport, path: &Path) -> anyhow::Result<()> {
+    if let Some(dir) = path.parent() {
+        create_dir_all(dir)?;
+    }
+    let file = File::create(path)?;
+    serde_json::to_writer_pretty(file, report)?;
+    Ok(())
+}
+
+pub(crate) fn load_constraint_report(path: &Path) -> anyhow::Result<ConstraintReport> {
+    let file = File::open(path)?;
+    Ok(serde_json::from_reader(file)?)
+}
+
+/// Compares the report with the baseline, returns description of every changed value.
+/// Block sizes missing from one of the reports are compared only if they are present in both.
+pub(crate) fn diff_constraint_reports(
+    baseline: &ConstraintReport,
+    report: &ConstraintReport,
+) -> Vec<String> {
+    let common_block_sizes = |report: &ConstraintReport, other: &ConstraintReport| {
+        let mut report = report.clone();
+        report
+            .block_sizes
+            .retain(|block_chunks, _| other.block_sizes.contains_key(block_chunks));
+        report.flatten()
+    };
+    let old_values = common_block_sizes(baseline, report);
+    let new_values = common_block_sizes(report, baseline);
+
+    let mut changes = Vec::new();
+    for (key, old) in &old_values {
+        match new_values.get(key) {
+            Some(new) if new != old => {
+                let delta = *new as i64 - *old as i64;
+                let percent = if *old == 0 {
+                    String::from("n/a")
+                } else {
+                    format!("{:+.2}%", delta as f64 * 100.0 / *old as f64)
+                };
+                changes.push(format!(
+                    "{}: {} -> {} ({:+}, {})",
+                    key, old, new, delta, percent
+                ));
+            }
+            Some(_) => {}
+            None => changes.push(format!("{}: {} -> removed", key, old)),
+        }
+    }
+    for (key, new) in &new_values {
+        if !old_values.contains_key(key) {
+            changes.push(format!("{}: added -> {}", key, new));
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block_report(per_chunk_common: usize, transfer: usize) -> BlockSizeReport {
+        let per_chunk_branches: BTreeMap<String, usize> = vec![
+            ("deposit".to_string(), 100),
+            ("transfer".to_string(), transfer),
+        ]
+        .into_iter()
+        .collect();
+        let per_chunk = per_chunk_common + per_chunk_branches.values().sum::<usize>();
+        BlockSizeReport {
+            total: 1000 + per_chunk * 6,
+            block: 1000,
+            per_chunk,
+            per_chunk_common,
+            per_chunk_branches,
+        }
+    }
+
+    fn report(block_sizes: Vec<(usize, BlockSizeReport)>) -> ConstraintReport {
+        let block_sizes: BTreeMap<usize, BlockSizeReport> = block_sizes.into_iter().collect();
+        let per_chunk = block_sizes.values().next().unwrap().per_chunk;
+        let operations = vec![(
+            "transfer".to_string(),
+            OperationReport {
+                chunks: 2,
+                constraints: 2 * per_chunk,
+            },
+        )]
+        .into_iter()
+        .collect();
+        ConstraintReport {
+            block_sizes,
+            operations,
+        }
+    }
+
+    fn enforce(cs: &mut CountingConstraintSystem, annotation: &str) {
+        cs.enforce(|| annotation, |lc| lc, |lc| lc, |lc| lc);
+    }
+
+    #[test]
+    fn constraints_are_attributed_to_circuit_parts() {
+        let mut cs = CountingConstraintSystem::new();
+        enforce(&mut cs, "block constraint");
+        cs.push_namespace(|| "fee collection");
+        enforce(&mut cs, "fee constraint");
+        cs.pop_namespace();
+
+        cs.push_namespace(|| "chunk number 0");
+        cs.push_namespace(|| "verify_correct_chunking");
+        enforce(&mut cs, "chunking constraint");
+        cs.pop_namespace();
+        cs.pop_namespace();
+
+        cs.push_namespace(|| "chunk number 1");
+        cs.push_namespace(|| "transfer");
+        enforce(&mut cs, "transfer constraint");
+        cs.pop_namespace();
+        cs.push_namespace(|| "execute");
+        cs.push_namespace(|| "swap");
+        enforce(&mut cs, "swap constraint");
+        cs.pop_namespace();
+        cs.pop_namespace();
+        cs.pop_namespace();
+
+        assert_eq!(cs.block, 2);
+        assert_eq!(
+            cs.chunk_common,
+            vec![(0, 1)].into_iter().collect::<BTreeMap<_, _>>()
+        );
+        assert_eq!(
+            cs.chunk_branches,
+            vec![(1, vec![("swap", 1), ("transfer", 1)].into_iter().collect())]
+                .into_iter()
+                .collect::<BTreeMap<_, _>>()
+        );
+    }
+
+    #[test]
+    fn nested_chunk_namespaces_belong_to_block() {
+        let mut cs = CountingConstraintSystem::new();
+        // Block level hashing of the fee tree opens `chunk number N` namespaces of its own.
+        cs.push_namespace(|| "fee tree root");
+        cs.push_namespace(|| "chunk number 0");
+        enforce(&mut cs, "hash constraint");
+        cs.pop_namespace();
+        cs.push_namespace(|| "chunk number 1");
+        cs.push_namespace(|| "transfer");
+        enforce(&mut cs, "hash constraint");
+        cs.pop_namespace();
+        cs.pop_namespace();
+        cs.pop_namespace();
+
+        assert_eq!(cs.block, 2);
+        assert!(cs.chunk_common.is_empty());
+        assert!(cs.chunk_branches.is_empty());
+    }
+
+    #[test]
+    fn diff_of_equal_reports_is_empty() {
+        let baseline = report(vec![(6, block_report(50, 200))]);
+        assert!(diff_constraint_reports(&baseline, &baseline).is_empty());
+    }
+
+    #[test]
+    fn diff_reports_changed_values() {
+        let baseline = report(vec![(6, block_report(50, 200))]);
+        let new = report(vec![(6, block_report(50, 250))]);
+
+        let changes = diff_constraint_reports(&baseline, &new);
+        assert_eq!(
+            changes,
+            vec![
+                "block_sizes.6.per_chunk: 350 -> 400 (+50, +14.29%)",
+                "block_sizes.6.per_chunk_branches.transfer: 200 -> 250 (+50, +25.00%)",
+                "block_sizes.6.total: 3100 -> 3400 (+300, +9.68%)",
+                "operations.transfer.constraints: 700 -> 800 (+100, +14.29%)",
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_reports_added_and_removed_values() {
+        let mut baseline = report(vec![(6, block_report(50, 200))]);
+        baseline
+            .block_sizes
+            .get_mut(&6)
+            .unwrap()
+            .per_chunk_branches
+            .insert("swap".to_string(), 0);
+        let mut new = report(vec![(6, block_report(50, 200))]);
+        new.operations.insert(
+            "deposit".to_string(),
+            OperationReport {
+                chunks: 6,
+                constraints: 2100,
+            },
+        );
+
+        let changes = diff_constraint_reports(&baseline, &new);
+        assert_eq!(
+            changes,
+            vec![
+                "block_sizes.6.per_chunk_branches.swap: 0 -> removed",
+                "operations.deposit.chunks: added -> 6",
+                "operations.deposit.constraints: added -> 2100",
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_ignores_block_sizes_missing_from_one_report() {
+        let baseline = report(vec![(6, block_report(50, 200))]);
+        let new = report(vec![
+            (6, block_report(50, 200)),
+            (30, block_report(50, 200)),
+        ]);
+
+        assert!(diff_constraint_reports(&baseline, &new).is_empty());
+        assert!(diff_constraint_reports(&new, &baseline).is_empty());
+    }
+}
@@ -0,0 +1,430 @@
+//! Consistency check of the verification keys, the verifier contract and the chain config.
+//! It's meant to be run before the upgrade, so the keys that don't match the deployed verifier
+//! or the block sizes produced by the server are noticed in advance.
+
+// Built-in deps
+use std::fs::File;
+use std::path::{Path, PathBuf};
+// Workspace deps
+use zksync_config::configs::ChainConfig;
+use zksync_crypto::bellman::plonk::better_better_cs::setup::VerificationKey;
+use zksync_crypto::params::RECURSIVE_CIRCUIT_VK_TREE_DEPTH;
+use zksync_crypto::recursive_aggregation_circuit::circuit::RecursiveAggregationCircuitBn256;
+use zksync_crypto::Engine;
+use zksync_prover_utils::fs_utils::{
+    get_exodus_verification_key_path, get_recursive_verification_key_path,
+    get_verifier_contract_key_path,
+};
+use zksync_prover_utils::{PlonkVerificationKey, SETUP_MIN_POW2};
+use zksync_utils::parse_env;
+// Local deps
+use crate::verifier_contract_generator::{get_vk_tree_root_hash, render_verifier_contract};
+
+/// Setup power of two of the circuit with the given size, the smallest available setup is used for the small circuits.
+fn setup_power(n: usize) -> u32 {
+    std::cmp::max(SETUP_MIN_POW2, n.next_power_of_two().trailing_zeros())
+}
+
+/// Returns the value of the Solidity constant, e.g. `VK_TREE_ROOT`, declared in the contract source.
+fn contract_constant<'a>(source: &'a str, name: &str) -> Option<&'a str> {
+    let declaration = format!("constant {} =", name);
+    let start = source.find(&declaration)? + declaration.len();
+    let end = start + source[start..].find(';')?;
+    Some(source[start..end].trim())
+}
+
+/// Path of the verifier contract copied into the contracts deployed on the network.
+fn get_deployed_verifier_contract_path() -> PathBuf {
+    let mut contract = parse_env::<PathBuf>("ZKSYNC_HOME");
+    contract.push("contracts");
+    contract.push("contracts");
+    contract.push("KeysWithPlonkVerifier.sol");
+    contract
+}
+
+/// Checks that the block sizes used by the server and the setup powers are consistent with the supported block sizes.
+fn check_config(config: &ChainConfig, mismatches: &mut Vec<String>) {
+    let circuit = &config.circuit;
+    if circuit.supported_block_chunks_sizes.len()
+        != circuit.supported_block_chunks_sizes_setup_powers.len()
+    {
+        mismatches.push(format!(
+            "{} supported block chunks sizes are configured with {} setup powers",
+            circuit.supported_block_chunks_sizes.len(),
+            circuit.supported_block_chunks_sizes_setup_powers.len()
+        ));
+    }
+    if circuit.supported_aggregated_proof_sizes.len()
+        != circuit.supported_aggregated_proof_sizes_setup_power2.len()
+    {
+        mismatches.push(format!(
+            "{} supported aggregated proof sizes are configured with {} setup powers",
+            circuit.supported_aggregated_proof_sizes.len(),
+            circuit.supported_aggregated_proof_sizes_setup_power2.len()
+        ));
+    }
+    if circuit.supported_block_chunks_sizes.len() > 1 << RECURSIVE_CIRCUIT_VK_TREE_DEPTH {
+        mismatches.push(format!(
+            "{} supported block chunks sizes don't fit into the verification keys tree of depth {}",
+            circuit.supported_block_chunks_sizes.len(),
+            RECURSIVE_CIRCUIT_VK_TREE_DEPTH
+        ));
+    }
+    for block_chunks in &config.state_keeper.block_chunk_sizes {
+        if !circuit.supported_block_chunks_sizes.contains(block_chunks) {
+            mismatches.push(format!(
+                "Blocks of {} chunks are created by the server, but can't be proven: supported sizes are {:?}",
+                block_chunks, circuit.supported_block_chunks_sizes
+            ));
+        }
+    }
+    for proof_size in &config.state_keeper.aggregated_proof_sizes {
+        if !circuit
+            .supported_aggregated_proof_sizes
+            .contains(proof_size)
+        {
+            mismatches.push(format!(
+                "Aggregated proofs of {} blocks are created by the server, but can't be proven: supported sizes are {:?}",
+                proof_size, circuit.supported_aggregated_proof_sizes
+            ));
+        }
+    }
+}
+
+/// Loads every verification key and checks it against the configured setup power.
+/// Returns whether all the keys required to render the verifier contract are loaded.
+fn check_keys(config: &ChainConfig, mismatches: &mut Vec<String>) -> bool {
+    let mut all_loaded = true;
+
+    for (block_chunks, expected_power) in config
+        .circuit
+        .supported_block_chunks_sizes
+        .iter()
+        .zip(&config.circuit.supported_block_chunks_sizes_setup_powers)
+    {
+        match PlonkVerificationKey::read_verification_key_for_main_circuit(*block_chunks) {
+            Ok(vk) => {
+                let power = setup_power(vk.0.n);
+                if power as usize != *expected_power {
+                    mismatches.push(format!(
+                        "Verification key for blocks of {} chunks requires setup power {}, but {} is configured",
+                        block_chunks, power, expected_power
+                    ));
+                }
+                vlog::info!(
+                    "Loaded verification key for blocks of {} chunks, setup power: {}",
+                    block_chunks,
+                    power
+                );
+            }
+            Err(e) => {
+                all_loaded = false;
+                mismatches.push(format!(
+                    "Failed to load verification key for blocks of {} chunks: {}",
+                    block_chunks, e
+                ));
+            }
+        }
+    }
+
+    for (proofs, expected_power) in config
+        .circuit
+        .supported_aggregated_proof_sizes_with_setup_pow()
+    {
+        let path = get_recursive_verification_key_path(proofs);
+        let vk = File::open(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|file| {
+                VerificationKey::<Engine, RecursiveAggregationCircuitBn256<'static>>::read(file)
+                    .map_err(anyhow::Error::from)
+            });
+        match vk {
+            Ok(vk) => {
+                let power = vk.n.next_power_of_two().trailing_zeros();
+                if power != expected_power {
+                    mismatches.push(format!(
+                        "Recursive verification key for {} proofs requires setup power {}, but {} is configured",
+                        proofs, power, expected_power
+                    ));
+                }
+                vlog::info!(
+                    "Loaded recursive verification key for {} proofs, setup power: {}",
+                    proofs,
+                    power
+                );
+            }
+            Err(e) => {
+                all_loaded = false;
+                mismatches.push(format!(
+                    "Failed to load recursive verification key {}: {}",
+                    path.display(),
+                    e
+                ));
+            }
+        }
+    }
+
+    match PlonkVerificationKey::read_verification_key_for_exit_circuit() {
+        Ok(vk) if vk.0.num_inputs != 1 => mismatches.push(format!(
+            "Exit verification key has {} public inputs, but the verifier contract provides 1",
+            vk.0.num_inputs
+        )),
+        Ok(_) => vlog::info!("Loaded exit verification key"),
+        Err(e) => {
+            all_loaded = false;
+            mismatches.push(format!(
+                "Failed to load exit verification key {}: {}",
+                get_exodus_verification_key_path().display(),
+                e
+            ));
+        }
+    }
+
+    all_loaded
+}
+
+/// Compares the verifier contract with the one rendered from the current keys.
+fn check_contract(
+    contract_path: &Path,
+    expected_contract: &str,
+    vk_tree_root: &str,
+    vk_max_index: usize,
+    mismatches: &mut Vec<String>,
+) {
+    let contract = match std::fs::read_to_string(contract_path) {
+        Ok(contract) => contract,
+        Err(e) => {
+            mismatches.push(format!(
+                "Failed to read verifier contract {}: {}",
+                contract_path.display(),
+                e
+            ));
+            return;
+        }
+    };
+
+    let mut constants_match = true;
+    for (name, expected) in &[
+        ("VK_TREE_ROOT", vk_tree_root.to_string()),
+        ("VK_MAX_INDEX", vk_max_index.to_string()),
+    ] {
+        let value = contract_constant(&contract, name);
+        if value != Some(expected.as_str()) {
+            constants_match = false;
+            mismatches.push(format!(
+                "{} in {} is {}, but {} is expected for the current keys",
+                name,
+                contract_path.display(),
+                value.unwrap_or("missing"),
+                expected
+            ));
+        }
+    }
+    // Constants are reported separately, so the difference in keys is reported only if they are the same.
+    if constants_match && contract != expected_contract {
+        mismatches.push(format!(
+            "Verification keys in {} don't match the current keys",
+            contract_path.display()
+        ));
+    }
+}
+
+/// Checks the consistency of the verification keys, the verifier contracts and the chain config.
+/// Returns the list of found mismatches.
+pub(crate) fn check_keys_consistency(config: ChainConfig) -> Vec<String> {
+    let mut mismatches = Vec::new();
+    if config.circuit.supported_block_chunks_sizes.is_empty() {
+        mismatches.push("No supported block chunks sizes are configured".to_string());
+        return mismatches;
+    }
+    check_config(&config, &mut mismatches);
+    if !check_keys(&config, &mut mismatches) {
+        mismatches.push(
+            "Verifier contracts are not checked, since some of the verification keys are not loaded"
+                .to_string(),
+        );
+        return mismatches;
+    }
+
+    let vk_tree_root = get_vk_tree_root_hash(&config.circuit.supported_block_chunks_sizes);
+    vlog::info!("Verification keys tree root: {}", vk_tree_root);
+    let vk_max_index = config.circuit.supported_block_chunks_sizes.len() - 1;
+    let expected_contract = render_verifier_contract(config);
+
+    check_contract(
+        &get_verifier_contract_key_path(),
+        &expected_contract,
+        &vk_tree_root,
+        vk_max_index,
+        &mut mismatches,
+    );
+    let deployed_contract_path = get_deployed_verifier_contract_path();
+    if deployed_contract_path.exists() {
+        check_contract(
+            &deployed_contract_path,
+            &expected_contract,
+            &vk_tree_root,
+            vk_max_index,
+            &mut mismatches,
+        );
+    } else {
+        vlog::warn!(
+            "Deployed verifier contract {} is not found, skipping it",
+            deployed_contract_path.display()
+        );
+    }
+
+    mismatches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_config::configs::chain::{Circuit, Eth, StateKeeper};
+    use zksync_types::Address;
+
+    fn chain_config() -> ChainConfig {
+        ChainConfig {
+            circuit: Circuit {
+                key_dir: "keys/plonk".into(),
+                supported_block_chunks_sizes: vec![6, 30],
+                supported_block_chunks_sizes_setup_powers: vec![21, 22],
+                supported_aggregated_proof_sizes: vec![1, 5],
+                supported_aggregated_proof_sizes_setup_power2: vec![22, 24],
+                account_tree_depth: 32,
+                balance_tree_depth: 11,
+            },
+            eth: Eth {
+                network: "localhost".parse().unwrap(),
+            },
+            state_keeper: StateKeeper {
+                block_chunk_sizes: vec![6, 30],
+                miniblock_iteration_interval: 200,
+                miniblock_iterations: 10,
+                fast_block_miniblock_iterations: 5,
+                fee_account_addr: Address::zero(),
+                aggregated_proof_sizes: vec![1, 5],
+                max_aggregated_blocks_to_commit: 3,
+                max_aggregated_blocks_to_execute: 4,
+                block_commit_deadline: 300,
+                block_prove_deadline: 3_000,
+                block_execute_deadline: 4_000,
+                max_aggregated_tx_gas: 4_000_000,
+            },
+        }
+    }
+
+    fn contract_source(vk_tree_root: &str, vk_max_index: usize, keys: &str) -> String {
+        format!(
+            "contract KeysWithPlonkVerifier {{\n    uint256 constant VK_TREE_ROOT = {};\n    uint8 constant VK_MAX_INDEX = {};\n    {}\n}}\n",
+            vk_tree_root, vk_max_index, keys
+        )
+    }
+
+    /// Writes the contract into the temporary file, unique for the test.
+    fn write_contract(test_name: &str, source: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "key_generator_keys_check_{}_{}.sol",
+            test_name,
+            std::process::id()
+        ));
+        std::fs::write(&path, source).unwrap();
+        path
+    }
+
+    #[test]
+    fn contract_constants_are_parsed() {
+        let source = contract_source("0x1234", 3, "");
+        assert_eq!(contract_constant(&source, "VK_TREE_ROOT"), Some("0x1234"));
+        assert_eq!(contract_constant(&source, "VK_MAX_INDEX"), Some("3"));
+        assert_eq!(contract_constant(&source, "VK_EXIT_INDEX"), None);
+    }
+
+    #[test]
+    fn consistent_config_has_no_mismatches() {
+        let mut mismatches = Vec::new();
+        check_config(&chain_config(), &mut mismatches);
+        assert!(mismatches.is_empty(), "{:?}", mismatches);
+    }
+
+    #[test]
+    fn config_mismatches_are_reported() {
+        let mut config = chain_config();
+        config
+            .circuit
+            .supported_block_chunks_sizes_setup_powers
+            .pop();
+        let mut mismatches = Vec::new();
+        check_config(&config, &mut mismatches);
+        assert_eq!(
+            mismatches,
+            vec!["2 supported block chunks sizes are configured with 1 setup powers"]
+        );
+
+        let mut config = chain_config();
+        config.state_keeper.block_chunk_sizes.push(74);
+        let mut mismatches = Vec::new();
+        check_config(&config, &mut mismatches);
+        assert_eq!(
+            mismatches,
+            vec!["Blocks of 74 chunks are created by the server, but can't be proven: supported sizes are [6, 30]"]
+        );
+
+        let mut config = chain_config();
+        config.state_keeper.aggregated_proof_sizes.push(10);
+        let mut mismatches = Vec::new();
+        check_config(&config, &mut mismatches);
+        assert_eq!(
+            mismatches,
+            vec!["Aggregated proofs of 10 blocks are created by the server, but can't be proven: supported sizes are [1, 5]"]
+        );
+    }
+
+    #[test]
+    fn matching_contract_has_no_mismatches() {
+        let expected = contract_source("0x1234", 1, "keys");
+        let path = write_contract("matching", &expected);
+
+        let mut mismatches = Vec::new();
+        check_contract(&path, &expected, "0x1234", 1, &mut mismatches);
+        std::fs::remove_file(&path).unwrap();
+        assert!(mismatches.is_empty(), "{:?}", mismatches);
+    }
+
+    #[test]
+    fn contract_constant_mismatch_is_reported_instead_of_keys_diff() {
+        let expected = contract_source("0x1234", 1, "new keys");
+        let path = write_contract(
+            "constant_mismatch",
+            &contract_source("0xabcd", 1, "old keys"),
+        );
+
+        let mut mismatches = Vec::new();
+        check_contract(&path, &expected, "0x1234", 1, &mut mismatches);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            mismatches,
+            vec![format!(
+                "VK_TREE_ROOT in {} is 0xabcd, but 0x1234 is expected for the current keys",
+                path.display()
+            )]
+        );
+    }
+
+    #[test]
+    fn keys_diff_is_reported() {
+        let expected = contract_source("0x1234", 1, "new keys");
+        let path = write_contract("keys_diff", &contract_source("0x1234", 1, "old keys"));
+
+        let mut mismatches = Vec::new();
+        check_contract(&path, &expected, "0x1234", 1, &mut mismatches);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            mismatches,
+            vec![format!(
+                "Verification keys in {} don't match the current keys",
+                path.display()
+            )]
+        );
+    }
+}
@@ -10,13 +10,22 @@
 //! and `SUPPORTED_BLOCK_CHUNKS_SIZES_SETUP_POWERS` that are read from env in config files.
 //! Before generating parameters universal setup keys should be downloaded using `zksync plonk-setup` command.
 
+mod constraint_report;
+mod keys_check;
 mod recursive_keys;
 mod sample_proofs;
 mod verifier_contract_generator;
 mod zksync_key;
 
+use std::path::PathBuf;
+
 use structopt::StructOpt;
 
+use crate::constraint_report::{
+    diff_constraint_reports, get_constraint_report_baseline_path, load_constraint_report,
+    make_constraint_report, save_constraint_report,
+};
+use crate::keys_check::check_keys_consistency;
 use crate::recursive_keys::{
     count_gates_recursive_verification_keys, make_recursive_verification_keys,
 };
@@ -36,6 +45,23 @@ enum Command {
     Contract,
     /// Counts available sizes (chunks and aggregated proof size) for available setups
     CircuitSize,
+    /// Check that verification keys, verifier contract and supported block sizes are consistent
+    Check,
+    /// Count constraints of the main circuit per block size and operation, and compare them with the baseline
+    ConstraintReport {
+        /// Block sizes to synthesize the circuit for, supported block sizes by default
+        #[structopt(long, use_delimiter = true)]
+        block_sizes: Vec<usize>,
+        /// Path to save the JSON report to, report is printed if not set
+        #[structopt(long)]
+        output: Option<PathBuf>,
+        /// Path of the baseline report, `$ZKSYNC_HOME/etc/circuit/constraint_report.json` by default
+        #[structopt(long)]
+        baseline: Option<PathBuf>,
+        /// Overwrite the baseline with the current report instead of comparing them
+        #[structopt(long)]
+        update: bool,
+    },
 }
 
 #[derive(StructOpt)]
@@ -65,5 +91,77 @@ fn main() {
             calculate_and_print_max_zksync_main_circuit_size();
             count_gates_recursive_verification_keys();
         }
+        Command::Check => {
+            let mismatches = check_keys_consistency(config);
+            if !mismatches.is_empty() {
+                for mismatch in &mismatches {
+                    vlog::error!("{}", mismatch);
+                }
+                vlog::error!("Found {} mismatches", mismatches.len());
+                std::process::exit(1);
+            }
+            vlog::info!("Verification keys, verifier contract and chain config are consistent");
+        }
+        Command::ConstraintReport {
+            block_sizes,
+            output,
+            baseline,
+            update,
+        } => {
+            let block_sizes = if block_sizes.is_empty() {
+                config.circuit.supported_block_chunks_sizes
+            } else {
+                block_sizes
+            };
+            let report =
+                make_constraint_report(&block_sizes).expect("Failed to make constraint report");
+            let baseline_path = baseline.unwrap_or_else(get_constraint_report_baseline_path);
+            if update {
+                save_constraint_report(&report, &baseline_path)
+                    .expect("Failed to save constraint report");
+                vlog::info!("Baseline updated: {}", baseline_path.display());
+                return;
+            }
+            let is_saved = output.is_some();
+            match output {
+                Some(output) => {
+                    save_constraint_report(&report, &output)
+                        .expect("Failed to save constraint report");
+                    vlog::info!("Constraint report saved into: {}", output.display());
+                }
+                None => println!(
+                    "{}",
+                    serde_json::to_string_pretty(&report).expect("Failed to serialize report")
+                ),
+            }
+
+            if !baseline_path.exists() {
+                // Report saved into a file is not necessarily meant to be compared with anything.
+                if is_saved {
+                    vlog::warn!("Baseline {} doesn't exist", baseline_path.display());
+                    return;
+                }
+                vlog::error!(
+                    "Baseline {} doesn't exist, it can be created with `--update`",
+                    baseline_path.display()
+                );
+                std::process::exit(1);
+            }
+            let baseline =
+                load_constraint_report(&baseline_path).expect("Failed to load baseline report");
+            let changes = diff_constraint_reports(&baseline, &report);
+            if !changes.is_empty() {
+                for change in &changes {
+                    vlog::error!("{}", change);
+                }
+                vlog::error!(
+                    "Found {} changes compared to {}, run with `--update` if they are expected",
+                    changes.len(),
+                    baseline_path.display()
+                );
+                std::process::exit(1);
+            }
+            vlog::info!("Circuit constraints match the baseline");
+        }
     }
 }
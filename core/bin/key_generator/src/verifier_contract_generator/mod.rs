@@ -6,9 +6,8 @@ use std::path::PathBuf;
 use handlebars::to_json;
 use handlebars::Handlebars;
 
-use crate::verifier_contract_generator::render_vk::{
-    get_vk_tree_root_hash, rendered_key, rendered_key_single_proof,
-};
+pub(crate) use crate::verifier_contract_generator::render_vk::get_vk_tree_root_hash;
+use crate::verifier_contract_generator::render_vk::{rendered_key, rendered_key_single_proof};
 use zksync_config::configs::ChainConfig;
 use zksync_prover_utils::fs_utils::{
     get_exodus_verification_key_path, get_recursive_verification_key_path,
@@ -19,8 +18,15 @@ use zksync_utils::parse_env;
 mod render_vk;
 
 /// Creates verifier contract compatible with our main contract using generated verification keys.
-/// Contract is created from the template using `handlebars` crate.
 pub(crate) fn create_verifier_contract(config: ChainConfig) {
+    let res = render_verifier_contract(config);
+    std::fs::write(get_verifier_contract_key_path(), res).expect("failed to wrtie Verifier.sol");
+    vlog::info!("Verifier contract successfully generated");
+}
+
+/// Renders verifier contract using generated verification keys.
+/// Contract is created from the template using `handlebars` crate.
+pub(crate) fn render_verifier_contract(config: ChainConfig) -> String {
     let template = &std::fs::read_to_string(get_verifier_template_file())
         .expect("failed to read Verifier template file");
     let mut template_params = HashMap::new();
@@ -59,11 +65,9 @@ pub(crate) fn create_verifier_contract(config: ChainConfig) {
     let exodus_key = rendered_key_single_proof("getVkExit", exodus_key_path);
     template_params.insert("single_keys".to_string(), to_json(vec![exodus_key]));
 
-    let res = Handlebars::new()
+    Handlebars::new()
         .render_template(template, &template_params)
-        .expect("failed to render Verifiers.sol template");
-    std::fs::write(get_verifier_contract_key_path(), res).expect("failed to wrtie Verifier.sol");
-    vlog::info!("Verifier contract successfully generated");
+        .expect("failed to render Verifiers.sol template")
 }
 
 fn get_verifier_template_file() -> PathBuf {
@@ -3,16 +3,14 @@ use std::fs::{remove_file, File};
 use std::path::Path;
 // Workspace deps
 use zksync_circuit::account::AccountWitness;
-use zksync_circuit::circuit::ZkSyncCircuit;
 use zksync_circuit::exit_circuit::ZkSyncExitCircuit;
-use zksync_circuit::operation::{
-    Operation, OperationArguments, OperationBranch, OperationBranchWitness, SignatureData,
-};
+use zksync_circuit::operation::{OperationBranch, OperationBranchWitness};
 use zksync_config::configs::ChainConfig;
 use zksync_crypto::bellman::plonk::{make_verification_key, setup, transpile_with_gates_count};
 use zksync_crypto::bellman::Circuit;
 use zksync_crypto::params;
 use zksync_crypto::Engine;
+use zksync_prover_utils::empty_circuit::empty_block_circuit;
 use zksync_prover_utils::fs_utils::{
     get_block_verification_key_path, get_exodus_verification_key_path,
     get_universal_setup_monomial_form,
@@ -41,7 +39,8 @@ pub(crate) fn make_plonk_blocks_verify_keys(config: ChainConfig) {
             block_chunks,
             key_path.display()
         );
-        let result_setup_power = generate_verification_key(zksync_circuit(block_chunks), key_path);
+        let result_setup_power =
+            generate_verification_key(empty_block_circuit(block_chunks), key_path);
         assert_eq!(
             result_setup_power, setup_power as u32,
             "setup power actually needed by circuit of size {} is not equal to that from SUPPORTED_BLOCK_CHUNKS_SIZES env variable", block_chunks
@@ -78,102 +77,6 @@ fn exit_circuit() -> impl Circuit<Engine> + Clone {
 }
 
 /// Creates instance of the main zkSync circuit with the given number chunks in block.
-fn zksync_circuit(block_chunks: usize) -> impl Circuit<Engine> + Clone {
-    let empty_operation = Operation {
-        new_root: None,
-        tx_type: None,
-        chunk: None,
-        pubdata_chunk: None,
-        signer_pub_key_packed: vec![None; params::FR_BIT_WIDTH_PADDED],
-        first_sig_msg: None,
-        second_sig_msg: None,
-        third_sig_msg: None,
-        signature_data: SignatureData::init_empty(),
-        args: OperationArguments {
-            a: None,
-            b: None,
-            amount_packed: None,
-            second_amount_packed: None,
-            special_amounts: vec![None; 2],
-            special_prices: vec![None; 4],
-            special_nonces: vec![None; 3],
-            special_tokens: vec![None; 3],
-            special_accounts: vec![None; 5],
-            special_eth_addresses: vec![None; 2],
-            full_amount: None,
-            fee: None,
-            pub_nonce: None,
-            new_pub_key_hash: None,
-            eth_address: None,
-            valid_from: None,
-            valid_until: None,
-            second_valid_from: None,
-            second_valid_until: None,
-            special_content_hash: vec![None; params::CONTENT_HASH_WIDTH],
-            special_serial_id: None,
-        },
-        lhs: OperationBranch {
-            address: None,
-            token: None,
-            witness: OperationBranchWitness {
-                account_witness: AccountWitness {
-                    nonce: None,
-                    pub_key_hash: None,
-                    address: None,
-                },
-                account_path: vec![None; params::account_tree_depth()],
-                balance_value: None,
-                balance_subtree_path: vec![None; params::balance_tree_depth()],
-            },
-        },
-        rhs: OperationBranch {
-            address: None,
-            token: None,
-            witness: OperationBranchWitness {
-                account_witness: AccountWitness {
-                    nonce: None,
-                    pub_key_hash: None,
-                    address: None,
-                },
-                account_path: vec![None; params::account_tree_depth()],
-                balance_value: None,
-                balance_subtree_path: vec![None; params::balance_tree_depth()],
-            },
-        },
-    };
-
-    ZkSyncCircuit::<'_, Engine> {
-        rescue_params: &params::RESCUE_PARAMS,
-        jubjub_params: &params::JUBJUB_PARAMS,
-        old_root: None,
-        initial_used_subtree_root: None,
-        validator_address: None,
-        block_number: None,
-        block_timestamp: None,
-        pub_data_commitment: None,
-        validator_balances: vec![None; params::number_of_processable_tokens()],
-        validator_audit_path: vec![None; params::account_tree_depth()],
-        validator_non_processable_tokens_audit_before_fees: vec![
-            None;
-            params::balance_tree_depth()
-                - params::PROCESSABLE_TOKENS_DEPTH
-                    as usize
-        ],
-        validator_non_processable_tokens_audit_after_fees: vec![
-            None;
-            params::balance_tree_depth()
-                - params::PROCESSABLE_TOKENS_DEPTH
-                    as usize
-        ],
-        operations: vec![empty_operation; block_chunks],
-        validator_account: AccountWitness {
-            nonce: None,
-            pub_key_hash: None,
-            address: None,
-        },
-    }
-}
-
 /// Generates PLONK verification key for given circuit and saves key at the given path.
 /// Returns used setup power of two. (e.g. 22)
 fn generate_verification_key<C: Circuit<Engine> + Clone, P: AsRef<Path>>(
@@ -227,7 +130,7 @@ fn generate_verification_key<C: Circuit<Engine> + Clone, P: AsRef<Path>>(
 /// Transpile zkSync circuit to get gate count and universal setup power of two
 fn gates_count_zksync_main_circuit(chunks: usize) -> (usize, u32) {
     let (gates_count, _) =
-        transpile_with_gates_count(zksync_circuit(chunks)).expect("failed to transpile");
+        transpile_with_gates_count(empty_block_circuit(chunks)).expect("failed to transpile");
     let size_log2 = gates_count.next_power_of_two().trailing_zeros();
 
     (gates_count, size_log2)
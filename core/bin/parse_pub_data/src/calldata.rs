@@ -0,0 +1,236 @@
+//! Decoding of the `commitBlocks` calldata of the zkSync contract.
+
+// External deps
+use anyhow::{ensure, format_err};
+use ethabi::{ParamType, Token};
+// Workspace deps
+use zksync_types::{AccountId, BlockNumber, H256};
+// Local deps
+use crate::decode::BlockContext;
+
+/// Onchain operation metadata passed to the contract along with the block public data.
+#[derive(Debug, Clone)]
+pub struct OnchainOperationData {
+    pub public_data_offset: u32,
+    pub eth_witness: Vec<u8>,
+}
+
+/// Block committed by the `commitBlocks` call.
+#[derive(Debug, Clone)]
+pub struct CommittedBlock {
+    pub context: BlockContext,
+    pub public_data: Vec<u8>,
+    pub onchain_operations: Vec<OnchainOperationData>,
+}
+
+fn stored_block_info_param() -> ParamType {
+    ParamType::Tuple(vec![
+        ParamType::Uint(32),       // uint32 blockNumber
+        ParamType::Uint(64),       // uint64 priorityOperations
+        ParamType::FixedBytes(32), // bytes32 pendingOnchainOperationsHash
+        ParamType::Uint(256),      // uint256 timestamp
+        ParamType::FixedBytes(32), // bytes32 stateHash
+        ParamType::FixedBytes(32), // bytes32 commitment
+    ])
+}
+
+fn commit_block_info_param() -> ParamType {
+    ParamType::Tuple(vec![
+        ParamType::FixedBytes(32), // bytes32 newStateHash
+        ParamType::Bytes,          // bytes publicData
+        ParamType::Uint(256),      // uint256 timestamp
+        ParamType::Array(Box::new(ParamType::Tuple(vec![
+            ParamType::Bytes,    // bytes ethWitness
+            ParamType::Uint(32), // uint32 publicDataOffset
+        ]))),
+        ParamType::Uint(32), // uint32 blockNumber
+        ParamType::Uint(32), // uint32 feeAccount
+    ])
+}
+
+fn into_tuple(token: Token, name: &str) -> anyhow::Result<Vec<Token>> {
+    token
+        .into_tuple()
+        .ok_or_else(|| format_err!("`{}` is not a tuple", name))
+}
+
+fn into_h256(token: Token, name: &str) -> anyhow::Result<H256> {
+    let bytes = token
+        .into_fixed_bytes()
+        .ok_or_else(|| format_err!("`{}` is not bytes32", name))?;
+    Ok(H256::from_slice(&bytes))
+}
+
+fn into_u64(token: Token, name: &str) -> anyhow::Result<u64> {
+    let value = token
+        .into_uint()
+        .ok_or_else(|| format_err!("`{}` is not uint", name))?;
+    ensure!(value.bits() <= 64, "`{}` doesn't fit into u64", name);
+    Ok(value.as_u64())
+}
+
+fn into_u32(token: Token, name: &str) -> anyhow::Result<u32> {
+    let value = into_u64(token, name)?;
+    ensure!(
+        value <= u64::from(u32::MAX),
+        "`{}` doesn't fit into u32",
+        name
+    );
+    Ok(value as u32)
+}
+
+fn decode_onchain_operation(token: Token) -> anyhow::Result<OnchainOperationData> {
+    let mut fields = into_tuple(token, "OnchainOperationData")?.into_iter();
+    let mut next = || fields.next().ok_or_else(|| format_err!("missing field"));
+    let eth_witness = next()?
+        .into_bytes()
+        .ok_or_else(|| format_err!("`ethWitness` is not bytes"))?;
+    let public_data_offset = into_u32(next()?, "publicDataOffset")?;
+    Ok(OnchainOperationData {
+        public_data_offset,
+        eth_witness,
+    })
+}
+
+fn decode_commit_block_info(token: Token, old_state_hash: H256) -> anyhow::Result<CommittedBlock> {
+    let mut fields = into_tuple(token, "CommitBlockInfo")?.into_iter();
+    let mut next = || fields.next().ok_or_else(|| format_err!("missing field"));
+    let new_state_hash = into_h256(next()?, "newStateHash")?;
+    let public_data = next()?
+        .into_bytes()
+        .ok_or_else(|| format_err!("`publicData` is not bytes"))?;
+    let timestamp = into_u64(next()?, "timestamp")?;
+    let onchain_operations = next()?
+        .into_array()
+        .ok_or_else(|| format_err!("`onchainOperations` is not an array"))?
+        .into_iter()
+        .map(decode_onchain_operation)
+        .collect::<anyhow::Result<_>>()?;
+    let block_number = BlockNumber(into_u32(next()?, "blockNumber")?);
+    let fee_account = AccountId(into_u32(next()?, "feeAccount")?);
+
+    Ok(CommittedBlock {
+        context: BlockContext {
+            block_number,
+            fee_account,
+            timestamp,
+            old_state_hash: Some(old_state_hash),
+            new_state_hash,
+        },
+        public_data,
+        onchain_operations,
+    })
+}
+
+/// Decodes the calldata of the `commitBlocks` call, including the function selector.
+/// State hash of the previous block is taken from the last committed block passed to the call.
+pub fn decode_commit_calldata(calldata: &[u8]) -> anyhow::Result<Vec<CommittedBlock>> {
+    let params = [
+        stored_block_info_param(),
+        ParamType::Array(Box::new(commit_block_info_param())),
+    ];
+    let selector = ethabi::short_signature("commitBlocks", &params);
+    ensure!(
+        calldata.len() >= 4,
+        "calldata is shorter than the function selector"
+    );
+    ensure!(
+        calldata[..4] == selector,
+        "calldata selector 0x{} doesn't match `commitBlocks` selector 0x{}",
+        hex::encode(&calldata[..4]),
+        hex::encode(selector)
+    );
+
+    let mut tokens = ethabi::decode(&params, &calldata[4..])
+        .map_err(|err| format_err!("can't decode `commitBlocks` arguments: {}", err))?
+        .into_iter();
+    let (last_committed_block, new_blocks) = match (tokens.next(), tokens.next()) {
+        (Some(last_committed_block), Some(new_blocks)) => (last_committed_block, new_blocks),
+        _ => anyhow::bail!("`commitBlocks` arguments are missing"),
+    };
+
+    // `stateHash` is the fifth field of `StoredBlockInfo`.
+    let mut old_state_hash = into_tuple(last_committed_block, "StoredBlockInfo")?
+        .into_iter()
+        .nth(4)
+        .map(|token| into_h256(token, "stateHash"))
+        .ok_or_else(|| format_err!("`StoredBlockInfo` has no `stateHash`"))??;
+
+    let mut blocks = Vec::new();
+    for block in new_blocks
+        .into_array()
+        .ok_or_else(|| format_err!("`newBlocksData` is not an array"))?
+    {
+        let block = decode_commit_block_info(block, old_state_hash)?;
+        old_state_hash = block.context.new_state_hash;
+        blocks.push(block);
+    }
+    Ok(blocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `commitBlocks` call committing block 25 with a deposit before the v6 upgrade.
+    const COMMIT_CALLDATA: &str = "45269298000000000000000000000000000000000000000000\
+        00000000000000000000180000000000000000000000000000\
+        000000000000000000000000000000000001c5d2460186f723\
+        3c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470\
+        00000000000000000000000000000000000000000000000000\
+        00000060180bd21ebc71244dfd0ec72156cabe55ae2e5dd35e\
+        1b0a1cffe0b52a158f27c1dd34314cebb54dbafb6885b8628c\
+        a09d8f4992f4efd7f04e2dda0121896e88a5158f8100000000\
+        00000000000000000000000000000000000000000000000000\
+        0000e000000000000000000000000000000000000000000000\
+        00000000000000000001000000000000000000000000000000\
+        000000000000000000000000000000002026bb57dafd75ff97\
+        f3c664c511c5e334f0266c6bd0e29e9a69f5c36152fef48100\
+        00000000000000000000000000000000000000000000000000\
+        0000000000c000000000000000000000000000000000000000\
+        00000000000000000060183511000000000000000000000000\
+        00000000000000000000000000000000000001400000000000\
+        00000000000000000000000000000000000000000000000000\
+        00190000000000000000000000000000000000000000000000\
+        00000000000000000000000000000000000000000000000000\
+        0000000000000000000000000000005a010000000e00000000\
+        00000000006c6b935b8bbd4000001e65c448e0486449a0b446\
+        bc9a340b933237f6e000000000000000000000000000000000\
+        00000000000000000000000000000000000000000000000000\
+        00000000000000000000000000000000000000000000000000\
+        00000000000000000000000000000000000001000000000000\
+        00000000000000000000000000000000000000000000000000\
+        20000000000000000000000000000000000000000000000000\
+        00000000000000400000000000000000000000000000000000\
+        00000000000000000000000000000000000000000000000000\
+        00000000000000000000000000000000000000000000";
+
+    #[test]
+    fn decode_commit_blocks_calldata() {
+        let calldata = hex::decode(COMMIT_CALLDATA).unwrap();
+        let blocks = decode_commit_calldata(&calldata).unwrap();
+        assert_eq!(blocks.len(), 1);
+
+        let block = &blocks[0];
+        assert_eq!(block.context.block_number, BlockNumber(25));
+        assert_eq!(block.context.fee_account, AccountId(0));
+        assert_eq!(block.public_data.len(), 90);
+        assert_eq!(block.onchain_operations.len(), 1);
+        assert_eq!(block.onchain_operations[0].public_data_offset, 0);
+
+        let report =
+            crate::decode::decode_block(&block.public_data, Some(block.context.clone()), true);
+        assert_eq!(report.error, None);
+        assert_eq!(report.ops.len(), 1);
+        assert_eq!(report.ops[0].op_type, "Deposit");
+        assert!(report.commitment.is_some());
+    }
+
+    #[test]
+    fn decode_malformed_calldata() {
+        let calldata = hex::decode(COMMIT_CALLDATA).unwrap();
+        assert!(decode_commit_calldata(&calldata[..100]).is_err());
+        assert!(decode_commit_calldata(&calldata[4..]).is_err());
+        assert!(decode_commit_calldata(&[]).is_err());
+    }
+}
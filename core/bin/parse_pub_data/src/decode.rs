@@ -0,0 +1,386 @@
+//! Decoding of the block public data into the zkSync operations.
+
+// External deps
+use parity_crypto::Keccak256;
+use serde::Serialize;
+// Workspace deps
+use zksync_crypto::params::{CHUNK_BYTES, LEGACY_CHUNK_BYTES};
+use zksync_types::block::Block;
+use zksync_types::{AccountId, Address, BlockNumber, TokenId, ZkSyncOp, H256};
+
+/// Block data committed along with the public data, it's required to calculate the block commitment.
+#[derive(Debug, Clone, Serialize)]
+pub struct BlockContext {
+    pub block_number: BlockNumber,
+    pub fee_account: AccountId,
+    pub timestamp: u64,
+    /// State hash of the previous block, the commitment can't be calculated without it.
+    pub old_state_hash: Option<H256>,
+    pub new_state_hash: H256,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountInfo {
+    pub id: AccountId,
+    pub address: Option<Address>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenInfo {
+    pub id: TokenId,
+    pub symbol: Option<String>,
+}
+
+/// Operation decoded from the public data along with its position in the block.
+#[derive(Debug, Serialize)]
+pub struct DecodedOp {
+    /// Offset of the operation in the public data, in bytes.
+    pub public_data_offset: usize,
+    pub first_chunk: usize,
+    pub chunks: usize,
+    pub op_type: &'static str,
+    pub is_onchain: bool,
+    pub is_priority: bool,
+    /// Accounts and tokens referenced by the operation, addresses and symbols are set only if resolved.
+    pub accounts: Vec<AccountInfo>,
+    pub tokens: Vec<TokenInfo>,
+    pub op: ZkSyncOp,
+}
+
+/// Onchain operation of the block as the zkSync contract sees it.
+#[derive(Debug, Serialize)]
+pub struct OnchainOperation {
+    pub public_data_offset: usize,
+    pub op_type: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BlockReport {
+    pub context: Option<BlockContext>,
+    pub public_data_length: usize,
+    pub chunks: usize,
+    /// Chunks filled with noop operations, they aren't listed in `ops`.
+    pub noop_chunks: usize,
+    pub priority_ops: usize,
+    pub ops: Vec<DecodedOp>,
+    pub onchain_operations: Vec<OnchainOperation>,
+    /// Hash of the public data of the operations processed by the contract (withdrawals and exits).
+    pub processable_ops_hash: H256,
+    #[serde(serialize_with = "serialize_hex")]
+    pub onchain_op_commitment: Vec<u8>,
+    /// Commitment calculated from the public data and the block context.
+    pub commitment: Option<H256>,
+    /// Commitment stored in the database for the block.
+    pub stored_commitment: Option<H256>,
+    /// Inconsistencies of the data that don't prevent decoding.
+    pub warnings: Vec<String>,
+    /// Decoding stops at the first malformed operation, ops decoded before it are still reported.
+    pub error: Option<String>,
+}
+
+fn serialize_hex<S: serde::Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("0x{}", hex::encode(bytes)))
+}
+
+/// Returns the name of the operation type.
+pub fn op_type_name(op: &ZkSyncOp) -> &'static str {
+    match op {
+        ZkSyncOp::Deposit(_) => "Deposit",
+        ZkSyncOp::Transfer(_) => "Transfer",
+        ZkSyncOp::TransferToNew(_) => "TransferToNew",
+        ZkSyncOp::Withdraw(_) => "Withdraw",
+        ZkSyncOp::WithdrawNFT(_) => "WithdrawNFT",
+        ZkSyncOp::Close(_) => "Close",
+        ZkSyncOp::FullExit(_) => "FullExit",
+        ZkSyncOp::ChangePubKeyOffchain(_) => "ChangePubKey",
+        ZkSyncOp::ForcedExit(_) => "ForcedExit",
+        ZkSyncOp::MintNFTOp(_) => "MintNFT",
+        ZkSyncOp::Noop(_) => "Noop",
+        ZkSyncOp::Swap(_) => "Swap",
+    }
+}
+
+/// Returns tokens used by the operation, including the fee token.
+fn op_tokens(op: &ZkSyncOp) -> Vec<TokenId> {
+    let mut tokens: Vec<TokenId> = op
+        .get_amount_info()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(token, _)| token)
+        .collect();
+    if let Ok(tx) = op.try_get_tx() {
+        tokens.push(tx.token_id());
+    }
+    if let Ok(priority_op) = op.try_get_priority_op() {
+        tokens.push(priority_op.token_id());
+    }
+    tokens.sort_unstable();
+    tokens.dedup();
+    tokens
+}
+
+/// Decodes the block public data, `legacy` is set for the blocks committed before the v6 upgrade.
+pub fn decode_block(
+    public_data: &[u8],
+    context: Option<BlockContext>,
+    legacy: bool,
+) -> BlockReport {
+    let chunk_bytes = if legacy {
+        LEGACY_CHUNK_BYTES
+    } else {
+        CHUNK_BYTES
+    };
+    let chunks = public_data.len() / chunk_bytes;
+    let mut report = BlockReport {
+        context,
+        public_data_length: public_data.len(),
+        chunks,
+        noop_chunks: 0,
+        priority_ops: 0,
+        ops: Vec::new(),
+        onchain_operations: Vec::new(),
+        processable_ops_hash: H256::from(Vec::new().keccak256()),
+        onchain_op_commitment: vec![0u8; chunks],
+        commitment: None,
+        stored_commitment: None,
+        warnings: Vec::new(),
+        error: None,
+    };
+    if public_data.len() % chunk_bytes != 0 {
+        report.warnings.push(format!(
+            "public data length {} is not a multiple of the chunk size {}",
+            public_data.len(),
+            chunk_bytes
+        ));
+    }
+
+    let mut offset = 0;
+    while offset < public_data.len() {
+        match decode_op(&public_data[offset..], legacy) {
+            Ok((op, op_length)) => {
+                let op_chunks = op_length / chunk_bytes;
+                if op.is_processable_onchain_operation() {
+                    report.processable_ops_hash = H256::from(
+                        [
+                            report.processable_ops_hash.as_bytes(),
+                            &public_data[offset..offset + op_length],
+                        ]
+                        .concat()
+                        .keccak256(),
+                    );
+                }
+                if op.is_priority_op() {
+                    report.priority_ops += 1;
+                }
+                if op.is_onchain_operation() {
+                    report.onchain_op_commitment[offset / chunk_bytes] = 0x01;
+                    report.onchain_operations.push(OnchainOperation {
+                        public_data_offset: offset,
+                        op_type: op_type_name(&op),
+                    });
+                }
+
+                if let ZkSyncOp::Noop(_) = op {
+                    report.noop_chunks += op_chunks;
+                } else {
+                    report.ops.push(DecodedOp {
+                        public_data_offset: offset,
+                        first_chunk: offset / chunk_bytes,
+                        chunks: op_chunks,
+                        op_type: op_type_name(&op),
+                        is_onchain: op.is_onchain_operation(),
+                        is_priority: op.is_priority_op(),
+                        accounts: op
+                            .get_updated_account_ids()
+                            .into_iter()
+                            .map(|id| AccountInfo { id, address: None })
+                            .collect(),
+                        tokens: op_tokens(&op)
+                            .into_iter()
+                            .map(|id| TokenInfo { id, symbol: None })
+                            .collect(),
+                        op,
+                    });
+                }
+                offset += op_length;
+            }
+            Err(err) => {
+                report.error = Some(format!(
+                    "offset {} (chunk {}): {}",
+                    offset,
+                    offset / chunk_bytes,
+                    err
+                ));
+                break;
+            }
+        }
+    }
+
+    if let Some(context) = &report.context {
+        if report.error.is_none() {
+            report.commitment = context.old_state_hash.map(|old_state_hash| {
+                Block::get_commitment(
+                    context.block_number,
+                    context.fee_account,
+                    old_state_hash,
+                    context.new_state_hash,
+                    context.timestamp,
+                    &report.onchain_op_commitment,
+                    public_data,
+                )
+            });
+        }
+    }
+    report
+}
+
+/// Decodes the operation at the start of the data, returns it with the length of its public data.
+fn decode_op(data: &[u8], legacy: bool) -> anyhow::Result<(ZkSyncOp, usize)> {
+    let op_type = data[0];
+    let op_length = if legacy {
+        ZkSyncOp::legacy_public_data_length(op_type)
+    } else {
+        ZkSyncOp::public_data_length(op_type)
+    }
+    .map_err(|_| anyhow::format_err!("unknown operation type {}", op_type))?;
+    anyhow::ensure!(
+        data.len() >= op_length,
+        "operation type {} requires {} bytes, but only {} are left",
+        op_type,
+        op_length,
+        data.len()
+    );
+    let op = if legacy {
+        ZkSyncOp::from_legacy_public_data(&data[..op_length])
+    } else {
+        ZkSyncOp::from_public_data(&data[..op_length])
+    };
+    let op = op.map_err(|err| {
+        anyhow::format_err!("failed to decode operation type {}: {}", op_type, err)
+    })?;
+    Ok((op, op_length))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num::BigUint;
+    use zksync_types::operations::NoopOp;
+    use zksync_types::{Deposit, DepositOp, Transfer, TransferOp};
+
+    fn deposit_op() -> ZkSyncOp {
+        ZkSyncOp::Deposit(Box::new(DepositOp {
+            priority_op: Deposit {
+                from: Address::repeat_byte(0x11),
+                token: TokenId(2),
+                amount: BigUint::from(100u32),
+                to: Address::repeat_byte(0x22),
+            },
+            account_id: AccountId(7),
+        }))
+    }
+
+    fn transfer_op() -> ZkSyncOp {
+        let tx = Transfer::new(
+            AccountId(7),
+            Address::repeat_byte(0x22),
+            Address::repeat_byte(0x33),
+            TokenId(2),
+            BigUint::from(10u32),
+            BigUint::from(1u32),
+            Default::default(),
+            Default::default(),
+            None,
+        );
+        ZkSyncOp::Transfer(Box::new(TransferOp {
+            tx,
+            from: AccountId(7),
+            to: AccountId(8),
+        }))
+    }
+
+    fn block_public_data(ops: &[ZkSyncOp], chunks: usize) -> Vec<u8> {
+        let mut public_data: Vec<u8> = ops.iter().flat_map(ZkSyncOp::public_data).collect();
+        public_data.resize(chunks * CHUNK_BYTES, 0x00);
+        public_data
+    }
+
+    /// Checks that the report contains the operations with their offsets, onchain operations and noops.
+    #[test]
+    fn decode_block_ops() {
+        let public_data = block_public_data(&[transfer_op(), deposit_op()], 10);
+        let report = decode_block(&public_data, None, false);
+
+        assert_eq!(report.error, None);
+        assert_eq!(report.chunks, 10);
+        assert_eq!(
+            report.noop_chunks,
+            10 - TransferOp::CHUNKS - DepositOp::CHUNKS
+        );
+        assert_eq!(report.priority_ops, 1);
+        assert_eq!(report.ops.len(), 2);
+        assert_eq!(report.ops[0].op_type, "Transfer");
+        assert_eq!(report.ops[1].op_type, "Deposit");
+        assert_eq!(report.ops[1].first_chunk, TransferOp::CHUNKS);
+        assert_eq!(
+            report.ops[1].public_data_offset,
+            TransferOp::CHUNKS * CHUNK_BYTES
+        );
+        assert_eq!(report.ops[1].accounts[0].id, AccountId(7));
+        assert_eq!(report.ops[1].tokens[0].id, TokenId(2));
+
+        assert_eq!(report.onchain_operations.len(), 1);
+        assert_eq!(
+            report.onchain_operations[0].public_data_offset,
+            TransferOp::CHUNKS * CHUNK_BYTES
+        );
+        let mut expected_commitment = vec![0u8; 10];
+        expected_commitment[TransferOp::CHUNKS] = 0x01;
+        assert_eq!(report.onchain_op_commitment, expected_commitment);
+        assert_eq!(report.commitment, None);
+    }
+
+    /// Checks that the commitment matches the one calculated for the block.
+    #[test]
+    fn decode_block_commitment() {
+        let ops = vec![deposit_op(), ZkSyncOp::Noop(NoopOp {})];
+        let public_data = block_public_data(&ops, 10);
+        let context = BlockContext {
+            block_number: BlockNumber(5),
+            fee_account: AccountId(1),
+            timestamp: 1_000,
+            old_state_hash: Some(H256::repeat_byte(0x01)),
+            new_state_hash: H256::repeat_byte(0x02),
+        };
+        let report = decode_block(&public_data, Some(context), false);
+
+        let mut onchain_op_commitment = vec![0u8; 10];
+        onchain_op_commitment[0] = 0x01;
+        let expected = Block::get_commitment(
+            BlockNumber(5),
+            AccountId(1),
+            H256::repeat_byte(0x01),
+            H256::repeat_byte(0x02),
+            1_000,
+            &onchain_op_commitment,
+            &public_data,
+        );
+        assert_eq!(report.commitment, Some(expected));
+    }
+
+    /// Checks that malformed data is reported as an error and the ops before it are kept.
+    #[test]
+    fn decode_malformed_block() {
+        let mut public_data = transfer_op().public_data();
+        public_data.push(0xff);
+        public_data.resize(public_data.len() + CHUNK_BYTES - 1, 0x00);
+        let report = decode_block(&public_data, None, false);
+        assert_eq!(report.ops.len(), 1);
+        assert!(report.error.unwrap().contains("unknown operation type 255"));
+
+        let public_data = deposit_op().public_data();
+        let report = decode_block(&public_data[..CHUNK_BYTES * 2], None, false);
+        assert!(report.ops.is_empty());
+        assert!(report.error.unwrap().contains("only"));
+    }
+}
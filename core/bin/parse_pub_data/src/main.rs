@@ -1,22 +1,167 @@
-use zksync_types::ZkSyncOp;
-
-fn main() {
-    let hex_data = std::env::args()
-        .nth(1)
-        .expect("cli arg should be hex of pubdata");
-    let data = hex::decode(&hex_data).expect("failed to decode hex");
-
-    let mut unparsed_data = data.as_slice();
-    while !unparsed_data.is_empty() {
-        let op_type = unparsed_data[0];
-        let op_data_len = ZkSyncOp::public_data_length(op_type).expect("wrong op type");
-        assert!(
-            data.len() > op_data_len,
-            "not enough bytes in the pubdata for current op"
-        );
-        let (current_op, unparsed) = unparsed_data.split_at(op_data_len);
-        let op = ZkSyncOp::from_public_data(current_op).expect("failed to parse pubdata");
-        println!("{:#?}", op);
-        unparsed_data = unparsed;
+//! Inspection tool for the block public data.
+//!
+//! Public data is taken from the block stored in the database, from the calldata of the `commitBlocks`
+//! transaction or passed as is. Every operation is decoded along with its position in the block, and
+//! when the block context is known the onchain operations and the block commitment are checked as well.
+
+// Built-in deps
+use std::collections::BTreeSet;
+// External deps
+use anyhow::{ensure, format_err};
+use structopt::StructOpt;
+// Workspace deps
+use zksync_storage::StorageProcessor;
+use zksync_types::BlockNumber;
+// Local deps
+use crate::calldata::{decode_commit_calldata, OnchainOperationData};
+use crate::decode::{decode_block, BlockContext, BlockReport};
+use crate::output::print_block_table;
+use crate::resolve::Resolver;
+
+mod calldata;
+mod decode;
+mod output;
+mod resolve;
+
+#[derive(StructOpt)]
+#[structopt(name = "zkSync public data parser", author = "Matter Labs")]
+struct Opt {
+    /// Hex encoded public data of the block
+    #[structopt(required_unless_one = &["block", "calldata"])]
+    pubdata: Option<String>,
+    /// Number of the block to load from the database
+    #[structopt(long, conflicts_with_all = &["pubdata", "calldata"])]
+    block: Option<u32>,
+    /// Hex encoded calldata of the `commitBlocks` transaction
+    #[structopt(long, conflicts_with = "pubdata")]
+    calldata: Option<String>,
+    /// Resolve account addresses and token symbols using the database, always done for `--block`
+    #[structopt(long)]
+    resolve: bool,
+    /// Data was committed before the v6 upgrade, when token ids had 2 bytes
+    #[structopt(long)]
+    legacy: bool,
+    /// Print the JSON report instead of the table
+    #[structopt(long)]
+    json: bool,
+}
+
+fn parse_hex(data: &str) -> anyhow::Result<Vec<u8>> {
+    let data = data.trim();
+    let data = data.strip_prefix("0x").unwrap_or(data);
+    hex::decode(data).map_err(|err| format_err!("input is not a valid hex string: {}", err))
+}
+
+async fn load_block(
+    storage: &mut StorageProcessor<'_>,
+    block_number: BlockNumber,
+) -> anyhow::Result<BlockReport> {
+    let block = storage
+        .chain()
+        .block_schema()
+        .get_block(block_number)
+        .await?
+        .ok_or_else(|| format_err!("block {} is not found in the database", *block_number))?;
+    let old_state_hash = match block_number.checked_sub(1) {
+        Some(previous_block) => storage
+            .chain()
+            .block_schema()
+            .get_block(BlockNumber(previous_block))
+            .await?
+            .map(|block| block.get_eth_encoded_root()),
+        None => None,
+    };
+
+    let context = BlockContext {
+        block_number,
+        fee_account: block.fee_account,
+        timestamp: block.timestamp,
+        old_state_hash,
+        new_state_hash: block.get_eth_encoded_root(),
+    };
+    let mut report = decode_block(&block.get_eth_public_data(), Some(context), false);
+    report.stored_commitment = Some(block.block_commitment);
+    if report.commitment.is_some() && report.commitment != report.stored_commitment {
+        report
+            .warnings
+            .push("calculated commitment doesn't match the stored one".to_string());
+    }
+    Ok(report)
+}
+
+/// Compares onchain operations passed to the contract with the ones found in the public data.
+fn check_onchain_operations(report: &mut BlockReport, declared: &[OnchainOperationData]) {
+    if report.error.is_some() {
+        return;
     }
+    let declared: BTreeSet<_> = declared
+        .iter()
+        .map(|op| op.public_data_offset as usize)
+        .collect();
+    let decoded: BTreeSet<_> = report
+        .onchain_operations
+        .iter()
+        .map(|op| op.public_data_offset)
+        .collect();
+    for offset in declared.difference(&decoded) {
+        report.warnings.push(format!(
+            "onchain operation is declared at offset {}, but there is none in the public data",
+            offset
+        ));
+    }
+    for offset in decoded.difference(&declared) {
+        report.warnings.push(format!(
+            "onchain operation at offset {} is not declared in the calldata",
+            offset
+        ));
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opt = Opt::from_args();
+    // Database is only used if the block is loaded from it or the ids have to be resolved.
+    let mut storage = if opt.block.is_some() || opt.resolve {
+        Some(StorageProcessor::establish_connection().await?)
+    } else {
+        None
+    };
+
+    let mut reports = Vec::new();
+    if let (Some(block_number), Some(storage)) = (opt.block, storage.as_mut()) {
+        reports.push(load_block(storage, BlockNumber(block_number)).await?);
+    } else if let Some(calldata) = &opt.calldata {
+        for block in decode_commit_calldata(&parse_hex(calldata)?)? {
+            let mut report = decode_block(&block.public_data, Some(block.context), opt.legacy);
+            check_onchain_operations(&mut report, &block.onchain_operations);
+            reports.push(report);
+        }
+    } else {
+        let pubdata = parse_hex(opt.pubdata.as_deref().unwrap_or_default())?;
+        reports.push(decode_block(&pubdata, None, opt.legacy));
+    }
+
+    if let Some(storage) = storage.as_mut() {
+        let mut resolver = Resolver::default();
+        for report in &mut reports {
+            resolver.resolve_block(storage, report).await?;
+        }
+    }
+
+    if opt.json {
+        println!("{}", serde_json::to_string_pretty(&reports)?);
+    } else {
+        reports.iter().for_each(print_block_table);
+    }
+
+    let malformed = reports
+        .iter()
+        .filter(|report| report.error.is_some())
+        .count();
+    ensure!(
+        malformed == 0,
+        "{} block(s) can't be fully decoded",
+        malformed
+    );
+    Ok(())
 }
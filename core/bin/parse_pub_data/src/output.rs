@@ -0,0 +1,87 @@
+//! Human-readable output of the decoded blocks.
+
+// Local deps
+use crate::decode::{AccountInfo, BlockReport, TokenInfo};
+
+fn format_account(account: &AccountInfo) -> String {
+    match account.address {
+        Some(address) => format!("{} ({:#x})", *account.id, address),
+        None => format!("{}", *account.id),
+    }
+}
+
+fn format_token(token: &TokenInfo) -> String {
+    match &token.symbol {
+        Some(symbol) => format!("{} ({})", *token.id, symbol),
+        None => format!("{}", *token.id),
+    }
+}
+
+/// Prints the block report as a table of operations preceded by the block summary.
+pub fn print_block_table(report: &BlockReport) {
+    match &report.context {
+        Some(context) => {
+            println!(
+                "Block {} (fee account {}, timestamp {})",
+                *context.block_number, *context.fee_account, context.timestamp
+            );
+            if let Some(old_state_hash) = context.old_state_hash {
+                println!("  old state hash:       {:?}", old_state_hash);
+            }
+            println!("  new state hash:       {:?}", context.new_state_hash);
+        }
+        None => println!("Public data"),
+    }
+    println!(
+        "  size:                 {} bytes, {} chunks ({} noop)",
+        report.public_data_length, report.chunks, report.noop_chunks
+    );
+    println!(
+        "  operations:           {} ({} onchain, {} priority)",
+        report.ops.len(),
+        report.onchain_operations.len(),
+        report.priority_ops
+    );
+    println!("  processable ops hash: {:?}", report.processable_ops_hash);
+    println!(
+        "  onchain commitment:   0x{}",
+        hex::encode(&report.onchain_op_commitment)
+    );
+    if let Some(commitment) = report.commitment {
+        println!("  commitment:           {:?}", commitment);
+    }
+    if let Some(stored_commitment) = report.stored_commitment {
+        println!("  stored commitment:    {:?}", stored_commitment);
+    }
+
+    if !report.ops.is_empty() {
+        println!();
+        println!(
+            "  {:>4} {:>7} {:>6} {:>6}  {:<14} {:<8} {:<30} TOKENS",
+            "#", "OFFSET", "CHUNK", "CHUNKS", "TYPE", "ONCHAIN", "ACCOUNTS"
+        );
+    }
+    for (index, op) in report.ops.iter().enumerate() {
+        let accounts: Vec<_> = op.accounts.iter().map(format_account).collect();
+        let tokens: Vec<_> = op.tokens.iter().map(format_token).collect();
+        println!(
+            "  {:>4} {:>7} {:>6} {:>6}  {:<14} {:<8} {:<30} {}",
+            index,
+            op.public_data_offset,
+            op.first_chunk,
+            op.chunks,
+            op.op_type,
+            if op.is_onchain { "yes" } else { "no" },
+            accounts.join(", "),
+            tokens.join(", ")
+        );
+    }
+
+    for warning in &report.warnings {
+        println!("  warning: {}", warning);
+    }
+    if let Some(error) = &report.error {
+        println!("  error: {}", error);
+    }
+    println!();
+}
@@ -0,0 +1,69 @@
+//! Resolution of the account and token ids referenced by the operations using the database.
+
+// Built-in deps
+use std::collections::HashMap;
+// Workspace deps
+use zksync_storage::StorageProcessor;
+use zksync_types::{AccountId, Address, TokenId, TokenLike};
+// Local deps
+use crate::decode::BlockReport;
+
+/// Caches the resolved values, since the same accounts and tokens are used by many operations.
+#[derive(Debug, Default)]
+pub struct Resolver {
+    addresses: HashMap<AccountId, Option<Address>>,
+    symbols: HashMap<TokenId, Option<String>>,
+}
+
+impl Resolver {
+    async fn address(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        account_id: AccountId,
+    ) -> anyhow::Result<Option<Address>> {
+        if let Some(address) = self.addresses.get(&account_id) {
+            return Ok(*address);
+        }
+        let address = storage
+            .chain()
+            .account_schema()
+            .account_address_by_id(account_id)
+            .await?;
+        self.addresses.insert(account_id, address);
+        Ok(address)
+    }
+
+    async fn symbol(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        token_id: TokenId,
+    ) -> anyhow::Result<Option<String>> {
+        if let Some(symbol) = self.symbols.get(&token_id) {
+            return Ok(symbol.clone());
+        }
+        let symbol = storage
+            .tokens_schema()
+            .get_token(TokenLike::Id(token_id))
+            .await?
+            .map(|token| token.symbol);
+        self.symbols.insert(token_id, symbol.clone());
+        Ok(symbol)
+    }
+
+    /// Sets addresses of the accounts and symbols of the tokens of the block operations.
+    pub async fn resolve_block(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        report: &mut BlockReport,
+    ) -> anyhow::Result<()> {
+        for op in &mut report.ops {
+            for account in &mut op.accounts {
+                account.address = self.address(storage, account.id).await?;
+            }
+            for token in &mut op.tokens {
+                token.symbol = self.symbol(storage, token.id).await?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -6,7 +6,10 @@ use structopt::StructOpt;
 use zksync_config::configs::ProverConfig as EnvProverConfig;
 use zksync_utils::{get_env, parse_env};
 // Local deps
-use crate::{client, prover_work_cycle, ProverConfig, ProverImpl, ShutdownRequest};
+use crate::{
+    client, parallel_prover_work_cycle, prover_work_cycle, ProverConfig, ProverImpl,
+    ShutdownRequest,
+};
 use zksync_config::configs::api::PrometheusConfig;
 use zksync_prometheus_exporter::run_prometheus_exporter;
 
@@ -36,14 +39,14 @@ where
     let opt = Opt::from_args();
     let worker_name = opt.worker_name;
 
+    let _vlog_guard = vlog::init();
+
     // used env
     let prover_options = EnvProverConfig::from_env();
     let prover_config = <PROVER as ProverImpl>::Config::from_env();
     let api_client = api_client_from_env();
     let prover = PROVER::create_from_config(prover_config);
 
-    let _vlog_guard = vlog::init();
-
     vlog::info!("creating prover, worker name: {}", worker_name);
 
     // Create client.
@@ -73,12 +76,26 @@ where
         run_prometheus_exporter(prom_config.port);
     }
 
-    prover_work_cycle(
-        prover,
-        api_client,
-        shutdown_request,
-        prover_options,
-        &worker_name,
-    )
-    .await;
+    let parallel_jobs = prover.get_request_aux_data().parallel_jobs.unwrap_or(1);
+    if parallel_jobs > 1 {
+        vlog::info!("proving {} jobs in parallel", parallel_jobs);
+        parallel_prover_work_cycle(
+            prover,
+            api_client,
+            shutdown_request,
+            prover_options,
+            &worker_name,
+            parallel_jobs,
+        )
+        .await;
+    } else {
+        prover_work_cycle(
+            prover,
+            api_client,
+            shutdown_request,
+            prover_options,
+            &worker_name,
+        )
+        .await;
+    }
 }
@@ -8,9 +8,10 @@ use futures::Future;
 use reqwest::Url;
 use vlog::*;
 // Workspace deps
-use crate::auth_utils::AuthTokenGenerator;
+use zksync_auth::AuthTokenGenerator;
 use zksync_prover_utils::api::{
-    ProverInputRequest, ProverInputResponse, ProverOutputRequest, ProverStopped, WorkingOn,
+    ProverInputRequest, ProverInputResponse, ProverJobFailure, ProverOutputRequest, ProverStopped,
+    WorkingOn,
 };
 
 #[derive(Debug, Clone)]
@@ -19,6 +20,7 @@ pub struct ApiClient {
     working_on_url: Url,
     publish_url: Url,
     stopped_url: Url,
+    report_failure_url: Url,
     // Client keeps connection pool inside, so it is recommended to reuse it (see docstring for reqwest::Client).
     http_client: reqwest::Client,
     // A generator that create the authentication token upon request to any endpoint.
@@ -41,6 +43,7 @@ impl ApiClient {
             working_on_url: base_url.join("/working_on").unwrap(),
             publish_url: base_url.join("/publish").unwrap(),
             stopped_url: base_url.join("/stopped").unwrap(),
+            report_failure_url: base_url.join("/report_failure").unwrap(),
             http_client,
             auth_token_generator,
         }
@@ -196,4 +199,42 @@ impl crate::ApiClient for ApiClient {
 
         self.with_retries(operation).await
     }
+
+    async fn report_failure(
+        &self,
+        job_id: i32,
+        prover_name: &str,
+        error: String,
+    ) -> anyhow::Result<()> {
+        let operation = || async {
+            let response = self
+                .http_client
+                .post(self.report_failure_url.clone())
+                .bearer_auth(&self.get_encoded_token()?)
+                .json(&ProverJobFailure {
+                    prover_name: prover_name.to_string(),
+                    job_id,
+                    error: error.clone(),
+                })
+                .send()
+                .await
+                .map_err(|e| {
+                    Transient(format_err!("failed to send report_failure request: {}", e))
+                })?;
+
+            if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+                return Err(Permanent(format_err!("authorization error")));
+            }
+            if response.status() == reqwest::StatusCode::CONFLICT {
+                return Err(Permanent(format_err!(
+                    "job {} is not held by the prover anymore",
+                    job_id
+                )));
+            }
+
+            Ok(())
+        };
+
+        self.with_retries(operation).await
+    }
 }
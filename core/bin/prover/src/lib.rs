@@ -1,12 +1,13 @@
-pub mod auth_utils;
 pub mod cli_utils;
 pub mod client;
 pub mod dummy_prover;
+pub mod parallelism;
 pub mod plonk_step_by_step_prover;
 
 // Built-in deps
 use futures::{pin_mut, FutureExt};
 use std::fmt::Debug;
+use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::sync::{
     atomic::{AtomicBool, AtomicI32, Ordering},
     Arc,
@@ -79,34 +80,69 @@ pub trait ProverImpl {
     type Config: ProverConfig;
     /// Creates prover from config and API client.
     fn create_from_config(config: Self::Config) -> Self;
+    /// Returns the capabilities of the prover used by the server to choose the job.
     fn get_request_aux_data(&self) -> ProverInputRequestAuxData {
         Default::default()
-        // TODO: Add the ability to define different config (ZKS-283).
     }
     /// Resource heavy operation
     fn create_proof(&self, data: JobRequestData) -> anyhow::Result<JobResultData>;
 }
+
+/// Shared prover is used to prove several jobs in parallel.
+impl<PROVER: ProverImpl> ProverImpl for Arc<PROVER> {
+    type Config = PROVER::Config;
+
+    fn create_from_config(config: Self::Config) -> Self {
+        Arc::new(PROVER::create_from_config(config))
+    }
+
+    fn get_request_aux_data(&self) -> ProverInputRequestAuxData {
+        self.as_ref().get_request_aux_data()
+    }
+
+    fn create_proof(&self, data: JobRequestData) -> anyhow::Result<JobResultData> {
+        self.as_ref().create_proof(data)
+    }
+}
+
 #[async_trait::async_trait]
 pub trait ApiClient: Debug {
     async fn get_job(&self, req: ProverInputRequest) -> anyhow::Result<ProverInputResponse>;
     async fn working_on(&self, job_id: i32, prover_name: &str) -> anyhow::Result<()>;
     async fn publish(&self, data: ProverOutputRequest) -> anyhow::Result<()>;
     async fn prover_stopped(&self, prover_name: String) -> anyhow::Result<()>;
+    async fn report_failure(
+        &self,
+        job_id: i32,
+        prover_name: &str,
+        error: String,
+    ) -> anyhow::Result<()>;
 }
 
+/// Computes the proof in the separate thread.
+/// The prover is returned back even if the proof computation failed or panicked,
+/// so the worker can report the failure and continue with the next job.
 async fn compute_proof_no_blocking<PROVER>(
     prover: PROVER,
     data: JobRequestData,
-) -> anyhow::Result<(PROVER, JobResultData)>
+) -> anyhow::Result<(PROVER, anyhow::Result<JobResultData>)>
 where
     PROVER: ProverImpl + Send + Sync + 'static,
 {
     let (result_sender, result_receiver) = oneshot::channel();
     std::thread::spawn(move || {
-        let prover_with_proof = prover.create_proof(data).map(|proof| (prover, proof));
-        result_sender.send(prover_with_proof).unwrap_or_default();
+        let proof =
+            catch_unwind(AssertUnwindSafe(|| prover.create_proof(data))).unwrap_or_else(|panic| {
+                let message = panic
+                    .downcast_ref::<&str>()
+                    .map(|message| message.to_string())
+                    .or_else(|| panic.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "unknown panic".to_string());
+                Err(anyhow::format_err!("Prover panicked: {}", message))
+            });
+        result_sender.send((prover, proof)).unwrap_or_default();
     });
-    result_receiver.await?
+    Ok(result_receiver.await?)
 }
 
 /// Endlessly sends requests to the server, in case of not receiving a response
@@ -210,12 +246,30 @@ pub async fn prover_work_cycle<PROVER, CLIENT>(
 
         let (ret_prover, proof) = futures::select! {
             comp_proof = compute_proof_future => {
-                comp_proof.expect("Failed to compute proof")
+                comp_proof.expect("Proof computation thread was terminated")
             },
             _ = heartbeat_future_handle => unreachable!(),
         };
         prover = ret_prover;
 
+        let proof = match proof {
+            Ok(proof) => proof,
+            Err(e) => {
+                vlog::error!(
+                    "Failed to compute proof for blocks: [{}, {}]: {}",
+                    first_block,
+                    last_block,
+                    e
+                );
+                client
+                    .report_failure(job_id, prover_name, e.to_string())
+                    .await
+                    .map_err(|e| vlog::warn!("Failed to report job failure: {}", e))
+                    .unwrap_or_default();
+                continue;
+            }
+        };
+
         client
             .publish(ProverOutputRequest {
                 job_id,
@@ -237,3 +291,31 @@ pub async fn prover_work_cycle<PROVER, CLIENT>(
         }
     }
 }
+
+/// Runs the worker cycle for each of the `parallel_jobs` slots sharing the same prover,
+/// so several jobs are proven at the same time. Slots request the jobs separately
+/// under the names `{prover_name}_{slot}`.
+pub async fn parallel_prover_work_cycle<PROVER, CLIENT>(
+    prover: PROVER,
+    client: CLIENT,
+    shutdown: ShutdownRequest,
+    prover_options: EnvProverConfig,
+    prover_name: &str,
+    parallel_jobs: usize,
+) where
+    CLIENT: 'static + Sync + Send + ApiClient + Clone,
+    PROVER: ProverImpl + Send + Sync + 'static,
+{
+    let prover = Arc::new(prover);
+    let work_cycles = (0..parallel_jobs).map(|slot| {
+        let prover = prover.clone();
+        let client = client.clone();
+        let shutdown = shutdown.clone();
+        let prover_options = prover_options.clone();
+        let slot_name = format!("{}_{}", prover_name, slot);
+        async move {
+            prover_work_cycle(prover, client, shutdown, prover_options, &slot_name).await;
+        }
+    });
+    futures::future::join_all(work_cycles).await;
+}
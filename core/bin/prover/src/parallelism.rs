@@ -0,0 +1,57 @@
+//! Choice of the amount of jobs proven in parallel by a single prover instance.
+
+// Workspace deps
+use zksync_config::configs::prover::Prover as ProverOptions;
+
+/// Amount of CPU cores required for a single job to be proven in a reasonable time.
+/// The prover splits every job across all the cores, so the parallel jobs share them,
+/// but the jobs that can't keep several cores busy only make each other slower.
+pub const CORES_PER_JOB: usize = 4;
+
+/// Returns the amount of jobs that fit into the given amount of CPU cores and available memory (in MB).
+/// The memory is not taken into account if the memory required by a single job is unknown.
+pub fn parallel_jobs_for_resources(
+    cores: usize,
+    available_memory: Option<u64>,
+    job_memory: Option<u64>,
+) -> usize {
+    let jobs_by_cores = cores / CORES_PER_JOB;
+    let jobs_by_memory = match (available_memory, job_memory) {
+        (Some(available_memory), Some(job_memory)) if job_memory > 0 => {
+            (available_memory / job_memory) as usize
+        }
+        _ => usize::MAX,
+    };
+    std::cmp::max(std::cmp::min(jobs_by_cores, jobs_by_memory), 1)
+}
+
+/// Returns the memory available for the new processes in MB, if it's known.
+fn available_memory() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let line = meminfo
+        .lines()
+        .find(|line| line.starts_with("MemAvailable:"))?;
+    let kilobytes: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kilobytes / 1024)
+}
+
+/// Returns the amount of jobs proven in parallel configured for the prover.
+pub fn parallel_jobs(options: &ProverOptions) -> usize {
+    match options.parallel_jobs {
+        None => 1,
+        Some(0) => {
+            let cores = num_cpus::get();
+            let memory = available_memory();
+            let jobs = parallel_jobs_for_resources(cores, memory, options.job_memory);
+            vlog::info!(
+                "Proving {} jobs in parallel, CPU cores: {}, available memory: {:?} MB, job memory: {:?} MB",
+                jobs,
+                cores,
+                memory,
+                options.job_memory
+            );
+            jobs
+        }
+        Some(jobs) => jobs,
+    }
+}
@@ -1,28 +1,26 @@
 // Built-in deps
-use std::sync::Mutex;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 // Workspace deps
-use zksync_config::ChainConfig;
+use zksync_config::{ChainConfig, ProverConfig as EnvProverConfig};
 use zksync_crypto::proof::{AggregatedProof, PrecomputedSampleProofs, SingleProof};
 use zksync_crypto::Engine;
 use zksync_prover_utils::aggregated_proofs::{gen_aggregate_proof, prepare_proof_data};
-use zksync_prover_utils::api::{JobRequestData, JobResultData};
+use zksync_prover_utils::api::{JobRequestData, JobResultData, ProverInputRequestAuxData};
+use zksync_prover_utils::empty_circuit::empty_block_circuit;
 use zksync_prover_utils::{PlonkVerificationKey, SetupForStepByStepProver};
 use zksync_utils::parse_env;
 // Local deps
+use crate::parallelism::parallel_jobs;
 use crate::{ProverConfig, ProverImpl};
 use tokio::time::Instant;
 use zksync_prover_utils::fs_utils::load_precomputed_proofs;
 
-/// We prepare some data before making proof for each block size, so we cache it in case next block
-/// would be of our size
-struct PreparedComputations {
-    block_size: usize,
-    setup: SetupForStepByStepProver,
-}
-
 pub struct PlonkStepByStepProver {
     config: PlonkStepByStepProverConfig,
-    prepared_computations: Mutex<Option<PreparedComputations>>,
+    /// We prepare some data before making proof for each block size, so we cache it in case next block
+    /// would be of the same size. Setups are shared by the jobs proven in parallel.
+    prepared_setups: Mutex<HashMap<usize, Arc<SetupForStepByStepProver>>>,
     precomputed_sample_proofs: PrecomputedSampleProofs,
 }
 
@@ -31,67 +29,115 @@ pub struct PlonkStepByStepProverConfig {
     pub block_sizes: Vec<usize>,
     pub download_setup_from_network: bool,
     pub aggregated_proof_sizes_with_setup_pow: Vec<(usize, u32)>,
+    /// Capabilities reported to the server when requesting a job.
+    pub request_aux_data: ProverInputRequestAuxData,
+    /// Whether the setups for all the provable block sizes are prepared on start and kept in memory.
+    /// Otherwise, only the setup for the last proven block size is cached.
+    pub preload_setups: bool,
 }
 
 impl ProverConfig for PlonkStepByStepProverConfig {
     fn from_env() -> Self {
         let env_config = ChainConfig::from_env();
+        let prover_config = EnvProverConfig::from_env().prover;
 
         let aggregated_proof_sizes_with_setup_pow = env_config
             .circuit
             .supported_aggregated_proof_sizes_with_setup_pow();
+        let parallel_jobs = parallel_jobs(&prover_config);
 
         Self {
             download_setup_from_network: parse_env("MISC_PROVER_DOWNLOAD_SETUP"),
             all_block_sizes: env_config.circuit.supported_block_chunks_sizes,
             block_sizes: env_config.state_keeper.block_chunk_sizes,
             aggregated_proof_sizes_with_setup_pow,
+            request_aux_data: ProverInputRequestAuxData {
+                prefer_aggregated_proof: prover_config.prefer_aggregated_proof,
+                preferred_block_size: prover_config.max_block_chunk_size,
+                parallel_jobs: Some(parallel_jobs),
+            },
+            // Parallel jobs are likely to be of different sizes, so the setups shouldn't be replaced.
+            preload_setups: parallel_jobs > 1,
         }
     }
 }
 
+impl PlonkStepByStepProverConfig {
+    /// Block sizes the prover is able to prove.
+    fn provable_block_sizes(&self) -> Vec<usize> {
+        self.block_sizes
+            .iter()
+            .copied()
+            .filter(|size| {
+                self.request_aux_data
+                    .preferred_block_size
+                    .map(|max_size| *size <= max_size)
+                    .unwrap_or(true)
+            })
+            .collect()
+    }
+}
+
 impl PlonkStepByStepProver {
     fn create_single_block_proof(
         &self,
         witness: zksync_circuit::circuit::ZkSyncCircuit<'_, Engine>,
         block_size: usize,
     ) -> anyhow::Result<SingleProof> {
-        let valid_cached_precomp = {
-            self.prepared_computations
-                .lock()
-                .unwrap()
-                .take()
-                .filter(|p| p.block_size == block_size)
-        };
-
-        let precomp = if let Some(precomp) = valid_cached_precomp {
-            precomp
+        let cached_setup = self
+            .prepared_setups
+            .lock()
+            .unwrap()
+            .get(&block_size)
+            .cloned();
+
+        let setup = if let Some(setup) = cached_setup {
+            setup
         } else {
-            let setup = SetupForStepByStepProver::prepare_setup_for_step_by_step_prover(
-                witness.clone(),
-                self.config.download_setup_from_network,
-            )?;
-            PreparedComputations { block_size, setup }
+            let setup = Arc::new(
+                SetupForStepByStepProver::prepare_setup_for_step_by_step_prover(
+                    witness.clone(),
+                    self.config.download_setup_from_network,
+                )?,
+            );
+            let mut prepared_setups = self.prepared_setups.lock().unwrap();
+            if !self.config.preload_setups {
+                prepared_setups.clear();
+            }
+            prepared_setups.insert(block_size, setup.clone());
+            setup
         };
 
         let vk = PlonkVerificationKey::read_verification_key_for_main_circuit(block_size)?;
-        let verified_proof = precomp
-            .setup
-            .gen_step_by_step_proof_using_prepared_setup(witness, &vk)?;
-
-        *self.prepared_computations.lock().unwrap() = Some(precomp);
+        let verified_proof = setup.gen_step_by_step_proof_using_prepared_setup(witness, &vk)?;
 
         Ok(verified_proof)
     }
 
+    /// Prepares the setups for all the provable block sizes.
+    fn preload_setups(&self) -> anyhow::Result<()> {
+        for block_size in self.config.provable_block_sizes() {
+            vlog::info!("Preparing setup for block size: {}", block_size);
+            let setup = SetupForStepByStepProver::prepare_setup_for_step_by_step_prover(
+                empty_block_circuit(block_size),
+                self.config.download_setup_from_network,
+            )?;
+            self.prepared_setups
+                .lock()
+                .unwrap()
+                .insert(block_size, Arc::new(setup));
+        }
+        Ok(())
+    }
+
     fn create_aggregated_block_proof(
         &self,
         proofs: Vec<(SingleProof, usize)>,
     ) -> anyhow::Result<AggregatedProof> {
         let start = Instant::now();
-        // drop setup cache
-        {
-            self.prepared_computations.lock().unwrap().take();
+        // drop setup cache, unless the setups are kept for the parallel jobs
+        if !self.config.preload_setups {
+            self.prepared_setups.lock().unwrap().clear();
         }
 
         let proofs_to_pad = {
@@ -138,6 +184,10 @@ impl PlonkStepByStepProver {
 impl ProverImpl for PlonkStepByStepProver {
     type Config = PlonkStepByStepProverConfig;
 
+    fn get_request_aux_data(&self) -> ProverInputRequestAuxData {
+        self.config.request_aux_data.clone()
+    }
+
     fn create_proof(&self, data: JobRequestData) -> Result<JobResultData, anyhow::Error> {
         let proof = match data {
             JobRequestData::AggregatedBlockProof(proofs_to_aggregate) => {
@@ -175,11 +225,17 @@ impl ProverImpl for PlonkStepByStepProver {
 
     fn create_from_config(config: PlonkStepByStepProverConfig) -> Self {
         assert!(!config.block_sizes.is_empty());
-        PlonkStepByStepProver {
+        let prover = PlonkStepByStepProver {
             config,
-            prepared_computations: Mutex::new(None),
+            prepared_setups: Mutex::new(HashMap::new()),
             precomputed_sample_proofs: load_precomputed_proofs()
                 .expect("Failed to load precomputed sample proofs"),
+        };
+        if prover.config.preload_setups {
+            prover
+                .preload_setups()
+                .expect("Failed to prepare setups for the block sizes");
         }
+        prover
     }
 }
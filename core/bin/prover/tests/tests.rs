@@ -1,5 +1,5 @@
 // Built-in deps
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
 // External deps
@@ -18,12 +18,13 @@ use zksync_crypto::{
     Fr,
 };
 use zksync_prover::dummy_prover::{DummyProver, DummyProverConfig};
+use zksync_prover::parallelism::{parallel_jobs_for_resources, CORES_PER_JOB};
 use zksync_prover::plonk_step_by_step_prover::{
     PlonkStepByStepProver, PlonkStepByStepProverConfig,
 };
 use zksync_prover::{ProverImpl, ShutdownRequest};
 use zksync_prover_utils::api::{
-    JobRequestData, ProverInputRequest, ProverInputResponse, ProverOutputRequest,
+    JobRequestData, JobResultData, ProverInputRequest, ProverInputResponse, ProverOutputRequest,
 };
 use zksync_types::{
     block::smallest_block_size_for_chunks, operations::DepositOp, Account, AccountId, Address,
@@ -47,6 +48,8 @@ impl Default for MockProverConfigs {
             aggregated_proof_sizes_with_setup_pow: vec![(1, 22), (4, 23), (8, 24), (18, 25)],
             block_sizes: vec![10, 32],
             download_setup_from_network: false,
+            request_aux_data: Default::default(),
+            preload_setups: false,
         };
         let dummy_config = DummyProverConfig {
             block_sizes: vec![10, 32],
@@ -57,14 +60,25 @@ impl Default for MockProverConfigs {
                 cycle_wait: 500,
                 request_timeout: 1,
                 die_after_proof: false,
+                max_block_chunk_size: None,
+                prefer_aggregated_proof: None,
+                parallel_jobs: None,
+                job_memory: None,
             },
             core: zksync_config::configs::prover::Core {
                 gone_timeout: 2,
                 idle_provers: 1,
+                job_starvation_timeout: 600000,
+                max_job_attempts: 5,
+                failed_job_webhook_url: String::new(),
+                scaler_target_latency: 1800000,
+                scaler_history_window: 3600000,
+                scaler_scale_down_delay: 600000,
             },
             witness_generator: zksync_config::configs::prover::WitnessGenerator {
                 prepare_data_interval: 5000,
                 witness_generators: 2,
+                pipeline_workers: 0,
             },
         };
 
@@ -256,6 +270,148 @@ async fn test_publishing_proof() {
     };
 }
 
+#[tokio::test]
+async fn test_reporting_failures() {
+    let MockProverConfigs {
+        plonk_config: _,
+        dummy_config: _,
+        prover_options,
+        shutdown_request,
+        prover_name,
+    } = MockProverConfigs::default();
+
+    let prover = FailingProver::create_from_config(FailingProverConfig);
+    let client = MockApiClient::default();
+
+    let prover_work_cycle = zksync_prover::prover_work_cycle(
+        prover,
+        client.clone(),
+        shutdown_request.clone(),
+        prover_options.clone(),
+        &prover_name,
+    )
+    .fuse();
+    let timeout = tokio::time::sleep(Duration::from_secs(5)).fuse();
+
+    pin_mut!(prover_work_cycle, timeout);
+
+    futures::select! {
+        _ = prover_work_cycle => panic!("prover work ended too quickly"),
+        _ = timeout => {
+            shutdown_request.set();
+            let failures = client.failures.lock().await;
+            // Both the returned error and the panic are reported, and the prover keeps taking new jobs.
+            assert_eq!(
+                failures.get(&0).cloned(),
+                Some("Failed to create proof for job 0".to_string())
+            );
+            assert_eq!(
+                failures.get(&1).cloned(),
+                Some("Prover panicked: Unexpected proof failure".to_string())
+            );
+            assert!(client.published_prof.lock().await.is_empty());
+        },
+    };
+}
+
+#[tokio::test]
+async fn test_parallel_proving() {
+    let MockProverConfigs {
+        plonk_config: _,
+        dummy_config,
+        prover_options,
+        shutdown_request,
+        prover_name,
+    } = MockProverConfigs::default();
+
+    let prover = DummyProver::create_from_config(dummy_config);
+    let client = MockApiClient::default();
+
+    let prover_work_cycle = zksync_prover::parallel_prover_work_cycle(
+        prover,
+        client.clone(),
+        shutdown_request.clone(),
+        prover_options.clone(),
+        &prover_name,
+        3,
+    )
+    .fuse();
+    let timeout = tokio::time::sleep(Duration::from_secs(5)).fuse();
+
+    pin_mut!(prover_work_cycle, timeout);
+
+    futures::select! {
+        _ = prover_work_cycle => panic!("prover work ended too quickly"),
+        _ = timeout => {
+            shutdown_request.set();
+            // Every slot requests the jobs under its own name.
+            let job_requests = client.job_requests.lock().await.clone();
+            let expected_names = (0..3)
+                .map(|slot| format!("{}_{}", prover_name, slot))
+                .collect::<HashSet<_>>();
+            assert_eq!(job_requests, expected_names);
+            assert!(client.published_prof.lock().await.len() >= 3);
+        },
+    };
+}
+
+#[test]
+fn test_parallel_jobs_for_resources() {
+    // Not enough cores for even a single job still allows one job.
+    assert_eq!(parallel_jobs_for_resources(1, None, None), 1);
+    // Memory is not taken into account if the job memory is unknown.
+    assert_eq!(
+        parallel_jobs_for_resources(CORES_PER_JOB * 8, Some(1024), None),
+        8
+    );
+    // Memory limits the amount of jobs.
+    assert_eq!(
+        parallel_jobs_for_resources(CORES_PER_JOB * 8, Some(20_000), Some(8_000)),
+        2
+    );
+    // Cores limit the amount of jobs.
+    assert_eq!(
+        parallel_jobs_for_resources(CORES_PER_JOB * 2, Some(100_000), Some(8_000)),
+        2
+    );
+    // Not enough memory for even a single job still allows one job.
+    assert_eq!(
+        parallel_jobs_for_resources(CORES_PER_JOB * 2, Some(1_000), Some(8_000)),
+        1
+    );
+}
+
+struct FailingProverConfig;
+
+impl zksync_prover::ProverConfig for FailingProverConfig {
+    fn from_env() -> Self {
+        Self
+    }
+}
+
+/// Prover that returns an error for the first job and panics for the rest of them.
+struct FailingProver {
+    jobs: std::sync::atomic::AtomicUsize,
+}
+
+impl ProverImpl for FailingProver {
+    type Config = FailingProverConfig;
+
+    fn create_from_config(_: Self::Config) -> Self {
+        Self {
+            jobs: Default::default(),
+        }
+    }
+
+    fn create_proof(&self, _: JobRequestData) -> anyhow::Result<JobResultData> {
+        let job = self.jobs.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        if job == 0 {
+            anyhow::bail!("Failed to create proof for job {}", job);
+        }
+        panic!("Unexpected proof failure");
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 struct MockApiClient {
     /// All published proofs are saved by `job_id`.
@@ -264,11 +420,16 @@ struct MockApiClient {
     working_on: Arc<Mutex<HashMap<i32, String>>>,
     /// `gob_id` of the last work that has not yet been submitted.
     last_job_id: Arc<Mutex<i32>>,
+    /// Errors reported via `self.report_failure()` by `job_id`.
+    failures: Arc<Mutex<HashMap<i32, String>>>,
+    /// Names of the provers requested the jobs via `self.get_job()`.
+    job_requests: Arc<Mutex<HashSet<String>>>,
 }
 
 #[async_trait::async_trait]
 impl zksync_prover::ApiClient for MockApiClient {
-    async fn get_job(&self, _: ProverInputRequest) -> anyhow::Result<ProverInputResponse> {
+    async fn get_job(&self, request: ProverInputRequest) -> anyhow::Result<ProverInputResponse> {
+        self.job_requests.lock().await.insert(request.prover_name);
         let last_job_id = *self.last_job_id.lock().await;
         *self.last_job_id.lock().await += 1;
         let response = ProverInputResponse {
@@ -299,4 +460,10 @@ impl zksync_prover::ApiClient for MockApiClient {
     async fn prover_stopped(&self, _: String) -> anyhow::Result<()> {
         Ok(())
     }
+
+    async fn report_failure(&self, job_id: i32, _: &str, error: String) -> anyhow::Result<()> {
+        self.failures.lock().await.insert(job_id, error);
+
+        Ok(())
+    }
 }
@@ -1,6 +1,6 @@
 use anyhow::ensure;
 use structopt::StructOpt;
-use zksync_storage::StorageProcessor;
+use zksync_storage::{blob_store, StorageProcessor};
 use zksync_types::BlockNumber;
 
 async fn remove_operations(
@@ -63,6 +63,9 @@ async fn main() -> anyhow::Result<()> {
     let opt = Opt::from_args();
 
     let mut storage = StorageProcessor::establish_connection().await?;
+    if let Some(blob_store) = blob_store::from_env() {
+        storage.set_blob_store(blob_store);
+    }
 
     let last_commited_block = storage
         .chain()
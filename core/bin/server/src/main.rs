@@ -14,16 +14,19 @@ use zksync_gateway_watcher::run_gateway_watcher_if_multiplexed;
 use zksync_witness_generator::run_prover_server;
 
 use tokio::task::JoinHandle;
-use zksync_config::configs::api::{PrivateApiConfig, PrometheusConfig, TokenConfig};
+use zksync_config::configs::api::{
+    AdminApiConfig, PrivateApiConfig, PrometheusConfig, TokenConfig,
+};
 use zksync_config::{
     configs::api::{CommonApiConfig, JsonRpcConfig, ProverApiConfig, RestApiConfig, Web3Config},
-    ChainConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, ETHWatchConfig,
-    ForcedExitRequestsConfig, GatewayWatcherConfig, ProverConfig, TickerConfig, ZkSyncConfig,
+    BlobStoreConfig, ChainConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig,
+    ETHWatchConfig, ForcedExitRequestsConfig, GatewayWatcherConfig, ProverConfig, TickerConfig,
+    ZkSyncConfig,
 };
 use zksync_core::rejected_tx_cleaner::run_rejected_tx_cleaner;
 use zksync_mempool::run_mempool_tx_handler;
 use zksync_prometheus_exporter::{run_operation_counter, run_prometheus_exporter};
-use zksync_storage::ConnectionPool;
+use zksync_storage::{blob_store, ConnectionPool};
 use zksync_types::ChainId;
 
 const DEFAULT_CHANNEL_CAPACITY: usize = 32_768;
@@ -41,6 +44,7 @@ pub enum Component {
     Web3Api,
     RpcApi,
     RpcWebSocketApi,
+    AdminApi,
 
     // Core components
     Fetchers,
@@ -64,6 +68,7 @@ impl FromStr for Component {
             "web3-api" => Ok(Component::Web3Api),
             "rpc-api" => Ok(Component::RpcApi),
             "rpc-websocket-api" => Ok(Component::RpcWebSocketApi),
+            "admin-api" => Ok(Component::AdminApi),
             "eth-sender" => Ok(Component::EthSender),
             "witness-generator" => Ok(Component::WitnessGenerator),
             "forced-exit" => Ok(Component::ForcedExit),
@@ -152,7 +157,7 @@ async fn main() -> anyhow::Result<()> {
 }
 
 async fn run_server(components: &ComponentsToRun) {
-    let connection_pool = ConnectionPool::new(None);
+    let connection_pool = ConnectionPool::new(None).with_blob_store(blob_store::from_env());
     let read_only_connection_pool = ConnectionPool::new_readonly_pool(None);
     let (stop_signal_sender, mut stop_signal_receiver) = mpsc::channel(256);
 
@@ -160,10 +165,28 @@ async fn run_server(components: &ComponentsToRun) {
 
     if components.0.contains(&Component::Web3Api) {
         // Run web3 api
+        let web3_config = Web3Config::from_env();
+        let token_config = TokenConfig::from_env();
         tasks.push(zksync_api::api_server::web3::start_rpc_server(
             connection_pool.clone(),
-            &Web3Config::from_env(),
-            &TokenConfig::from_env(),
+            &web3_config,
+            &token_config,
+        ));
+        tasks.push(zksync_api::api_server::web3::start_ws_rpc_server(
+            connection_pool.clone(),
+            &web3_config,
+            &token_config,
+            ChainConfig::from_env()
+                .state_keeper
+                .miniblock_iteration_interval(),
+        ));
+    }
+
+    if components.0.contains(&Component::AdminApi) {
+        // Run admin api, it's not started by default since it allows to change the token list.
+        tasks.push(zksync_api::api_server::admin_server::start_admin_server(
+            connection_pool.clone(),
+            &AdminApiConfig::from_env(),
         ));
     }
 
@@ -373,8 +396,14 @@ pub fn run_witness_generator(connection_pool: ConnectionPool) -> JoinHandle<()>
     vlog::info!("Starting the Prover server actors");
     let prover_api_config = ProverApiConfig::from_env();
     let prover_config = ProverConfig::from_env();
+    let blob_store_config = BlobStoreConfig::from_env_opt();
     let database = zksync_witness_generator::database::Database::new(connection_pool);
-    run_prover_server(database, prover_api_config, prover_config)
+    run_prover_server(
+        database,
+        prover_api_config,
+        prover_config,
+        blob_store_config,
+    )
 }
 
 pub fn run_eth_sender(connection_pool: ConnectionPool) -> JoinHandle<()> {
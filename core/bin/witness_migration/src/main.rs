@@ -0,0 +1,75 @@
+//! Re-encodes the block witnesses stored in the legacy JSON format
+//! into the current binary format.
+
+use structopt::StructOpt;
+use zksync_circuit::serialization::{ProverData, WitnessFormat};
+use zksync_storage::StorageProcessor;
+use zksync_types::BlockNumber;
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "witness_migration",
+    about = "Re-encodes stored block witnesses into the current binary format"
+)]
+struct Opt {
+    /// Amount of witnesses processed in a single transaction.
+    #[structopt(long, default_value = "100")]
+    batch_size: u32,
+    /// Only count the witnesses to migrate without updating them.
+    #[structopt(long)]
+    dry_run: bool,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opt = Opt::from_args();
+    let mut storage = StorageProcessor::establish_connection().await?;
+
+    let mut last_block = BlockNumber(0);
+    let mut migrated = 0;
+    let mut skipped = 0;
+    loop {
+        let mut transaction = storage.start_transaction().await?;
+        let witnesses = transaction
+            .prover_schema()
+            .load_witnesses_batch(last_block, opt.batch_size)
+            .await?;
+        let batch_last_block = match witnesses.last() {
+            Some((block, _)) => *block,
+            None => break,
+        };
+
+        for (block, witness) in witnesses {
+            if WitnessFormat::of(&witness)? == WitnessFormat::CURRENT {
+                skipped += 1;
+                continue;
+            }
+
+            let encoded = ProverData::decode(&witness)
+                .and_then(|prover_data| prover_data.encode())
+                .map_err(|err| anyhow::format_err!("Block {}: {}", block, err))?;
+            if !opt.dry_run {
+                transaction
+                    .prover_schema()
+                    .update_witness(block, &encoded)
+                    .await?;
+            }
+            migrated += 1;
+        }
+        transaction.commit().await?;
+
+        println!(
+            "Processed witnesses up to the block {}: {} migrated, {} already in the current format",
+            batch_last_block, migrated, skipped
+        );
+        last_block = batch_last_block;
+    }
+
+    if opt.dry_run {
+        println!("Dry run finished, {} witnesses require migration", migrated);
+    } else {
+        println!("Finish, {} witnesses migrated", migrated);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,347 @@
+//! Admin API server is used by the operator to manage the token list.
+//!
+//! The operator may correct the symbol and decimals of a token, hide it from the token lists,
+//! allow or deny it for paying fees regardless of its market volume and provide its logo and
+//! metadata URIs. Every request must be authorized with a JWT signed by the `secret_auth`
+//! of the admin API config.
+//!
+//! The server is not started by default, since it allows to change the token list.
+//! It should be enabled explicitly with the `admin-api` component, e.g.
+//! `zksync_server --components=core,...,admin-api`.
+//!
+//! Note that the API servers cache tokens, so the corrected symbol and decimals are picked up
+//! after the token cache invalidation period.
+
+// Built-in uses
+use std::thread;
+// External uses
+use actix_web::{web, App, HttpResponse, HttpServer};
+use actix_web_httpauth::middleware::HttpAuthentication;
+use num::Zero;
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+// Workspace uses
+use zksync_auth::AuthTokenValidator;
+use zksync_config::configs::api::AdminApiConfig;
+use zksync_storage::ConnectionPool;
+use zksync_types::{tokens::TokenOverride, Token, TokenId, TokenKind, TokenLike};
+use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
+
+/// Maximum length of the overridden token symbol.
+const MAX_SYMBOL_LENGTH: usize = 32;
+/// Maximum length of the logo and metadata URIs.
+const MAX_URI_LENGTH: usize = 2048;
+/// Schemes allowed for the logo and metadata URIs, since they are shown to the users as is.
+const ALLOWED_URI_SCHEMES: &[&str] = &["https://", "ipfs://"];
+
+#[derive(Debug, Clone)]
+struct AppState {
+    secret_auth: String,
+    connection_pool: ConnectionPool,
+}
+
+impl AppState {
+    async fn access_storage(&self) -> actix_web::Result<zksync_storage::StorageProcessor<'_>> {
+        self.connection_pool.access_storage().await.map_err(|e| {
+            vlog::warn!("Failed to access storage: {}", e);
+            actix_web::error::ErrorInternalServerError(e)
+        })
+    }
+}
+
+/// Token override as it's set by the operator, all the fields are optional.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenOverrideRequest {
+    pub symbol: Option<String>,
+    pub decimals: Option<u8>,
+    #[serde(default)]
+    pub hidden: bool,
+    pub fee_token: Option<bool>,
+    pub logo_uri: Option<String>,
+    pub metadata_uri: Option<String>,
+}
+
+impl TokenOverrideRequest {
+    fn into_token_override(self, token_id: TokenId) -> TokenOverride {
+        TokenOverride {
+            token_id,
+            symbol: self.symbol,
+            decimals: self.decimals,
+            hidden: self.hidden,
+            fee_token: self.fee_token,
+            logo_uri: self.logo_uri,
+            metadata_uri: self.metadata_uri,
+        }
+    }
+
+    /// Checks the request for the token, the uniqueness of the symbol is checked separately.
+    fn validate(&self, token: &Token) -> Result<(), String> {
+        if token.kind != TokenKind::ERC20 {
+            return Err(format!("token {} is not an ERC20 token", *token.id));
+        }
+        if let Some(symbol) = &self.symbol {
+            if symbol.is_empty() || symbol.len() > MAX_SYMBOL_LENGTH {
+                return Err(format!(
+                    "symbol must contain from 1 to {} characters",
+                    MAX_SYMBOL_LENGTH
+                ));
+            }
+            if !symbol.chars().all(|c| c.is_ascii_graphic()) {
+                return Err("symbol must contain only printable ASCII characters".to_string());
+            }
+        }
+        for uri in self.logo_uri.iter().chain(self.metadata_uri.iter()) {
+            if uri.len() > MAX_URI_LENGTH {
+                return Err(format!("URI is longer than {} characters", MAX_URI_LENGTH));
+            }
+            if !ALLOWED_URI_SCHEMES
+                .iter()
+                .any(|scheme| uri.starts_with(scheme))
+            {
+                return Err(format!(
+                    "URI scheme must be one of {:?}: {}",
+                    ALLOWED_URI_SCHEMES, uri
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn token_overrides(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    let token_overrides = storage
+        .tokens_schema()
+        .load_token_overrides()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let mut token_overrides: Vec<_> = token_overrides.into_values().collect();
+    token_overrides.sort_by_key(|token_override| token_override.token_id);
+    Ok(HttpResponse::Ok().json(token_overrides))
+}
+
+async fn token_override(
+    data: web::Data<AppState>,
+    token_id: web::Path<u32>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    let token_override = storage
+        .tokens_schema()
+        .get_token_override(TokenId(token_id.into_inner()))
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?
+        .ok_or_else(|| actix_web::error::ErrorNotFound("token override not found"))?;
+
+    Ok(HttpResponse::Ok().json(token_override))
+}
+
+async fn store_token_override(
+    data: web::Data<AppState>,
+    token_id: web::Path<u32>,
+    request: web::Json<TokenOverrideRequest>,
+) -> actix_web::Result<HttpResponse> {
+    let token_id = TokenId(token_id.into_inner());
+    let request = request.into_inner();
+    let mut storage = data.access_storage().await?;
+    let mut transaction = storage
+        .start_transaction()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let token = transaction
+        .tokens_schema()
+        .get_token(TokenLike::Id(token_id))
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?
+        .ok_or_else(|| actix_web::error::ErrorNotFound("token not found"))?;
+    request
+        .validate(&token)
+        .map_err(actix_web::error::ErrorBadRequest)?;
+
+    // The fee ticker can't calculate fees in the token without its price.
+    if request.fee_token == Some(true) {
+        let has_price = transaction
+            .tokens_schema()
+            .get_historical_ticker_price(token_id)
+            .await
+            .map_err(actix_web::error::ErrorInternalServerError)?
+            .map_or(false, |price| !price.usd_price.is_zero());
+        if !has_price {
+            return Err(actix_web::error::ErrorBadRequest(format!(
+                "token {} has no price and can't be allowed for fees",
+                *token_id
+            )));
+        }
+    }
+
+    // Tokens can be requested by symbol, so the symbol must stay unique.
+    if let Some(symbol) = &request.symbol {
+        let existing_token = transaction
+            .tokens_schema()
+            .get_token(TokenLike::Symbol(symbol.clone()))
+            .await
+            .map_err(actix_web::error::ErrorInternalServerError)?;
+        if let Some(existing_token) = existing_token {
+            if existing_token.id != token_id {
+                return Err(actix_web::error::ErrorConflict(format!(
+                    "symbol {} is already used by token {}",
+                    symbol, *existing_token.id
+                )));
+            }
+        }
+    }
+
+    let token_override = request.into_token_override(token_id);
+    transaction
+        .tokens_schema()
+        .store_token_override(&token_override)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    transaction
+        .commit()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    vlog::info!("Token override is stored: {:?}", token_override);
+    Ok(HttpResponse::Ok().json(token_override))
+}
+
+async fn remove_token_override(
+    data: web::Data<AppState>,
+    token_id: web::Path<u32>,
+) -> actix_web::Result<HttpResponse> {
+    let token_id = TokenId(token_id.into_inner());
+    let mut storage = data.access_storage().await?;
+    let removed = storage
+        .tokens_schema()
+        .remove_token_override(token_id)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    if !removed {
+        return Err(actix_web::error::ErrorNotFound("token override not found"));
+    }
+
+    vlog::info!("Token override is removed for token {}", *token_id);
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Starts the admin API server in a separate thread.
+#[must_use]
+pub fn start_admin_server(
+    connection_pool: ConnectionPool,
+    config: &AdminApiConfig,
+) -> JoinHandle<()> {
+    let (handler, panic_sender) = spawn_panic_handler();
+    let bind_to = config.bind_addr();
+    let secret_auth = config.secret_auth.clone();
+
+    thread::Builder::new()
+        .name("actix-admin-api".to_string())
+        .spawn(move || {
+            let _panic_sentinel = ThreadPanicNotify(panic_sender);
+            actix_rt::System::new().block_on(async move {
+                HttpServer::new(move || {
+                    let app_state = AppState {
+                        secret_auth: secret_auth.clone(),
+                        connection_pool: connection_pool.clone(),
+                    };
+
+                    let auth = HttpAuthentication::bearer(move |req, credentials| async {
+                        let secret_auth = req
+                            .app_data::<web::Data<AppState>>()
+                            .expect("failed get AppState upon receipt of the authentication token")
+                            .secret_auth
+                            .clone();
+                        AuthTokenValidator::new(&secret_auth)
+                            .validator(req, credentials)
+                            .await
+                    });
+
+                    App::new()
+                        .wrap(auth)
+                        .app_data(web::Data::new(app_state))
+                        .route("/tokens/overrides", web::get().to(token_overrides))
+                        .route("/tokens/{id}/override", web::get().to(token_override))
+                        .route("/tokens/{id}/override", web::put().to(store_token_override))
+                        .route(
+                            "/tokens/{id}/override",
+                            web::delete().to(remove_token_override),
+                        )
+                })
+                .bind(bind_to)
+                .expect("failed to bind")
+                .run()
+                .await
+                .expect("Admin API server has crashed");
+            });
+        })
+        .expect("Admin API server thread");
+
+    handler
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::Address;
+
+    fn erc20_token() -> Token {
+        Token::new(TokenId(1), Address::random(), "SCAM", 18, TokenKind::ERC20)
+    }
+
+    #[test]
+    fn validate_token_override_request() {
+        let token = erc20_token();
+        let request = TokenOverrideRequest {
+            symbol: Some("DAI".to_string()),
+            decimals: Some(6),
+            hidden: true,
+            fee_token: Some(false),
+            logo_uri: Some("https://example.com/dai.png".to_string()),
+            metadata_uri: Some("ipfs://QmMetadata".to_string()),
+        };
+        assert_eq!(request.validate(&token), Ok(()));
+        assert_eq!(TokenOverrideRequest::default().validate(&token), Ok(()));
+
+        let nft = Token::new_nft(TokenId(70000), "NFT-70000");
+        assert!(request.validate(&nft).is_err());
+
+        let long_symbol = "D".repeat(MAX_SYMBOL_LENGTH + 1);
+        for symbol in &["", "D AI", "DAI\n", long_symbol.as_str()] {
+            let request = TokenOverrideRequest {
+                symbol: Some(symbol.to_string()),
+                ..request.clone()
+            };
+            assert!(request.validate(&token).is_err(), "{:?}", symbol);
+        }
+
+        let long_uri = format!("https://{}", "a".repeat(MAX_URI_LENGTH));
+        for uri in &[
+            "javascript:alert(1)",
+            "http://example.com/dai.png",
+            long_uri.as_str(),
+        ] {
+            let request = TokenOverrideRequest {
+                logo_uri: Some(uri.to_string()),
+                ..request.clone()
+            };
+            assert!(request.validate(&token).is_err(), "{:?}", uri);
+        }
+    }
+
+    #[test]
+    fn token_override_request_defaults() {
+        let request: TokenOverrideRequest = serde_json::from_str(r#"{"feeToken": true}"#).unwrap();
+        let token_override = request.into_token_override(TokenId(5));
+        assert_eq!(
+            token_override,
+            TokenOverride {
+                token_id: TokenId(5),
+                fee_token: Some(true),
+                ..Default::default()
+            }
+        );
+    }
+}
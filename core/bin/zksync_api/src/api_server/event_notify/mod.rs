@@ -11,7 +11,9 @@ use zksync_types::tx::TxHash;
 use zksync_types::BlockNumber;
 use zksync_types::{block::ExecutedOperations, ActionType, Address};
 
-use self::{event_fetcher::EventFetcher, operation_notifier::OperationNotifier};
+use self::operation_notifier::OperationNotifier;
+
+pub use self::event_fetcher::EventFetcher;
 
 mod event_fetcher;
 mod operation_notifier;
@@ -17,7 +17,7 @@ use chrono::Duration;
 use num::{rational::Ratio, BigUint, FromPrimitive};
 use std::time::Instant;
 use zksync_storage::chain::operations_ext::SearchDirection;
-use zksync_types::{Address, BlockNumber, Token, TokenId, TokenKind};
+use zksync_types::{tokens::ListedToken, Address, BlockNumber, Token, TokenId, TokenKind};
 
 /// Helper macro which wraps the serializable object into `Ok(HttpResponse::Ok().json(...))`.
 macro_rules! ok_json {
@@ -49,9 +49,22 @@ impl ApiV01 {
             .load_tokens()
             .await
             .map_err(Self::db_error)?;
+        let token_overrides = storage
+            .tokens_schema()
+            .load_token_overrides()
+            .await
+            .map_err(Self::db_error)?;
 
-        let mut vec_tokens = tokens.values().cloned().collect::<Vec<_>>();
-        vec_tokens.sort_by_key(|t| t.id);
+        let mut vec_tokens = tokens
+            .into_iter()
+            .map(|(id, token)| {
+                let hidden = token_overrides
+                    .get(&id)
+                    .map_or(false, |token_override| token_override.hidden);
+                ListedToken { token, hidden }
+            })
+            .collect::<Vec<_>>();
+        vec_tokens.sort_by_key(|t| t.token.id);
 
         metrics::histogram!("api", start.elapsed(), "type" => "v01", "endpoint_name" => "tokens");
         ok_json!(vec_tokens)
@@ -27,7 +27,8 @@ use zksync_storage::{
     test_data::{
         dummy_ethereum_tx_hash, gen_acc_random_updates, gen_sample_block,
         gen_unique_aggregated_operation_with_txs, generate_nft, get_sample_aggregated_proof,
-        get_sample_single_proof, BLOCK_SIZE_CHUNKS,
+        get_sample_aggregated_proof_job_data, get_sample_single_proof,
+        get_sample_single_proof_job_data, BLOCK_SIZE_CHUNKS,
     },
     ConnectionPool,
 };
@@ -514,12 +515,11 @@ impl TestServerConfig {
             // Add verification for the block if required.
             if *block_number <= VERIFIED_BLOCKS_COUNT {
                 // Add jobs to `job_prover_queue`.
-                let job_data = serde_json::Value::default();
                 ProverSchema(&mut storage)
                     .add_prover_job_to_job_queue(
                         block_number,
                         block_number,
-                        job_data.clone(),
+                        &get_sample_single_proof_job_data(10),
                         0,
                         ProverJobType::SingleProof,
                     )
@@ -528,7 +528,7 @@ impl TestServerConfig {
                     .add_prover_job_to_job_queue(
                         block_number,
                         block_number,
-                        job_data,
+                        &get_sample_aggregated_proof_job_data(),
                         1,
                         ProverJobType::AggregatedProof,
                     )
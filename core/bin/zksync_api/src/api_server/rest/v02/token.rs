@@ -92,12 +92,22 @@ impl ApiTokenData {
                     .filter_tokens_by_market_volume(tokens_to_check, &self.min_market_volume)
                     .await
                     .map_err(Error::storage)?;
+                let token_overrides = storage
+                    .tokens_schema()
+                    .load_token_overrides()
+                    .await
+                    .map_err(Error::storage)?;
                 let list = paginated_tokens
                     .list
                     .into_iter()
                     .map(|token| {
                         let eligibility = tokens_enabled_for_fees.contains(&token.id);
-                        ApiToken::from_token_and_eligibility(token, eligibility)
+                        let token_override = token_overrides.get(&token.id);
+                        let api_token = ApiToken::from_token_and_eligibility(token, eligibility);
+                        match token_override {
+                            Some(token_override) => api_token.with_token_override(token_override),
+                            None => api_token,
+                        }
                     })
                     .collect();
                 Ok(Paginated::new(
@@ -146,10 +156,16 @@ impl ApiTokenData {
         let enabled_for_fees = self
             .is_token_enabled_for_fees(&mut storage, token.id)
             .await?;
-        Ok(ApiToken::from_token_and_eligibility(
-            token,
-            enabled_for_fees,
-        ))
+        let token_override = storage
+            .tokens_schema()
+            .get_token_override(token.id)
+            .await
+            .map_err(Error::storage)?;
+        let api_token = ApiToken::from_token_and_eligibility(token, enabled_for_fees);
+        Ok(match token_override {
+            Some(token_override) => api_token.with_token_override(&token_override),
+            None => api_token,
+        })
     }
 
     async fn token_price_usd(&self, token: TokenLike) -> Result<BigDecimal, Error> {
@@ -320,7 +336,7 @@ mod tests {
         SharedData,
     };
     use zksync_api_types::v02::{pagination::PaginationDirection, ApiVersion};
-    use zksync_types::{Address, BlockNumber, ZkSyncTx};
+    use zksync_types::{tokens::TokenOverride, Address, BlockNumber, ZkSyncTx};
 
     async fn is_token_enabled_for_fees(
         storage: &mut StorageProcessor<'_>,
@@ -503,4 +519,73 @@ mod tests {
         server.stop().await;
         Ok(())
     }
+
+    /// Checks that hidden tokens are still listed and can be resolved by the clients.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn hidden_tokens() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let hidden_token_id = TokenId(1);
+        {
+            let mut storage = cfg.pool.access_storage().await?;
+            storage
+                .tokens_schema()
+                .store_token_override(&TokenOverride {
+                    token_id: hidden_token_id,
+                    hidden: true,
+                    ..Default::default()
+                })
+                .await?;
+        }
+
+        let prices = vec![
+            (TokenLike::Id(hidden_token_id), 10_u64.into()),
+            (Address::default().into(), 1_u64.into()),
+        ];
+        let fee_ticker = dummy_fee_ticker(&prices, None);
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+        let (client, server) = cfg.start_server(
+            move |cfg| {
+                api_scope(
+                    &cfg.config,
+                    cfg.pool.clone(),
+                    TokenDBCache::new(cfg.config.api.token_config.invalidate_token_cache_period()),
+                    fee_ticker.clone(),
+                )
+            },
+            Some(shared_data),
+        );
+
+        let query = PaginationQuery {
+            from: ApiEither::from(TokenId(0)),
+            limit: 100,
+            direction: PaginationDirection::Newer,
+        };
+        let response = client.token_pagination(&query).await?;
+        let pagination: Paginated<ApiToken, TokenId> = deserialize_response_result(response)?;
+        for token in &pagination.list {
+            assert_eq!(token.hidden, token.id == hidden_token_id);
+        }
+        assert!(pagination
+            .list
+            .iter()
+            .any(|token| token.id == hidden_token_id));
+
+        let response = client.token_by_id(&TokenLike::Id(hidden_token_id)).await?;
+        let api_token: ApiToken = deserialize_response_result(response)?;
+        assert_eq!(api_token.id, hidden_token_id);
+        assert!(api_token.hidden);
+
+        server.stop().await;
+        Ok(())
+    }
 }
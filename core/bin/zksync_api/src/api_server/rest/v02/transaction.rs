@@ -270,13 +270,16 @@ mod tests {
         ApiVersion,
     };
     use zksync_mempool::MempoolTransactionRequest;
+    use zksync_test_account::ZkSyncAccount;
     use zksync_types::{
-        tokens::{Token, TokenMarketVolume},
+        helpers::closest_packable_fee_amount,
+        tokens::{Token, TokenMarketVolume, TokenOverride},
         tx::{
             EthBatchSignData, EthBatchSignatures, PackedEthSignature, TxEthSignature,
             TxEthSignatureVariant,
         },
-        Address, BlockNumber, ChainId, SignedZkSyncTx, TokenId, TokenKind, TokenLike,
+        AccountId, Address, BlockNumber, ChainId, SignedZkSyncTx, TokenId, TokenKind, TokenLike,
+        ZkSyncTx,
     };
 
     fn submit_txs_loopback() -> (mpsc::Sender<MempoolTransactionRequest>, JoinHandle<()>) {
@@ -503,4 +506,97 @@ mod tests {
         task.abort();
         Ok(())
     }
+
+    /// Checks that a token hidden by the operator can still be withdrawn.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn hidden_token_withdraw() -> anyhow::Result<()> {
+        let (sender, task) = submit_txs_loopback();
+
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let token = {
+            let mut storage = cfg.pool.access_storage().await?;
+            storage
+                .tokens_schema()
+                .store_token_override(&TokenOverride {
+                    token_id: TokenId(1),
+                    hidden: true,
+                    ..Default::default()
+                })
+                .await?;
+            storage
+                .tokens_schema()
+                .get_token(TokenLike::Id(TokenId(1)))
+                .await?
+                .unwrap()
+        };
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+
+        let mut tokens = HashMap::new();
+        tokens.insert(TokenLike::Id(token.id), token.clone());
+        let mut market = HashMap::new();
+        market.insert(
+            token.id,
+            TokenMarketVolume {
+                market_volume: Ratio::from_integer(BigUint::from(400u32)),
+                last_updated: Utc::now(),
+            },
+        );
+        let cache = TokenInMemoryCache::new()
+            .with_tokens(tokens)
+            .with_market(market);
+
+        let prices = vec![
+            (TokenLike::Id(token.id), 10500_u64.into()),
+            (Address::default().into(), 100000_u64.into()),
+        ];
+
+        let (client, server) = cfg.start_server(
+            move |cfg: &TestServerConfig| {
+                api_scope(TxSender::new(
+                    cfg.pool.clone(),
+                    dummy_sign_verifier(),
+                    dummy_fee_ticker(&prices, Some(cache.clone())),
+                    &cfg.config.api.common,
+                    &cfg.config.api.token_config,
+                    sender.clone(),
+                    ChainId(cfg.config.eth_client.chain_id),
+                ))
+            },
+            Some(shared_data),
+        );
+
+        let account = ZkSyncAccount::rand();
+        account.set_account_id(Some(AccountId(0xf00d)));
+        let (withdraw, eth_signature) = account.sign_withdraw(
+            token.id,
+            &token.symbol,
+            1_u64.into(),
+            closest_packable_fee_amount(&100_u64.into()),
+            &account.address,
+            None,
+            false,
+            Default::default(),
+        );
+        let tx = ZkSyncTx::Withdraw(Box::new(withdraw));
+        let signature =
+            TxEthSignatureVariant::Single(eth_signature.map(TxEthSignature::EthereumSignature));
+
+        let response = client.submit_tx(tx.clone(), signature).await?;
+        let tx_hash: TxHash = deserialize_response_result(response)?;
+        assert_eq!(tx.hash(), tx_hash);
+
+        server.stop().await;
+        task.abort();
+        Ok(())
+    }
 }
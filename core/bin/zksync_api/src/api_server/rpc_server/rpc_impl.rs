@@ -14,8 +14,9 @@ use zksync_api_types::{
 };
 use zksync_crypto::params::MIN_NFT_TOKEN_ID;
 use zksync_types::{
+    tokens::ListedToken,
     tx::{EthBatchSignatures, TxEthSignatureVariant, TxHash},
-    AccountId, Address, Fee, Token, TokenId, TokenLike, TotalFee, TxFeeTypes, ZkSyncTx,
+    AccountId, Address, Fee, TokenId, TokenLike, TotalFee, TxFeeTypes, ZkSyncTx,
 };
 // Local uses
 use crate::{
@@ -249,22 +250,34 @@ impl RpcApp {
         Ok(nft)
     }
 
-    pub async fn _impl_tokens(self) -> Result<HashMap<String, Token>> {
+    pub async fn _impl_tokens(self) -> Result<HashMap<String, ListedToken>> {
         let start = Instant::now();
         let mut storage = self.access_storage().await?;
         let mut tokens = storage.tokens_schema().load_tokens().await.map_err(|err| {
             vlog::warn!("Internal Server Error: '{}'; input: N/A", err);
             Error::internal_error()
         })?;
+        let token_overrides = storage
+            .tokens_schema()
+            .load_token_overrides()
+            .await
+            .map_err(|err| {
+                vlog::warn!("Internal Server Error: '{}'; input: N/A", err);
+                Error::internal_error()
+            })?;
 
         let result: HashMap<_, _> = tokens
             .drain()
             .map(|(id, token)| {
-                if *id == 0 {
-                    ("ETH".to_string(), token)
+                let hidden = token_overrides
+                    .get(&id)
+                    .map_or(false, |token_override| token_override.hidden);
+                let symbol = if *id == 0 {
+                    "ETH".to_string()
                 } else {
-                    (token.symbol.clone(), token)
-                }
+                    token.symbol.clone()
+                };
+                (symbol, ListedToken { token, hidden })
             })
             .collect();
 
@@ -15,8 +15,9 @@ use zksync_api_types::{
 };
 use zksync_crypto::params::ZKSYNC_VERSION;
 use zksync_types::{
+    tokens::ListedToken,
     tx::{EthBatchSignatures, TxEthSignatureVariant, TxHash},
-    AccountId, Address, Fee, Token, TokenId, TokenLike, TotalFee, ZkSyncTx,
+    AccountId, Address, Fee, TokenId, TokenLike, TotalFee, ZkSyncTx,
 };
 
 // Local uses
@@ -63,8 +64,8 @@ pub trait Rpc {
     fn contract_address(&self) -> BoxFutureResult<ContractAddressResp>;
 
     /// "ETH" | #ERC20_ADDRESS => {Token}
-    #[rpc(name = "tokens", returns = "Token")]
-    fn tokens(&self) -> BoxFutureResult<HashMap<String, Token>>;
+    #[rpc(name = "tokens", returns = "ListedToken")]
+    fn tokens(&self) -> BoxFutureResult<HashMap<String, ListedToken>>;
 
     // _address argument is left for the backward compatibility.
     #[rpc(name = "get_tx_fee", returns = "Fee")]
@@ -150,7 +151,7 @@ impl Rpc for RpcApp {
         spawn!(self._impl_contract_address())
     }
 
-    fn tokens(&self) -> BoxFutureResult<HashMap<String, Token>> {
+    fn tokens(&self) -> BoxFutureResult<HashMap<String, ListedToken>> {
         spawn!(self._impl_tokens())
     }
 
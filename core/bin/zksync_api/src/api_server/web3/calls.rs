@@ -34,6 +34,13 @@ pub struct CallsHelper {
 impl CallsHelper {
     const SHA256_MULTI_HASH: [u8; 2] = [18, 32]; // 0x1220
     const ALPHABET: &'static str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+    /// ERC-165 identifiers of the interfaces implemented by the NFT factory:
+    /// ERC-165 itself, ERC-721 and ERC-721 metadata extension.
+    const NFT_FACTORY_INTERFACES: [Selector; 3] = [
+        [0x01, 0xff, 0xc9, 0xa7],
+        [0x80, 0xac, 0x58, 0xcd],
+        [0x5b, 0x5e, 0x13, 0x9f],
+    ];
 
     fn revert_error(message: &str) -> Error {
         Error {
@@ -261,6 +268,33 @@ impl CallsHelper {
                         ));
                     }
                 }
+                "supportsInterface" => {
+                    let interface_id = params[0]
+                        .clone()
+                        .into_fixed_bytes()
+                        .ok_or_else(Error::internal_error)?;
+                    let supported = Self::NFT_FACTORY_INTERFACES
+                        .iter()
+                        .any(|id| id[..] == interface_id[..]);
+                    encode(&[AbiToken::Bool(supported)])
+                }
+                "getNftsByOwner" => {
+                    let address = params[0]
+                        .clone()
+                        .into_address()
+                        .ok_or_else(Error::internal_error)?;
+                    let nfts = transaction
+                        .chain()
+                        .account_schema()
+                        .get_account_nfts(address)
+                        .await
+                        .map_err(|_| Error::internal_error())?;
+                    let nfts = nfts
+                        .into_iter()
+                        .map(|id| AbiToken::Uint(U256::from(id.0)))
+                        .collect();
+                    encode(&[AbiToken::Array(nfts)])
+                }
                 _ => unreachable!(),
             }
         } else {
@@ -273,7 +307,17 @@ impl CallsHelper {
             match function.name.as_str() {
                 "name" | "symbol" => encode(&[AbiToken::String(token.symbol)]),
                 "decimals" => encode(&[AbiToken::Uint(U256::from(token.decimals))]),
-                "totalSupply" | "allowance" => encode(&[AbiToken::Uint(U256::max_value())]),
+                "totalSupply" => {
+                    let total_supply = transaction
+                        .chain()
+                        .account_schema()
+                        .get_token_total_supply(token.id)
+                        .await
+                        .map_err(|_| Error::internal_error())?;
+                    encode(&[AbiToken::Uint(u256_from_biguint(total_supply))])
+                }
+                // There are no approvals in zkSync.
+                "allowance" => encode(&[AbiToken::Uint(U256::zero())]),
                 "balanceOf" => {
                     let block = transaction
                         .chain()
@@ -0,0 +1,112 @@
+//! Registry of the filters installed via `eth_newFilter` and `eth_newBlockFilter`.
+
+// Built-in uses
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+// External uses
+use jsonrpc_core::{Error, Result};
+// Workspace uses
+use zksync_types::BlockNumber;
+// Local uses
+use super::types::{Filter, U256};
+
+/// Max amount of the filters that may be installed at the same time.
+pub const MAX_INSTALLED_FILTERS: usize = 10_000;
+/// Filters that were not polled for this time are uninstalled.
+pub const FILTER_TTL: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum InstalledFilter {
+    Logs(Filter),
+    Blocks,
+}
+
+#[derive(Debug, Clone)]
+struct FilterEntry {
+    filter: InstalledFilter,
+    last_seen_block: BlockNumber,
+    last_access: Instant,
+}
+
+/// Thread-safe storage of the installed filters.
+///
+/// Every filter remembers the last block that was reported to the client,
+/// so `eth_getFilterChanges` only returns data for the blocks created since the previous poll.
+#[derive(Debug, Clone)]
+pub struct FilterRegistry {
+    filters: Arc<Mutex<HashMap<U256, FilterEntry>>>,
+    max_filters: usize,
+    ttl: Duration,
+}
+
+impl FilterRegistry {
+    pub fn new(max_filters: usize, ttl: Duration) -> Self {
+        Self {
+            filters: Arc::new(Mutex::new(HashMap::new())),
+            max_filters,
+            ttl,
+        }
+    }
+
+    fn remove_expired(&self, filters: &mut HashMap<U256, FilterEntry>) {
+        let ttl = self.ttl;
+        filters.retain(|_, entry| entry.last_access.elapsed() < ttl);
+    }
+
+    /// Installs the filter and returns its ID.
+    pub fn install(&self, filter: InstalledFilter, last_seen_block: BlockNumber) -> Result<U256> {
+        let mut filters = self.filters.lock().unwrap();
+        self.remove_expired(&mut filters);
+        if filters.len() >= self.max_filters {
+            return Err(Error::invalid_params("Too many filters are installed"));
+        }
+
+        let mut id = U256::from(zksync_crypto::rand::random::<u64>());
+        while filters.contains_key(&id) {
+            id = U256::from(zksync_crypto::rand::random::<u64>());
+        }
+        filters.insert(
+            id,
+            FilterEntry {
+                filter,
+                last_seen_block,
+                last_access: Instant::now(),
+            },
+        );
+        Ok(id)
+    }
+
+    /// Returns the filter with the last block reported to the client, refreshing its TTL.
+    pub fn get(&self, id: U256) -> Option<(InstalledFilter, BlockNumber)> {
+        let mut filters = self.filters.lock().unwrap();
+        self.remove_expired(&mut filters);
+        filters.get_mut(&id).map(|entry| {
+            entry.last_access = Instant::now();
+            (entry.filter.clone(), entry.last_seen_block)
+        })
+    }
+
+    /// Updates the last block reported to the client.
+    /// Does nothing if the filter was uninstalled in the meantime.
+    pub fn update_last_seen_block(&self, id: U256, last_seen_block: BlockNumber) {
+        let mut filters = self.filters.lock().unwrap();
+        if let Some(entry) = filters.get_mut(&id) {
+            entry.last_seen_block = entry.last_seen_block.max(last_seen_block);
+            entry.last_access = Instant::now();
+        }
+    }
+
+    /// Removes the filter, returns `false` if there was no such filter.
+    pub fn uninstall(&self, id: U256) -> bool {
+        let mut filters = self.filters.lock().unwrap();
+        self.remove_expired(&mut filters);
+        filters.remove(&id).is_some()
+    }
+}
+
+impl Default for FilterRegistry {
+    fn default() -> Self {
+        Self::new(MAX_INSTALLED_FILTERS, FILTER_TTL)
+    }
+}
@@ -14,7 +14,7 @@ use zksync_types::{Nonce, Token, TokenId, TokenKind, ZkSyncOp, NFT};
 // Local uses
 use super::{
     converter::{log, u256_from_biguint},
-    types::{Bytes, CommonLogData, Event, Log, H160, H256, U256},
+    types::{Bytes, CommonLogData, Event, Filter, Log, H160, H256, U256},
     NFT_FACTORY_ADDRESS, ZKSYNC_PROXY_ADDRESS,
 };
 
@@ -632,3 +632,59 @@ impl LogsHelper {
         bytes.into()
     }
 }
+
+/// Address and topic constraints of the `Filter`, used to select the logs
+/// both for `eth_getLogs` and for the `logs` subscriptions.
+#[derive(Debug, Clone, Default)]
+pub struct LogsMatcher {
+    topics: Vec<H256>,
+    addresses: Vec<H160>,
+    matches_nothing: bool,
+}
+
+impl LogsMatcher {
+    pub fn new(filter: &Filter) -> Self {
+        let addresses = filter
+            .address
+            .as_ref()
+            .map(|a| a.0.clone())
+            .unwrap_or_default();
+        let topics = filter.topics.clone().unwrap_or_default();
+        // If there is non-null topic at the non-first position then nothing can match,
+        // since all our logs contain exactly one topic.
+        let matches_nothing = topics
+            .iter()
+            .enumerate()
+            .any(|(i, topic)| i > 0 && topic.is_some());
+        let topics = topics
+            .into_iter()
+            .next()
+            .flatten()
+            .map(|topic| topic.0)
+            .unwrap_or_default();
+
+        Self {
+            topics,
+            addresses,
+            matches_nothing,
+        }
+    }
+
+    /// Returns `true` if no log can satisfy the filter.
+    pub fn matches_nothing(&self) -> bool {
+        self.matches_nothing
+    }
+
+    pub fn matches(&self, log: &Log) -> bool {
+        if self.matches_nothing {
+            return false;
+        }
+        if !self.topics.is_empty() && !self.topics.contains(&log.topics[0]) {
+            return false;
+        }
+        if !self.addresses.is_empty() && !self.addresses.contains(&log.address) {
+            return false;
+        }
+        true
+    }
+}
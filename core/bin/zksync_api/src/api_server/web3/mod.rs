@@ -1,14 +1,24 @@
 // Built-in uses
+use std::sync::Arc;
+use std::time::Duration;
 // External uses
-
+use futures::channel::mpsc;
 use jsonrpc_core::{Error, IoHandler, MetaIoHandler, Metadata, Middleware, Result};
 use jsonrpc_http_server::ServerBuilder;
+use jsonrpc_pubsub::{PubSubHandler, Session};
+use jsonrpc_ws_server::RequestContext;
 // Workspace uses
 
 use zksync_storage::{ConnectionPool, StorageProcessor};
 use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
 // Local uses
-use self::{calls::CallsHelper, logs::LogsHelper, rpc_trait::Web3Rpc};
+use self::{
+    calls::CallsHelper,
+    filters::FilterRegistry,
+    logs::LogsHelper,
+    pubsub::{start_web3_sub_notifier, Web3PubSub, Web3PubSubApp},
+    rpc_trait::Web3Rpc,
+};
 
 use tokio::task::JoinHandle;
 use zksync_config::configs::api::{TokenConfig, Web3Config};
@@ -16,7 +26,9 @@ use zksync_types::ChainId;
 
 mod calls;
 mod converter;
+mod filters;
 mod logs;
+mod pubsub;
 mod rpc_impl;
 mod rpc_trait;
 #[cfg(test)]
@@ -31,6 +43,7 @@ pub struct Web3RpcApp {
     connection_pool: ConnectionPool,
     logs_helper: LogsHelper,
     calls_helper: CallsHelper,
+    filters: FilterRegistry,
     max_block_range: u32,
     chain_id: ChainId,
 }
@@ -45,6 +58,7 @@ impl Web3RpcApp {
             connection_pool,
             logs_helper: LogsHelper::new(token_config.invalidate_token_cache_period()),
             calls_helper: CallsHelper::new(token_config.invalidate_token_cache_period()),
+            filters: FilterRegistry::default(),
             max_block_range: config.max_block_range,
             chain_id: ChainId(config.chain_id),
         }
@@ -86,3 +100,39 @@ pub fn start_rpc_server(
     });
     handler
 }
+
+/// Starts the WebSocket server which serves both the regular web3 methods
+/// and the `eth_subscribe` subscriptions.
+pub fn start_ws_rpc_server(
+    connection_pool: ConnectionPool,
+    web3_config: &Web3Config,
+    token_config: &TokenConfig,
+    miniblock_iteration_interval: Duration,
+) -> JoinHandle<()> {
+    let addr = web3_config.ws_bind_addr();
+
+    let rpc_app = Web3RpcApp::new(connection_pool, web3_config, token_config);
+    let (sub_sender, sub_receiver) = mpsc::channel(2048);
+    start_web3_sub_notifier(rpc_app.clone(), sub_receiver, miniblock_iteration_interval);
+
+    let (handler, panic_sender) = spawn_panic_handler();
+
+    std::thread::spawn(move || {
+        let _panic_sentinel = ThreadPanicNotify(panic_sender);
+        let mut io = PubSubHandler::new(MetaIoHandler::default());
+
+        rpc_app.extend(&mut io);
+        io.extend_with(Web3PubSubApp::new(sub_sender).to_delegate());
+
+        let server = jsonrpc_ws_server::ServerBuilder::with_meta_extractor(
+            io,
+            |context: &RequestContext| Arc::new(Session::new(context.sender())),
+        )
+        .max_connections(1000)
+        .start(&addr)
+        .expect("Unable to start web3 ws server");
+
+        server.wait().expect("web3 ws server start");
+    });
+    handler
+}
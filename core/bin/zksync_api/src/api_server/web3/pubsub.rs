@@ -0,0 +1,294 @@
+//! Web3 subscriptions (`eth_subscribe` / `eth_unsubscribe`) served over WebSocket.
+//!
+//! Subscribers are notified once the block is finalized, which is consistent with
+//! the `latest` block of the HTTP API.
+
+// Built-in uses
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+// External uses
+use futures::{channel::mpsc, select, stream::StreamExt};
+use jsonrpc_core::{Error, Result};
+use jsonrpc_derive::rpc;
+use jsonrpc_pubsub::{
+    typed::{Sink, Subscriber},
+    SubscriptionId,
+};
+use tokio::task::JoinHandle;
+// Workspace uses
+use zksync_types::aggregated_operations::AggregatedOperation;
+use zksync_types::BlockNumber;
+// Local uses
+use super::{
+    logs::LogsMatcher,
+    types::{Filter, PubSubResult, SubscriptionKind},
+    Web3RpcApp,
+};
+use crate::api_server::event_notify::EventFetcher;
+
+const NOTIFIER_CHANNEL_CAPACITY: usize = 32_768;
+/// Max amount of the active subscriptions for one server.
+const MAX_SUBSCRIPTIONS: usize = 65_536;
+
+#[rpc]
+pub trait Web3PubSub {
+    type Metadata;
+
+    #[pubsub(subscription = "eth_subscription", subscribe, name = "eth_subscribe")]
+    fn subscribe(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<PubSubResult>,
+        kind: SubscriptionKind,
+        filter: Option<Filter>,
+    );
+
+    #[pubsub(
+        subscription = "eth_subscription",
+        unsubscribe,
+        name = "eth_unsubscribe"
+    )]
+    fn unsubscribe(&self, meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool>;
+}
+
+pub enum Web3SubRequest {
+    Sub {
+        kind: SubscriptionKind,
+        filter: Option<Filter>,
+        subscriber: Subscriber<PubSubResult>,
+    },
+    Unsub(SubscriptionId),
+}
+
+#[derive(Debug, Clone)]
+pub struct Web3PubSubApp {
+    sub_sender: mpsc::Sender<Web3SubRequest>,
+}
+
+impl Web3PubSubApp {
+    pub fn new(sub_sender: mpsc::Sender<Web3SubRequest>) -> Self {
+        Self { sub_sender }
+    }
+}
+
+impl Web3PubSub for Web3PubSubApp {
+    type Metadata = std::sync::Arc<jsonrpc_pubsub::Session>;
+
+    fn subscribe(
+        &self,
+        _meta: Self::Metadata,
+        subscriber: Subscriber<PubSubResult>,
+        kind: SubscriptionKind,
+        filter: Option<Filter>,
+    ) {
+        if let Err(err) = self.sub_sender.clone().try_send(Web3SubRequest::Sub {
+            kind,
+            filter,
+            subscriber,
+        }) {
+            // The notifier is overloaded, the subscriber has to retry later.
+            if let Web3SubRequest::Sub { subscriber, .. } = err.into_inner() {
+                subscriber
+                    .reject(Error::internal_error())
+                    .unwrap_or_default();
+            }
+        }
+    }
+
+    fn unsubscribe(&self, _meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool> {
+        self.sub_sender
+            .clone()
+            .try_send(Web3SubRequest::Unsub(id))
+            .map_err(|_| Error::internal_error())?;
+        Ok(true)
+    }
+}
+
+struct Web3Subscription {
+    kind: SubscriptionKind,
+    matcher: LogsMatcher,
+    sink: Sink<PubSubResult>,
+}
+
+/// Actor that broadcasts new finalized blocks and their logs to the web3 subscribers.
+pub struct Web3SubNotifier {
+    rpc_app: Web3RpcApp,
+    subscriptions: HashMap<SubscriptionId, Web3Subscription>,
+    /// The event fetcher delivers the same operation once per block it covers,
+    /// so blocks up to this one have already been announced.
+    last_notified_block: Option<BlockNumber>,
+}
+
+impl Web3SubNotifier {
+    pub fn new(rpc_app: Web3RpcApp) -> Self {
+        Self {
+            rpc_app,
+            subscriptions: HashMap::new(),
+            last_notified_block: None,
+        }
+    }
+
+    fn generate_sub_id() -> SubscriptionId {
+        SubscriptionId::String(format!(
+            "0x{:016x}{:016x}",
+            zksync_crypto::rand::random::<u64>(),
+            zksync_crypto::rand::random::<u64>()
+        ))
+    }
+
+    /// Handles incoming subscription/unsubscription request.
+    pub fn handle_sub_request(&mut self, request: Web3SubRequest) -> anyhow::Result<()> {
+        match request {
+            Web3SubRequest::Sub {
+                kind,
+                filter,
+                subscriber,
+            } => {
+                if self.subscriptions.len() >= MAX_SUBSCRIPTIONS {
+                    subscriber
+                        .reject(Error::invalid_params("Too many active subscriptions"))
+                        .map_err(|_| anyhow::format_err!("SubReject"))?;
+                    return Ok(());
+                }
+                let matcher = filter.as_ref().map(LogsMatcher::new).unwrap_or_default();
+                let sub_id = Self::generate_sub_id();
+                let sink = subscriber
+                    .assign_id(sub_id.clone())
+                    .map_err(|_| anyhow::format_err!("SubIdAssign"))?;
+                self.subscriptions.insert(
+                    sub_id,
+                    Web3Subscription {
+                        kind,
+                        matcher,
+                        sink,
+                    },
+                );
+            }
+            Web3SubRequest::Unsub(sub_id) => {
+                self.subscriptions.remove(&sub_id);
+            }
+        }
+        Ok(())
+    }
+
+    /// Processes new block action, notifying the subscribers about executed blocks.
+    pub async fn handle_new_block(
+        &mut self,
+        aggregated_operation: AggregatedOperation,
+    ) -> anyhow::Result<()> {
+        let blocks = match aggregated_operation {
+            AggregatedOperation::ExecuteBlocks(operation) => operation.blocks,
+            _ => return Ok(()),
+        };
+        for block in blocks {
+            if matches!(self.last_notified_block, Some(last) if block.block_number <= last) {
+                continue;
+            }
+            self.notify_block(block.block_number).await?;
+            self.last_notified_block = Some(block.block_number);
+        }
+        Ok(())
+    }
+
+    /// Sends the header and the logs of the block to the interested subscribers.
+    pub async fn notify_block(&mut self, block_number: BlockNumber) -> anyhow::Result<()> {
+        let start = Instant::now();
+        let has_subscription = |kind| self.subscriptions.values().any(|sub| sub.kind == kind);
+        let notify_heads = has_subscription(SubscriptionKind::NewHeads);
+        let notify_logs = has_subscription(SubscriptionKind::Logs);
+        if !notify_heads && !notify_logs {
+            return Ok(());
+        }
+
+        let mut storage = self
+            .rpc_app
+            .access_storage()
+            .await
+            .map_err(|err| anyhow::format_err!("{}", err.message))?;
+        let header = if notify_heads {
+            let header = Web3RpcApp::block_by_number(&mut storage, block_number, false)
+                .await
+                .map_err(|err| anyhow::format_err!("{}", err.message))?;
+            Some(header)
+        } else {
+            None
+        };
+        let logs = if notify_logs {
+            self.rpc_app
+                .block_logs(&mut storage, block_number)
+                .await
+                .map_err(|err| anyhow::format_err!("{}", err.message))?
+        } else {
+            Vec::new()
+        };
+        drop(storage);
+
+        self.subscriptions.retain(|sub_id, sub| {
+            let sent = match sub.kind {
+                SubscriptionKind::NewHeads => header.iter().try_for_each(|header| {
+                    sub.sink.notify(Ok(PubSubResult::Header(header.clone())))
+                }),
+                SubscriptionKind::Logs => logs
+                    .iter()
+                    .filter(|log| sub.matcher.matches(log))
+                    .try_for_each(|log| sub.sink.notify(Ok(PubSubResult::Log(log.clone())))),
+            };
+            if let Err(err) = sent {
+                // Subscriber has gone away, there is no reason to keep the subscription.
+                vlog::debug!("Dropping web3 subscription {:?}: {}", sub_id, err);
+                return false;
+            }
+            true
+        });
+
+        metrics::histogram!("api.web3.notifier.notify_block", start.elapsed());
+        Ok(())
+    }
+}
+
+pub fn start_web3_sub_notifier(
+    rpc_app: Web3RpcApp,
+    mut sub_receiver: mpsc::Receiver<Web3SubRequest>,
+    miniblock_interval: Duration,
+) -> JoinHandle<()> {
+    let (new_block_sender, mut new_block_receiver) = mpsc::channel(NOTIFIER_CHANNEL_CAPACITY);
+    // Pending block updates are not exposed through the web3 subscriptions,
+    // so the receiving side of this channel is dropped right away.
+    let (new_txs_sender, _) = mpsc::channel(NOTIFIER_CHANNEL_CAPACITY);
+
+    let mut notifier = Web3SubNotifier::new(rpc_app.clone());
+
+    tokio::spawn(async move {
+        let fetcher = EventFetcher::new(
+            rpc_app.connection_pool.clone(),
+            miniblock_interval,
+            new_block_sender,
+            new_txs_sender,
+        )
+        .await
+        .expect("Unable to create event fetcher");
+
+        tokio::spawn(fetcher.run());
+
+        loop {
+            select! {
+                new_block = new_block_receiver.next() => {
+                    if let Some(new_block) = new_block {
+                        notifier.handle_new_block(new_block)
+                            .await
+                            .map_err(|e| vlog::warn!("Failed to handle new block: {}", e))
+                            .unwrap_or_default();
+                    }
+                },
+                new_sub = sub_receiver.next() => {
+                    if let Some(new_sub) = new_sub {
+                        notifier.handle_sub_request(new_sub)
+                            .map_err(|e| vlog::warn!("Failed to handle subscription request: {}", e))
+                            .unwrap_or_default();
+                    }
+                },
+                complete => break,
+            }
+        }
+    })
+}
@@ -14,9 +14,11 @@ use zksync_types::{ExecutedOperations, TokenId, ZkSyncOp};
 // Local uses
 use super::{
     converter::{resolve_block_number, transaction_from_tx_data, u256_from_biguint},
+    filters::InstalledFilter,
+    logs::LogsMatcher,
     types::{
-        BlockInfo, BlockNumber, Bytes, CallRequest, CommonLogData, Filter, Log, Transaction,
-        TransactionReceipt, TxData, H160, H2048, H256, U256, U64,
+        BlockInfo, BlockNumber, Bytes, CallRequest, CommonLogData, Filter, FilterChanges, Log,
+        Transaction, TransactionReceipt, TxData, H160, H2048, H256, U256, U64,
     },
     Web3RpcApp,
 };
@@ -253,55 +255,122 @@ impl Web3RpcApp {
             )));
         }
 
-        let topics = if let Some(mut topics) = filter.topics {
-            // If there is non-null topic at the non-first position then return empty vec,
-            // since all our logs contain exactly one topic.
-            let has_not_first = topics
-                .iter()
-                .enumerate()
-                .any(|(i, topic)| i > 0 && topic.is_some());
-            if has_not_first {
-                return Ok(Vec::new());
-            } else if topics.is_empty() {
-                Vec::new()
-            } else {
-                topics.remove(0).unwrap_or_default().0
-            }
-        } else {
-            Vec::new()
-        };
-        let addresses = filter.address.map(|a| a.0).unwrap_or_default();
-        let mut result = Vec::new();
+        let result = self
+            .logs_in_range(&mut transaction, from_block, to_block, &filter)
+            .await?;
 
-        let receipts = transaction
-            .chain()
-            .operations_ext_schema()
-            .web3_receipts(from_block, to_block)
+        transaction
+            .commit()
             .await
             .map_err(|_| Error::internal_error())?;
-        for receipt in receipts {
-            let logs = self.logs_from_receipt(&mut transaction, receipt).await?;
-            let filtered = logs.into_iter().filter(|log| {
-                if !topics.is_empty() && !topics.contains(&log.topics[0]) {
-                    return false;
-                }
-                if !addresses.is_empty() && !addresses.contains(&log.address) {
-                    return false;
-                }
-                true
-            });
-            result.extend(filtered);
-        }
 
+        metrics::histogram!("api", start.elapsed(), "type" => "web3", "endpoint_name" => "get_logs");
+        Ok(result)
+    }
+
+    pub async fn _impl_new_filter(self, filter: Filter) -> Result<U256> {
+        let start = Instant::now();
+        let mut storage = self.access_storage().await?;
+
+        let last_block = Self::last_block(&mut storage).await?;
+        let result = self
+            .filters
+            .install(InstalledFilter::Logs(filter), last_block);
+
+        metrics::histogram!("api", start.elapsed(), "type" => "web3", "endpoint_name" => "new_filter");
+        result
+    }
+
+    pub async fn _impl_new_block_filter(self) -> Result<U256> {
+        let start = Instant::now();
+        let mut storage = self.access_storage().await?;
+
+        let last_block = Self::last_block(&mut storage).await?;
+        let result = self.filters.install(InstalledFilter::Blocks, last_block);
+
+        metrics::histogram!("api", start.elapsed(), "type" => "web3", "endpoint_name" => "new_block_filter");
+        result
+    }
+
+    pub async fn _impl_get_filter_changes(self, filter_id: U256) -> Result<FilterChanges> {
+        let start = Instant::now();
+        let (filter, last_seen_block) = self
+            .filters
+            .get(filter_id)
+            .ok_or_else(|| Error::invalid_params("Filter not found"))?;
+
+        let mut storage = self.access_storage().await?;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(|_| Error::internal_error())?;
+
+        // Changes are reported in chunks not larger than `max_block_range`,
+        // the rest of them will be returned on the next poll.
+        let from_block = last_seen_block + 1;
+        let to_block = Self::last_block(&mut transaction)
+            .await?
+            .min(from_block + self.max_block_range);
+
+        let result = match filter {
+            InstalledFilter::Blocks => {
+                let mut hashes = Vec::new();
+                for block_number in *from_block..=*to_block {
+                    let block = Self::storage_block(
+                        &mut transaction,
+                        zksync_types::BlockNumber(block_number),
+                    )
+                    .await?
+                    .ok_or_else(Error::internal_error)?;
+                    hashes.push(H256::from_slice(&block.root_hash));
+                }
+                FilterChanges::Hashes(hashes)
+            }
+            InstalledFilter::Logs(filter) => {
+                // Block range of the filter narrows the range of the new blocks.
+                let from_block = match filter.from_block {
+                    Some(block) => resolve_block_number(&mut transaction, Some(block))
+                        .await?
+                        // The first block of the filter may be not created yet.
+                        .map_or(to_block + 1, |block| block.max(from_block)),
+                    None => from_block,
+                };
+                let to_block = match filter.to_block {
+                    Some(block) => resolve_block_number(&mut transaction, Some(block))
+                        .await?
+                        .map_or(to_block, |block| block.min(to_block)),
+                    None => to_block,
+                };
+
+                let logs = if from_block <= to_block {
+                    self.logs_in_range(&mut transaction, from_block, to_block, &filter)
+                        .await?
+                } else {
+                    Vec::new()
+                };
+                FilterChanges::Logs(logs)
+            }
+        };
         transaction
             .commit()
             .await
             .map_err(|_| Error::internal_error())?;
+        if from_block <= to_block {
+            self.filters.update_last_seen_block(filter_id, to_block);
+        }
 
-        metrics::histogram!("api", start.elapsed(), "type" => "web3", "endpoint_name" => "get_logs");
+        metrics::histogram!("api", start.elapsed(), "type" => "web3", "endpoint_name" => "get_filter_changes");
         Ok(result)
     }
 
+    pub async fn _impl_get_filter_logs(self, filter_id: U256) -> Result<Vec<Log>> {
+        let filter = match self.filters.get(filter_id) {
+            Some((InstalledFilter::Logs(filter), _)) => filter,
+            _ => return Err(Error::invalid_params("Filter not found")),
+        };
+        self._impl_get_logs(filter).await
+    }
+
     pub async fn _impl_call(self, req: CallRequest, _block: Option<BlockNumber>) -> Result<Bytes> {
         let start = Instant::now();
         let mut storage = self.access_storage().await?;
@@ -361,6 +430,60 @@ impl Web3RpcApp {
         Ok(logs)
     }
 
+    /// Returns the logs emitted in the blocks range that match the filter.
+    /// Block range of the filter itself is not taken into account.
+    async fn logs_in_range(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        from_block: zksync_types::BlockNumber,
+        to_block: zksync_types::BlockNumber,
+        filter: &Filter,
+    ) -> Result<Vec<Log>> {
+        let matcher = LogsMatcher::new(filter);
+        if matcher.matches_nothing() {
+            return Ok(Vec::new());
+        }
+        let mut result = Vec::new();
+
+        let receipts = storage
+            .chain()
+            .operations_ext_schema()
+            .web3_receipts(from_block, to_block)
+            .await
+            .map_err(|_| Error::internal_error())?;
+        for receipt in receipts {
+            let logs = self.logs_from_receipt(storage, receipt).await?;
+            result.extend(logs.into_iter().filter(|log| matcher.matches(log)));
+        }
+        Ok(result)
+    }
+
+    async fn last_block(storage: &mut StorageProcessor<'_>) -> Result<zksync_types::BlockNumber> {
+        // `None` is resolved to the `latest` block which always exists.
+        resolve_block_number(storage, None)
+            .await?
+            .ok_or_else(Error::internal_error)
+    }
+
+    /// Returns all the logs emitted in the block.
+    pub(crate) async fn block_logs(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        block_number: zksync_types::BlockNumber,
+    ) -> Result<Vec<Log>> {
+        let receipts = storage
+            .chain()
+            .operations_ext_schema()
+            .web3_receipts(block_number, block_number)
+            .await
+            .map_err(|_| Error::internal_error())?;
+        let mut logs = Vec::new();
+        for receipt in receipts {
+            logs.extend(self.logs_from_receipt(storage, receipt).await?);
+        }
+        Ok(logs)
+    }
+
     pub(crate) async fn tx_receipt(
         &self,
         storage: &mut StorageProcessor<'_>,
@@ -5,8 +5,8 @@ use zksync_types::withdrawals::WithdrawalPendingEvent;
 // Local uses
 use super::{
     types::{
-        BlockInfo, BlockNumber, Bytes, CallRequest, Filter, Log, Transaction, TransactionReceipt,
-        H160, H256, U256, U64,
+        BlockInfo, BlockNumber, Bytes, CallRequest, Filter, FilterChanges, Log, Transaction,
+        TransactionReceipt, H160, H256, U256, U64,
     },
     Web3RpcApp,
 };
@@ -93,6 +93,21 @@ pub trait Web3Rpc {
     #[rpc(name = "eth_getLogs", returns = "Vec<Log>")]
     fn get_logs(&self, filter: Filter) -> BoxFutureResult<Vec<Log>>;
 
+    #[rpc(name = "eth_newFilter", returns = "U256")]
+    fn new_filter(&self, filter: Filter) -> BoxFutureResult<U256>;
+
+    #[rpc(name = "eth_newBlockFilter", returns = "U256")]
+    fn new_block_filter(&self) -> BoxFutureResult<U256>;
+
+    #[rpc(name = "eth_getFilterChanges", returns = "FilterChanges")]
+    fn get_filter_changes(&self, filter_id: U256) -> BoxFutureResult<FilterChanges>;
+
+    #[rpc(name = "eth_getFilterLogs", returns = "Vec<Log>")]
+    fn get_filter_logs(&self, filter_id: U256) -> BoxFutureResult<Vec<Log>>;
+
+    #[rpc(name = "eth_uninstallFilter", returns = "bool")]
+    fn uninstall_filter(&self, filter_id: U256) -> Result<bool>;
+
     #[rpc(name = "eth_call", returns = "Bytes")]
     fn call(&self, req: CallRequest, _block: Option<BlockNumber>) -> BoxFutureResult<Bytes>;
 
@@ -184,6 +199,26 @@ impl Web3Rpc for Web3RpcApp {
         spawn!(self._impl_get_logs(filter))
     }
 
+    fn new_filter(&self, filter: Filter) -> BoxFutureResult<U256> {
+        spawn!(self._impl_new_filter(filter))
+    }
+
+    fn new_block_filter(&self) -> BoxFutureResult<U256> {
+        spawn!(self._impl_new_block_filter())
+    }
+
+    fn get_filter_changes(&self, filter_id: U256) -> BoxFutureResult<FilterChanges> {
+        spawn!(self._impl_get_filter_changes(filter_id))
+    }
+
+    fn get_filter_logs(&self, filter_id: U256) -> BoxFutureResult<Vec<Log>> {
+        spawn!(self._impl_get_filter_logs(filter_id))
+    }
+
+    fn uninstall_filter(&self, filter_id: U256) -> Result<bool> {
+        Ok(self.filters.uninstall(filter_id))
+    }
+
     fn call(&self, req: CallRequest, block: Option<BlockNumber>) -> BoxFutureResult<Bytes> {
         spawn! { self._impl_call(req, block) }
     }
@@ -1,10 +1,13 @@
 // Built-in uses
 use std::str::FromStr;
+use std::time::Duration;
 // External uses
 use ethabi::{ParamType, Token};
 use futures::future::{join, join5, Future};
+use futures::StreamExt;
 use jsonrpc_core::{Error, ErrorCode, IoHandler, Params};
 use jsonrpc_core_client::{RawClient, RpcError, RpcResult};
+use jsonrpc_pubsub::typed::Subscriber;
 use num::BigUint;
 use serde_json::{Map, Value};
 // Workspace uses
@@ -12,18 +15,25 @@ use serde_json::{Map, Value};
 use zksync_storage::{chain::operations_ext::records::Web3TxReceipt, ConnectionPool};
 use zksync_test_account::ZkSyncAccount;
 use zksync_types::{
-    tx::ChangePubKeyType, AccountId, AccountUpdate, BlockNumber, ChangePubKeyOp, CloseOp, Deposit,
-    DepositOp, ForcedExitOp, FullExit, FullExitOp, MintNFTOp, Nonce, SwapOp, TokenId, TransferOp,
+    aggregated_operations::{AggregatedOperation, BlocksExecuteOperation},
+    tx::ChangePubKeyType,
+    AccountId, AccountUpdate, BlockNumber, ChangePubKeyOp, CloseOp, Deposit, DepositOp,
+    ForcedExitOp, FullExit, FullExitOp, MintNFTOp, Nonce, SwapOp, TokenId, TransferOp,
     WithdrawNFTOp, WithdrawOp, ZkSyncOp, NFT,
 };
 // Local uses
 use super::{
     calls::CallsHelper,
     converter::{transaction_from_tx_data, u256_from_biguint},
-    types::{BlockInfo, Event, Log, Transaction, TransactionReceipt, H160, H256, U256, U64},
+    filters::{FilterRegistry, InstalledFilter},
+    pubsub::{Web3SubNotifier, Web3SubRequest},
+    types::{
+        BlockInfo, BlockNumber as Web3BlockNumber, Event, Filter, FilterChanges, Log,
+        SubscriptionKind, Transaction, TransactionReceipt, ValueOrArray, H160, H256, U256, U64,
+    },
     Web3RpcApp, NFT_FACTORY_ADDRESS, ZKSYNC_PROXY_ADDRESS,
 };
-use crate::api_server::rest::v02::test_utils::TestServerConfig;
+use crate::api_server::rest::v02::test_utils::{TestServerConfig, EXECUTED_BLOCKS_COUNT};
 use zksync_config::configs::api::{TokenConfig, Web3Config};
 
 async fn local_client() -> anyhow::Result<(RawClient, impl Future<Output = RpcResult<()>>)> {
@@ -908,6 +918,8 @@ async fn get_logs() -> anyhow::Result<()> {
             let config = Web3Config {
                 port: 0,
                 url: "".to_string(),
+                ws_port: 0,
+                ws_url: "".to_string(),
                 max_block_range: 3,
                 chain_id: 9,
             };
@@ -1042,6 +1054,285 @@ async fn get_logs() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Tests `eth_newFilter`, `eth_newBlockFilter`, `eth_getFilterChanges` and `eth_uninstallFilter` methods.
+#[tokio::test(flavor = "multi_thread")]
+#[cfg_attr(
+    not(feature = "api_test"),
+    ignore = "Use `zk test rust-api` command to perform this test"
+)]
+async fn filters() -> anyhow::Result<()> {
+    let cfg = TestServerConfig::default();
+    cfg.fill_database().await?;
+    let rpc_app = Web3RpcApp::new(
+        cfg.pool.clone(),
+        &cfg.config.api.web3,
+        &cfg.config.api.token_config,
+    );
+
+    // New filters are created at the latest block, so there are no changes yet.
+    let block_filter = rpc_app.clone()._impl_new_block_filter().await?;
+    let changes = rpc_app
+        .clone()
+        ._impl_get_filter_changes(block_filter)
+        .await?;
+    assert_eq!(changes, FilterChanges::Hashes(Vec::new()));
+    let logs_filter = rpc_app.clone()._impl_new_filter(Filter::default()).await?;
+    let changes = rpc_app
+        .clone()
+        ._impl_get_filter_changes(logs_filter)
+        .await?;
+    assert_eq!(changes, FilterChanges::Logs(Vec::new()));
+
+    // Block filter that has seen only the genesis block reports all the blocks up to the latest one.
+    let block_filter = rpc_app
+        .filters
+        .install(InstalledFilter::Blocks, BlockNumber(0))?;
+    let mut hashes = Vec::new();
+    loop {
+        match rpc_app
+            .clone()
+            ._impl_get_filter_changes(block_filter)
+            .await?
+        {
+            FilterChanges::Hashes(new_hashes) if new_hashes.is_empty() => break,
+            FilterChanges::Hashes(new_hashes) => hashes.extend(new_hashes),
+            changes => panic!("Unexpected changes: {:?}", changes),
+        }
+    }
+    let expected_hashes = {
+        let mut storage = cfg.pool.access_storage().await?;
+        let last_block = storage
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await?;
+        let mut hashes = Vec::new();
+        for block_number in 1..=*last_block {
+            let block = storage
+                .chain()
+                .block_schema()
+                .get_storage_block(BlockNumber(block_number))
+                .await?
+                .unwrap();
+            hashes.push(H256::from_slice(&block.root_hash));
+        }
+        hashes
+    };
+    assert_eq!(hashes, expected_hashes);
+
+    // Logs filter respects its own block range and reports every log only once.
+    let filter = Filter {
+        from_block: Some(Web3BlockNumber::Number(1.into())),
+        to_block: Some(Web3BlockNumber::Number(1.into())),
+        ..Default::default()
+    };
+    let expected_logs = rpc_app.clone()._impl_get_logs(filter.clone()).await?;
+    assert!(!expected_logs.is_empty());
+    let logs_filter = rpc_app
+        .filters
+        .install(InstalledFilter::Logs(filter.clone()), BlockNumber(0))?;
+    let changes = rpc_app
+        .clone()
+        ._impl_get_filter_changes(logs_filter)
+        .await?;
+    assert_eq!(changes, FilterChanges::Logs(expected_logs.clone()));
+    let changes = rpc_app
+        .clone()
+        ._impl_get_filter_changes(logs_filter)
+        .await?;
+    assert_eq!(changes, FilterChanges::Logs(Vec::new()));
+    let logs = rpc_app.clone()._impl_get_filter_logs(logs_filter).await?;
+    assert_eq!(logs, expected_logs);
+
+    // Filters are not shared between the servers.
+    let fut = {
+        let (client, server) = local_client().await?;
+        let id = Value::String(format!("{:#x}", logs_filter));
+        join(
+            client.call_method("eth_uninstallFilter", Params::Array(vec![id])),
+            server,
+        )
+    };
+    assert_eq!(fut.await.0.unwrap(), Value::Bool(false));
+    // Uninstalled filters cannot be polled.
+    assert!(rpc_app.filters.uninstall(logs_filter));
+    assert!(!rpc_app.filters.uninstall(logs_filter));
+    assert!(rpc_app
+        .clone()
+        ._impl_get_filter_changes(logs_filter)
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+/// Checks that the filter registry is bounded and evicts the filters that are not polled.
+#[test]
+fn filter_registry_eviction() {
+    let registry = FilterRegistry::new(1, Duration::from_secs(3600));
+    let id = registry
+        .install(InstalledFilter::Blocks, BlockNumber(1))
+        .unwrap();
+    assert!(registry
+        .install(InstalledFilter::Blocks, BlockNumber(1))
+        .is_err());
+    assert_eq!(
+        registry.get(id),
+        Some((InstalledFilter::Blocks, BlockNumber(1)))
+    );
+    registry.update_last_seen_block(id, BlockNumber(5));
+    assert_eq!(
+        registry.get(id),
+        Some((InstalledFilter::Blocks, BlockNumber(5)))
+    );
+
+    let registry = FilterRegistry::new(1, Duration::from_secs(0));
+    let id = registry
+        .install(InstalledFilter::Blocks, BlockNumber(1))
+        .unwrap();
+    // Expired filter is evicted and doesn't occupy the slot anymore.
+    assert!(registry
+        .install(InstalledFilter::Blocks, BlockNumber(1))
+        .is_ok());
+    assert_eq!(registry.get(id), None);
+}
+
+/// Tests that `eth_subscribe` subscribers receive headers and the logs matching their filters.
+#[tokio::test(flavor = "multi_thread")]
+#[cfg_attr(
+    not(feature = "api_test"),
+    ignore = "Use `zk test rust-api` command to perform this test"
+)]
+async fn subscriptions() -> anyhow::Result<()> {
+    fn notification_result(notification: String) -> Value {
+        let notification: Value = serde_json::from_str(&notification).unwrap();
+        assert_eq!(notification["method"], "eth_subscription");
+        notification["params"]["result"].clone()
+    }
+
+    let cfg = TestServerConfig::default();
+    cfg.fill_database().await?;
+    let rpc_app = Web3RpcApp::new(
+        cfg.pool.clone(),
+        &cfg.config.api.web3,
+        &cfg.config.api.token_config,
+    );
+    let mut notifier = Web3SubNotifier::new(rpc_app.clone());
+
+    let (subscriber, heads_id, mut heads) = Subscriber::new_test("eth_subscription");
+    notifier.handle_sub_request(Web3SubRequest::Sub {
+        kind: SubscriptionKind::NewHeads,
+        filter: None,
+        subscriber,
+    })?;
+    let heads_id = heads_id.await.unwrap().unwrap();
+
+    let proxy_address = H160::from_str(ZKSYNC_PROXY_ADDRESS).unwrap();
+    let filter = Filter {
+        address: Some(ValueOrArray(vec![proxy_address])),
+        ..Default::default()
+    };
+    let (subscriber, logs_id, mut logs) = Subscriber::new_test("eth_subscription");
+    notifier.handle_sub_request(Web3SubRequest::Sub {
+        kind: SubscriptionKind::Logs,
+        filter: Some(filter),
+        subscriber,
+    })?;
+    logs_id.await.unwrap().unwrap();
+
+    notifier.notify_block(BlockNumber(1)).await?;
+
+    let (expected_header, expected_logs) = {
+        let mut storage = cfg.pool.access_storage().await?;
+        let header = Web3RpcApp::block_by_number(&mut storage, BlockNumber(1), false).await?;
+        let logs: Vec<Log> = rpc_app
+            .block_logs(&mut storage, BlockNumber(1))
+            .await?
+            .into_iter()
+            .filter(|log| log.address == proxy_address)
+            .collect();
+        (header, logs)
+    };
+    assert!(!expected_logs.is_empty());
+
+    let header = notification_result(heads.next().await.unwrap());
+    assert_eq!(
+        serde_json::from_value::<BlockInfo>(header).unwrap(),
+        expected_header
+    );
+    for expected_log in expected_logs {
+        let log = notification_result(logs.next().await.unwrap());
+        assert_eq!(serde_json::from_value::<Log>(log).unwrap(), expected_log);
+    }
+    assert!(!matches!(logs.try_next(), Ok(Some(_))));
+
+    // Unsubscribed subscriber should not be notified anymore.
+    notifier.handle_sub_request(Web3SubRequest::Unsub(heads_id))?;
+    notifier.notify_block(BlockNumber(1)).await?;
+    assert!(!matches!(heads.try_next(), Ok(Some(_))));
+    assert!(matches!(logs.try_next(), Ok(Some(_))));
+
+    Ok(())
+}
+
+/// Tests that each executed block is announced once, even though the event fetcher
+/// delivers a multi-block `ExecuteBlocks` operation once per block it covers.
+#[tokio::test(flavor = "multi_thread")]
+#[cfg_attr(
+    not(feature = "api_test"),
+    ignore = "Use `zk test rust-api` command to perform this test"
+)]
+async fn subscriptions_multi_block_execute() -> anyhow::Result<()> {
+    let cfg = TestServerConfig::default();
+    cfg.fill_database().await?;
+    let rpc_app = Web3RpcApp::new(
+        cfg.pool.clone(),
+        &cfg.config.api.web3,
+        &cfg.config.api.token_config,
+    );
+    let mut notifier = Web3SubNotifier::new(rpc_app);
+
+    let (subscriber, heads_id, mut heads) = Subscriber::new_test("eth_subscription");
+    notifier.handle_sub_request(Web3SubRequest::Sub {
+        kind: SubscriptionKind::NewHeads,
+        filter: None,
+        subscriber,
+    })?;
+    heads_id.await.unwrap().unwrap();
+
+    let mut blocks = Vec::new();
+    {
+        let mut storage = cfg.pool.access_storage().await?;
+        for block_number in 1..=EXECUTED_BLOCKS_COUNT {
+            let block = storage
+                .chain()
+                .block_schema()
+                .get_block(BlockNumber(block_number))
+                .await?
+                .unwrap();
+            blocks.push(block);
+        }
+    }
+    let operation = AggregatedOperation::ExecuteBlocks(BlocksExecuteOperation { blocks });
+
+    // The event fetcher sends the operation once for every block it contains.
+    for _ in 1..=EXECUTED_BLOCKS_COUNT {
+        notifier.handle_new_block(operation.clone()).await?;
+    }
+
+    let mut storage = cfg.pool.access_storage().await?;
+    for block_number in 1..=EXECUTED_BLOCKS_COUNT {
+        let expected_header =
+            Web3RpcApp::block_by_number(&mut storage, BlockNumber(block_number), false).await?;
+        let notification: Value = serde_json::from_str(&heads.next().await.unwrap())?;
+        let header: BlockInfo = serde_json::from_value(notification["params"]["result"].clone())?;
+        assert_eq!(header, expected_header);
+    }
+    assert!(!matches!(heads.try_next(), Ok(Some(_))));
+
+    Ok(())
+}
+
 /// Tests `eth_call` method for erc20 contracts
 #[tokio::test(flavor = "multi_thread")]
 #[cfg_attr(
@@ -1172,7 +1463,18 @@ async fn erc20_calls() -> anyhow::Result<()> {
         &hex::decode(resp_data.strip_prefix("0x").unwrap()).unwrap(),
     )
     .unwrap();
-    assert_eq!(outputs[0].clone().into_uint().unwrap(), U256::max_value());
+    let expected_total_supply = {
+        let mut storage = pool.access_storage().await?;
+        storage
+            .chain()
+            .account_schema()
+            .get_token_total_supply(token.id)
+            .await?
+    };
+    assert_eq!(
+        outputs[0].clone().into_uint().unwrap(),
+        u256_from_biguint(expected_total_supply)
+    );
 
     // Test `balanceOf` function.
     let fut = {
@@ -1226,7 +1528,7 @@ async fn erc20_calls() -> anyhow::Result<()> {
         &hex::decode(resp_data.strip_prefix("0x").unwrap()).unwrap(),
     )
     .unwrap();
-    assert_eq!(outputs[0].clone().into_uint().unwrap(), U256::max_value());
+    assert_eq!(outputs[0].clone().into_uint().unwrap(), U256::zero());
 
     Ok(())
 }
@@ -1499,6 +1801,76 @@ async fn erc721_calls() -> anyhow::Result<()> {
         U256::from(expected_balance)
     );
 
+    // Test `supportsInterface` function.
+    for (interface_id, expected) in [
+        ("01ffc9a7", true),
+        ("80ac58cd", true),
+        ("5b5e139f", true),
+        ("ffffffff", false),
+    ]
+    .iter()
+    {
+        let fut = {
+            let (client, server) = local_client().await?;
+            let mut req = Map::new();
+            req.insert(
+                "to".to_string(),
+                Value::String(format!("{:#?}", nft_factory_address)),
+            );
+            let mut data = "0x01ffc9a7".to_string();
+            let interface_id = ethabi::encode(&[Token::FixedBytes(hex::decode(interface_id)?)]);
+            data.push_str(hex::encode(interface_id).as_str());
+            req.insert("data".to_string(), Value::String(data));
+            join(
+                client.call_method("eth_call", Params::Array(vec![Value::Object(req)])),
+                server,
+            )
+        };
+        let resp_data = fut.await.0.unwrap();
+        let resp_data = serde_json::from_value::<String>(resp_data).unwrap();
+        let outputs = ethabi::decode(
+            &[ParamType::Bool],
+            &hex::decode(resp_data.strip_prefix("0x").unwrap()).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(outputs[0].clone().into_bool().unwrap(), *expected);
+    }
+
+    // Test `getNftsByOwner` function.
+    let fut = {
+        let (client, server) = local_client().await?;
+        let mut req = Map::new();
+        req.insert(
+            "to".to_string(),
+            Value::String(format!("{:#?}", nft_factory_address)),
+        );
+        let selector = ethabi::short_signature("getNftsByOwner", &[ParamType::Address]);
+        let mut data = format!("0x{}", hex::encode(selector));
+        let address = ethabi::encode(&[Token::Address(expected_owner)]);
+        data.push_str(hex::encode(address).as_str());
+        req.insert("data".to_string(), Value::String(data));
+        join(
+            client.call_method("eth_call", Params::Array(vec![Value::Object(req)])),
+            server,
+        )
+    };
+    let resp_data = fut.await.0.unwrap();
+    let resp_data = serde_json::from_value::<String>(resp_data).unwrap();
+    let outputs = ethabi::decode(
+        &[ParamType::Array(Box::new(ParamType::Uint(256)))],
+        &hex::decode(resp_data.strip_prefix("0x").unwrap()).unwrap(),
+    )
+    .unwrap();
+    let nfts: Vec<U256> = outputs[0]
+        .clone()
+        .into_array()
+        .unwrap()
+        .into_iter()
+        .map(|token| token.into_uint().unwrap())
+        .collect();
+    assert_eq!(nfts.len(), expected_balance as usize);
+    assert!(nfts.contains(&U256::from(nft.id.0)));
+
     Ok(())
 }
 
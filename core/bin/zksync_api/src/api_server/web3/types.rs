@@ -248,6 +248,34 @@ pub struct Filter {
     pub topics: Option<Vec<Option<ValueOrArray<H256>>>>,
 }
 
+/// Result of the `eth_getFilterChanges` method.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FilterChanges {
+    /// Hashes of the new blocks, for filters created with `eth_newBlockFilter`.
+    Hashes(Vec<H256>),
+    /// New logs, for filters created with `eth_newFilter`.
+    Logs(Vec<Log>),
+}
+
+/// Kind of the `eth_subscribe` subscription.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SubscriptionKind {
+    /// Header of every new finalized block.
+    NewHeads,
+    /// Logs of finalized blocks that match the provided filter.
+    Logs,
+}
+
+/// Notification sent to the `eth_subscribe` subscribers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PubSubResult {
+    Header(BlockInfo),
+    Log(Log),
+}
+
 #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
 pub enum Event {
     ZkSyncTransfer,
@@ -5,7 +5,10 @@ use tokio::sync::Mutex;
 use zksync_token_db_cache::TokenDBCache;
 
 use zksync_storage::ConnectionPool;
-use zksync_types::{tokens::TokenMarketVolume, Token, TokenId, TokenLike};
+use zksync_types::{
+    tokens::{TokenMarketVolume, TokenPrice},
+    Token, TokenId, TokenLike,
+};
 
 #[derive(Debug, Clone)]
 pub(crate) enum TokenCacheWrapper {
@@ -25,6 +28,8 @@ pub(crate) struct TokenInDBCache {
 pub struct TokenInMemoryCache {
     tokens: Arc<Mutex<HashMap<TokenLike, Token>>>,
     market: Arc<Mutex<HashMap<TokenId, TokenMarketVolume>>>,
+    prices: Arc<Mutex<HashMap<TokenId, TokenPrice>>>,
+    fee_token_overrides: Arc<Mutex<HashMap<TokenId, bool>>>,
 }
 
 impl TokenInDBCache {
@@ -52,6 +57,20 @@ impl TokenInMemoryCache {
             ..self
         }
     }
+
+    pub fn with_prices(self, prices: HashMap<TokenId, TokenPrice>) -> Self {
+        Self {
+            prices: Arc::new(Mutex::new(prices)),
+            ..self
+        }
+    }
+
+    pub fn with_fee_token_overrides(self, fee_token_overrides: HashMap<TokenId, bool>) -> Self {
+        Self {
+            fee_token_overrides: Arc::new(Mutex::new(fee_token_overrides)),
+            ..self
+        }
+    }
 }
 
 #[cfg(test)]
@@ -109,6 +128,36 @@ impl TokenCacheWrapper {
         }
     }
 
+    pub async fn get_token_price(&self, token_id: TokenId) -> anyhow::Result<Option<TokenPrice>> {
+        match self {
+            Self::DB(cache) => {
+                TokenDBCache::get_token_price(&mut cache.pool.access_storage().await?, token_id)
+                    .await
+            }
+            #[cfg(test)]
+            Self::Memory(cache) => Ok(cache.prices.lock().await.get(&token_id).cloned()),
+        }
+    }
+
+    pub async fn get_fee_token_override(&self, token_id: TokenId) -> anyhow::Result<Option<bool>> {
+        match self {
+            Self::DB(cache) => {
+                TokenDBCache::get_fee_token_override(
+                    &mut cache.pool.access_storage().await?,
+                    token_id,
+                )
+                .await
+            }
+            #[cfg(test)]
+            Self::Memory(cache) => Ok(cache
+                .fee_token_overrides
+                .lock()
+                .await
+                .get(&token_id)
+                .cloned()),
+        }
+    }
+
     pub async fn update_token_market_volume(
         &mut self,
         token_id: TokenId,
@@ -12,6 +12,7 @@ use std::{
 
 use bigdecimal::BigDecimal;
 use chrono::Utc;
+use num::Zero;
 
 // Workspace uses
 use zksync_types::{
@@ -118,12 +119,21 @@ impl FeeTokenValidator {
     }
 
     /// Returns `true` if token can be used to pay fees.
+    ///
+    /// The allow/deny override set by the operator takes precedence over the market volume,
+    /// but unconditionally valid tokens can't be denied. Allowed tokens must have a price anyway,
+    /// since the fee can't be calculated without it.
     pub(crate) async fn token_allowed(&self, token: TokenLike) -> anyhow::Result<bool> {
         let token = self.resolve_token(token).await?;
         if let Some(token) = token {
             if self.unconditionally_valid.contains(&token.address) {
                 return Ok(true);
             }
+            match self.tokens_cache.get_fee_token_override(token.id).await? {
+                Some(true) => return self.token_has_price(&token).await,
+                Some(false) => return Ok(false),
+                None => {}
+            }
             self.check_token(token).await
         } else {
             // Unknown tokens aren't suitable for our needs, obviously.
@@ -150,6 +160,11 @@ impl FeeTokenValidator {
         Ok(allowed)
     }
 
+    async fn token_has_price(&self, token: &Token) -> anyhow::Result<bool> {
+        let price = self.tokens_cache.get_token_price(token.id).await?;
+        Ok(price.map_or(false, |price| !price.usd_price.is_zero()))
+    }
+
     async fn get_token_market_volume(
         &self,
         token: &Token,
@@ -168,7 +183,7 @@ mod tests {
     use std::str::FromStr;
     use std::sync::Arc;
     use tokio::sync::Mutex;
-    use zksync_types::{TokenId, TokenKind};
+    use zksync_types::{tokens::TokenPrice, TokenId, TokenKind};
 
     #[derive(Clone)]
     struct InMemoryTokenWatcher {
@@ -196,10 +211,31 @@ mod tests {
         let phnx_token_address =
             Address::from_str("38A2fDc11f526Ddd5a607C1F251C065f40fBF2f7").unwrap();
         let phnx_token = Token::new(TokenId(2), phnx_token_address, "PHNX", 18, TokenKind::ERC20);
+        let scam_token_address =
+            Address::from_str("1111111111111111111111111111111111111111").unwrap();
+        let scam_token = Token::new(TokenId(3), scam_token_address, "SCAM", 18, TokenKind::ERC20);
+        let new_token_address =
+            Address::from_str("2222222222222222222222222222222222222222").unwrap();
+        let new_token = Token::new(TokenId(4), new_token_address, "NEW", 18, TokenKind::ERC20);
+        let unpriced_token_address =
+            Address::from_str("3333333333333333333333333333333333333333").unwrap();
+        let unpriced_token = Token::new(
+            TokenId(5),
+            unpriced_token_address,
+            "UNPRICED",
+            18,
+            TokenKind::ERC20,
+        );
 
         let eth_address = Address::from_str("0000000000000000000000000000000000000000").unwrap();
         let eth_token = Token::new(TokenId(2), eth_address, "ETH", 18, TokenKind::ERC20);
-        let all_tokens = vec![dai_token.clone(), phnx_token.clone()];
+        let all_tokens = vec![
+            dai_token.clone(),
+            phnx_token.clone(),
+            scam_token.clone(),
+            new_token.clone(),
+            unpriced_token.clone(),
+        ];
 
         let mut market = HashMap::new();
         market.insert(
@@ -220,16 +256,48 @@ mod tests {
         let mut tokens = HashMap::new();
         tokens.insert(TokenLike::Address(dai_token_address), dai_token.clone());
         tokens.insert(TokenLike::Address(phnx_token_address), phnx_token.clone());
+        tokens.insert(TokenLike::Address(scam_token_address), scam_token.clone());
+        tokens.insert(TokenLike::Address(new_token_address), new_token.clone());
+        tokens.insert(
+            TokenLike::Address(unpriced_token_address),
+            unpriced_token.clone(),
+        );
         tokens.insert(TokenLike::Address(eth_address), eth_token);
         let mut amounts = HashMap::new();
         amounts.insert(dai_token_address, BigDecimal::from(200));
         amounts.insert(phnx_token_address, BigDecimal::from(10));
+        amounts.insert(scam_token_address, BigDecimal::from(1000));
+        amounts.insert(new_token_address, BigDecimal::from(0));
+        amounts.insert(unpriced_token_address, BigDecimal::from(0));
+        // The operator denies the scam token and allows the new ones regardless of their market,
+        // but the token without a price still can't be used to pay fees.
+        let mut fee_token_overrides = HashMap::new();
+        fee_token_overrides.insert(scam_token.id, false);
+        fee_token_overrides.insert(new_token.id, true);
+        fee_token_overrides.insert(unpriced_token.id, true);
+        let mut prices = HashMap::new();
+        prices.insert(
+            new_token.id,
+            TokenPrice {
+                usd_price: Ratio::new(BigUint::from(1u32), BigUint::from(1u32)),
+                last_updated: Utc::now(),
+            },
+        );
+        prices.insert(
+            unpriced_token.id,
+            TokenPrice {
+                usd_price: Ratio::from_integer(BigUint::from(0u32)),
+                last_updated: Utc::now(),
+            },
+        );
         let mut unconditionally_valid = HashSet::new();
         unconditionally_valid.insert(eth_address);
 
         let cache = TokenInMemoryCache::new()
             .with_tokens(tokens)
-            .with_market(market);
+            .with_market(market)
+            .with_prices(prices)
+            .with_fee_token_overrides(fee_token_overrides);
 
         let watcher = InMemoryTokenWatcher {
             amounts: Arc::new(Mutex::new(amounts)),
@@ -280,8 +348,23 @@ mod tests {
             .token_allowed(TokenLike::Address(eth_address))
             .await
             .unwrap();
+        let scam_allowed = validator
+            .token_allowed(TokenLike::Address(scam_token_address))
+            .await
+            .unwrap();
+        let new_allowed = validator
+            .token_allowed(TokenLike::Address(new_token_address))
+            .await
+            .unwrap();
         assert!(dai_allowed);
         assert!(!phnx_allowed);
         assert!(eth_allowed);
+        assert!(!scam_allowed);
+        let unpriced_allowed = validator
+            .token_allowed(TokenLike::Address(unpriced_token_address))
+            .await
+            .unwrap();
+        assert!(new_allowed);
+        assert!(!unpriced_allowed);
     }
 }
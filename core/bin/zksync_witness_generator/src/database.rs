@@ -5,13 +5,20 @@
 
 // Built-in
 use std::clone::Clone;
+use std::time::Duration;
+// External uses
+use chrono::{DateTime, Utc};
 // Workspace uses
 use zksync_crypto::proof::{AggregatedProof, SingleProof};
+use zksync_prover_utils::api::JobRequestData;
 use zksync_storage::{ConnectionPool, StorageProcessor};
 use zksync_types::{
     aggregated_operations::{AggregatedActionType, AggregatedOperation},
     block::Block,
-    prover::{ProverJob, ProverJobType},
+    prover::{
+        FailedProverJob, ProverCapabilities, ProverJob, ProverJobFailureOutcome, ProverJobInfo,
+        ProverJobStats, ProverJobType,
+    },
     AccountMap, AccountUpdates, BlockNumber,
 };
 // Local uses
@@ -58,7 +65,7 @@ impl DatabaseInterface for Database {
         &self,
         connection: &mut StorageProcessor<'_>,
         block_number: BlockNumber,
-    ) -> anyhow::Result<Option<serde_json::Value>> {
+    ) -> anyhow::Result<Option<Vec<u8>>> {
         let witness = connection.prover_schema().get_witness(block_number).await?;
 
         Ok(witness)
@@ -69,7 +76,7 @@ impl DatabaseInterface for Database {
         connection: &mut StorageProcessor<'_>,
         first_block: BlockNumber,
         last_block: BlockNumber,
-        job_data: serde_json::Value,
+        job_data: &JobRequestData,
         job_priority: i32,
         job_type: ProverJobType,
     ) -> anyhow::Result<()> {
@@ -109,10 +116,52 @@ impl DatabaseInterface for Database {
     async fn mark_stale_jobs_as_idle(
         &self,
         connection: &mut StorageProcessor<'_>,
-    ) -> anyhow::Result<()> {
-        connection.prover_schema().mark_stale_jobs_as_idle().await?;
+        max_attempts: u32,
+    ) -> anyhow::Result<Vec<FailedProverJob>> {
+        let failed_jobs = connection
+            .prover_schema()
+            .mark_stale_jobs_as_idle(max_attempts)
+            .await?;
 
-        Ok(())
+        Ok(failed_jobs)
+    }
+
+    async fn record_prover_job_failure(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        job_id: i32,
+        prover_name: &str,
+        error: &str,
+        max_attempts: u32,
+    ) -> anyhow::Result<Option<ProverJobFailureOutcome>> {
+        let outcome = connection
+            .prover_schema()
+            .record_prover_job_failure(job_id, prover_name, error, max_attempts)
+            .await?;
+
+        Ok(outcome)
+    }
+
+    async fn load_failed_prover_jobs(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<FailedProverJob>> {
+        let failed_jobs = connection.prover_schema().load_failed_prover_jobs().await?;
+
+        Ok(failed_jobs)
+    }
+
+    async fn requeue_failed_prover_job(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        job_id: i32,
+    ) -> anyhow::Result<bool> {
+        let requeued = connection
+            .prover_schema()
+            .requeue_failed_prover_job(job_id)
+            .await?;
+
+        Ok(requeued)
     }
 
     async fn load_last_verified_block(
@@ -154,10 +203,12 @@ impl DatabaseInterface for Database {
     async fn load_idle_prover_job_from_job_queue(
         &self,
         connection: &mut StorageProcessor<'_>,
+        capabilities: &ProverCapabilities,
+        starvation_timeout: Duration,
     ) -> anyhow::Result<Option<ProverJob>> {
         let proof = connection
             .prover_schema()
-            .get_idle_prover_job_from_job_queue()
+            .get_idle_prover_job_for_capabilities(capabilities, starvation_timeout)
             .await?;
 
         Ok(proof)
@@ -277,7 +328,7 @@ impl DatabaseInterface for Database {
         &self,
         connection: &mut StorageProcessor<'_>,
         block: BlockNumber,
-        witness: serde_json::Value,
+        witness: &[u8],
     ) -> anyhow::Result<()> {
         connection
             .prover_schema()
@@ -295,4 +346,43 @@ impl DatabaseInterface for Database {
 
         Ok(count)
     }
+
+    async fn load_prover_job_stats(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        since: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<ProverJobStats>> {
+        let stats = connection
+            .prover_schema()
+            .load_prover_job_stats(since)
+            .await?;
+
+        Ok(stats)
+    }
+
+    async fn load_prover_activity(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        since: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<ProverJobInfo>> {
+        let jobs = connection
+            .prover_schema()
+            .load_prover_activity(since)
+            .await?;
+
+        Ok(jobs)
+    }
+
+    async fn remove_executed_blobs(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        limit: u32,
+    ) -> anyhow::Result<usize> {
+        let removed = connection
+            .prover_schema()
+            .remove_executed_blobs(limit)
+            .await?;
+
+        Ok(removed)
+    }
 }
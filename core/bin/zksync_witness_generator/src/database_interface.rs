@@ -6,13 +6,20 @@
 // Built-in
 use std::clone::Clone;
 use std::marker::{Send, Sync};
+use std::time::Duration;
+// External uses
+use chrono::{DateTime, Utc};
 // Workspace uses
 use zksync_crypto::proof::{AggregatedProof, SingleProof};
+use zksync_prover_utils::api::JobRequestData;
 use zksync_storage::StorageProcessor;
 use zksync_types::aggregated_operations::{AggregatedActionType, AggregatedOperation};
 use zksync_types::{
     block::Block,
-    prover::{ProverJob, ProverJobType},
+    prover::{
+        FailedProverJob, ProverCapabilities, ProverJob, ProverJobFailureOutcome, ProverJobInfo,
+        ProverJobStats, ProverJobType,
+    },
     AccountMap, AccountUpdates, BlockNumber,
 };
 
@@ -29,19 +36,19 @@ pub trait DatabaseInterface: Send + Sync + Clone + 'static {
         job_type: ProverJobType,
     ) -> anyhow::Result<BlockNumber>;
 
-    /// Returns stored witness for a block.
+    /// Returns stored encoded witness for a block.
     async fn load_witness(
         &self,
         connection: &mut StorageProcessor<'_>,
         block_number: BlockNumber,
-    ) -> anyhow::Result<Option<serde_json::Value>>;
+    ) -> anyhow::Result<Option<Vec<u8>>>;
 
     async fn add_prover_job_to_job_queue(
         &self,
         connection: &mut StorageProcessor<'_>,
         first_block: BlockNumber,
         last_block: BlockNumber,
-        job_data: serde_json::Value,
+        job_data: &JobRequestData,
         job_priority: i32,
         job_type: ProverJobType,
     ) -> anyhow::Result<()>;
@@ -59,10 +66,35 @@ pub trait DatabaseInterface: Send + Sync + Clone + 'static {
         block_number: BlockNumber,
     ) -> anyhow::Result<Option<SingleProof>>;
 
+    /// Returns timed out jobs to the queue, jobs that exhausted `max_attempts` are marked as failed.
     async fn mark_stale_jobs_as_idle(
         &self,
         connection: &mut StorageProcessor<'_>,
-    ) -> anyhow::Result<()>;
+        max_attempts: u32,
+    ) -> anyhow::Result<Vec<FailedProverJob>>;
+
+    /// Records the failure reported by the prover holding the job.
+    /// Returns `None` if the job is not in progress or is held by another prover.
+    async fn record_prover_job_failure(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        job_id: i32,
+        prover_name: &str,
+        error: &str,
+        max_attempts: u32,
+    ) -> anyhow::Result<Option<ProverJobFailureOutcome>>;
+
+    async fn load_failed_prover_jobs(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<FailedProverJob>>;
+
+    /// Returns the failed job to the queue, returns `false` if there is no such failed job.
+    async fn requeue_failed_prover_job(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        job_id: i32,
+    ) -> anyhow::Result<bool>;
 
     async fn load_last_verified_block(
         &self,
@@ -80,9 +112,13 @@ pub trait DatabaseInterface: Send + Sync + Clone + 'static {
         connection: &mut StorageProcessor<'_>,
     ) -> anyhow::Result<Option<(BlockNumber, Vec<u8>)>>;
 
+    /// Returns the idle job that fits the prover capabilities, or the job that
+    /// is waiting for longer than `starvation_timeout` if there are no such jobs.
     async fn load_idle_prover_job_from_job_queue(
         &self,
         connection: &mut StorageProcessor<'_>,
+        capabilities: &ProverCapabilities,
+        starvation_timeout: Duration,
     ) -> anyhow::Result<Option<ProverJob>>;
 
     async fn record_prover_is_working(
@@ -139,11 +175,33 @@ pub trait DatabaseInterface: Send + Sync + Clone + 'static {
         &self,
         connection: &mut StorageProcessor<'_>,
         block: BlockNumber,
-        witness: serde_json::Value,
+        witness: &[u8],
     ) -> anyhow::Result<()>;
 
     async fn pending_jobs_count(
         &self,
         connection: &mut StorageProcessor<'_>,
     ) -> anyhow::Result<u32>;
+
+    /// Loads the statistics of the pending jobs and the jobs created or proven since the given time.
+    async fn load_prover_job_stats(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        since: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<ProverJobStats>>;
+
+    /// Loads the jobs in progress along with the jobs completed or failed since the given time.
+    async fn load_prover_activity(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        since: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<ProverJobInfo>>;
+
+    /// Removes up to `limit` blobs of the blocks executed on L1 from the blob store.
+    /// Returns the amount of removed blobs.
+    async fn remove_executed_blobs(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        limit: u32,
+    ) -> anyhow::Result<usize>;
 }
@@ -0,0 +1,243 @@
+//! Observability of the prover fleet: the status of every prover and the metrics
+//! built from the prover job queue.
+
+// Built-in deps
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+// External deps
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+// Workspace deps
+use zksync_types::prover::{ProverJobInfo, ProverJobStatus};
+use zksync_types::BlockNumber;
+// Local deps
+use crate::database_interface::DatabaseInterface;
+
+/// Name used for the jobs given to the provers that haven't reported about them yet.
+const UNKNOWN_PROVER: &str = "unknown";
+
+/// Job the prover is working on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActiveProverJob {
+    pub job_id: i32,
+    pub job_type: String,
+    pub job_size: Option<u64>,
+    pub first_block: BlockNumber,
+    pub last_block: BlockNumber,
+    pub attempts: i32,
+    pub started_at: Option<DateTime<Utc>>,
+    pub last_heartbeat: DateTime<Utc>,
+    /// Time (in seconds) since the last heartbeat.
+    pub heartbeat_lag: f64,
+}
+
+/// Status of the prover, returned by the `/provers` endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProverStatus {
+    pub prover_name: String,
+    /// Jobs the prover is working on, usually there is at most one.
+    pub active_jobs: Vec<ActiveProverJob>,
+    /// Jobs proven by the prover during the observed period.
+    pub completed_jobs: u32,
+    /// Average proof duration (in seconds) of the completed jobs.
+    pub avg_proof_duration: Option<f64>,
+    /// Jobs that exhausted their attempts on this prover during the observed period.
+    pub failed_jobs: u32,
+}
+
+impl ProverStatus {
+    fn new(prover_name: String) -> Self {
+        Self {
+            prover_name,
+            active_jobs: Vec::new(),
+            completed_jobs: 0,
+            avg_proof_duration: None,
+            failed_jobs: 0,
+        }
+    }
+}
+
+fn prover_name(job: &ProverJobInfo) -> String {
+    job.prover_name
+        .clone()
+        .unwrap_or_else(|| UNKNOWN_PROVER.to_string())
+}
+
+fn proof_duration(job: &ProverJobInfo) -> Option<Duration> {
+    (job.updated_at - job.started_at?).to_std().ok()
+}
+
+/// Builds the status of every prover from the jobs in progress and the recently finished jobs.
+pub fn provers_status(jobs: &[ProverJobInfo], now: DateTime<Utc>) -> Vec<ProverStatus> {
+    let mut provers = BTreeMap::new();
+    let mut total_durations = BTreeMap::new();
+    for job in jobs {
+        let name = prover_name(job);
+        let prover = provers
+            .entry(name.clone())
+            .or_insert_with(|| ProverStatus::new(name.clone()));
+        match job.status {
+            ProverJobStatus::InProgress => prover.active_jobs.push(ActiveProverJob {
+                job_id: job.job_id,
+                job_type: job.job_type.to_string(),
+                job_size: job.job_size,
+                first_block: job.first_block,
+                last_block: job.last_block,
+                attempts: job.attempts,
+                started_at: job.started_at,
+                last_heartbeat: job.updated_at,
+                heartbeat_lag: (now - job.updated_at)
+                    .to_std()
+                    .unwrap_or_default()
+                    .as_secs_f64(),
+            }),
+            ProverJobStatus::Done => {
+                prover.completed_jobs += 1;
+                if let Some(duration) = proof_duration(job) {
+                    let (count, total) = total_durations.entry(name).or_insert((0u32, 0f64));
+                    *count += 1;
+                    *total += duration.as_secs_f64();
+                }
+            }
+            ProverJobStatus::Failed => prover.failed_jobs += 1,
+            ProverJobStatus::Idle => {}
+        }
+    }
+
+    for (name, (count, total)) in total_durations {
+        if let Some(prover) = provers.get_mut(&name) {
+            prover.avg_proof_duration = Some(total / count as f64);
+        }
+    }
+    provers.into_iter().map(|(_, prover)| prover).collect()
+}
+
+/// Reports the metrics for the jobs finished since the previous report, jobs in progress are skipped.
+fn report_finished_jobs(jobs: &[ProverJobInfo]) {
+    for job in jobs {
+        let status = match job.status {
+            ProverJobStatus::Done => "done",
+            ProverJobStatus::Failed => "failed",
+            _ => continue,
+        };
+        let job_type = job.job_type.to_string();
+        metrics::increment_counter!(
+            "prover_server.jobs",
+            "prover" => prover_name(job),
+            "job_type" => job_type.clone(),
+            "status" => status
+        );
+        if job.status != ProverJobStatus::Done {
+            continue;
+        }
+
+        let job_size = job
+            .job_size
+            .map(|size| size.to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+        if let Some(duration) = proof_duration(job) {
+            metrics::histogram!(
+                "prover_server.proof_duration",
+                duration,
+                "job_type" => job_type.clone(),
+                "job_size" => job_size
+            );
+        }
+        if let Some(wait_time) = job
+            .started_at
+            .and_then(|started_at| (started_at - job.created_at).to_std().ok())
+        {
+            metrics::histogram!(
+                "prover_server.queue_wait_time",
+                wait_time,
+                "job_type" => job_type
+            );
+        }
+    }
+}
+
+/// Updates the set of the provers reported to the metrics with the latest snapshot
+/// and returns the provers that are missing from it.
+pub(crate) fn gone_provers(
+    reported: &mut BTreeSet<String>,
+    provers: &[ProverStatus],
+) -> Vec<String> {
+    let current: BTreeSet<_> = provers
+        .iter()
+        .map(|prover| prover.prover_name.clone())
+        .collect();
+    let gone = reported.difference(&current).cloned().collect();
+    *reported = current;
+    gone
+}
+
+/// Reports the gauges for the provers working on the jobs right now.
+///
+/// Provers that disappeared since the previous report are reported with zeroes,
+/// otherwise their gauges would be stuck at the last reported values.
+fn report_active_provers(provers: &[ProverStatus], reported: &mut BTreeSet<String>) {
+    for prover_name in gone_provers(reported, provers) {
+        metrics::gauge!("prover_server.heartbeat_lag", 0.0, "prover" => prover_name.clone());
+        metrics::gauge!("prover_server.active_jobs", 0.0, "prover" => prover_name);
+    }
+
+    let mut working_provers = 0;
+    for prover in provers {
+        if !prover.active_jobs.is_empty() {
+            working_provers += 1;
+        }
+        // Provers that finished their jobs are reported with zero lag, so the gauge is not stuck.
+        let heartbeat_lag = prover
+            .active_jobs
+            .iter()
+            .map(|job| job.heartbeat_lag)
+            .fold(0.0, f64::max);
+        metrics::gauge!(
+            "prover_server.heartbeat_lag",
+            heartbeat_lag,
+            "prover" => prover.prover_name.clone()
+        );
+        metrics::gauge!(
+            "prover_server.active_jobs",
+            prover.active_jobs.len() as f64,
+            "prover" => prover.prover_name.clone()
+        );
+    }
+    metrics::gauge!("prover_server.working_provers", working_provers as f64);
+}
+
+/// Periodically reports the prover fleet metrics built from the prover job queue.
+pub async fn prover_metrics_loop<DB: DatabaseInterface>(database: DB, report_interval: Duration) {
+    let mut last_reported = Utc::now();
+    let mut reported_provers = BTreeSet::new();
+    let mut interval = tokio::time::interval(report_interval);
+    loop {
+        interval.tick().await;
+
+        let result = async {
+            let mut connection = database.acquire_connection().await?;
+            database
+                .load_prover_activity(&mut connection, last_reported)
+                .await
+        }
+        .await;
+        let jobs = match result {
+            Ok(jobs) => jobs,
+            Err(e) => {
+                vlog::warn!("Failed to load the prover activity: {}", e);
+                continue;
+            }
+        };
+
+        report_finished_jobs(&jobs);
+        report_active_provers(&provers_status(&jobs, Utc::now()), &mut reported_provers);
+        if let Some(last_finished) = jobs
+            .iter()
+            .filter(|job| job.status != ProverJobStatus::InProgress)
+            .map(|job| job.updated_at)
+            .max()
+        {
+            last_reported = last_finished;
+        }
+    }
+}
@@ -4,35 +4,31 @@ use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 // External
-use actix_web::dev::ServiceRequest;
 use actix_web::{web, App, HttpResponse, HttpServer};
-use actix_web_httpauth::extractors::{
-    bearer::{BearerAuth, Config},
-    AuthenticationError,
-};
 use actix_web_httpauth::middleware::HttpAuthentication;
-
-use jsonwebtoken::errors::Error as JwtError;
-use jsonwebtoken::{decode, DecodingKey, Validation};
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 // Workspace deps
-use zksync_config::ProverConfig;
+use zksync_auth::AuthTokenValidator;
+use zksync_config::{BlobStoreConfig, ProverConfig};
 // Local deps
 use self::database_interface::DatabaseInterface;
-use self::scaler::ScalerOracle;
+use self::scaler::{ScalerOracle, ScalerParams, ScalerReasoning};
 use tokio::task::JoinHandle;
 use zksync_circuit::serialization::ProverData;
 use zksync_config::configs::api::ProverApiConfig;
+use zksync_notifier::Notifier;
 use zksync_prover_utils::api::{
-    JobRequestData, JobResultData, ProverInputRequest, ProverInputResponse, ProverOutputRequest,
-    WorkingOn,
+    JobRequestData, JobResultData, ProverInputRequest, ProverInputResponse, ProverJobFailure,
+    ProverOutputRequest, WorkingOn,
 };
 use zksync_types::aggregated_operations::{
     AggregatedActionType, AggregatedOperation, BlocksCreateProofOperation,
 };
 use zksync_types::prover::{
-    ProverJobType, AGGREGATED_PROOF_JOB_PRIORITY, SINGLE_PROOF_JOB_PRIORITY,
+    FailedProverJob, ProverJobFailureOutcome, ProverJobType, AGGREGATED_PROOF_JOB_PRIORITY,
+    SINGLE_PROOF_JOB_PRIORITY,
 };
 use zksync_types::BlockNumber;
 use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
@@ -42,35 +38,41 @@ mod tests;
 
 pub mod database;
 mod database_interface;
+mod fleet;
 mod scaler;
 mod witness_generator;
 
-#[derive(Debug, Serialize, Deserialize)]
-struct PayloadAuthToken {
-    /// Subject (whom auth token refers to).
-    sub: String,
-    /// Expiration time (as UTC timestamp).
-    exp: usize,
-}
+/// Interval between the reports of the prover fleet metrics.
+const PROVER_METRICS_INTERVAL: Duration = Duration::from_secs(15);
 
 #[derive(Debug, Clone)]
 struct AppState<DB: DatabaseInterface> {
     secret_auth: String,
     database: DB,
     scaler_oracle: Arc<RwLock<ScalerOracle<DB>>>,
+    /// Time after which a job is given to any prover regardless of its capabilities.
+    job_starvation_timeout: Duration,
+    /// Amount of times the job is given to provers before it's marked as failed.
+    max_job_attempts: u32,
+    notifier: Option<Arc<Notifier>>,
 }
 
 impl<DB: DatabaseInterface> AppState<DB> {
-    pub fn new(secret_auth: String, database: DB, idle_provers: u32) -> Self {
-        let scaler_oracle = Arc::new(RwLock::new(ScalerOracle::new(
-            database.clone(),
-            idle_provers,
-        )));
-
+    pub fn new(
+        secret_auth: String,
+        database: DB,
+        scaler_oracle: Arc<RwLock<ScalerOracle<DB>>>,
+        job_starvation_timeout: Duration,
+        max_job_attempts: u32,
+        notifier: Option<Arc<Notifier>>,
+    ) -> Self {
         Self {
             secret_auth,
             database,
             scaler_oracle,
+            job_starvation_timeout,
+            max_job_attempts,
+            notifier,
         }
     }
 
@@ -82,39 +84,6 @@ impl<DB: DatabaseInterface> AppState<DB> {
     }
 }
 
-/// The structure that stores the secret key for checking JsonWebToken matching.
-struct AuthTokenValidator<'a> {
-    decoding_key: DecodingKey<'a>,
-}
-
-impl<'a> AuthTokenValidator<'a> {
-    fn new(secret: &'a str) -> Self {
-        Self {
-            decoding_key: DecodingKey::from_secret(secret.as_ref()),
-        }
-    }
-
-    /// Checks whether the secret key and the authorization token match.
-    fn validate_auth_token(&self, token: &str) -> Result<(), JwtError> {
-        decode::<PayloadAuthToken>(token, &self.decoding_key, &Validation::default())?;
-
-        Ok(())
-    }
-
-    async fn validator(
-        &self,
-        req: ServiceRequest,
-        credentials: BearerAuth,
-    ) -> actix_web::Result<ServiceRequest> {
-        let config = req.app_data::<Config>().cloned().unwrap_or_default();
-
-        self.validate_auth_token(credentials.token())
-            .map_err(|_| AuthenticationError::from(config))?;
-
-        Ok(req)
-    }
-}
-
 async fn status() -> actix_web::Result<String> {
     Ok("alive".into())
 }
@@ -123,14 +92,22 @@ async fn get_job<DB: DatabaseInterface>(
     data: web::Data<AppState<DB>>,
     r: web::Json<ProverInputRequest>,
 ) -> actix_web::Result<HttpResponse> {
-    vlog::trace!("request block to prove from worker: {}", r.prover_name);
+    vlog::trace!(
+        "request block to prove from worker: {}, parallel jobs: {}",
+        r.prover_name,
+        r.aux_data.parallel_jobs.unwrap_or(1)
+    );
     if r.prover_name.is_empty() {
         return Err(actix_web::error::ErrorBadRequest("empty name"));
     }
     let mut storage = data.access_storage().await?;
     let ret = data
         .database
-        .load_idle_prover_job_from_job_queue(&mut storage)
+        .load_idle_prover_job_from_job_queue(
+            &mut storage,
+            &r.aux_data.capabilities(),
+            data.job_starvation_timeout,
+        )
         .await
         .map_err(|e| {
             vlog::warn!("could not get next unverified commit operation: {}", e);
@@ -138,6 +115,14 @@ async fn get_job<DB: DatabaseInterface>(
         })?;
     if let Some(prover_job) = ret {
         vlog::info!("satisfied request to prove from worker");
+        // The first heartbeat is recorded right away, so the prover holding the job is known.
+        data.database
+            .record_prover_is_working(&mut storage, prover_job.job_id, &r.prover_name)
+            .await
+            .map_err(|e| {
+                vlog::warn!("failed to record the prover working on the job: {}", e);
+                actix_web::error::ErrorInternalServerError("storage layer error")
+            })?;
         Ok(HttpResponse::Ok().json(ProverInputResponse {
             job_id: prover_job.job_id,
             first_block: prover_job.first_block,
@@ -254,6 +239,134 @@ async fn stopped<DB: DatabaseInterface>(
     Ok(HttpResponse::Ok().finish())
 }
 
+async fn report_failure<DB: DatabaseInterface>(
+    data: web::Data<AppState<DB>>,
+    r: web::Json<ProverJobFailure>,
+) -> actix_web::Result<HttpResponse> {
+    vlog::warn!(
+        "Prover instance '{}' failed the job {}: {}",
+        r.prover_name,
+        r.job_id,
+        r.error
+    );
+    metrics::increment_counter!("prover_server.job_failures", "prover" => r.prover_name.clone());
+    let mut storage = data.access_storage().await?;
+    let outcome = data
+        .database
+        .record_prover_job_failure(
+            &mut storage,
+            r.job_id,
+            &r.prover_name,
+            &r.error,
+            data.max_job_attempts,
+        )
+        .await
+        .map_err(|e| {
+            vlog::warn!("failed to record prover job failure: {}", e);
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+    match outcome {
+        Some(ProverJobFailureOutcome::Failed(failed_job)) => {
+            notify_failed_job(data.notifier.as_deref(), &failed_job).await;
+        }
+        Some(ProverJobFailureOutcome::Requeued) => {}
+        None => {
+            return Err(actix_web::error::ErrorConflict(
+                "job is not in progress or is held by another prover",
+            ));
+        }
+    }
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+async fn provers<DB: DatabaseInterface>(
+    data: web::Data<AppState<DB>>,
+) -> actix_web::Result<HttpResponse> {
+    // Period for which the completed and failed jobs are counted.
+    const PROVERS_STATUS_PERIOD: Duration = Duration::from_secs(3600);
+
+    let since = Utc::now() - chrono::Duration::from_std(PROVERS_STATUS_PERIOD).unwrap();
+    let mut storage = data.access_storage().await?;
+    let jobs = data
+        .database
+        .load_prover_activity(&mut storage, since)
+        .await
+        .map_err(|e| {
+            vlog::warn!("failed to load the prover activity: {}", e);
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+
+    Ok(HttpResponse::Ok().json(fleet::provers_status(&jobs, Utc::now())))
+}
+
+async fn failed_jobs<DB: DatabaseInterface>(
+    data: web::Data<AppState<DB>>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    let failed_jobs = data
+        .database
+        .load_failed_prover_jobs(&mut storage)
+        .await
+        .map_err(|e| {
+            vlog::warn!("failed to load failed prover jobs: {}", e);
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+
+    Ok(HttpResponse::Ok().json(failed_jobs))
+}
+
+/// Input of the `/api/internal/prover/requeue` endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequeueJobInput {
+    /// ID of the failed job to return to the queue.
+    job_id: i32,
+}
+
+async fn requeue_job<DB: DatabaseInterface>(
+    data: web::Data<AppState<DB>>,
+    input: web::Json<RequeueJobInput>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    let requeued = data
+        .database
+        .requeue_failed_prover_job(&mut storage, input.job_id)
+        .await
+        .map_err(|e| {
+            vlog::warn!("failed to requeue prover job: {}", e);
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+    if !requeued {
+        return Err(actix_web::error::ErrorNotFound(
+            "no failed job with such id",
+        ));
+    }
+
+    vlog::info!(
+        "Failed prover job {} was returned to the queue",
+        input.job_id
+    );
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Reports the job that has exhausted its attempts.
+async fn notify_failed_job(notifier: Option<&Notifier>, job: &FailedProverJob) {
+    vlog::error!(
+        "Prover job {} for blocks [{}, {}] failed after {} attempts: {}",
+        job.job_id,
+        job.first_block,
+        job.last_block,
+        job.attempts,
+        job.last_error.as_deref().unwrap_or("unknown error")
+    );
+    if let Some(notifier) = notifier {
+        notifier
+            .send_failed_prover_job_notify(job)
+            .await
+            .unwrap_or_else(|e| vlog::warn!("Failed to send failed job notification: {}", e));
+    }
+}
+
 /// Input of the `/scaler/replicas` endpoint.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RequiredReplicasInput {
@@ -267,20 +380,31 @@ pub struct RequiredReplicasOutput {
     /// Amount of the prover entities required for server
     /// to run optimally.
     needed_count: u32,
+    /// Explanation of how `needed_count` was estimated.
+    reasoning: ScalerReasoning,
 }
 
 async fn required_replicas<DB: DatabaseInterface>(
     data: web::Data<AppState<DB>>,
-    _input: web::Json<RequiredReplicasInput>,
+    input: web::Json<RequiredReplicasInput>,
 ) -> actix_web::Result<HttpResponse> {
     let mut oracle = data.scaler_oracle.write().await;
 
-    let needed_count = oracle
-        .provers_required()
+    let (needed_count, reasoning) = oracle
+        .provers_required(input.current_count)
         .await
         .map_err(actix_web::error::ErrorInternalServerError)?;
+    vlog::debug!(
+        "Required replicas: {} (current: {}): {}",
+        needed_count,
+        input.current_count,
+        reasoning.summary
+    );
 
-    let response = RequiredReplicasOutput { needed_count };
+    let response = RequiredReplicasOutput {
+        needed_count,
+        reasoning,
+    };
 
     Ok(HttpResponse::Ok().json(response))
 }
@@ -288,6 +412,8 @@ async fn required_replicas<DB: DatabaseInterface>(
 async fn update_prover_job_queue_loop<DB: DatabaseInterface>(
     database: DB,
     prepare_data_interval: Duration,
+    max_job_attempts: u32,
+    notifier: Option<Arc<Notifier>>,
 ) {
     // We use `prepare_data_interval` as timeout in this function to align creating prover jobs
     // with witness generator routine.
@@ -296,7 +422,7 @@ async fn update_prover_job_queue_loop<DB: DatabaseInterface>(
     loop {
         interval.tick().await;
 
-        update_prover_job_queue(database.clone())
+        update_prover_job_queue(database.clone(), max_job_attempts, notifier.as_deref())
             .await
             .unwrap_or_else(|e| {
                 vlog::warn!("Failed to update prover job queue: {}", e);
@@ -304,7 +430,35 @@ async fn update_prover_job_queue_loop<DB: DatabaseInterface>(
     }
 }
 
-async fn update_prover_job_queue<DB: DatabaseInterface>(database: DB) -> anyhow::Result<()> {
+/// Removes the witnesses and proofs of the blocks executed on L1 from the blob store.
+async fn remove_executed_blobs_loop<DB: DatabaseInterface>(database: DB, gc_interval: Duration) {
+    // Maximum amount of the blobs removed per iteration, so the transaction is not held for too long.
+    const REMOVED_BLOBS_LIMIT: u32 = 1000;
+
+    let mut interval = tokio::time::interval(gc_interval);
+    loop {
+        interval.tick().await;
+
+        let result = async {
+            let mut connection = database.acquire_connection().await?;
+            database
+                .remove_executed_blobs(&mut connection, REMOVED_BLOBS_LIMIT)
+                .await
+        }
+        .await;
+        match result {
+            Ok(0) => {}
+            Ok(removed) => vlog::info!("Removed {} blobs of the executed blocks", removed),
+            Err(e) => vlog::warn!("Failed to remove blobs of the executed blocks: {}", e),
+        }
+    }
+}
+
+async fn update_prover_job_queue<DB: DatabaseInterface>(
+    database: DB,
+    max_job_attempts: u32,
+    notifier: Option<&Notifier>,
+) -> anyhow::Result<()> {
     let mut connection = database.acquire_connection().await?;
     {
         let next_single_block_to_add = database
@@ -315,18 +469,15 @@ async fn update_prover_job_queue<DB: DatabaseInterface>(database: DB) -> anyhow:
             .load_witness(&mut connection, next_single_block_to_add)
             .await?;
         if let Some(witness) = witness_for_next_single_block {
-            let prover_data: ProverData =
-                serde_json::from_value(witness).expect("incorrect single block witness");
+            let prover_data = ProverData::decode(&witness).expect("incorrect single block witness");
             let block_size = prover_data.operations.len();
-            let job_data =
-                serde_json::to_value(JobRequestData::BlockProof(prover_data, block_size))
-                    .expect("Failed to serialize single proof job data");
+            let job_data = JobRequestData::BlockProof(prover_data, block_size);
             database
                 .add_prover_job_to_job_queue(
                     &mut connection,
                     next_single_block_to_add,
                     next_single_block_to_add,
-                    job_data,
+                    &job_data,
                     SINGLE_PROOF_JOB_PRIORITY,
                     ProverJobType::SingleProof,
                 )
@@ -368,21 +519,25 @@ async fn update_prover_job_queue<DB: DatabaseInterface>(database: DB) -> anyhow:
                 let block_size = block.block_chunks_size;
                 data.push((proof, block_size));
             }
-            let job_data = serde_json::to_value(JobRequestData::AggregatedBlockProof(data))
-                .expect("Failed to serialize aggregated proof job");
+            let job_data = JobRequestData::AggregatedBlockProof(data);
             database
                 .add_prover_job_to_job_queue(
                     &mut connection,
                     first_block,
                     last_block,
-                    job_data,
+                    &job_data,
                     AGGREGATED_PROOF_JOB_PRIORITY,
                     ProverJobType::AggregatedProof,
                 )
                 .await?;
         }
     }
-    database.mark_stale_jobs_as_idle(&mut connection).await?;
+    let failed_jobs = database
+        .mark_stale_jobs_as_idle(&mut connection, max_job_attempts)
+        .await?;
+    for failed_job in failed_jobs {
+        notify_failed_job(notifier, &failed_job).await;
+    }
 
     Ok(())
 }
@@ -391,10 +546,14 @@ pub fn run_prover_server<DB: DatabaseInterface>(
     database: DB,
     prover_api_opts: ProverApiConfig,
     prover_opts: ProverConfig,
+    blob_store_opts: Option<BlobStoreConfig>,
 ) -> JoinHandle<()> {
     let witness_generator_opts = prover_opts.witness_generator;
     let core_opts = prover_opts.core;
     let (handler, panic_sender) = spawn_panic_handler();
+    let notifier = reqwest::Url::parse(&core_opts.failed_job_webhook_url)
+        .ok()
+        .map(|webhook_url| Arc::new(Notifier::with_mattermost(webhook_url)));
 
     thread::Builder::new()
         .name("prover_server".to_string())
@@ -406,7 +565,19 @@ pub fn run_prover_server<DB: DatabaseInterface>(
                 tokio::spawn(update_prover_job_queue_loop(
                     database.clone(),
                     witness_generator_opts.prepare_data_interval(),
+                    core_opts.max_job_attempts,
+                    notifier.clone(),
                 ));
+                tokio::spawn(fleet::prover_metrics_loop(
+                    database.clone(),
+                    PROVER_METRICS_INTERVAL,
+                ));
+                if let Some(blob_store_opts) = blob_store_opts {
+                    tokio::spawn(remove_executed_blobs_loop(
+                        database.clone(),
+                        blob_store_opts.gc_interval(),
+                    ));
+                }
 
                 let last_verified_block = {
                     let mut storage = database
@@ -424,9 +595,16 @@ pub fn run_prover_server<DB: DatabaseInterface>(
                 // Start pool maintainer threads.
                 let cache = Arc::new(RwLock::new(BTreeMap::default()));
 
-                for offset in 0..witness_generator_opts.witness_generators {
+                // The pipelined witness generator builds the witnesses for all the blocks by itself.
+                let pipeline_workers = witness_generator_opts.pipeline_workers;
+                let witness_generators = if pipeline_workers > 0 {
+                    1
+                } else {
+                    witness_generator_opts.witness_generators
+                };
+                for offset in 0..witness_generators {
                     let start_block = (last_verified_block + offset + 1) as u32;
-                    let block_step = witness_generator_opts.witness_generators as u32;
+                    let block_step = witness_generators as u32;
                     vlog::info!(
                         "Starting witness generator ({},{})",
                         start_block,
@@ -440,15 +618,34 @@ pub fn run_prover_server<DB: DatabaseInterface>(
                         BlockNumber(start_block),
                         BlockNumber(block_step),
                         cache.clone(),
+                        pipeline_workers,
                     );
                     pool_maintainer.start(panic_sender.clone());
                 }
                 // Start HTTP server.
                 let secret_auth = prover_api_opts.secret_auth.clone();
-                let idle_provers = core_opts.idle_provers;
+                let scaler_params = ScalerParams {
+                    idle_provers: core_opts.idle_provers,
+                    target_latency: core_opts.scaler_target_latency(),
+                    history_window: core_opts.scaler_history_window(),
+                    scale_down_delay: core_opts.scaler_scale_down_delay(),
+                };
+                // The oracle is shared between the workers, since it keeps the state of the previous decisions.
+                let scaler_oracle = Arc::new(RwLock::new(ScalerOracle::new(
+                    database.clone(),
+                    scaler_params,
+                )));
+                let job_starvation_timeout = core_opts.job_starvation_timeout();
+                let max_job_attempts = core_opts.max_job_attempts;
                 HttpServer::new(move || {
-                    let app_state =
-                        AppState::new(secret_auth.clone(), database.clone(), idle_provers);
+                    let app_state = AppState::new(
+                        secret_auth.clone(),
+                        database.clone(),
+                        scaler_oracle.clone(),
+                        job_starvation_timeout,
+                        max_job_attempts,
+                        notifier.clone(),
+                    );
 
                     let auth = HttpAuthentication::bearer(move |req, credentials| async {
                         let secret_auth = req
@@ -471,6 +668,16 @@ pub fn run_prover_server<DB: DatabaseInterface>(
                         .route("/working_on", web::post().to(working_on::<DB>))
                         .route("/publish", web::post().to(publish::<DB>))
                         .route("/stopped", web::post().to(stopped::<DB>))
+                        .route("/report_failure", web::post().to(report_failure::<DB>))
+                        .route("/provers", web::get().to(provers::<DB>))
+                        .route(
+                            "/api/internal/prover/failed_jobs",
+                            web::get().to(failed_jobs::<DB>),
+                        )
+                        .route(
+                            "/api/internal/prover/requeue",
+                            web::post().to(requeue_job::<DB>),
+                        )
                         .route(
                             "/api/internal/prover/replicas",
                             web::post().to(required_replicas::<DB>),
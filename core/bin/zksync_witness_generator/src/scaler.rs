@@ -1,34 +1,212 @@
 //! Module with utilities for prover scaler service.
 
+// Built-in deps
+use std::time::{Duration, Instant};
+// External deps
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
 // Workspace deps
+use zksync_types::prover::{ProverJobStats, ProverJobType};
+// Local deps
 use crate::database_interface::DatabaseInterface;
+
+/// Parameters of the prover scaler.
+#[derive(Debug, Clone)]
+pub struct ScalerParams {
+    /// Number of idle provers running for faster up-scaling.
+    pub idle_provers: u32,
+    /// Desired time between the job creation and receiving its proof.
+    pub target_latency: Duration,
+    /// Period of the job history used to estimate the proof durations and the jobs rate.
+    pub history_window: Duration,
+    /// Time the required amount of provers must stay lower before it's reduced.
+    pub scale_down_delay: Duration,
+}
+
+/// Explanation of the amount of provers required by the scaler oracle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScalerReasoning {
+    /// Jobs waiting for a prover or being proven right now.
+    pub pending_jobs: u32,
+    /// Pending jobs with no proof durations history, each of them requires a separate prover.
+    pub unestimated_jobs: u32,
+    /// Estimated prover time (in seconds) required to prove the pending jobs.
+    pub pending_work: f64,
+    /// Average amount of provers kept busy by the newly created jobs.
+    pub incoming_load: f64,
+    /// Desired time (in seconds) between the job creation and receiving its proof.
+    pub target_latency: u64,
+    /// Amount of provers required to prove the jobs within the target latency.
+    pub estimated_provers: u32,
+    /// Minimal amount of provers.
+    pub idle_provers: u32,
+    /// Whether the reduction of the provers amount is postponed to avoid oscillation.
+    pub scale_down_postponed: bool,
+    /// Human-readable summary of the decision.
+    pub summary: String,
+}
+
+/// Returns the expected proof duration for the job of the given type and size.
+///
+/// The average duration for the same job size is used if it's known. Otherwise, the duration
+/// for the closest larger size is taken (proving a larger job is not faster), and then the one
+/// for the closest smaller size.
+fn expected_proof_duration(
+    stats: &[ProverJobStats],
+    job_type: &ProverJobType,
+    job_size: Option<u64>,
+) -> Option<Duration> {
+    stats
+        .iter()
+        .filter(|stats| stats.job_type == *job_type)
+        .filter_map(|stats| Some((stats.job_size, stats.avg_proof_duration?)))
+        .min_by_key(|(size, _)| match (*size, job_size) {
+            (size, job_size) if size == job_size => (0, 0),
+            (Some(size), Some(job_size)) if size > job_size => (1, size - job_size),
+            (Some(size), Some(job_size)) => (2, job_size - size),
+            _ => (3, 0),
+        })
+        .map(|(_, duration)| duration)
+}
+
+/// Estimates the amount of provers required to prove the pending jobs within the target latency,
+/// while keeping up with the rate of the new jobs.
+///
+/// Proof durations are estimated from the history of the jobs of the same type and size.
+/// Pending jobs with no history are assumed to require a prover each, which matches
+/// the behavior of the scaler before any proofs are generated.
+pub fn estimate_provers(stats: &[ProverJobStats], params: &ScalerParams) -> ScalerReasoning {
+    let history_window = params.history_window.as_secs_f64().max(1.0);
+    let target_latency = params.target_latency.as_secs_f64().max(1.0);
+
+    let mut pending_jobs = 0;
+    let mut unestimated_jobs = 0;
+    let mut pending_work = 0.0;
+    let mut incoming_load = 0.0;
+    for job_stats in stats {
+        pending_jobs += job_stats.pending_jobs;
+        match expected_proof_duration(stats, &job_stats.job_type, job_stats.job_size) {
+            Some(duration) => {
+                let duration = duration.as_secs_f64();
+                pending_work += job_stats.pending_jobs as f64 * duration;
+                incoming_load += job_stats.created_jobs as f64 * duration / history_window;
+            }
+            None => unestimated_jobs += job_stats.pending_jobs,
+        }
+    }
+
+    // Provers have to keep up with the new jobs and prove the existing ones within the target latency,
+    // but a single job can't be proven by more than one prover.
+    let estimated =
+        (incoming_load + pending_work / target_latency).ceil() as u32 + unestimated_jobs;
+    let estimated_provers = estimated.min(pending_jobs + incoming_load.ceil() as u32);
+
+    let summary = format!(
+        "{} pending jobs ({} without proof history) require {:.0}s of proving, new jobs keep {:.2} provers busy: \
+         {} provers are required to meet the {}s latency target ({} idle provers at least)",
+        pending_jobs,
+        unestimated_jobs,
+        pending_work,
+        incoming_load,
+        estimated_provers,
+        params.target_latency.as_secs(),
+        params.idle_provers
+    );
+
+    ScalerReasoning {
+        pending_jobs,
+        unestimated_jobs,
+        pending_work,
+        incoming_load,
+        target_latency: params.target_latency.as_secs(),
+        estimated_provers,
+        idle_provers: params.idle_provers,
+        scale_down_postponed: false,
+        summary,
+    }
+}
+
 /// Scaler oracle provides information for prover scaler
 /// service about required amount of provers for server
 /// to operate optimally.
 #[derive(Debug)]
 pub struct ScalerOracle<DB: DatabaseInterface> {
-    /// Database access to gather the information about amount of pending blocks.
+    /// Database access to gather the information about pending jobs and proof durations.
     db: DB,
 
-    /// Number of idle provers running for faster up-scaling.
-    idle_provers: u32,
+    params: ScalerParams,
+
+    /// Amount of provers required by the last decision.
+    current_provers: Option<u32>,
+
+    /// Time since the required amount of provers is lower than the current one,
+    /// and the largest amount required during that time.
+    scale_down_candidate: Option<(Instant, u32)>,
 }
 
 impl<DB: DatabaseInterface> ScalerOracle<DB> {
-    pub fn new(db: DB, idle_provers: u32) -> Self {
-        Self { db, idle_provers }
+    pub fn new(db: DB, params: ScalerParams) -> Self {
+        Self {
+            db,
+            params,
+            current_provers: None,
+            scale_down_candidate: None,
+        }
     }
 
-    /// Decides how many prover entities should be created depending on the amount of pending blocks.
-    pub async fn provers_required(&mut self) -> anyhow::Result<u32> {
-        // Currently the logic of this method is very simple:
-        // We require a prover for each pending block or IDLE_RROVERS amount if there are not so many
-        // pending jobs.
-
+    /// Decides how many prover entities should be created depending on the pending jobs,
+    /// historical proof durations and the rate of the new jobs.
+    ///
+    /// `current_provers` is the amount of the running provers reported by the scaler service,
+    /// it's used as the starting point after the server restart.
+    pub async fn provers_required(
+        &mut self,
+        current_provers: u32,
+    ) -> anyhow::Result<(u32, ScalerReasoning)> {
+        let since = Utc::now() - chrono::Duration::from_std(self.params.history_window)?;
         let mut storage = self.db.acquire_connection().await?;
-        let pending_jobs = self.db.pending_jobs_count(&mut storage).await?;
-        let provers_required = std::cmp::max(pending_jobs, self.idle_provers);
+        let stats = self.db.load_prover_job_stats(&mut storage, since).await?;
+
+        let mut reasoning = estimate_provers(&stats, &self.params);
+        let required = std::cmp::max(reasoning.estimated_provers, self.params.idle_provers);
+        let (provers_required, scale_down_postponed) =
+            self.apply_scale_down_delay(required, current_provers, Instant::now());
+        if scale_down_postponed {
+            reasoning.scale_down_postponed = true;
+            reasoning.summary += &format!(
+                "; scale down to {} is postponed, {} provers are kept",
+                required, provers_required
+            );
+        }
+
+        Ok((provers_required, reasoning))
+    }
+
+    /// Reduces the amount of provers only if it stays lower for `scale_down_delay`,
+    /// so short gaps between blocks don't make the provers oscillate.
+    /// Returns the amount of provers and whether the scale down was postponed.
+    pub fn apply_scale_down_delay(
+        &mut self,
+        required: u32,
+        current_provers: u32,
+        now: Instant,
+    ) -> (u32, bool) {
+        let current = *self.current_provers.get_or_insert(current_provers);
+        if required >= current {
+            self.current_provers = Some(required);
+            self.scale_down_candidate = None;
+            return (required, false);
+        }
+
+        let (since, max_required) = self.scale_down_candidate.get_or_insert((now, required));
+        *max_required = std::cmp::max(*max_required, required);
+        if now.duration_since(*since) < self.params.scale_down_delay {
+            return (current, true);
+        }
 
-        Ok(provers_required)
+        let provers = *max_required;
+        self.current_provers = Some(provers);
+        self.scale_down_candidate = None;
+        (provers, false)
     }
 }
@@ -0,0 +1,148 @@
+// Built-in deps
+use std::collections::BTreeSet;
+use std::time::Duration;
+// External deps
+use chrono::{TimeZone, Utc};
+// Workspace deps
+use zksync_storage::test_data::get_sample_single_proof_job_data;
+use zksync_types::prover::{
+    ProverJobInfo, ProverJobStatus, ProverJobType, SINGLE_PROOF_JOB_PRIORITY,
+};
+use zksync_types::BlockNumber;
+// Local deps
+use super::mock::MockDatabase;
+use crate::fleet::{gone_provers, provers_status};
+use crate::DatabaseInterface;
+
+fn job_info(
+    job_id: i32,
+    status: ProverJobStatus,
+    prover_name: Option<&str>,
+    proof_duration: i64,
+) -> ProverJobInfo {
+    let created_at = Utc.ymd(2021, 6, 1).and_hms(12, 0, 0);
+    let started_at = created_at + chrono::Duration::seconds(60);
+    ProverJobInfo {
+        job_id,
+        job_type: ProverJobType::SingleProof,
+        job_size: Some(10),
+        first_block: BlockNumber(job_id as u32),
+        last_block: BlockNumber(job_id as u32),
+        status,
+        prover_name: prover_name.map(String::from),
+        attempts: 1,
+        created_at,
+        started_at: Some(started_at),
+        updated_at: started_at + chrono::Duration::seconds(proof_duration),
+    }
+}
+
+/// Checks that the jobs are grouped by the provers.
+#[test]
+fn test_provers_status() {
+    let jobs = vec![
+        job_info(1, ProverJobStatus::Done, Some("prover_1"), 100),
+        job_info(2, ProverJobStatus::Done, Some("prover_1"), 200),
+        job_info(3, ProverJobStatus::Failed, Some("prover_2"), 50),
+        job_info(4, ProverJobStatus::InProgress, Some("prover_1"), 30),
+        job_info(5, ProverJobStatus::InProgress, None, 0),
+    ];
+    let now = jobs[3].updated_at + chrono::Duration::seconds(5);
+
+    let provers = provers_status(&jobs, now);
+    let names: Vec<_> = provers
+        .iter()
+        .map(|prover| prover.prover_name.as_str())
+        .collect();
+    assert_eq!(names, vec!["prover_1", "prover_2", "unknown"]);
+
+    assert_eq!(provers[0].completed_jobs, 2);
+    assert_eq!(provers[0].avg_proof_duration, Some(150.0));
+    assert_eq!(provers[0].failed_jobs, 0);
+    assert_eq!(provers[0].active_jobs.len(), 1);
+    assert_eq!(provers[0].active_jobs[0].job_id, 4);
+    assert_eq!(provers[0].active_jobs[0].heartbeat_lag, 5.0);
+
+    assert_eq!(provers[1].completed_jobs, 0);
+    assert_eq!(provers[1].avg_proof_duration, None);
+    assert_eq!(provers[1].failed_jobs, 1);
+    assert!(provers[1].active_jobs.is_empty());
+
+    assert_eq!(provers[2].active_jobs[0].heartbeat_lag, 35.0);
+}
+
+/// Checks that the provers missing from the latest snapshot are detected, so their gauges are reset.
+#[test]
+fn test_gone_provers() {
+    let mut reported = BTreeSet::new();
+    let now = Utc.ymd(2021, 6, 1).and_hms(13, 0, 0);
+
+    let jobs = vec![
+        job_info(1, ProverJobStatus::InProgress, Some("prover_1"), 10),
+        job_info(2, ProverJobStatus::InProgress, Some("prover_2"), 10),
+    ];
+    assert!(gone_provers(&mut reported, &provers_status(&jobs, now)).is_empty());
+
+    let jobs = vec![job_info(
+        3,
+        ProverJobStatus::InProgress,
+        Some("prover_2"),
+        10,
+    )];
+    assert_eq!(
+        gone_provers(&mut reported, &provers_status(&jobs, now)),
+        vec!["prover_1".to_string()]
+    );
+    // The gone prover is reported only once.
+    assert!(gone_provers(&mut reported, &provers_status(&jobs, now)).is_empty());
+
+    assert_eq!(
+        gone_provers(&mut reported, &[]),
+        vec!["prover_2".to_string()]
+    );
+    assert!(reported.is_empty());
+}
+
+/// Checks that the prover holding the job is known from the heartbeats.
+#[tokio::test]
+async fn test_prover_activity() {
+    let database = MockDatabase::new();
+    let mut storage = database.acquire_connection().await.unwrap();
+    for block in 1..=2 {
+        database
+            .add_prover_job_to_job_queue(
+                &mut storage,
+                BlockNumber(block),
+                BlockNumber(block),
+                &get_sample_single_proof_job_data(10),
+                SINGLE_PROOF_JOB_PRIORITY,
+                ProverJobType::SingleProof,
+            )
+            .await
+            .unwrap();
+    }
+    let since = Utc::now() - chrono::Duration::hours(1);
+
+    let job = database
+        .load_idle_prover_job_from_job_queue(&mut storage, &Default::default(), Duration::ZERO)
+        .await
+        .unwrap()
+        .unwrap();
+    database
+        .record_prover_is_working(&mut storage, job.job_id, "prover_1")
+        .await
+        .unwrap();
+
+    let jobs = database
+        .load_prover_activity(&mut storage, since)
+        .await
+        .unwrap();
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].job_id, job.job_id);
+    assert_eq!(jobs[0].job_size, Some(10));
+
+    let provers = provers_status(&jobs, Utc::now());
+    assert_eq!(provers.len(), 1);
+    assert_eq!(provers[0].prover_name, "prover_1");
+    assert_eq!(provers[0].active_jobs[0].job_id, job.job_id);
+}
@@ -0,0 +1,187 @@
+// Built-in deps
+use std::time::Duration;
+// Workspace deps
+use zksync_prover_utils::api::ProverInputRequestAuxData;
+use zksync_storage::test_data::{
+    get_sample_aggregated_proof_job_data, get_sample_single_proof_job_data,
+};
+use zksync_types::prover::{
+    ProverJobFailureOutcome, ProverJobType, AGGREGATED_PROOF_JOB_PRIORITY,
+    SINGLE_PROOF_JOB_PRIORITY,
+};
+use zksync_types::BlockNumber;
+// Local deps
+use super::mock::MockDatabase;
+use crate::DatabaseInterface;
+
+const STARVATION_TIMEOUT: Duration = Duration::from_secs(3600);
+
+/// Creates the mock database with single proof jobs for blocks 1 (654 chunks)
+/// and 2 (10 chunks), and the aggregated proof job for these blocks.
+async fn database_with_jobs() -> MockDatabase {
+    let database = MockDatabase::new();
+    let mut storage = database.acquire_connection().await.unwrap();
+
+    for (block, block_size) in [(1, 654), (2, 10)] {
+        database
+            .add_prover_job_to_job_queue(
+                &mut storage,
+                BlockNumber(block),
+                BlockNumber(block),
+                &get_sample_single_proof_job_data(block_size),
+                SINGLE_PROOF_JOB_PRIORITY,
+                ProverJobType::SingleProof,
+            )
+            .await
+            .unwrap();
+    }
+    database
+        .add_prover_job_to_job_queue(
+            &mut storage,
+            BlockNumber(1),
+            BlockNumber(2),
+            &get_sample_aggregated_proof_job_data(),
+            AGGREGATED_PROOF_JOB_PRIORITY,
+            ProverJobType::AggregatedProof,
+        )
+        .await
+        .unwrap();
+    drop(storage);
+
+    database
+}
+
+/// Requests jobs until the queue is empty and returns their block ranges.
+async fn load_jobs(
+    database: &MockDatabase,
+    aux_data: ProverInputRequestAuxData,
+    starvation_timeout: Duration,
+) -> Vec<(u32, u32)> {
+    let mut storage = database.acquire_connection().await.unwrap();
+    let mut jobs = Vec::new();
+    while let Some(job) = database
+        .load_idle_prover_job_from_job_queue(
+            &mut storage,
+            &aux_data.capabilities(),
+            starvation_timeout,
+        )
+        .await
+        .unwrap()
+    {
+        jobs.push((*job.first_block, *job.last_block));
+    }
+
+    jobs
+}
+
+/// Checks that the prover without declared capabilities gets jobs in the queue order.
+#[tokio::test]
+async fn test_dispatch_without_capabilities() {
+    let database = database_with_jobs().await;
+
+    let jobs = load_jobs(&database, Default::default(), STARVATION_TIMEOUT).await;
+    assert_eq!(jobs, vec![(1, 2), (1, 1), (2, 2)]);
+}
+
+/// Checks that jobs of the preferred type are given first.
+#[tokio::test]
+async fn test_dispatch_preferred_job_type() {
+    let database = database_with_jobs().await;
+
+    let aux_data = ProverInputRequestAuxData {
+        prefer_aggregated_proof: Some(false),
+        preferred_block_size: None,
+        parallel_jobs: None,
+    };
+    let jobs = load_jobs(&database, aux_data, STARVATION_TIMEOUT).await;
+    assert_eq!(jobs, vec![(1, 1), (2, 2), (1, 2)]);
+}
+
+/// Checks that the prover doesn't get blocks larger than it's able to prove
+/// until the job is starved.
+#[tokio::test]
+async fn test_dispatch_block_size() {
+    let database = database_with_jobs().await;
+
+    let aux_data = ProverInputRequestAuxData {
+        prefer_aggregated_proof: Some(false),
+        preferred_block_size: Some(32),
+        parallel_jobs: None,
+    };
+    let jobs = load_jobs(&database, aux_data.clone(), STARVATION_TIMEOUT).await;
+    assert_eq!(jobs, vec![(2, 2), (1, 2)]);
+
+    // The job for the large block is given to anyone once it's starved.
+    let jobs = load_jobs(&database, aux_data, Duration::from_secs(0)).await;
+    assert_eq!(jobs, vec![(1, 1)]);
+}
+
+/// Checks that the failing job is retried until it exhausts the attempts
+/// and then it's moved to the failed jobs until requeued.
+#[tokio::test]
+async fn test_failed_job_retries() {
+    const MAX_ATTEMPTS: u32 = 2;
+
+    let database = database_with_jobs().await;
+    let mut storage = database.acquire_connection().await.unwrap();
+    let capabilities = ProverInputRequestAuxData::default().capabilities();
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        let job = database
+            .load_idle_prover_job_from_job_queue(&mut storage, &capabilities, STARVATION_TIMEOUT)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!((*job.first_block, *job.last_block), (1, 2));
+        database
+            .record_prover_is_working(&mut storage, job.job_id, "prover")
+            .await
+            .unwrap();
+
+        // Only the prover holding the job can report its failure.
+        let outcome = database
+            .record_prover_job_failure(&mut storage, job.job_id, "other", "Failure", MAX_ATTEMPTS)
+            .await
+            .unwrap();
+        assert_eq!(outcome, None);
+
+        let outcome = database
+            .record_prover_job_failure(&mut storage, job.job_id, "prover", "Failure", MAX_ATTEMPTS)
+            .await
+            .unwrap();
+        if attempt == MAX_ATTEMPTS {
+            assert!(matches!(outcome, Some(ProverJobFailureOutcome::Failed(_))));
+        } else {
+            assert_eq!(outcome, Some(ProverJobFailureOutcome::Requeued));
+        }
+    }
+
+    let failed_jobs = database
+        .load_failed_prover_jobs(&mut storage)
+        .await
+        .unwrap();
+    assert_eq!(failed_jobs.len(), 1);
+    assert_eq!(failed_jobs[0].attempts, MAX_ATTEMPTS as i32);
+    assert_eq!(failed_jobs[0].last_error.as_deref(), Some("Failure"));
+
+    // The failed job isn't given to the provers anymore.
+    drop(storage);
+    let jobs = load_jobs(&database, Default::default(), STARVATION_TIMEOUT).await;
+    assert_eq!(jobs, vec![(1, 1), (2, 2)]);
+
+    let mut storage = database.acquire_connection().await.unwrap();
+    let job_id = failed_jobs[0].job_id;
+    assert!(database
+        .requeue_failed_prover_job(&mut storage, job_id)
+        .await
+        .unwrap());
+    assert!(!database
+        .requeue_failed_prover_job(&mut storage, job_id)
+        .await
+        .unwrap());
+    assert!(database
+        .load_failed_prover_jobs(&mut storage)
+        .await
+        .unwrap()
+        .is_empty());
+}
@@ -5,19 +5,24 @@ use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 // External uses
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use tokio::sync::RwLock;
 use tokio::time::sleep;
 // Workspace uses
 use zksync_crypto::params::account_tree_depth;
 use zksync_crypto::proof::{AggregatedProof, SingleProof};
+use zksync_prover_utils::api::JobRequestData;
 use zksync_storage::chain::tree_cache::records::AccountTreeCache;
 use zksync_storage::prover::records::{StorageBlockWitness, StorageProverJobQueue, StoredProof};
 use zksync_storage::StorageProcessor;
 use zksync_types::{
     aggregated_operations::{AggregatedActionType, AggregatedOperation},
     block::Block,
-    prover::{ProverJob, ProverJobStatus, ProverJobType},
+    helpers::{apply_updates, reverse_updates},
+    prover::{
+        FailedProverJob, ProverCapabilities, ProverJob, ProverJobFailureOutcome, ProverJobInfo,
+        ProverJobStats, ProverJobStatus, ProverJobType,
+    },
     AccountId, AccountMap, AccountTree, AccountUpdates, Address, BlockNumber,
 };
 // Local uses
@@ -32,6 +37,8 @@ pub struct MockDatabase {
     blocks: Arc<RwLock<Vec<Block>>>,
     account_tree_cache: Arc<RwLock<AccountTreeCache>>,
     accounts_state: Arc<RwLock<(u32, AccountMap)>>,
+    /// Account updates of the blocks added via `add_block_with_updates`.
+    state_diffs: Arc<RwLock<Vec<(BlockNumber, AccountUpdates)>>>,
 }
 
 impl MockDatabase {
@@ -49,6 +56,7 @@ impl MockDatabase {
                 tree_cache_binary,
             })),
             accounts_state: Arc::new(RwLock::new((0, accounts))),
+            state_diffs: Arc::new(RwLock::new(Vec::new())),
         }
     }
 
@@ -75,6 +83,68 @@ impl MockDatabase {
     pub async fn add_block(&self, block: Block) {
         self.blocks.write().await.push(block);
     }
+
+    /// Adds the block along with the account updates it made, so the committed state follows the blocks.
+    pub async fn add_block_with_updates(&self, block: Block, updates: AccountUpdates) {
+        self.state_diffs
+            .write()
+            .await
+            .push((block.block_number, updates));
+        self.add_block(block).await;
+    }
+
+    /// Returns the account updates made by the blocks in the range `(from_block, to_block]`.
+    async fn account_updates(
+        &self,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+    ) -> AccountUpdates {
+        self.state_diffs
+            .read()
+            .await
+            .iter()
+            .filter(|(block, _)| *block > from_block && *block <= to_block)
+            .flat_map(|(_, updates)| updates.clone())
+            .collect()
+    }
+
+    /// Returns the job to the queue or marks it as failed if it exhausted the attempts.
+    fn fail_job(
+        job: &mut StorageProverJobQueue,
+        error: &str,
+        max_attempts: u32,
+    ) -> Option<FailedProverJob> {
+        job.last_error = Some(error.to_string());
+        if job.attempts >= max_attempts as i32 {
+            job.job_status = ProverJobStatus::Failed.to_number();
+            Some(Self::failed_job(job))
+        } else {
+            job.job_status = ProverJobStatus::Idle.to_number();
+            None
+        }
+    }
+
+    /// Returns the type of the job and its size as defined in `ProverJobStats`.
+    fn job_type_and_size(job: &StorageProverJobQueue) -> (ProverJobType, Option<u64>) {
+        if job.job_type == ProverJobType::SingleProof.to_string() {
+            let block_size = job.block_size.map(|size| size as u64);
+            (ProverJobType::SingleProof, block_size)
+        } else {
+            let blocks = (job.last_block - job.first_block + 1) as u64;
+            (ProverJobType::AggregatedProof, Some(blocks))
+        }
+    }
+
+    fn failed_job(job: &StorageProverJobQueue) -> FailedProverJob {
+        FailedProverJob {
+            job_id: job.id,
+            job_type: job.job_type.clone(),
+            first_block: BlockNumber(job.first_block as u32),
+            last_block: BlockNumber(job.last_block as u32),
+            attempts: job.attempts,
+            last_error: job.last_error.clone(),
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -90,7 +160,7 @@ impl DatabaseInterface for MockDatabase {
         _: &mut StorageProcessor<'_>,
         first_block: BlockNumber,
         last_block: BlockNumber,
-        job_data: serde_json::Value,
+        job_data: &JobRequestData,
         job_priority: i32,
         job_type: ProverJobType,
     ) -> anyhow::Result<()> {
@@ -108,7 +178,12 @@ impl DatabaseInterface for MockDatabase {
             updated_by: "server_add_job".to_string(),
             id,
             job_priority,
-            job_data,
+            job_data: job_data.encode()?,
+            attempts: 0,
+            last_error: None,
+            started_at: None,
+            prover_name: None,
+            block_size: job_data.block_size().map(|size| size as i64),
         };
 
         prover_job_queue.1.push(new_job);
@@ -148,6 +223,102 @@ impl DatabaseInterface for MockDatabase {
         Ok(count as u32)
     }
 
+    async fn load_prover_job_stats(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        since: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<ProverJobStats>> {
+        let mut stats: Vec<ProverJobStats> = Vec::new();
+        for job in &self.prover_job_queue.read().await.1 {
+            let (job_type, job_size) = Self::job_type_and_size(job);
+            let pending = job.job_status == ProverJobStatus::Idle.to_number()
+                || job.job_status == ProverJobStatus::InProgress.to_number();
+            let proof_duration = job
+                .started_at
+                .filter(|started_at| {
+                    job.job_status == ProverJobStatus::Done.to_number() && *started_at >= since
+                })
+                .and_then(|started_at| (job.updated_at - started_at).to_std().ok());
+
+            let index = match stats
+                .iter()
+                .position(|stats| stats.job_type == job_type && stats.job_size == job_size)
+            {
+                Some(index) => index,
+                None => {
+                    stats.push(ProverJobStats {
+                        job_type,
+                        job_size,
+                        pending_jobs: 0,
+                        created_jobs: 0,
+                        proven_jobs: 0,
+                        avg_proof_duration: None,
+                    });
+                    stats.len() - 1
+                }
+            };
+            let entry = &mut stats[index];
+            entry.pending_jobs += pending as u32;
+            entry.created_jobs += (job.created_at >= since) as u32;
+            if let Some(proof_duration) = proof_duration {
+                let total = entry.avg_proof_duration.unwrap_or_default() * entry.proven_jobs
+                    + proof_duration;
+                entry.proven_jobs += 1;
+                entry.avg_proof_duration = Some(total / entry.proven_jobs);
+            }
+        }
+        stats.retain(|stats| stats.pending_jobs + stats.created_jobs + stats.proven_jobs > 0);
+
+        Ok(stats)
+    }
+
+    async fn load_prover_activity(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        since: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<ProverJobInfo>> {
+        let mut jobs: Vec<_> = self
+            .prover_job_queue
+            .read()
+            .await
+            .1
+            .iter()
+            .filter(|job| {
+                let finished = job.job_status == ProverJobStatus::Done.to_number()
+                    || job.job_status == ProverJobStatus::Failed.to_number();
+                job.job_status == ProverJobStatus::InProgress.to_number()
+                    || (finished && job.updated_at > since)
+            })
+            .map(|job| {
+                let (job_type, job_size) = Self::job_type_and_size(job);
+                ProverJobInfo {
+                    job_id: job.id,
+                    job_type,
+                    job_size,
+                    first_block: BlockNumber(job.first_block as u32),
+                    last_block: BlockNumber(job.last_block as u32),
+                    status: ProverJobStatus::from_number(job.job_status).unwrap(),
+                    prover_name: job.prover_name.clone(),
+                    attempts: job.attempts,
+                    created_at: job.created_at,
+                    started_at: job.started_at,
+                    updated_at: job.updated_at,
+                }
+            })
+            .collect();
+        jobs.sort_by_key(|job| (job.updated_at, job.job_id));
+
+        Ok(jobs)
+    }
+
+    async fn remove_executed_blobs(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        _: u32,
+    ) -> anyhow::Result<usize> {
+        Ok(0)
+    }
+
     async fn load_aggregated_op_that_affects_block(
         &self,
         _: &mut StorageProcessor<'_>,
@@ -171,19 +342,90 @@ impl DatabaseInterface for MockDatabase {
         Ok(single_proof)
     }
 
-    async fn mark_stale_jobs_as_idle(&self, _: &mut StorageProcessor<'_>) -> anyhow::Result<()> {
+    async fn mark_stale_jobs_as_idle(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        max_attempts: u32,
+    ) -> anyhow::Result<Vec<FailedProverJob>> {
         let now = Utc::now();
         let prover_job_queue = &mut self.prover_job_queue.write().await.1;
 
+        let mut failed_jobs = Vec::new();
         for job in prover_job_queue.iter_mut() {
-            if now - job.updated_at > chrono::Duration::seconds(10) {
-                job.job_status = ProverJobStatus::Idle.to_number();
+            if job.job_status == ProverJobStatus::InProgress.to_number()
+                && now - job.updated_at > chrono::Duration::seconds(10)
+            {
                 job.updated_at = now;
                 job.updated_by = "server_clean_idle".to_string();
+                if let Some(failed_job) = Self::fail_job(job, "Job timed out", max_attempts) {
+                    failed_jobs.push(failed_job);
+                }
             }
         }
 
-        Ok(())
+        Ok(failed_jobs)
+    }
+
+    async fn record_prover_job_failure(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        job_id: i32,
+        prover_name: &str,
+        error: &str,
+        max_attempts: u32,
+    ) -> anyhow::Result<Option<ProverJobFailureOutcome>> {
+        let prover_job_queue = &mut self.prover_job_queue.write().await.1;
+        let job = prover_job_queue.iter_mut().find(|job| {
+            job.id == job_id
+                && job.job_status == ProverJobStatus::InProgress.to_number()
+                && job.updated_by == prover_name
+        });
+
+        Ok(job.map(|job| {
+            job.updated_at = Utc::now();
+            job.prover_name = Some(prover_name.to_string());
+            match Self::fail_job(job, error, max_attempts) {
+                Some(failed_job) => ProverJobFailureOutcome::Failed(failed_job),
+                None => ProverJobFailureOutcome::Requeued,
+            }
+        }))
+    }
+
+    async fn load_failed_prover_jobs(
+        &self,
+        _: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<FailedProverJob>> {
+        let failed_jobs = self
+            .prover_job_queue
+            .read()
+            .await
+            .1
+            .iter()
+            .filter(|job| job.job_status == ProverJobStatus::Failed.to_number())
+            .map(Self::failed_job)
+            .collect();
+
+        Ok(failed_jobs)
+    }
+
+    async fn requeue_failed_prover_job(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        job_id: i32,
+    ) -> anyhow::Result<bool> {
+        let prover_job_queue = &mut self.prover_job_queue.write().await.1;
+        let job = prover_job_queue
+            .iter_mut()
+            .find(|job| job.id == job_id && job.job_status == ProverJobStatus::Failed.to_number());
+
+        Ok(job
+            .map(|job| {
+                job.job_status = ProverJobStatus::Idle.to_number();
+                job.updated_at = Utc::now();
+                job.updated_by = "admin_requeue".to_string();
+                job.attempts = 0;
+            })
+            .is_some())
     }
 
     async fn load_last_verified_block(
@@ -224,23 +466,43 @@ impl DatabaseInterface for MockDatabase {
     async fn load_idle_prover_job_from_job_queue(
         &self,
         _: &mut StorageProcessor<'_>,
+        capabilities: &ProverCapabilities,
+        starvation_timeout: Duration,
     ) -> anyhow::Result<Option<ProverJob>> {
+        let starved_since = Utc::now() - chrono::Duration::from_std(starvation_timeout).unwrap();
         let prover_job_queue = &mut self.prover_job_queue.write().await.1;
         let idle_prover_job = prover_job_queue
             .iter_mut()
             .filter(|job| job.job_status == ProverJobStatus::Idle.to_number())
-            .max_by_key(|job| (job.job_priority, job.id));
+            .map(|job| {
+                let job_type = if job.job_type == ProverJobType::SingleProof.to_string() {
+                    ProverJobType::SingleProof
+                } else {
+                    ProverJobType::AggregatedProof
+                };
+                let block_size = job.block_size.map(|size| size as usize);
+                let can_handle = capabilities.can_handle(&job_type, block_size);
+                (can_handle, capabilities.prefers(&job_type), job)
+            })
+            .filter(|(can_handle, _, job)| *can_handle || job.created_at <= starved_since)
+            .min_by_key(|(can_handle, prefers, job)| {
+                (!can_handle, !prefers, job.job_priority, job.id)
+            })
+            .map(|(_, _, job)| job);
 
         let prover_job = if let Some(job) = idle_prover_job {
             job.job_status = ProverJobStatus::InProgress.to_number();
             job.updated_at = Utc::now();
             job.updated_by = "server_give_job".to_string();
+            job.started_at = Some(Utc::now());
+            job.attempts += 1;
 
+            let job_data = JobRequestData::decode(&job.job_data)?;
             Some(ProverJob::new(
                 job.id,
                 BlockNumber(job.first_block as u32),
                 BlockNumber(job.last_block as u32),
-                job.job_data.clone(),
+                serde_json::to_value(job_data)?,
             ))
         } else {
             None
@@ -261,6 +523,7 @@ impl DatabaseInterface for MockDatabase {
         if let Some(job) = prover_job {
             job.updated_at = Utc::now();
             job.updated_by = prover_name.to_string();
+            job.prover_name = Some(prover_name.to_string());
         }
 
         Ok(())
@@ -315,6 +578,7 @@ impl DatabaseInterface for MockDatabase {
             {
                 job.job_status = ProverJobStatus::Idle.to_number();
                 job.updated_at = Utc::now();
+                job.attempts = (job.attempts - 1).max(0);
             }
         }
 
@@ -324,19 +588,44 @@ impl DatabaseInterface for MockDatabase {
     async fn load_committed_state(
         &self,
         _: &mut StorageProcessor<'_>,
-        _block: Option<BlockNumber>,
+        block: Option<BlockNumber>,
     ) -> anyhow::Result<(BlockNumber, AccountMap)> {
-        let (last_block, accounts) = self.accounts_state.read().await.clone();
-        Ok((BlockNumber(last_block), accounts))
+        let (last_block, mut accounts) = self.accounts_state.read().await.clone();
+        let block = block.unwrap_or(BlockNumber(u32::MAX));
+        let updates = self.account_updates(BlockNumber(last_block), block).await;
+        let last_block = self
+            .state_diffs
+            .read()
+            .await
+            .iter()
+            .map(|(diff_block, _)| *diff_block)
+            .filter(|diff_block| *diff_block <= block)
+            .fold(BlockNumber(last_block), std::cmp::max);
+        apply_updates(&mut accounts, updates);
+        Ok((last_block, accounts))
     }
 
     async fn load_state_diff(
         &self,
         _: &mut StorageProcessor<'_>,
-        _from_block: BlockNumber,
-        _to_block: Option<BlockNumber>,
+        from_block: BlockNumber,
+        to_block: Option<BlockNumber>,
     ) -> anyhow::Result<Option<(BlockNumber, AccountUpdates)>> {
-        Ok(None)
+        let to_block = to_block.unwrap_or(BlockNumber(u32::MAX));
+        let time_forward = from_block <= to_block;
+        let mut updates = self
+            .account_updates(
+                std::cmp::min(from_block, to_block),
+                std::cmp::max(from_block, to_block),
+            )
+            .await;
+        if updates.is_empty() {
+            return Ok(None);
+        }
+        if !time_forward {
+            reverse_updates(&mut updates);
+        }
+        Ok(Some((to_block, updates)))
     }
 
     async fn store_account_tree_cache(
@@ -362,12 +651,12 @@ impl DatabaseInterface for MockDatabase {
         &self,
         _: &mut StorageProcessor<'_>,
         block_number: BlockNumber,
-    ) -> anyhow::Result<Option<serde_json::Value>> {
+    ) -> anyhow::Result<Option<Vec<u8>>> {
         let block_witness = self.block_witness.read().await;
         let witness = block_witness
             .iter()
             .find(|witness| witness.block == *block_number as i64)
-            .map(|w| serde_json::from_str(&w.witness).expect("Failed to deserialize witness"));
+            .map(|w| w.witness.clone());
 
         Ok(witness)
     }
@@ -376,9 +665,8 @@ impl DatabaseInterface for MockDatabase {
         &self,
         _: &mut StorageProcessor<'_>,
         block: BlockNumber,
-        witness: serde_json::Value,
+        witness: &[u8],
     ) -> anyhow::Result<()> {
-        let witness_str = serde_json::to_string(&witness).expect("Failed to serialize witness");
         let mut block_witness = self.block_witness.write().await;
         let is_block_not_saved_yet = !block_witness
             .iter()
@@ -387,7 +675,7 @@ impl DatabaseInterface for MockDatabase {
         if is_block_not_saved_yet {
             block_witness.push(StorageBlockWitness {
                 block: *block as i64,
-                witness: witness_str,
+                witness: witness.to_vec(),
             });
         }
 
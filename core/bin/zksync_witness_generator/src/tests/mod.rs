@@ -1,2 +1,6 @@
+mod fleet;
+mod job_dispatch;
 mod mock;
 mod prover_server;
+mod scaler;
+mod witness_generator;
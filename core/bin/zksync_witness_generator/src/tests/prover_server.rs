@@ -39,14 +39,25 @@ impl Default for MockProverOptions {
                 cycle_wait: 500,
                 request_timeout: 10,
                 die_after_proof: false,
+                max_block_chunk_size: None,
+                prefer_aggregated_proof: None,
+                parallel_jobs: None,
+                job_memory: None,
             },
             core: Core {
                 gone_timeout: 60000,
                 idle_provers: 1,
+                job_starvation_timeout: 600000,
+                max_job_attempts: 5,
+                failed_job_webhook_url: String::new(),
+                scaler_target_latency: 1800000,
+                scaler_history_window: 3600000,
+                scaler_scale_down_delay: 600000,
             },
             witness_generator: WitnessGenerator {
                 prepare_data_interval: 500,
                 witness_generators: 1,
+                pipeline_workers: 0,
             },
         };
 
@@ -57,7 +68,7 @@ impl Default for MockProverOptions {
 async fn spawn_server(database: MockDatabase) {
     let prover_options = MockProverOptions::default();
 
-    run_prover_server(database, prover_options.0, prover_options.1);
+    run_prover_server(database, prover_options.0, prover_options.1, None);
 }
 
 #[tokio::test]
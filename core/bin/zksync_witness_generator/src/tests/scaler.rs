@@ -0,0 +1,111 @@
+// Built-in deps
+use std::time::{Duration, Instant};
+// Workspace deps
+use zksync_types::prover::{ProverJobStats, ProverJobType};
+// Local deps
+use super::mock::MockDatabase;
+use crate::scaler::{estimate_provers, ScalerOracle, ScalerParams};
+
+fn scaler_params() -> ScalerParams {
+    ScalerParams {
+        idle_provers: 1,
+        target_latency: Duration::from_secs(1800),
+        history_window: Duration::from_secs(3600),
+        scale_down_delay: Duration::from_secs(600),
+    }
+}
+
+fn job_stats(
+    job_type: ProverJobType,
+    job_size: u64,
+    pending_jobs: u32,
+    created_jobs: u32,
+    avg_proof_duration: Option<u64>,
+) -> ProverJobStats {
+    ProverJobStats {
+        job_type,
+        job_size: Some(job_size),
+        pending_jobs,
+        created_jobs,
+        proven_jobs: avg_proof_duration.map(|_| 1).unwrap_or_default(),
+        avg_proof_duration: avg_proof_duration.map(Duration::from_secs),
+    }
+}
+
+/// Checks that every pending job requires a prover if there are no proof durations known.
+#[test]
+fn test_estimate_without_history() {
+    let stats = vec![
+        job_stats(ProverJobType::SingleProof, 10, 3, 3, None),
+        job_stats(ProverJobType::AggregatedProof, 3, 1, 1, None),
+    ];
+
+    let reasoning = estimate_provers(&stats, &scaler_params());
+    assert_eq!(reasoning.pending_jobs, 4);
+    assert_eq!(reasoning.unestimated_jobs, 4);
+    assert_eq!(reasoning.estimated_provers, 4);
+}
+
+/// Checks that the estimation takes into account the proof durations and the rate of the new jobs.
+#[test]
+fn test_estimate_with_history() {
+    let stats = vec![
+        // 30 single proof jobs of 10 minutes each are created per hour, so 5 provers are busy with them.
+        // Proving 6 pending jobs requires 3600 seconds, i.e. 2 provers within the target latency.
+        job_stats(ProverJobType::SingleProof, 10, 6, 30, Some(600)),
+        // Duration of the larger blocks is used for the blocks with no history.
+        job_stats(ProverJobType::SingleProof, 20, 0, 0, Some(900)),
+        job_stats(ProverJobType::SingleProof, 15, 2, 0, None),
+        // Aggregated proof job of 900 seconds adds 0.5 prover.
+        job_stats(ProverJobType::AggregatedProof, 3, 1, 0, Some(900)),
+    ];
+
+    let reasoning = estimate_provers(&stats, &scaler_params());
+    assert_eq!(reasoning.pending_jobs, 9);
+    assert_eq!(reasoning.unestimated_jobs, 0);
+    assert_eq!(reasoning.pending_work, 6.0 * 600.0 + 2.0 * 900.0 + 900.0);
+    assert_eq!(reasoning.incoming_load, 5.0);
+    // 5 + (3600 + 1800 + 900) / 1800 = 8.5
+    assert_eq!(reasoning.estimated_provers, 9);
+    assert!(!reasoning.scale_down_postponed);
+
+    // Jobs are not split between the provers.
+    let stats = vec![job_stats(ProverJobType::SingleProof, 10, 2, 0, Some(7200))];
+    let reasoning = estimate_provers(&stats, &scaler_params());
+    assert_eq!(reasoning.estimated_provers, 2);
+}
+
+/// Checks that the amount of provers is reduced only after it stays lower for the scale down delay.
+#[test]
+fn test_scale_down_delay() {
+    let mut oracle = ScalerOracle::new(MockDatabase::new(), scaler_params());
+    let start = Instant::now();
+    let after = |secs| start + Duration::from_secs(secs);
+
+    // Scaling up is applied immediately.
+    assert_eq!(oracle.apply_scale_down_delay(5, 1, start), (5, false));
+    assert_eq!(oracle.apply_scale_down_delay(8, 5, after(10)), (8, false));
+
+    // Scaling down is postponed and the largest required amount is chosen after the delay.
+    assert_eq!(oracle.apply_scale_down_delay(2, 8, after(20)), (8, true));
+    assert_eq!(oracle.apply_scale_down_delay(4, 8, after(300)), (8, true));
+    assert_eq!(oracle.apply_scale_down_delay(3, 8, after(620)), (4, false));
+
+    // Scaling up cancels the pending scale down.
+    assert_eq!(oracle.apply_scale_down_delay(1, 4, after(700)), (4, true));
+    assert_eq!(oracle.apply_scale_down_delay(6, 4, after(800)), (6, false));
+    assert_eq!(oracle.apply_scale_down_delay(1, 6, after(1350)), (6, true));
+}
+
+/// Checks that the amount of running provers is used as the starting point.
+#[test]
+fn test_scale_down_delay_after_restart() {
+    let mut oracle = ScalerOracle::new(MockDatabase::new(), scaler_params());
+    let start = Instant::now();
+
+    assert_eq!(oracle.apply_scale_down_delay(1, 10, start), (10, true));
+    assert_eq!(
+        oracle.apply_scale_down_delay(1, 10, start + Duration::from_secs(600)),
+        (1, false)
+    );
+}
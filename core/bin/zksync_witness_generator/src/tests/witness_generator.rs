@@ -0,0 +1,156 @@
+// Built-in deps
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+// External deps
+use futures::channel::mpsc;
+use num::BigUint;
+use tokio::sync::RwLock;
+// Workspace deps
+use zksync_crypto::franklin_crypto::bellman::pairing::ff::{PrimeField, PrimeFieldRepr};
+use zksync_state::state::ZkSyncState;
+use zksync_types::{
+    block::Block, AccountId, AccountUpdates, Address, BlockNumber, Deposit, ExecutedOperations,
+    ExecutedPriorityOp, PriorityOp, TokenId, ZkSyncPriorityOp, H256,
+};
+// Local deps
+use super::mock::MockDatabase;
+use crate::witness_generator::WitnessGenerator;
+use crate::DatabaseInterface;
+
+const WITNESSES_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Creates the blocks with a deposit to a new account in each of them, along with their account updates.
+fn test_blocks(count: u32) -> Vec<(Block, AccountUpdates)> {
+    let (_, accounts) = MockDatabase::get_default_tree_and_accounts();
+    let mut state = ZkSyncState::from_acc_map(accounts);
+
+    (1..=count)
+        .map(|block_number| {
+            let old_hash = {
+                let mut be_bytes = [0u8; 32];
+                state
+                    .root_hash()
+                    .into_repr()
+                    .write_be(be_bytes.as_mut())
+                    .expect("Write commit bytes");
+                H256::from(be_bytes)
+            };
+
+            let serial_id = u64::from(block_number - 1);
+            let address = Address::from_low_u64_be(u64::from(block_number));
+            let deposit = ZkSyncPriorityOp::Deposit(Deposit {
+                from: address,
+                token: TokenId(0),
+                amount: BigUint::from(10u32 * block_number),
+                to: address,
+            });
+            let op_success = state.execute_priority_op(deposit.clone());
+            let operation = ExecutedOperations::PriorityOp(Box::new(ExecutedPriorityOp {
+                op: op_success.executed_op,
+                priority_op: PriorityOp {
+                    serial_id,
+                    data: deposit,
+                    deadline_block: 2,
+                    eth_hash: H256::zero(),
+                    eth_block: 10,
+                    eth_block_index: Some(1),
+                },
+                block_index: 1,
+                created_at: chrono::Utc::now(),
+            }));
+
+            let block = Block::new_from_available_block_sizes(
+                BlockNumber(block_number),
+                state.root_hash(),
+                AccountId(0),
+                vec![operation],
+                (serial_id, serial_id + 1),
+                &[10],
+                1_000_000.into(),
+                1_500_000.into(),
+                old_hash,
+                0,
+            );
+            (block, op_success.updates)
+        })
+        .collect()
+}
+
+/// Returns the stored witnesses for the blocks, if all of them are stored.
+async fn load_witnesses(database: &MockDatabase, blocks: u32) -> Option<Vec<Vec<u8>>> {
+    let mut storage = database.acquire_connection().await.unwrap();
+    let mut witnesses = Vec::new();
+    for block in 1..=blocks {
+        witnesses.push(
+            database
+                .load_witness(&mut storage, BlockNumber(block))
+                .await
+                .unwrap()?,
+        );
+    }
+    Some(witnesses)
+}
+
+/// Runs the witness generator over the blocks and waits for the witnesses of all of them.
+/// `stored_witnesses` are put into the database before the generator is started.
+async fn generate_witnesses(
+    blocks: &[(Block, AccountUpdates)],
+    stored_witnesses: &[(BlockNumber, Vec<u8>)],
+    pipeline_workers: usize,
+) -> Vec<Vec<u8>> {
+    let database = MockDatabase::new();
+    let mut storage = database.acquire_connection().await.unwrap();
+    for (block, updates) in blocks {
+        database
+            .add_block_with_updates(block.clone(), updates.clone())
+            .await;
+    }
+    for (block, witness) in stored_witnesses {
+        database
+            .store_witness(&mut storage, *block, witness)
+            .await
+            .unwrap();
+    }
+    drop(storage);
+
+    let (panic_sender, _panic_receiver) = mpsc::channel(1);
+    WitnessGenerator::new(
+        database.clone(),
+        Duration::from_millis(50),
+        Duration::from_millis(0),
+        BlockNumber(1),
+        BlockNumber(1),
+        Arc::new(RwLock::new(BTreeMap::new())),
+        pipeline_workers,
+    )
+    .start(panic_sender);
+
+    let start = Instant::now();
+    loop {
+        if let Some(witnesses) = load_witnesses(&database, blocks.len() as u32).await {
+            return witnesses;
+        }
+        assert!(
+            start.elapsed() < WITNESSES_TIMEOUT,
+            "witnesses were not generated in time"
+        );
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+/// Checks that the pipelined witness generator produces the same witnesses as the sequential one,
+/// including the case when some of the blocks already have their witnesses.
+#[tokio::test]
+async fn test_pipelined_witnesses_match_sequential() {
+    let blocks = test_blocks(4);
+
+    let expected_witnesses = generate_witnesses(&blocks, &[], 0).await;
+
+    let pipelined_witnesses = generate_witnesses(&blocks, &[], 2).await;
+    assert_eq!(pipelined_witnesses, expected_witnesses);
+
+    let stored_witnesses = [(BlockNumber(2), expected_witnesses[1].clone())];
+    let pipelined_witnesses = generate_witnesses(&blocks, &stored_witnesses, 3).await;
+    assert_eq!(pipelined_witnesses, expected_witnesses);
+}
@@ -6,7 +6,7 @@ use std::sync::Arc;
 use std::{thread, time};
 // External
 use futures::channel::mpsc;
-use tokio::sync::RwLock;
+use tokio::sync::{OwnedSemaphorePermit, RwLock, Semaphore};
 use tokio::time::sleep;
 use zksync_crypto::merkle_tree::parallel_smt::SparseMerkleTreeSerializableCacheBN256;
 // Workspace deps
@@ -16,14 +16,23 @@ use zksync_circuit::witness::utils::build_block_witness;
 use zksync_crypto::circuit::CircuitAccountTree;
 use zksync_crypto::params::account_tree_depth;
 use zksync_types::block::Block;
-use zksync_types::BlockNumber;
+use zksync_types::helpers::apply_updates;
+use zksync_types::{AccountMap, BlockNumber};
 use zksync_utils::panic_notify::ThreadPanicNotify;
 
+/// Interval in blocks between the account tree caches stored by the pipelined mode,
+/// so after the restart the tree is restored from a recent cache.
+const PIPELINE_TREE_CACHE_INTERVAL: u32 = 100;
+
 /// The essential part of this structure is `maintain` function
 /// which runs forever and adds data to the database.
 ///
 /// This will generate and store in db witnesses for blocks with indexes
 /// start_block, start_block + block_step, start_block + 2*block_step, ...
+///
+/// In the pipelined mode (`pipeline_workers > 0`) a single account tree is advanced
+/// block by block, while the witnesses are built by the pool of `pipeline_workers` workers.
+/// Each worker holds a copy of the account tree, so the memory usage grows with the pool size.
 pub struct WitnessGenerator<DB: DatabaseInterface> {
     /// Connection to the database.
     database: DB,
@@ -34,6 +43,8 @@ pub struct WitnessGenerator<DB: DatabaseInterface> {
     block_step: BlockNumber,
     start_wait: time::Duration,
     cached_account_tree: Arc<RwLock<BTreeMap<BlockNumber, CircuitAccountTree>>>,
+    /// Amount of workers building the witnesses in the pipelined mode, the mode is disabled if 0.
+    pipeline_workers: usize,
 }
 
 #[derive(Debug)]
@@ -52,6 +63,7 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
         start_block: BlockNumber,
         block_step: BlockNumber,
         cached_account_tree: Arc<RwLock<BTreeMap<BlockNumber, CircuitAccountTree>>>,
+        pipeline_workers: usize,
     ) -> Self {
         Self {
             database,
@@ -60,6 +72,7 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
             block_step,
             start_wait,
             cached_account_tree,
+            pipeline_workers,
         }
     }
 
@@ -75,7 +88,11 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
                     .expect("Unable to build runtime for a witness generator");
 
                 runtime.block_on(async move {
-                    self.maintain().await;
+                    if self.pipeline_workers > 0 {
+                        self.maintain_pipelined().await;
+                    } else {
+                        self.maintain().await;
+                    }
                 });
             })
             .expect("failed to start provers server");
@@ -256,9 +273,13 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
 
     /// Remove old account tree cache we want to keep more than step just to make sure that we won't go to the database
     async fn remove_cache(&self, block: BlockNumber) {
+        let oldest_kept_block = match block.checked_sub(2 * self.block_step.0) {
+            Some(block) => BlockNumber(block),
+            None => return,
+        };
         let mut cache = self.cached_account_tree.write().await;
         let keys: Vec<_> = cache
-            .range((Unbounded, Included(block - 2 * self.block_step.0)))
+            .range((Unbounded, Included(oldest_kept_block)))
             .map(|(key, _)| *key)
             .collect();
         for key in keys {
@@ -266,6 +287,41 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
         }
     }
 
+    /// Builds the witness for the block from the account tree state before it and encodes it.
+    fn build_encoded_witness(
+        circuit_account_tree: &mut CircuitAccountTree,
+        block: &Block,
+    ) -> anyhow::Result<Vec<u8>> {
+        let start = Instant::now();
+        let witness: ProverData = build_block_witness(circuit_account_tree, block)?.into();
+        metrics::histogram!("witness_generator", start.elapsed(), "stage" => "build_witness");
+
+        let start = Instant::now();
+        let encoded_witness = witness.encode()?;
+        metrics::histogram!("witness_generator", start.elapsed(), "stage" => "encode_witness");
+        Ok(encoded_witness)
+    }
+
+    async fn save_witness(
+        database: &DB,
+        block_number: BlockNumber,
+        encoded_witness: &[u8],
+    ) -> anyhow::Result<()> {
+        let start = Instant::now();
+        let mut storage = database.acquire_connection().await?;
+        database
+            .store_witness(&mut storage, block_number, encoded_witness)
+            .await?;
+        metrics::histogram!("witness_generator", start.elapsed(), "stage" => "store_witness");
+
+        metrics::gauge!(
+            "last_processed_block",
+            block_number.0 as f64,
+            "stage" => "witness_generator"
+        );
+        Ok(())
+    }
+
     async fn prepare_witness_and_save_it(&mut self, block: Block) -> anyhow::Result<()> {
         let fn_start = Instant::now();
 
@@ -273,31 +329,127 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
         let mut circuit_account_tree = self.load_account_tree(block.block_number - 1).await?;
         metrics::histogram!("witness_generator", start.elapsed(), "stage" => "load_tree_full");
 
-        let start = Instant::now();
-        let witness: ProverData = build_block_witness(&mut circuit_account_tree, &block)?.into();
-        metrics::histogram!("witness_generator", start.elapsed(), "stage" => "build_witness");
+        let encoded_witness = Self::build_encoded_witness(&mut circuit_account_tree, &block)?;
+        Self::save_witness(&self.database, block.block_number, &encoded_witness).await?;
 
-        let start = Instant::now();
+        metrics::histogram!("witness_generator", fn_start.elapsed(), "stage" => "prepare_witness_and_save_it");
+        Ok(())
+    }
+
+    async fn load_existing_block(&self, block_number: BlockNumber) -> anyhow::Result<Block> {
         let mut storage = self.database.acquire_connection().await?;
         self.database
-            .store_witness(
-                &mut storage,
-                block.block_number,
-                serde_json::to_value(witness).expect("Witness serialize to json"),
-            )
-            .await?;
-        metrics::histogram!("witness_generator", start.elapsed(), "stage" => "store_witness");
+            .load_block(&mut storage, block_number)
+            .await?
+            .ok_or_else(|| anyhow::format_err!("Block {} doesn't exist", block_number))
+    }
 
-        metrics::histogram!("witness_generator", fn_start.elapsed(), "stage" => "prepare_witness_and_save_it");
+    /// Loads the account tree and the accounts state after the given block.
+    async fn load_pipeline_state(
+        &mut self,
+        block: BlockNumber,
+    ) -> anyhow::Result<(CircuitAccountTree, AccountMap)> {
+        let circuit_account_tree = self.load_account_tree(block).await?;
+        let mut storage = self.database.acquire_connection().await?;
+        let (_, accounts) = self
+            .database
+            .load_committed_state(&mut storage, Some(block))
+            .await?;
+        Ok((circuit_account_tree, accounts))
+    }
 
-        metrics::gauge!(
-            "last_processed_block",
-            block.block_number.0 as f64,
-            "stage" => "witness_generator"
+    /// Applies the account updates of the block to the account tree and checks the resulting root hash.
+    async fn advance_account_tree(
+        &self,
+        circuit_account_tree: &mut CircuitAccountTree,
+        accounts: &mut AccountMap,
+        block: &Block,
+    ) -> anyhow::Result<()> {
+        let start = Instant::now();
+        let mut storage = self.database.acquire_connection().await?;
+        if let Some((_, account_updates)) = self
+            .database
+            .load_state_diff(
+                &mut storage,
+                block.block_number - 1,
+                Some(block.block_number),
+            )
+            .await?
+        {
+            let mut updated_accounts = account_updates
+                .iter()
+                .map(|(id, _)| *id)
+                .collect::<Vec<_>>();
+            updated_accounts.sort_unstable();
+            updated_accounts.dedup();
+            apply_updates(accounts, account_updates);
+            for idx in updated_accounts {
+                circuit_account_tree
+                    .insert(*idx, accounts.get(&idx).cloned().unwrap_or_default().into());
+            }
+        }
+        anyhow::ensure!(
+            circuit_account_tree.root_hash() == block.new_root_hash,
+            "account tree root hash for block {} doesn't match the stored one",
+            block.block_number
         );
+        metrics::histogram!("witness_generator", start.elapsed(), "stage" => "advance_tree");
         Ok(())
     }
 
+    /// Stores the cache of the account tree after the given block and removes the old in-memory caches.
+    async fn store_pipeline_tree_cache(
+        &self,
+        circuit_account_tree: &CircuitAccountTree,
+        block: BlockNumber,
+    ) -> anyhow::Result<()> {
+        let start = Instant::now();
+        let tree_cache = circuit_account_tree.get_internals().encode_bincode();
+        metrics::histogram!("tree_cache_size", tree_cache.len() as f64);
+        let mut storage = self.database.acquire_connection().await?;
+        self.database
+            .store_account_tree_cache(&mut storage, block, tree_cache)
+            .await?;
+        self.remove_cache(block).await;
+        metrics::histogram!("witness_generator", start.elapsed(), "stage" => "store_cache");
+        Ok(())
+    }
+
+    /// Builds and stores the witness for the block in a separate task.
+    /// The permit is released once the worker is done, failed blocks are sent to `failed_blocks`.
+    fn spawn_witness_worker(
+        &self,
+        block: Block,
+        mut circuit_account_tree: CircuitAccountTree,
+        permit: OwnedSemaphorePermit,
+        failed_blocks: mpsc::UnboundedSender<BlockNumber>,
+    ) {
+        let database = self.database.clone();
+        tokio::spawn(async move {
+            let block_number = block.block_number;
+            let result = async {
+                let encoded_witness = tokio::task::spawn_blocking(move || {
+                    Self::build_encoded_witness(&mut circuit_account_tree, &block)
+                })
+                .await??;
+                Self::save_witness(&database, block_number, &encoded_witness).await
+            }
+            .await;
+
+            if let Err(err) = result {
+                vlog::warn!(
+                    "Witness generator worker failed to prepare witness for block: {}, err: {}",
+                    block_number,
+                    err
+                );
+                failed_blocks
+                    .unbounded_send(block_number)
+                    .unwrap_or_default();
+            }
+            drop(permit);
+        });
+    }
+
     /// Returns next block for generating witness
     fn next_witness_block(
         current_block: BlockNumber,
@@ -356,6 +508,122 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
             sleep(self.rounds_interval).await;
         }
     }
+
+    /// Updates witness data in database in an infinite loop, advancing a single account tree
+    /// block by block and building the witnesses by the pool of workers.
+    async fn maintain_pipelined(mut self) {
+        vlog::info!(
+            "pipelined preparing prover data routine started with start_block({}), workers({})",
+            *self.start_block,
+            self.pipeline_workers
+        );
+
+        let workers = Arc::new(Semaphore::new(self.pipeline_workers));
+        let (failed_sender, mut failed_receiver) = mpsc::unbounded();
+        let mut current_block = self.start_block;
+        // Account tree and accounts state after the block preceding the current one.
+        // It's loaded from the cache on start and after the failures.
+        let mut state = None;
+        loop {
+            if let Ok(Some(failed_block)) = failed_receiver.try_next() {
+                // Wait for the rest of the workers, so no witness is built twice,
+                // and restart from the earliest failed block.
+                let _all_workers = workers
+                    .acquire_many(self.pipeline_workers as u32)
+                    .await
+                    .expect("Workers semaphore is never closed");
+                let mut restart_block = failed_block;
+                while let Ok(Some(failed_block)) = failed_receiver.try_next() {
+                    restart_block = std::cmp::min(restart_block, failed_block);
+                }
+                vlog::warn!(
+                    "Pipelined witness generator restarts from block {}",
+                    restart_block
+                );
+                current_block = std::cmp::min(current_block, restart_block);
+                state = None;
+            }
+
+            if state.is_none() {
+                match self.load_pipeline_state(current_block - 1).await {
+                    Ok(loaded_state) => state = Some(loaded_state),
+                    Err(err) => {
+                        vlog::warn!(
+                            "failed to load account tree for block {}: {}",
+                            current_block - 1,
+                            err
+                        );
+                        sleep(self.rounds_interval).await;
+                        continue;
+                    }
+                }
+            }
+
+            let block = match self.should_work_on_block(current_block).await {
+                Ok(BlockInfo::NotReadyBlock) => {
+                    sleep(self.rounds_interval).await;
+                    continue;
+                }
+                Ok(BlockInfo::NoWitness(block)) => Ok((block, false)),
+                Ok(BlockInfo::WithWitness) => self
+                    .load_existing_block(current_block)
+                    .await
+                    .map(|block| (block, true)),
+                Err(err) => Err(err),
+            };
+            let (block, has_witness) = match block {
+                Ok(block) => block,
+                Err(err) => {
+                    vlog::warn!("witness for block {} check failed: {}", current_block, err);
+                    sleep(self.rounds_interval).await;
+                    continue;
+                }
+            };
+
+            let (circuit_account_tree, accounts) = state.as_mut().expect("State is loaded above");
+            let tree_before_block = if has_witness {
+                None
+            } else {
+                Some(circuit_account_tree.clone())
+            };
+            if let Err(err) = self
+                .advance_account_tree(circuit_account_tree, accounts, &block)
+                .await
+            {
+                vlog::warn!(
+                    "failed to advance account tree to block {}: {}",
+                    current_block,
+                    err
+                );
+                state = None;
+                sleep(self.rounds_interval).await;
+                continue;
+            }
+            if *current_block % PIPELINE_TREE_CACHE_INTERVAL == 0 {
+                // Failure to store the cache only makes the restart slower, so the pipeline is not stopped.
+                if let Err(err) = self
+                    .store_pipeline_tree_cache(circuit_account_tree, current_block)
+                    .await
+                {
+                    vlog::warn!(
+                        "failed to store account tree cache for block {}: {}",
+                        current_block,
+                        err
+                    );
+                }
+            }
+
+            if let Some(tree_before_block) = tree_before_block {
+                let permit = workers
+                    .clone()
+                    .acquire_owned()
+                    .await
+                    .expect("Workers semaphore is never closed");
+                self.spawn_witness_worker(block, tree_before_block, permit, failed_sender.clone());
+            }
+            current_block = BlockNumber(*current_block + 1);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1,6 +1,6 @@
 use bigdecimal::BigDecimal;
 use serde::{Deserialize, Serialize};
-use zksync_types::{AccountId, Address, Token, TokenId, H256};
+use zksync_types::{tokens::TokenOverride, AccountId, Address, Token, TokenId, H256};
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -10,6 +10,13 @@ pub struct ApiToken {
     pub symbol: String,
     pub decimals: u8,
     pub enabled_for_fees: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logo_uri: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata_uri: Option<String>,
+    /// Hidden tokens are listed so that clients can resolve them, but should not be displayed.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub hidden: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -30,6 +37,19 @@ impl ApiToken {
             symbol: token.symbol,
             decimals: token.decimals,
             enabled_for_fees: eligibility,
+            logo_uri: None,
+            metadata_uri: None,
+            hidden: false,
+        }
+    }
+
+    /// Sets the logo and metadata URIs and the visibility provided by the operator.
+    pub fn with_token_override(self, token_override: &TokenOverride) -> Self {
+        Self {
+            logo_uri: token_override.logo_uri.clone(),
+            metadata_uri: token_override.metadata_uri.clone(),
+            hidden: token_override.hidden,
+            ..self
         }
     }
 }
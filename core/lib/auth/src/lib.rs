@@ -0,0 +1,111 @@
+//! JsonWebToken authorization shared by the internal HTTP APIs, e.g. the prover server
+//! and the admin API. Tokens are signed with a secret shared by the server and its clients.
+//!
+//! Validation of the requests for the actix-web servers is available with the `actix` feature.
+
+// Built-in uses
+use std::time;
+// External uses
+use jsonwebtoken::{
+    decode, encode, errors::Error as JwtError, DecodingKey, EncodingKey, Header, Validation,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PayloadAuthToken {
+    /// Subject (whom auth token refers to).
+    sub: String,
+    /// Expiration time (as UTC timestamp).
+    exp: usize,
+}
+
+impl PayloadAuthToken {
+    fn new(exp: usize) -> Self {
+        Self {
+            sub: "Authorization".to_string(),
+            exp,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AuthTokenGenerator {
+    secret: String,
+    period_availability: time::Duration,
+}
+
+impl AuthTokenGenerator {
+    pub fn new(secret: String, period_availability: time::Duration) -> Self {
+        Self {
+            secret,
+            period_availability,
+        }
+    }
+
+    /// Encode JsonWebToken with shared secret
+    pub fn encode(&self) -> jsonwebtoken::errors::Result<String> {
+        // Time (Unix Timestamp) until which the token will be valid
+        let exp = time::UNIX_EPOCH.elapsed().unwrap() + self.period_availability;
+
+        encode(
+            &Header::default(),
+            &PayloadAuthToken::new(exp.as_secs() as usize),
+            &EncodingKey::from_secret(self.secret.as_ref()),
+        )
+    }
+}
+
+/// The structure that stores the secret key for checking JsonWebToken matching.
+pub struct AuthTokenValidator<'a> {
+    decoding_key: DecodingKey<'a>,
+}
+
+impl<'a> AuthTokenValidator<'a> {
+    pub fn new(secret: &'a str) -> Self {
+        Self {
+            decoding_key: DecodingKey::from_secret(secret.as_ref()),
+        }
+    }
+
+    /// Checks whether the secret key and the authorization token match.
+    pub fn validate_auth_token(&self, token: &str) -> Result<(), JwtError> {
+        decode::<PayloadAuthToken>(token, &self.decoding_key, &Validation::default())?;
+
+        Ok(())
+    }
+
+    /// Validates the bearer token of the request, used with `HttpAuthentication::bearer`.
+    #[cfg(feature = "actix")]
+    pub async fn validator(
+        &self,
+        req: actix_web::dev::ServiceRequest,
+        credentials: actix_web_httpauth::extractors::bearer::BearerAuth,
+    ) -> actix_web::Result<actix_web::dev::ServiceRequest> {
+        use actix_web_httpauth::extractors::{bearer::Config, AuthenticationError};
+
+        let config = req.app_data::<Config>().cloned().unwrap_or_default();
+
+        self.validate_auth_token(credentials.token())
+            .map_err(|_| AuthenticationError::from(config))?;
+
+        Ok(req)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_token_is_valid() {
+        let generator = AuthTokenGenerator::new("secret".into(), time::Duration::from_secs(60));
+        let token = generator.encode().unwrap();
+
+        assert!(AuthTokenValidator::new("secret")
+            .validate_auth_token(&token)
+            .is_ok());
+        assert!(AuthTokenValidator::new("another secret")
+            .validate_auth_token(&token)
+            .is_err());
+    }
+}
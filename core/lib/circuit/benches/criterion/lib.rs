@@ -8,6 +8,7 @@ use full_exit::full_exit_benches;
 use transfer::transfer_benches;
 use transfer_to_new::transfer_to_new_benches;
 use withdraw::withdraw_benches;
+use witness_serialization::witness_serialization_benches;
 use zksync_types::AccountId;
 
 mod change_pubkey_offchain;
@@ -19,6 +20,7 @@ mod transfer;
 mod transfer_to_new;
 mod utils;
 mod withdraw;
+mod witness_serialization;
 
 fn generate_accounts(count: usize) -> Vec<WitnessTestAccount> {
     let mut accounts: Vec<WitnessTestAccount> = Vec::new();
@@ -37,4 +39,5 @@ criterion_main!(
     transfer_to_new_benches,
     transfer_benches,
     withdraw_benches,
+    witness_serialization_benches,
 );
@@ -0,0 +1,107 @@
+use crate::generate_accounts;
+use crate::utils::{WitnessBuilder, ZkSyncStateGenerator, FEE_ACCOUNT_ID};
+use criterion::{black_box, criterion_group, Bencher, BenchmarkId, Criterion};
+use num::BigUint;
+use zksync_circuit::serialization::ProverData;
+use zksync_circuit::witness::{deposit::DepositWitness, Witness};
+use zksync_crypto::franklin_crypto::bellman::pairing::bn256::Bn256;
+use zksync_types::{BlockNumber, Deposit, DepositOp, TokenId};
+
+type DepositWitnessBn256 = DepositWitness<Bn256>;
+
+/// Creates the prover data for the block filled with deposits.
+fn deposits_prover_data(deposits_count: usize) -> ProverData {
+    let accounts = generate_accounts(1);
+    let account = &accounts[0];
+    let deposit_op = DepositOp {
+        priority_op: Deposit {
+            from: account.account.address,
+            token: TokenId(0),
+            amount: BigUint::from(1u32),
+            to: account.account.address,
+        },
+        account_id: account.id,
+    };
+    let (_, mut circuit_account_tree) = ZkSyncStateGenerator::generate(&accounts);
+
+    let mut witness_accum =
+        WitnessBuilder::new(&mut circuit_account_tree, FEE_ACCOUNT_ID, BlockNumber(1), 0);
+    for _ in 0..deposits_count {
+        let witness = DepositWitnessBn256::apply_tx(witness_accum.account_tree, &deposit_op);
+        witness_accum.add_operation_with_pubdata(
+            witness.calculate_operations(()),
+            witness.get_pubdata(),
+            witness.get_offset_commitment_data(),
+        );
+    }
+    witness_accum.extend_pubdata_with_noops(deposits_count * DepositOp::CHUNKS);
+    witness_accum.collect_fees(&[]);
+    witness_accum.calculate_pubdata_commitment();
+
+    witness_accum.into()
+}
+
+/// Measures the time of the witness encoding into JSON
+fn witness_encode_json(b: &mut Bencher<'_>, deposits_count: &usize) {
+    let prover_data = deposits_prover_data(*deposits_count);
+
+    b.iter(|| {
+        let _encoded = black_box(prover_data.encode_legacy_json().unwrap());
+    });
+}
+
+/// Measures the time of the witness encoding into the binary format
+fn witness_encode_binary(b: &mut Bencher<'_>, deposits_count: &usize) {
+    let prover_data = deposits_prover_data(*deposits_count);
+
+    b.iter(|| {
+        let _encoded = black_box(prover_data.encode().unwrap());
+    });
+}
+
+/// Measures the time of the witness decoding from JSON
+fn witness_decode_json(b: &mut Bencher<'_>, deposits_count: &usize) {
+    let encoded = deposits_prover_data(*deposits_count)
+        .encode_legacy_json()
+        .unwrap();
+
+    b.iter(|| {
+        let _prover_data = black_box(ProverData::decode(&encoded).unwrap());
+    });
+}
+
+/// Measures the time of the witness decoding from the binary format
+fn witness_decode_binary(b: &mut Bencher<'_>, deposits_count: &usize) {
+    let encoded = deposits_prover_data(*deposits_count).encode().unwrap();
+
+    b.iter(|| {
+        let _prover_data = black_box(ProverData::decode(&encoded).unwrap());
+    });
+}
+
+pub fn bench_witness_serialization(c: &mut Criterion) {
+    for deposits_count in [1usize, 10, 100] {
+        c.bench_with_input(
+            BenchmarkId::new("Witness encode JSON", deposits_count),
+            &deposits_count,
+            witness_encode_json,
+        );
+        c.bench_with_input(
+            BenchmarkId::new("Witness encode binary", deposits_count),
+            &deposits_count,
+            witness_encode_binary,
+        );
+        c.bench_with_input(
+            BenchmarkId::new("Witness decode JSON", deposits_count),
+            &deposits_count,
+            witness_decode_json,
+        );
+        c.bench_with_input(
+            BenchmarkId::new("Witness decode binary", deposits_count),
+            &deposits_count,
+            witness_decode_binary,
+        );
+    }
+}
+
+criterion_group!(witness_serialization_benches, bench_witness_serialization);
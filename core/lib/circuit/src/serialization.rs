@@ -1,6 +1,7 @@
 // Built-in
 // External
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use anyhow::bail;
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
 // Workspace
 use zksync_crypto::ff::PrimeField;
 use zksync_crypto::franklin_crypto::alt_babyjubjub::AltJubjubBn256;
@@ -15,6 +16,59 @@ use crate::operation::{
 };
 use crate::witness::WitnessBuilder;
 
+/// zstd compression level of the encoded witness.
+const WITNESS_COMPRESSION_LEVEL: i32 = 3;
+
+/// Format of the witness encoded into bytes, also used for the prover job payloads.
+///
+/// Every encoded witness starts with the format tag. Legacy witnesses are
+/// plain JSON objects, so they're recognized by the opening brace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WitnessFormat {
+    /// JSON object, used before the binary formats were introduced.
+    LegacyJson,
+    /// CBOR compressed with zstd.
+    CborZstdV1,
+}
+
+impl WitnessFormat {
+    /// The format used to encode new witnesses.
+    pub const CURRENT: Self = Self::CborZstdV1;
+
+    const LEGACY_JSON_TAG: u8 = b'{';
+    const CBOR_ZSTD_V1_TAG: u8 = 1;
+
+    /// Detects the format of the encoded witness.
+    pub fn of(encoded: &[u8]) -> anyhow::Result<Self> {
+        match encoded.first() {
+            Some(&Self::LEGACY_JSON_TAG) => Ok(Self::LegacyJson),
+            Some(&Self::CBOR_ZSTD_V1_TAG) => Ok(Self::CborZstdV1),
+            Some(tag) => bail!("Unknown witness format tag: {}", tag),
+            None => bail!("Encoded witness is empty"),
+        }
+    }
+
+    /// Encodes the value in the `WitnessFormat::CURRENT` format.
+    pub fn encode<T: Serialize>(value: &T) -> anyhow::Result<Vec<u8>> {
+        let cbor = serde_cbor::to_vec(value)?;
+        let mut encoded = vec![Self::CBOR_ZSTD_V1_TAG];
+        zstd::stream::copy_encode(cbor.as_slice(), &mut encoded, WITNESS_COMPRESSION_LEVEL)?;
+        Ok(encoded)
+    }
+
+    /// Decodes the value encoded in any of the supported formats.
+    pub fn decode<T: DeserializeOwned>(encoded: &[u8]) -> anyhow::Result<T> {
+        let value = match Self::of(encoded)? {
+            Self::LegacyJson => serde_json::from_slice(encoded)?,
+            Self::CborZstdV1 => {
+                let cbor = zstd::stream::decode_all(&encoded[1..])?;
+                serde_cbor::from_slice(&cbor)?
+            }
+        };
+        Ok(value)
+    }
+}
+
 /// ProverData is data prover needs to calculate proof of the given block.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ProverData {
@@ -74,6 +128,21 @@ impl From<WitnessBuilder<'_>> for ProverData {
 }
 
 impl ProverData {
+    /// Encodes the witness in the `WitnessFormat::CURRENT` format.
+    pub fn encode(&self) -> anyhow::Result<Vec<u8>> {
+        WitnessFormat::encode(self)
+    }
+
+    /// Decodes the witness encoded in any of the supported formats.
+    pub fn decode(encoded: &[u8]) -> anyhow::Result<Self> {
+        WitnessFormat::decode(encoded)
+    }
+
+    /// Encodes the witness in the legacy JSON format.
+    pub fn encode_legacy_json(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
     pub fn into_circuit(self) -> ZkSyncCircuit<'static, Engine> {
         ZkSyncCircuit {
             rescue_params: &zksync_crypto::params::RESCUE_PARAMS as &Bn256RescueParams,
@@ -0,0 +1,1176 @@
+//! Differential fuzzing of the state against the circuit.
+//!
+//! `ZkSyncState` handlers and the witness builders implement the protocol rules independently,
+//! and any divergence between them means that the server can create a block that cannot be proven.
+//!
+//! This module generates random sequences of operations (both well-formed and adversarial ones),
+//! applies them to the state one by one and builds a single-operation block witness on top of the
+//! state for each of them. The constraint system must be satisfied exactly when the operation is
+//! accepted by the state, and for the accepted operations the root hashes must match.
+//! Rejected transactions that can't be represented in the circuit (e.g. sent to a missing account)
+//! are not checked against it, and the run fails if they make up too big a share of the rejections.
+//! A diverging sequence is shrunk to a minimal one before being reported.
+//!
+//! The number of generated sequences can be set via the `CIRCUIT_FUZZ_RUNS` environment variable,
+//! and a single reported seed can be re-run via `CIRCUIT_FUZZ_SEED`.
+
+// Built-in deps
+use std::panic::{self, AssertUnwindSafe};
+// External deps
+use num::BigUint;
+use rayon::prelude::*;
+use zksync_crypto::franklin_crypto::bellman::pairing::bn256::Bn256;
+use zksync_crypto::rand::{Rng, SeedableRng, XorShiftRng};
+// Workspace deps
+use zksync_crypto::{
+    circuit::{account::CircuitAccount, CircuitAccountTree},
+    params::{account_tree_depth, MIN_NFT_TOKEN_ID, NFT_STORAGE_ACCOUNT_ID, NFT_TOKEN_ID},
+    Fr, PrivateKey,
+};
+use zksync_state::state::{CollectedFee, ZkSyncState};
+use zksync_test_account::ZkSyncAccount;
+use zksync_types::{
+    helpers::{closest_packable_fee_amount, closest_packable_token_amount},
+    operations::{
+        ChangePubKeyOp, ForcedExitOp, MintNFTOp, SwapOp, TransferOp, TransferToNewOp,
+        WithdrawNFTOp, WithdrawOp,
+    },
+    tx::{ChangePubKeyType, TimeRange},
+    Account, AccountId, Address, BlockNumber, Deposit, ForcedExit, FullExit, MintNFT, Nonce, Order,
+    Swap, TokenId, Transfer, Withdraw, WithdrawNFT, ZkSyncOp, ZkSyncPriorityOp, ZkSyncTx, H256,
+    NFT,
+};
+// Local deps
+use crate::witness::{
+    tests::test_utils::{
+        check_circuit_non_panicking, WitnessTestAccount, ZkSyncStateGenerator, BLOCK_TIMESTAMP,
+        FEE_ACCOUNT_ID,
+    },
+    utils::{SigDataInput, WitnessBuilder},
+    ChangePubkeyOffChainWitness, DepositWitness, ForcedExitWitness, FullExitWitness,
+    MintNFTWitness, SwapWitness, TransferToNewWitness, TransferWitness, WithdrawNFTWitness,
+    WithdrawWitness, Witness,
+};
+
+/// Number of sequences checked if `CIRCUIT_FUZZ_RUNS` is not set.
+const DEFAULT_RUNS: u64 = 8;
+/// Number of actions in a generated sequence.
+const SEQUENCE_LENGTH: usize = 12;
+/// Number of actors that have accounts with the public key hash set in the initial state.
+const INITIAL_ACCOUNTS: usize = 4;
+/// Number of actors that don't have accounts in the initial state.
+const FRESH_ACCOUNTS: usize = 2;
+const INITIAL_BALANCE: u64 = 1000;
+const FUNGIBLE_TOKENS: [TokenId; 2] = [TokenId(0), TokenId(1)];
+/// Max share of the state rejections that can't be checked against the circuit.
+/// Exceeding it means that the generated sequences mostly don't test the circuit rejections.
+const MAX_UNCHECKED_SHARE: f64 = 0.5;
+
+/// The way a generated transaction is corrupted.
+///
+/// It's only a hint for the generator: whether the operation is correct is always decided by the state,
+/// e.g. an overspending transfer may become correct after a big enough deposit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mutation {
+    None,
+    /// The amount (or the fee, if the transaction has no amount) exceeds the balance.
+    Overspend,
+    WrongNonce,
+    /// The transaction is signed with the key of another actor.
+    ForeignSigner,
+    /// The time range of the transaction ends before the block timestamp.
+    Expired,
+}
+
+impl Mutation {
+    fn random(rng: &mut XorShiftRng) -> Self {
+        match rng.gen_range(0, 8) {
+            0 => Self::Overspend,
+            1 => Self::WrongNonce,
+            2 => Self::ForeignSigner,
+            3 => Self::Expired,
+            _ => Self::None,
+        }
+    }
+
+    fn time_range(self) -> TimeRange {
+        if self == Self::Expired {
+            TimeRange::new(0, BLOCK_TIMESTAMP - 1)
+        } else {
+            TimeRange::default()
+        }
+    }
+}
+
+/// Token used in the action. NFTs are referred by the index among the minted ones,
+/// so that the action stays meaningful after the sequence is shrunk.
+#[derive(Debug, Clone, Copy)]
+enum TokenChoice {
+    Fungible(TokenId),
+    Nft(usize),
+}
+
+/// Operation to be applied, with the accounts referred by the index of the actor.
+///
+/// Actions are resolved into the transactions against the current state, i.e. the account IDs,
+/// nonces and balances are taken from the state at the moment the action is applied.
+#[derive(Debug, Clone)]
+enum Action {
+    Deposit {
+        to: usize,
+        token: TokenId,
+        amount: u64,
+    },
+    /// Full exit of the `account` requested by the `owner`.
+    FullExit {
+        account: usize,
+        owner: usize,
+        token: TokenChoice,
+    },
+    Transfer {
+        from: usize,
+        to: usize,
+        token: TokenChoice,
+        amount: u64,
+        fee: u64,
+        mutation: Mutation,
+    },
+    Withdraw {
+        from: usize,
+        token: TokenId,
+        amount: u64,
+        fee: u64,
+        mutation: Mutation,
+    },
+    ChangePubKey {
+        account: usize,
+        fee_token: TokenId,
+        fee: u64,
+        mutation: Mutation,
+    },
+    ForcedExit {
+        initiator: usize,
+        target: usize,
+        token: TokenId,
+        fee: u64,
+        mutation: Mutation,
+    },
+    MintNFT {
+        creator: usize,
+        recipient: usize,
+        content_hash: u64,
+        fee_token: TokenId,
+        fee: u64,
+        mutation: Mutation,
+    },
+    WithdrawNFT {
+        owner: usize,
+        nft: usize,
+        fee_token: TokenId,
+        fee: u64,
+        mutation: Mutation,
+    },
+    /// Swap of `amount` of `tokens.0` owned by `accounts.0` for the same amount of `tokens.1`
+    /// owned by `accounts.1`.
+    Swap {
+        submitter: usize,
+        accounts: (usize, usize),
+        tokens: (TokenId, TokenId),
+        amount: u64,
+        limit_orders: (bool, bool),
+        fee_token: TokenId,
+        fee: u64,
+        mutation: Mutation,
+    },
+}
+
+impl Action {
+    fn random(rng: &mut XorShiftRng) -> Self {
+        let actor = |rng: &mut XorShiftRng| rng.gen_range(0, INITIAL_ACCOUNTS + FRESH_ACCOUNTS);
+        let fungible = |rng: &mut XorShiftRng| FUNGIBLE_TOKENS[rng.gen_range(0, 2)];
+        let token = |rng: &mut XorShiftRng| {
+            if rng.gen_range(0, 4) == 0 {
+                TokenChoice::Nft(rng.gen_range(0, 4))
+            } else {
+                TokenChoice::Fungible(fungible(rng))
+            }
+        };
+        let amount = |rng: &mut XorShiftRng| rng.gen_range(1u64, 500);
+        let fee = |rng: &mut XorShiftRng| rng.gen_range(0u64, 20);
+
+        match rng.gen_range(0, 12) {
+            0 | 1 => Self::Deposit {
+                to: actor(rng),
+                token: fungible(rng),
+                amount: amount(rng),
+            },
+            2 => Self::FullExit {
+                account: actor(rng),
+                owner: actor(rng),
+                token: token(rng),
+            },
+            3 | 4 | 5 => Self::Transfer {
+                from: actor(rng),
+                to: actor(rng),
+                token: token(rng),
+                amount: amount(rng),
+                fee: fee(rng),
+                mutation: Mutation::random(rng),
+            },
+            6 => Self::Withdraw {
+                from: actor(rng),
+                token: fungible(rng),
+                amount: amount(rng),
+                fee: fee(rng),
+                mutation: Mutation::random(rng),
+            },
+            7 => Self::ChangePubKey {
+                account: actor(rng),
+                fee_token: fungible(rng),
+                fee: fee(rng),
+                mutation: Mutation::random(rng),
+            },
+            8 => Self::ForcedExit {
+                initiator: actor(rng),
+                target: actor(rng),
+                token: fungible(rng),
+                fee: fee(rng),
+                mutation: Mutation::random(rng),
+            },
+            9 => Self::MintNFT {
+                creator: actor(rng),
+                recipient: actor(rng),
+                content_hash: rng.gen(),
+                fee_token: fungible(rng),
+                fee: fee(rng),
+                mutation: Mutation::random(rng),
+            },
+            10 => Self::WithdrawNFT {
+                owner: actor(rng),
+                nft: rng.gen_range(0, 4),
+                fee_token: fungible(rng),
+                fee: fee(rng),
+                mutation: Mutation::random(rng),
+            },
+            _ => {
+                let tokens = if rng.gen() {
+                    (FUNGIBLE_TOKENS[0], FUNGIBLE_TOKENS[1])
+                } else {
+                    (FUNGIBLE_TOKENS[1], FUNGIBLE_TOKENS[0])
+                };
+                Self::Swap {
+                    submitter: actor(rng),
+                    accounts: (actor(rng), actor(rng)),
+                    tokens,
+                    amount: amount(rng),
+                    limit_orders: (rng.gen(), rng.gen()),
+                    fee_token: fungible(rng),
+                    fee: fee(rng),
+                    mutation: Mutation::random(rng),
+                }
+            }
+        }
+    }
+}
+
+/// Action resolved against the current state.
+enum Step {
+    Priority(ZkSyncPriorityOp),
+    /// Transaction along with the operation the circuit has to process if the state rejects the transaction.
+    /// The operation is `None` if the rejected transaction cannot be represented in the circuit,
+    /// e.g. if the recipient account doesn't exist.
+    Tx {
+        tx: ZkSyncTx,
+        rejected_op: Option<ZkSyncOp>,
+    },
+}
+
+/// Discrepancy between the state and the circuit.
+#[derive(Debug)]
+struct Divergence {
+    /// Index of the diverging action in the sequence.
+    step: usize,
+    action: Action,
+    details: String,
+}
+
+/// Result of the action that didn't diverge.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Outcome {
+    /// Both the state and the circuit accepted the operation.
+    Accepted,
+    /// Both the state and the circuit rejected the operation.
+    Rejected,
+    /// The state rejected the transaction that can't be represented in the circuit,
+    /// so the rejection wasn't checked against it.
+    Unchecked,
+    /// The action can't be represented as a transaction.
+    Skipped,
+}
+
+/// Statistics of the processed actions.
+#[derive(Debug, Default)]
+struct RunStats {
+    accepted: usize,
+    rejected: usize,
+    unchecked: usize,
+    /// Actions that cannot be represented as a transaction or processed by the circuit,
+    /// e.g. transactions from the accounts that don't exist.
+    skipped: usize,
+}
+
+impl RunStats {
+    fn add(&mut self, other: &RunStats) {
+        self.accepted += other.accepted;
+        self.rejected += other.rejected;
+        self.unchecked += other.unchecked;
+        self.skipped += other.skipped;
+    }
+
+    /// Share of the state rejections that weren't checked against the circuit.
+    fn unchecked_share(&self) -> f64 {
+        let rejections = self.rejected + self.unchecked;
+        if rejections == 0 {
+            return 0.0;
+        }
+        self.unchecked as f64 / rejections as f64
+    }
+}
+
+/// Returns the actors of the run, the first `INITIAL_ACCOUNTS` of them have accounts in the initial state.
+fn actors(seed: u64) -> Vec<ZkSyncAccount> {
+    (0..INITIAL_ACCOUNTS + FRESH_ACCOUNTS)
+        .map(|index| ZkSyncAccount::rand_with_seed(rng_seed(seed, index as u32 + 1)))
+        .collect()
+}
+
+fn rng_seed(seed: u64, salt: u32) -> [u32; 4] {
+    [seed as u32, (seed >> 32) as u32, salt, 0x5eed]
+}
+
+fn initial_state(actors: &[ZkSyncAccount]) -> ZkSyncState {
+    let mut accounts: Vec<_> = actors
+        .iter()
+        .take(INITIAL_ACCOUNTS)
+        .enumerate()
+        .map(|(index, actor)| {
+            let mut account = Account::default_with_address(&actor.address);
+            account.pub_key_hash = actor.pubkey_hash;
+            for token in &FUNGIBLE_TOKENS {
+                account.add_balance(*token, &BigUint::from(INITIAL_BALANCE));
+            }
+            WitnessTestAccount {
+                zksync_account: actor.clone(),
+                id: AccountId(index as u32 + 1),
+                account,
+            }
+        })
+        .collect();
+    accounts.push(WitnessTestAccount::new_with_token(
+        NFT_STORAGE_ACCOUNT_ID,
+        NFT_TOKEN_ID,
+        MIN_NFT_TOKEN_ID as u64,
+    ));
+
+    ZkSyncStateGenerator::generate(&accounts).0
+}
+
+fn circuit_account_tree(state: &ZkSyncState) -> CircuitAccountTree {
+    let mut tree = CircuitAccountTree::new(account_tree_depth());
+    for (id, account) in state.get_accounts() {
+        tree.insert(id, CircuitAccount::from(account));
+    }
+    tree
+}
+
+/// Applies the operation to the witness in the same way as it's done for the block.
+fn add_operation<W: Witness>(
+    witness_accum: &mut WitnessBuilder,
+    op: &W::OperationType,
+    input: W::CalculateOpsInput,
+) {
+    let witness = W::apply_tx(witness_accum.account_tree, op);
+    let circuit_operations = witness.calculate_operations(input);
+    let pub_data_from_witness = witness.get_pubdata();
+    let offset_commitment = witness.get_offset_commitment_data();
+
+    witness_accum.add_operation_with_pubdata(
+        circuit_operations,
+        pub_data_from_witness,
+        offset_commitment,
+    );
+}
+
+/// Applies the operation to the witness and returns the fee collected by the circuit.
+fn apply_op_on_circuit(witness_accum: &mut WitnessBuilder, op: &ZkSyncOp) -> Option<CollectedFee> {
+    let sig_input =
+        |input: anyhow::Result<SigDataInput>| input.expect("SigDataInput creation failed");
+
+    match op {
+        ZkSyncOp::Deposit(op) => {
+            add_operation::<DepositWitness<Bn256>>(witness_accum, op, ());
+            None
+        }
+        ZkSyncOp::FullExit(op) => {
+            let success = op.withdraw_amount.is_some();
+            add_operation::<FullExitWitness<Bn256>>(witness_accum, &(*op.clone(), success), ());
+            None
+        }
+        ZkSyncOp::Transfer(op) => {
+            let input = sig_input(SigDataInput::from_transfer_op(op));
+            add_operation::<TransferWitness<Bn256>>(witness_accum, op, input);
+            Some(CollectedFee {
+                token: op.tx.token,
+                amount: op.tx.fee.clone(),
+            })
+        }
+        ZkSyncOp::TransferToNew(op) => {
+            let input = sig_input(SigDataInput::from_transfer_to_new_op(op));
+            add_operation::<TransferToNewWitness<Bn256>>(witness_accum, op, input);
+            Some(CollectedFee {
+                token: op.tx.token,
+                amount: op.tx.fee.clone(),
+            })
+        }
+        ZkSyncOp::Withdraw(op) => {
+            let input = sig_input(SigDataInput::from_withdraw_op(op));
+            add_operation::<WithdrawWitness<Bn256>>(witness_accum, op, input);
+            Some(CollectedFee {
+                token: op.tx.token,
+                amount: op.tx.fee.clone(),
+            })
+        }
+        ZkSyncOp::ChangePubKeyOffchain(op) => {
+            let input = sig_input(SigDataInput::from_change_pubkey_op(op));
+            add_operation::<ChangePubkeyOffChainWitness<Bn256>>(witness_accum, op, input);
+            Some(CollectedFee {
+                token: op.tx.fee_token,
+                amount: op.tx.fee.clone(),
+            })
+        }
+        ZkSyncOp::ForcedExit(op) => {
+            let input = sig_input(SigDataInput::from_forced_exit_op(op));
+            add_operation::<ForcedExitWitness<Bn256>>(witness_accum, op, input);
+            Some(CollectedFee {
+                token: op.tx.token,
+                amount: op.tx.fee.clone(),
+            })
+        }
+        ZkSyncOp::MintNFTOp(op) => {
+            let input = sig_input(SigDataInput::from_mint_nft_op(op));
+            add_operation::<MintNFTWitness<Bn256>>(witness_accum, op, input);
+            Some(CollectedFee {
+                token: op.tx.fee_token,
+                amount: op.tx.fee.clone(),
+            })
+        }
+        ZkSyncOp::WithdrawNFT(op) => {
+            let input = sig_input(SigDataInput::from_withdraw_nft_op(op));
+            add_operation::<WithdrawNFTWitness<Bn256>>(witness_accum, op, input);
+            Some(CollectedFee {
+                token: op.tx.fee_token,
+                amount: op.tx.fee.clone(),
+            })
+        }
+        ZkSyncOp::Swap(op) => {
+            let input = (
+                sig_input(SigDataInput::from_order(&op.tx.orders.0)),
+                sig_input(SigDataInput::from_order(&op.tx.orders.1)),
+                sig_input(SigDataInput::from_swap_op(op)),
+            );
+            add_operation::<SwapWitness<Bn256>>(witness_accum, op, input);
+            Some(CollectedFee {
+                token: op.tx.fee_token,
+                amount: op.tx.fee.clone(),
+            })
+        }
+        ZkSyncOp::Close(_) | ZkSyncOp::Noop(_) => {
+            unreachable!("Close and Noop operations are not generated")
+        }
+    }
+}
+
+/// Builds the block containing the only operation on top of the state and checks the constraint system.
+/// Returns the root hash after the fees are collected (unless the witness generation has failed)
+/// along with the result of the check.
+fn check_op_in_circuit(state: &ZkSyncState, op: &ZkSyncOp) -> (Option<Fr>, Result<(), String>) {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut circuit_account_tree = circuit_account_tree(state);
+        let mut witness_accum = WitnessBuilder::new(
+            &mut circuit_account_tree,
+            FEE_ACCOUNT_ID,
+            BlockNumber(1),
+            BLOCK_TIMESTAMP,
+        );
+
+        let fees: Vec<_> = apply_op_on_circuit(&mut witness_accum, op)
+            .into_iter()
+            .collect();
+        witness_accum.collect_fees(&fees);
+        witness_accum.calculate_pubdata_commitment();
+
+        let root_after_fees = witness_accum.root_after_fees;
+        let check = check_circuit_non_panicking(witness_accum.into_circuit_instance());
+        (root_after_fees, check)
+    }));
+
+    result.unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| payload.downcast_ref::<&str>().map(|msg| msg.to_string()))
+            .unwrap_or_default();
+        (
+            None,
+            Err(format!("witness generation panicked: {}", message)),
+        )
+    })
+}
+
+/// Applies the actions to the state and checks each of them against the circuit.
+struct Runner<'a> {
+    actors: &'a [ZkSyncAccount],
+    state: ZkSyncState,
+}
+
+impl<'a> Runner<'a> {
+    fn new(actors: &'a [ZkSyncAccount]) -> Self {
+        Self {
+            actors,
+            state: initial_state(actors),
+        }
+    }
+
+    fn account(&self, actor: usize) -> Option<(AccountId, Account)> {
+        self.state
+            .get_account_by_address(&self.actors[actor].address)
+    }
+
+    fn account_id(&self, address: &Address) -> Option<AccountId> {
+        self.state.get_account_by_address(address).map(|(id, _)| id)
+    }
+
+    /// Returns the ID the next created account gets.
+    fn next_free_id(&self) -> AccountId {
+        let max_id = self
+            .state
+            .get_accounts()
+            .into_iter()
+            .map(|(id, _)| AccountId(id))
+            .filter(|id| *id != NFT_STORAGE_ACCOUNT_ID)
+            .max()
+            .unwrap_or(FEE_ACCOUNT_ID);
+        AccountId(*max_id + 1)
+    }
+
+    fn nft(&self, index: usize) -> Option<NFT> {
+        let mut ids: Vec<_> = self.state.nfts.keys().copied().collect();
+        if ids.is_empty() {
+            return None;
+        }
+        ids.sort_unstable();
+        self.state.nfts.get(&ids[index % ids.len()]).cloned()
+    }
+
+    fn token(&self, token: TokenChoice) -> Option<TokenId> {
+        match token {
+            TokenChoice::Fungible(token) => Some(token),
+            TokenChoice::Nft(index) => self.nft(index).map(|nft| nft.id),
+        }
+    }
+
+    fn nonce(account: &Account, mutation: Mutation) -> Nonce {
+        if mutation == Mutation::WrongNonce {
+            Nonce(*account.nonce + 1)
+        } else {
+            account.nonce
+        }
+    }
+
+    fn private_key(&self, actor: usize, mutation: Mutation) -> &PrivateKey {
+        let signer = if mutation == Mutation::ForeignSigner {
+            (actor + 1) % self.actors.len()
+        } else {
+            actor
+        };
+        &self.actors[signer].private_key
+    }
+
+    /// Returns the fee paid by the account, exceeding the balance for the `Overspend` mutation.
+    fn fee(account: &Account, token: TokenId, fee: u64, mutation: Mutation) -> BigUint {
+        let fee = if mutation == Mutation::Overspend {
+            account.get_balance(token) + fee + 1u32
+        } else {
+            BigUint::from(fee)
+        };
+        closest_packable_fee_amount(&fee)
+    }
+
+    /// Resolves the action against the current state.
+    /// Returns `None` if the action cannot be represented as an operation, e.g. if the signer doesn't have an account.
+    fn prepare(&self, action: &Action) -> Option<Step> {
+        let step = match *action {
+            Action::Deposit { to, token, amount } => {
+                let address = self.actors[to].address;
+                Step::Priority(ZkSyncPriorityOp::Deposit(Deposit {
+                    from: address,
+                    token,
+                    amount: BigUint::from(amount),
+                    to: address,
+                }))
+            }
+            Action::FullExit {
+                account,
+                owner,
+                token,
+            } => {
+                let account_id = self
+                    .account(account)
+                    .map(|(id, _)| id)
+                    .unwrap_or_else(|| AccountId(*self.next_free_id() + 1));
+                Step::Priority(ZkSyncPriorityOp::FullExit(FullExit {
+                    account_id,
+                    eth_address: self.actors[owner].address,
+                    token: self.token(token)?,
+                    is_legacy: false,
+                }))
+            }
+            Action::Transfer {
+                from,
+                to,
+                token: token_choice,
+                amount,
+                fee,
+                mutation,
+            } => {
+                let (from_id, from_account) = self.account(from)?;
+                let to_address = self.actors[to].address;
+                let token = self.token(token_choice)?;
+                let (amount, fee) = if let TokenChoice::Nft(_) = token_choice {
+                    // NFTs cannot be used to pay fees.
+                    let amount = if mutation == Mutation::Overspend {
+                        2u32
+                    } else {
+                        1u32
+                    };
+                    (BigUint::from(amount), BigUint::from(0u32))
+                } else {
+                    let amount = if mutation == Mutation::Overspend {
+                        from_account.get_balance(token) + amount
+                    } else {
+                        BigUint::from(amount)
+                    };
+                    (
+                        closest_packable_token_amount(&amount),
+                        closest_packable_fee_amount(&BigUint::from(fee)),
+                    )
+                };
+                let tx = Transfer::new_signed(
+                    from_id,
+                    self.actors[from].address,
+                    to_address,
+                    token,
+                    amount,
+                    fee,
+                    Self::nonce(&from_account, mutation),
+                    mutation.time_range(),
+                    self.private_key(from, mutation),
+                )
+                .ok()?;
+
+                let rejected_op = match self.account_id(&to_address) {
+                    Some(to_id) => TransferOp {
+                        tx: tx.clone(),
+                        from: from_id,
+                        to: to_id,
+                    }
+                    .into(),
+                    None => TransferToNewOp {
+                        tx: tx.clone(),
+                        from: from_id,
+                        to: self.next_free_id(),
+                    }
+                    .into(),
+                };
+                Step::Tx {
+                    tx: tx.into(),
+                    rejected_op: Some(rejected_op),
+                }
+            }
+            Action::Withdraw {
+                from,
+                token,
+                amount,
+                fee,
+                mutation,
+            } => {
+                let (from_id, from_account) = self.account(from)?;
+                let address = self.actors[from].address;
+                let amount = if mutation == Mutation::Overspend {
+                    from_account.get_balance(token) + amount
+                } else {
+                    BigUint::from(amount)
+                };
+                let tx = Withdraw::new_signed(
+                    from_id,
+                    address,
+                    address,
+                    token,
+                    closest_packable_token_amount(&amount),
+                    closest_packable_fee_amount(&BigUint::from(fee)),
+                    Self::nonce(&from_account, mutation),
+                    mutation.time_range(),
+                    self.private_key(from, mutation),
+                )
+                .ok()?;
+
+                let rejected_op = WithdrawOp {
+                    tx: tx.clone(),
+                    account_id: from_id,
+                };
+                Step::Tx {
+                    tx: tx.into(),
+                    rejected_op: Some(rejected_op.into()),
+                }
+            }
+            Action::ChangePubKey {
+                account,
+                fee_token,
+                fee,
+                mutation,
+            } => {
+                let (account_id, state_account) = self.account(account)?;
+                let actor = &self.actors[account];
+                // The transaction is signed with the new key, so the foreign signer is not applicable.
+                actor.set_account_id(Some(account_id));
+                let tx = actor.sign_change_pubkey_tx(
+                    Some(Self::nonce(&state_account, mutation)),
+                    false,
+                    fee_token,
+                    Self::fee(&state_account, fee_token, fee, mutation),
+                    ChangePubKeyType::ECDSA,
+                    mutation.time_range(),
+                );
+
+                let rejected_op = ChangePubKeyOp {
+                    tx: tx.clone(),
+                    account_id,
+                };
+                Step::Tx {
+                    tx: tx.into(),
+                    rejected_op: Some(rejected_op.into()),
+                }
+            }
+            Action::ForcedExit {
+                initiator,
+                target,
+                token,
+                fee,
+                mutation,
+            } => {
+                let (initiator_id, initiator_account) = self.account(initiator)?;
+                let target_address = self.actors[target].address;
+                let tx = ForcedExit::new_signed(
+                    initiator_id,
+                    target_address,
+                    token,
+                    Self::fee(&initiator_account, token, fee, mutation),
+                    Self::nonce(&initiator_account, mutation),
+                    mutation.time_range(),
+                    self.private_key(initiator, mutation),
+                )
+                .ok()?;
+
+                let rejected_op = self.account(target).map(|(target_id, target_account)| {
+                    ForcedExitOp {
+                        tx: tx.clone(),
+                        target_account_id: target_id,
+                        withdraw_amount: Some(target_account.get_balance(token).into()),
+                    }
+                    .into()
+                });
+                Step::Tx {
+                    tx: tx.into(),
+                    rejected_op,
+                }
+            }
+            Action::MintNFT {
+                creator,
+                recipient,
+                content_hash,
+                fee_token,
+                fee,
+                mutation,
+            } => {
+                let (creator_id, creator_account) = self.account(creator)?;
+                let recipient_address = self.actors[recipient].address;
+                // `MintNFT` doesn't have a time range, so it cannot expire.
+                let tx = MintNFT::new_signed(
+                    creator_id,
+                    self.actors[creator].address,
+                    H256::from_low_u64_be(content_hash),
+                    recipient_address,
+                    Self::fee(&creator_account, fee_token, fee, mutation),
+                    fee_token,
+                    Self::nonce(&creator_account, mutation),
+                    self.private_key(creator, mutation),
+                )
+                .ok()?;
+
+                let rejected_op = self.account_id(&recipient_address).map(|recipient_id| {
+                    MintNFTOp {
+                        tx: tx.clone(),
+                        creator_account_id: creator_id,
+                        recipient_account_id: recipient_id,
+                    }
+                    .into()
+                });
+                Step::Tx {
+                    tx: tx.into(),
+                    rejected_op,
+                }
+            }
+            Action::WithdrawNFT {
+                owner,
+                nft,
+                fee_token,
+                fee,
+                mutation,
+            } => {
+                let (owner_id, owner_account) = self.account(owner)?;
+                let nft = self.nft(nft)?;
+                let address = self.actors[owner].address;
+                let tx = WithdrawNFT::new_signed(
+                    owner_id,
+                    address,
+                    address,
+                    nft.id,
+                    fee_token,
+                    Self::fee(&owner_account, fee_token, fee, mutation),
+                    Self::nonce(&owner_account, mutation),
+                    mutation.time_range(),
+                    self.private_key(owner, mutation),
+                )
+                .ok()?;
+
+                let rejected_op = WithdrawNFTOp {
+                    tx: tx.clone(),
+                    creator_id: nft.creator_id,
+                    creator_address: nft.creator_address,
+                    serial_id: nft.serial_id,
+                    content_hash: nft.content_hash,
+                };
+                Step::Tx {
+                    tx: tx.into(),
+                    rejected_op: Some(rejected_op.into()),
+                }
+            }
+            Action::Swap {
+                submitter,
+                accounts,
+                tokens,
+                amount,
+                limit_orders,
+                fee_token,
+                fee,
+                mutation,
+            } => {
+                let (submitter_id, submitter_account) = self.account(submitter)?;
+                let (account_0, state_account_0) = self.account(accounts.0)?;
+                let (account_1, state_account_1) = self.account(accounts.1)?;
+                let amount = if mutation == Mutation::Overspend {
+                    state_account_0.get_balance(tokens.0) + amount
+                } else {
+                    BigUint::from(amount)
+                };
+                let amount = closest_packable_token_amount(&amount);
+
+                let order = |actor: usize,
+                             account_id: AccountId,
+                             account: &Account,
+                             tokens: (TokenId, TokenId),
+                             is_limit_order: bool,
+                             time_range: TimeRange| {
+                    let order_amount = if is_limit_order {
+                        BigUint::from(0u32)
+                    } else {
+                        amount.clone()
+                    };
+                    Order::new_signed(
+                        account_id,
+                        self.actors[actor].address,
+                        account.nonce,
+                        tokens.0,
+                        tokens.1,
+                        (BigUint::from(1u32), BigUint::from(1u32)),
+                        order_amount,
+                        time_range,
+                        &self.actors[actor].private_key,
+                    )
+                    .ok()
+                };
+                let orders = (
+                    order(
+                        accounts.0,
+                        account_0,
+                        &state_account_0,
+                        tokens,
+                        limit_orders.0,
+                        mutation.time_range(),
+                    )?,
+                    order(
+                        accounts.1,
+                        account_1,
+                        &state_account_1,
+                        (tokens.1, tokens.0),
+                        limit_orders.1,
+                        TimeRange::default(),
+                    )?,
+                );
+                let tx = Swap::new_signed(
+                    submitter_id,
+                    self.actors[submitter].address,
+                    Self::nonce(&submitter_account, mutation),
+                    orders,
+                    (amount.clone(), amount),
+                    closest_packable_fee_amount(&BigUint::from(fee)),
+                    fee_token,
+                    self.private_key(submitter, mutation),
+                )
+                .ok()?;
+
+                let rejected_op = SwapOp {
+                    tx: tx.clone(),
+                    submitter: submitter_id,
+                    accounts: (account_0, account_1),
+                    recipients: (account_0, account_1),
+                };
+                Step::Tx {
+                    tx: tx.into(),
+                    rejected_op: Some(rejected_op.into()),
+                }
+            }
+        };
+        Some(step)
+    }
+
+    /// Checks the operation accepted by the state against the circuit and moves to the new state.
+    fn accept(
+        &mut self,
+        mut new_state: ZkSyncState,
+        op: ZkSyncOp,
+        fee: Option<CollectedFee>,
+    ) -> Result<(), String> {
+        let (circuit_root, check) = check_op_in_circuit(&self.state, &op);
+        check.map_err(|err| {
+            format!(
+                "state accepted the operation, but the circuit is not satisfied: {}",
+                err
+            )
+        })?;
+
+        let fees: Vec<_> = fee.into_iter().collect();
+        new_state.collect_fee(&fees, FEE_ACCOUNT_ID);
+        if circuit_root != Some(new_state.root_hash()) {
+            return Err(format!(
+                "root hash mismatch: state has {}, circuit has {:?}",
+                new_state.root_hash(),
+                circuit_root
+            ));
+        }
+
+        self.state = new_state;
+        Ok(())
+    }
+
+    /// Applies the action and returns its outcome, or the description of the divergence.
+    fn apply(&mut self, action: &Action) -> Result<Outcome, String> {
+        let step = match self.prepare(action) {
+            Some(step) => step,
+            None => return Ok(Outcome::Skipped),
+        };
+
+        match step {
+            Step::Priority(op) => {
+                // Priority operations are always processed by the state.
+                let mut new_state = self.state.clone();
+                let success = new_state.execute_priority_op(op);
+                self.accept(new_state, success.executed_op, success.fee)?;
+                Ok(Outcome::Accepted)
+            }
+            Step::Tx {
+                mut tx,
+                rejected_op,
+            } => {
+                let mut new_state = self.state.clone();
+                let result = tx
+                    .check_correctness()
+                    .map_err(|err| err.to_string())
+                    .and_then(|_| {
+                        new_state
+                            .execute_tx(tx, BLOCK_TIMESTAMP)
+                            .map_err(|err| err.to_string())
+                    });
+
+                match (result, rejected_op) {
+                    (Ok(success), _) => {
+                        self.accept(new_state, success.executed_op, success.fee)?;
+                        Ok(Outcome::Accepted)
+                    }
+                    (Err(reason), Some(op)) => {
+                        let (_, check) = check_op_in_circuit(&self.state, &op);
+                        if check.is_ok() {
+                            return Err(format!(
+                                "state rejected the operation ({}), but the circuit is satisfied",
+                                reason
+                            ));
+                        }
+                        Ok(Outcome::Rejected)
+                    }
+                    (Err(_), None) => Ok(Outcome::Unchecked),
+                }
+            }
+        }
+    }
+}
+
+/// Runs the sequence of actions starting from the initial state.
+fn run_sequence(actors: &[ZkSyncAccount], actions: &[Action]) -> Result<RunStats, Divergence> {
+    let mut runner = Runner::new(actors);
+    let mut stats = RunStats::default();
+
+    for (step, action) in actions.iter().enumerate() {
+        let outcome = runner.apply(action).map_err(|details| Divergence {
+            step,
+            action: action.clone(),
+            details,
+        })?;
+        match outcome {
+            Outcome::Accepted => stats.accepted += 1,
+            Outcome::Rejected => stats.rejected += 1,
+            Outcome::Unchecked => stats.unchecked += 1,
+            Outcome::Skipped => stats.skipped += 1,
+        }
+    }
+    Ok(stats)
+}
+
+/// Shrinks the diverging sequence by removing chunks of actions for as long as it still diverges.
+fn minimize(
+    actors: &[ZkSyncAccount],
+    mut actions: Vec<Action>,
+    mut divergence: Divergence,
+) -> (Vec<Action>, Divergence) {
+    // Actions after the diverging one don't matter.
+    actions.truncate(divergence.step + 1);
+
+    let mut chunk_size = std::cmp::max(actions.len() / 2, 1);
+    while chunk_size > 0 {
+        let mut start = 0;
+        while start < actions.len() {
+            let end = std::cmp::min(start + chunk_size, actions.len());
+            let candidate: Vec<_> = actions[..start]
+                .iter()
+                .chain(&actions[end..])
+                .cloned()
+                .collect();
+
+            match run_sequence(actors, &candidate) {
+                Err(found) => {
+                    actions = candidate;
+                    actions.truncate(found.step + 1);
+                    divergence = found;
+                }
+                Ok(_) => start += chunk_size,
+            }
+        }
+        chunk_size /= 2;
+    }
+
+    (actions, divergence)
+}
+
+/// Generates and runs the sequence for the seed, returning the report of the minimized divergence if any.
+fn fuzz_seed(seed: u64) -> Result<RunStats, String> {
+    let actors = actors(seed);
+    let mut rng = XorShiftRng::from_seed(rng_seed(seed, 0));
+    let actions: Vec<_> = (0..SEQUENCE_LENGTH)
+        .map(|_| Action::random(&mut rng))
+        .collect();
+
+    run_sequence(&actors, &actions).map_err(|divergence| {
+        let (actions, divergence) = minimize(&actors, actions, divergence);
+        format!(
+            "{} at action #{} {:?}\nMinimized sequence: {:#?}",
+            divergence.details, divergence.step, divergence.action, actions
+        )
+    })
+}
+
+fn env_u64(name: &str) -> Option<u64> {
+    std::env::var(name).ok().map(|value| {
+        value
+            .parse()
+            .unwrap_or_else(|_| panic!("{} must be a number, got '{}'", name, value))
+    })
+}
+
+/// Applies random sequences of operations to the state and checks that the circuit
+/// accepts exactly the operations accepted by the state.
+#[test]
+#[ignore]
+fn state_and_circuit_agree_on_random_operations() {
+    let seeds: Vec<u64> = match env_u64("CIRCUIT_FUZZ_SEED") {
+        Some(seed) => vec![seed],
+        None => (0..env_u64("CIRCUIT_FUZZ_RUNS").unwrap_or(DEFAULT_RUNS)).collect(),
+    };
+
+    let results: Vec<_> = seeds
+        .into_par_iter()
+        .map(|seed| (seed, fuzz_seed(seed)))
+        .collect();
+
+    let mut total = RunStats::default();
+    let mut failures = Vec::new();
+    for (seed, result) in results {
+        match result {
+            Ok(stats) => total.add(&stats),
+            Err(report) => failures.push(format!("Seed {}: {}", seed, report)),
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "State and circuit diverged:\n{}",
+        failures.join("\n\n")
+    );
+    println!("Processed actions: {:?}", total);
+    assert!(
+        total.accepted > 0 && total.rejected > 0,
+        "Generated sequences don't cover both accepted and rejected operations: {:?}",
+        total
+    );
+    assert!(
+        total.unchecked_share() <= MAX_UNCHECKED_SHARE,
+        "Too many state rejections weren't checked against the circuit: {:?}",
+        total
+    );
+}
+
+/// Checks that the generated sequences are reproducible from the seed.
+#[test]
+fn sequences_are_reproducible() {
+    let generate = |seed| {
+        let mut rng = XorShiftRng::from_seed(rng_seed(seed, 0));
+        let actions: Vec<_> = (0..SEQUENCE_LENGTH)
+            .map(|_| Action::random(&mut rng))
+            .collect();
+        format!("{:?}", actions)
+    };
+
+    assert_eq!(generate(42), generate(42));
+    assert_ne!(generate(42), generate(43));
+
+    let addresses = |seed| -> Vec<_> { actors(seed).iter().map(|actor| actor.address).collect() };
+    assert_eq!(addresses(42), addresses(42));
+}
@@ -38,10 +38,12 @@ use crate::{
 
 mod change_pubkey_offchain;
 mod deposit;
+mod differential;
 mod forced_exit;
 mod full_exit;
 mod mint_nft;
 mod noop;
+mod serialization;
 mod swap;
 pub(crate) mod test_utils;
 mod transfer;
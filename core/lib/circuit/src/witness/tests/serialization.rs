@@ -0,0 +1,95 @@
+// External deps
+use num::BigUint;
+use zksync_crypto::franklin_crypto::bellman::pairing::bn256::Bn256;
+// Workspace deps
+use zksync_types::{operations::DepositOp, AccountId, BlockNumber, Deposit, TokenId};
+// Local deps
+use crate::{
+    serialization::{ProverData, WitnessFormat},
+    witness::{
+        deposit::DepositWitness,
+        tests::test_utils::{
+            WitnessTestAccount, ZkSyncStateGenerator, BLOCK_TIMESTAMP, FEE_ACCOUNT_ID,
+        },
+        utils::WitnessBuilder,
+        Witness,
+    },
+};
+
+/// Creates the prover data for the block with several deposits.
+fn deposits_prover_data(deposits_count: usize) -> ProverData {
+    let accounts = vec![WitnessTestAccount::new_empty(AccountId(1))];
+    let (_, mut circuit_account_tree) = ZkSyncStateGenerator::generate(&accounts);
+    let mut witness_accum = WitnessBuilder::new(
+        &mut circuit_account_tree,
+        FEE_ACCOUNT_ID,
+        BlockNumber(1),
+        BLOCK_TIMESTAMP,
+    );
+
+    let deposit_op = DepositOp {
+        priority_op: Deposit {
+            from: accounts[0].account.address,
+            token: TokenId(0),
+            amount: BigUint::from(1u32),
+            to: accounts[0].account.address,
+        },
+        account_id: accounts[0].id,
+    };
+    for _ in 0..deposits_count {
+        let witness = DepositWitness::<Bn256>::apply_tx(witness_accum.account_tree, &deposit_op);
+        witness_accum.add_operation_with_pubdata(
+            witness.calculate_operations(()),
+            witness.get_pubdata(),
+            witness.get_offset_commitment_data(),
+        );
+    }
+    witness_accum.extend_pubdata_with_noops(deposits_count * DepositOp::CHUNKS);
+    witness_accum.collect_fees(&[]);
+    witness_accum.calculate_pubdata_commitment();
+
+    witness_accum.into()
+}
+
+/// Checks that the witness is encoded in the current format and can be decoded back.
+#[test]
+fn witness_encoding_roundtrip() {
+    let prover_data = deposits_prover_data(2);
+
+    let encoded = prover_data.encode().unwrap();
+    assert_eq!(WitnessFormat::of(&encoded).unwrap(), WitnessFormat::CURRENT);
+
+    let decoded = ProverData::decode(&encoded).unwrap();
+    assert_eq!(
+        serde_json::to_value(&decoded).unwrap(),
+        serde_json::to_value(&prover_data).unwrap()
+    );
+
+    let legacy_json = prover_data.encode_legacy_json().unwrap();
+    assert!(encoded.len() < legacy_json.len());
+}
+
+/// Checks that the witnesses stored as JSON are still readable.
+#[test]
+fn legacy_json_witness_decoding() {
+    let prover_data = deposits_prover_data(1);
+
+    let encoded = prover_data.encode_legacy_json().unwrap();
+    assert_eq!(
+        WitnessFormat::of(&encoded).unwrap(),
+        WitnessFormat::LegacyJson
+    );
+
+    let decoded = ProverData::decode(&encoded).unwrap();
+    assert_eq!(
+        serde_json::to_value(&decoded).unwrap(),
+        serde_json::to_value(&prover_data).unwrap()
+    );
+}
+
+/// Checks that the witness in the unknown format is rejected.
+#[test]
+fn unknown_witness_format() {
+    assert!(ProverData::decode(&[]).is_err());
+    assert!(ProverData::decode(&[0xff, 0x00]).is_err());
+}
@@ -228,6 +228,10 @@ pub struct Web3Config {
     pub port: u16,
     /// URL to access web3 JSON RPC server.
     pub url: String,
+    /// Port to which the web3 WebSocket RPC server is listening.
+    pub ws_port: u16,
+    /// URL to access web3 WebSocket RPC server.
+    pub ws_url: String,
     /// Max difference between blocks in `eth_getLogs` method.
     pub max_block_range: u32,
     pub chain_id: u64,
@@ -237,6 +241,10 @@ impl Web3Config {
     pub fn bind_addr(&self) -> SocketAddr {
         SocketAddr::new("0.0.0.0".parse().unwrap(), self.port)
     }
+
+    pub fn ws_bind_addr(&self) -> SocketAddr {
+        SocketAddr::new("0.0.0.0".parse().unwrap(), self.ws_port)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -282,6 +290,8 @@ mod tests {
             web3: Web3Config {
                 port: 3002,
                 url: "http://127.0.0.1:3002".into(),
+                ws_port: 3003,
+                ws_url: "ws://127.0.0.1:3003".into(),
                 max_block_range: 10,
                 chain_id: 240,
             },
@@ -325,6 +335,8 @@ API_JSON_RPC_WS_PORT="3031"
 API_JSON_RPC_WS_URL="ws://127.0.0.1:3031"
 API_WEB3_PORT="3002"
 API_WEB3_URL="http://127.0.0.1:3002"
+API_WEB3_WS_PORT="3003"
+API_WEB3_WS_URL="ws://127.0.0.1:3003"
 API_WEB3_CHAIN_ID="240"
 API_WEB3_MAX_BLOCK_RANGE="10"
 API_PRIVATE_PORT="8090"
@@ -370,5 +382,9 @@ API_PROMETHEUS_PORT="3312"
             config.web3.bind_addr(),
             SocketAddr::new(bind_broadcast_addr, config.web3.port)
         );
+        assert_eq!(
+            config.web3.ws_bind_addr(),
+            SocketAddr::new(bind_broadcast_addr, config.web3.ws_port)
+        );
     }
 }
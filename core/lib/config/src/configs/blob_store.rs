@@ -0,0 +1,92 @@
+// Built-in uses
+use std::{env, time::Duration};
+// External uses
+use serde::Deserialize;
+// Local uses
+use crate::envy_load;
+
+/// Backend used to store the large prover data (witnesses and proofs).
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BlobStoreBackend {
+    /// Data is stored directly in the database tables.
+    Postgres,
+    /// Data is stored as files in the local directory.
+    Fs,
+    /// Data is stored in the S3-compatible object storage.
+    S3,
+}
+
+/// Configuration of the storage for witnesses and proofs.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct BlobStoreConfig {
+    pub backend: BlobStoreBackend,
+    /// Directory for the blobs, used by the `fs` backend.
+    pub fs_path: String,
+    /// Object storage endpoint, e.g. `https://s3.eu-central-1.amazonaws.com` or `http://127.0.0.1:9000`.
+    pub s3_endpoint: String,
+    pub s3_bucket: String,
+    pub s3_region: String,
+    /// Access key and secret key are defined in the `private.toml`.
+    pub s3_access_key: String,
+    pub s3_secret_key: String,
+    /// Interval (in seconds) between the removals of the blobs for the blocks executed on L1.
+    pub gc_interval: u64,
+}
+
+impl BlobStoreConfig {
+    pub fn from_env() -> Self {
+        envy_load!("blob_store", "BLOB_STORE_")
+    }
+
+    /// Loads the config if the backend is set in the environment.
+    /// Returns `None` otherwise, meaning that the data is stored in the database tables.
+    pub fn from_env_opt() -> Option<Self> {
+        env::var("BLOB_STORE_BACKEND")
+            .ok()
+            .map(|_| Self::from_env())
+    }
+
+    pub fn gc_interval(&self) -> Duration {
+        Duration::from_secs(self.gc_interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::test_utils::set_env;
+
+    fn expected_config() -> BlobStoreConfig {
+        BlobStoreConfig {
+            backend: BlobStoreBackend::S3,
+            fs_path: "./blobs".into(),
+            s3_endpoint: "http://127.0.0.1:9000".into(),
+            s3_bucket: "zksync-prover-data".into(),
+            s3_region: "us-east-1".into(),
+            s3_access_key: "minioadmin".into(),
+            s3_secret_key: "minioadmin".into(),
+            gc_interval: 600,
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+BLOB_STORE_BACKEND="s3"
+BLOB_STORE_FS_PATH="./blobs"
+BLOB_STORE_S3_ENDPOINT="http://127.0.0.1:9000"
+BLOB_STORE_S3_BUCKET="zksync-prover-data"
+BLOB_STORE_S3_REGION="us-east-1"
+BLOB_STORE_S3_ACCESS_KEY="minioadmin"
+BLOB_STORE_S3_SECRET_KEY="minioadmin"
+BLOB_STORE_GC_INTERVAL="600"
+        "#;
+        set_env(config);
+
+        let actual = BlobStoreConfig::from_env();
+        assert_eq!(actual, expected_config());
+        assert_eq!(actual.gc_interval(), Duration::from_secs(600));
+        assert_eq!(BlobStoreConfig::from_env_opt(), Some(expected_config()));
+    }
+}
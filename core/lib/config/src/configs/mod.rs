@@ -1,14 +1,15 @@
 // Public re-exports
 pub use self::{
-    api::ApiConfig, chain::ChainConfig, contracts::ContractsConfig, database::DBConfig,
-    dev_liquidity_token_watcher::DevLiquidityTokenWatcherConfig, eth_client::ETHClientConfig,
-    eth_sender::ETHSenderConfig, eth_watch::ETHWatchConfig, event_listener::EventListenerConfig,
-    forced_exit_requests::ForcedExitRequestsConfig, gateway_watcher::GatewayWatcherConfig,
-    misc::MiscConfig, prover::ProverConfig, ticker::TickerConfig,
-    token_handler::TokenHandlerConfig,
+    api::ApiConfig, blob_store::BlobStoreConfig, chain::ChainConfig, contracts::ContractsConfig,
+    database::DBConfig, dev_liquidity_token_watcher::DevLiquidityTokenWatcherConfig,
+    eth_client::ETHClientConfig, eth_sender::ETHSenderConfig, eth_watch::ETHWatchConfig,
+    event_listener::EventListenerConfig, forced_exit_requests::ForcedExitRequestsConfig,
+    gateway_watcher::GatewayWatcherConfig, misc::MiscConfig, prover::ProverConfig,
+    ticker::TickerConfig, token_handler::TokenHandlerConfig,
 };
 
 pub mod api;
+pub mod blob_store;
 pub mod chain;
 pub mod contracts;
 pub mod database;
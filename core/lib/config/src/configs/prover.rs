@@ -34,6 +34,15 @@ pub struct Prover {
     pub request_timeout: u64,
     /// Flag for dying after proving cycle
     pub die_after_proof: bool,
+    /// The largest block chunks size the prover is able to prove, not limited if not set.
+    pub max_block_chunk_size: Option<usize>,
+    /// Whether the prover prefers aggregated proof jobs, no preference if not set.
+    pub prefer_aggregated_proof: Option<bool>,
+    /// Amount of jobs proven in parallel, one job if not set.
+    /// If set to `0`, the amount is chosen from the available CPU cores and memory.
+    pub parallel_jobs: Option<usize>,
+    /// Memory required to prove a single job in MB, used to choose the amount of parallel jobs.
+    pub job_memory: Option<u64>,
 }
 
 impl Prover {
@@ -60,6 +69,18 @@ pub struct Core {
     pub gone_timeout: u64,
     /// Amount of provers in the cluser if there is no pending jobs.
     pub idle_provers: u32,
+    /// Time after which a job is given to any prover regardless of its capabilities in ms.
+    pub job_starvation_timeout: u64,
+    /// Amount of times the job is given to provers before it's marked as failed.
+    pub max_job_attempts: u32,
+    /// Webhook for the failed jobs notifications, notifications are disabled if empty.
+    pub failed_job_webhook_url: String,
+    /// Desired time between the job creation and receiving its proof in ms, used by the prover scaler.
+    pub scaler_target_latency: u64,
+    /// Period of the job history used to estimate the proof durations and the jobs rate in ms.
+    pub scaler_history_window: u64,
+    /// Time the required amount of provers must stay lower before the scaler reduces it in ms.
+    pub scaler_scale_down_delay: u64,
 }
 
 impl Core {
@@ -67,6 +88,26 @@ impl Core {
     pub fn gone_timeout(&self) -> Duration {
         Duration::from_millis(self.gone_timeout)
     }
+
+    /// Converts `self.job_starvation_timeout` into `Duration`.
+    pub fn job_starvation_timeout(&self) -> Duration {
+        Duration::from_millis(self.job_starvation_timeout)
+    }
+
+    /// Converts `self.scaler_target_latency` into `Duration`.
+    pub fn scaler_target_latency(&self) -> Duration {
+        Duration::from_millis(self.scaler_target_latency)
+    }
+
+    /// Converts `self.scaler_history_window` into `Duration`.
+    pub fn scaler_history_window(&self) -> Duration {
+        Duration::from_millis(self.scaler_history_window)
+    }
+
+    /// Converts `self.scaler_scale_down_delay` into `Duration`.
+    pub fn scaler_scale_down_delay(&self) -> Duration {
+        Duration::from_millis(self.scaler_scale_down_delay)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -75,6 +116,9 @@ pub struct WitnessGenerator {
     pub prepare_data_interval: u64,
     /// Amount of witness generator threads.
     pub witness_generators: usize,
+    /// Amount of workers building the witnesses in the pipelined mode, where a single witness generator
+    /// advances the account tree block by block. The pipelined mode is disabled if set to 0.
+    pub pipeline_workers: usize,
 }
 
 impl WitnessGenerator {
@@ -96,14 +140,25 @@ mod tests {
                 cycle_wait: 500,
                 request_timeout: 10,
                 die_after_proof: false,
+                max_block_chunk_size: Some(32),
+                prefer_aggregated_proof: None,
+                parallel_jobs: Some(0),
+                job_memory: Some(8192),
             },
             core: Core {
                 gone_timeout: 60000,
                 idle_provers: 1,
+                job_starvation_timeout: 600000,
+                max_job_attempts: 5,
+                failed_job_webhook_url: "http://127.0.0.1".into(),
+                scaler_target_latency: 1800000,
+                scaler_history_window: 3600000,
+                scaler_scale_down_delay: 600000,
             },
             witness_generator: WitnessGenerator {
                 prepare_data_interval: 500,
                 witness_generators: 2,
+                pipeline_workers: 4,
             },
         }
     }
@@ -115,10 +170,20 @@ PROVER_PROVER_HEARTBEAT_INTERVAL="1000"
 PROVER_PROVER_CYCLE_WAIT="500"
 PROVER_PROVER_REQUEST_TIMEOUT="10"
 PROVER_PROVER_DIE_AFTER_PROOF=false
+PROVER_PROVER_MAX_BLOCK_CHUNK_SIZE="32"
+PROVER_PROVER_PARALLEL_JOBS="0"
+PROVER_PROVER_JOB_MEMORY="8192"
 PROVER_CORE_GONE_TIMEOUT="60000"
 PROVER_CORE_IDLE_PROVERS="1"
+PROVER_CORE_JOB_STARVATION_TIMEOUT="600000"
+PROVER_CORE_MAX_JOB_ATTEMPTS="5"
+PROVER_CORE_FAILED_JOB_WEBHOOK_URL="http://127.0.0.1"
+PROVER_CORE_SCALER_TARGET_LATENCY="1800000"
+PROVER_CORE_SCALER_HISTORY_WINDOW="3600000"
+PROVER_CORE_SCALER_SCALE_DOWN_DELAY="600000"
 PROVER_WITNESS_GENERATOR_PREPARE_DATA_INTERVAL="500"
 PROVER_WITNESS_GENERATOR_WITNESS_GENERATORS="2"
+PROVER_WITNESS_GENERATOR_PIPELINE_WORKERS="4"
         "#;
         set_env(config);
 
@@ -148,6 +213,22 @@ PROVER_WITNESS_GENERATOR_WITNESS_GENERATORS="2"
             config.core.gone_timeout(),
             Duration::from_millis(config.core.gone_timeout)
         );
+        assert_eq!(
+            config.core.job_starvation_timeout(),
+            Duration::from_millis(config.core.job_starvation_timeout)
+        );
+        assert_eq!(
+            config.core.scaler_target_latency(),
+            Duration::from_millis(config.core.scaler_target_latency)
+        );
+        assert_eq!(
+            config.core.scaler_history_window(),
+            Duration::from_millis(config.core.scaler_history_window)
+        );
+        assert_eq!(
+            config.core.scaler_scale_down_delay(),
+            Duration::from_millis(config.core.scaler_scale_down_delay)
+        );
 
         assert_eq!(
             config.witness_generator.prepare_data_interval(),
@@ -1,9 +1,9 @@
 #![allow(clippy::derive_partial_eq_without_eq)]
 pub use crate::configs::{
-    ApiConfig, ChainConfig, ContractsConfig, DBConfig, DevLiquidityTokenWatcherConfig,
-    ETHClientConfig, ETHSenderConfig, ETHWatchConfig, EventListenerConfig,
-    ForcedExitRequestsConfig, GatewayWatcherConfig, MiscConfig, ProverConfig, TickerConfig,
-    TokenHandlerConfig,
+    ApiConfig, BlobStoreConfig, ChainConfig, ContractsConfig, DBConfig,
+    DevLiquidityTokenWatcherConfig, ETHClientConfig, ETHSenderConfig, ETHWatchConfig,
+    EventListenerConfig, ForcedExitRequestsConfig, GatewayWatcherConfig, MiscConfig, ProverConfig,
+    TickerConfig, TokenHandlerConfig,
 };
 
 pub mod configs;
@@ -1,4 +1,4 @@
-use crate::franklin_crypto::bellman::pairing::ff;
+use crate::franklin_crypto::bellman::pairing::{ff, GroupDecodingError};
 use hex::FromHexError;
 use thiserror::Error;
 
@@ -18,4 +18,6 @@ pub enum ConversionError {
     ParsingError(std::io::Error),
     #[error("Cannot convert into prime field value: {0}")]
     PrimeFieldDecodingError(#[from] ff::PrimeFieldDecodingError),
+    #[error("Cannot convert into curve point: {0}")]
+    GroupDecodingError(#[from] GroupDecodingError),
 }
@@ -4,14 +4,14 @@ use std::{convert::TryInto, mem};
 use crate::franklin_crypto::bellman::pairing::{
     bn256::Bn256,
     ff::{PrimeField, PrimeFieldRepr, ScalarEngine},
-    CurveAffine, Engine,
+    CurveAffine, EncodedPoint, Engine,
 };
 use num::{BigUint, ToPrimitive};
 use zksync_basic_types::U256;
 // Workspace deps
 use crate::{
     circuit::utils::append_le_fixed_width,
-    error::PackingError,
+    error::{ConversionError, PackingError},
     merkle_tree::{hasher::Hasher, rescue_hasher::BabyRescueHasher},
     params,
 };
@@ -91,6 +91,29 @@ impl EthereumSerializer {
             .expect("get new root BE bytes");
         U256::from_big_endian(&be_bytes[..])
     }
+
+    /// Reads the point serialized by `serialize_g1`, the point is checked to be on the curve.
+    pub fn deserialize_g1(
+        (x, y): (U256, U256),
+    ) -> Result<<Bn256 as Engine>::G1Affine, ConversionError> {
+        if x.is_zero() && y.is_zero() {
+            return Ok(<Bn256 as Engine>::G1Affine::zero());
+        }
+        let mut uncompressed = <<Bn256 as Engine>::G1Affine as CurveAffine>::Uncompressed::empty();
+        x.to_big_endian(&mut uncompressed.as_mut()[0..32]);
+        y.to_big_endian(&mut uncompressed.as_mut()[32..64]);
+        Ok(uncompressed.into_affine()?)
+    }
+
+    /// Reads the field element serialized by `serialize_fe`.
+    pub fn deserialize_fe(value: U256) -> Result<<Bn256 as ScalarEngine>::Fr, ConversionError> {
+        let mut be_bytes = [0u8; 32];
+        value.to_big_endian(&mut be_bytes);
+        let mut repr = <<Bn256 as ScalarEngine>::Fr as PrimeField>::Repr::default();
+        repr.read_be(&be_bytes[..])
+            .map_err(ConversionError::ParsingError)?;
+        Ok(<Bn256 as ScalarEngine>::Fr::from_repr(repr)?)
+    }
 }
 
 // Resulting iterator is little endian: lowest bit first
@@ -4,11 +4,13 @@
 //! common `zksync` types.
 
 use crate::{
+    bellman::pairing::bn256::G1Affine,
     bellman::plonk::{
         better_better_cs::{cs::Circuit as NewCircuit, proof::Proof as NewProof},
         better_cs::{cs::PlonkCsWidth4WithNextStepParams, keys::Proof as OldProof},
     },
     convert::FeConvert,
+    error::ConversionError,
     primitives::EthereumSerializer,
     proof::EncodedSingleProof,
     recursive_aggregation_circuit::circuit::RecursiveAggregationCircuitBn256,
@@ -391,6 +393,69 @@ pub fn serialize_single_proof(
     }
 }
 
+/// Amount of the values in the proof encoded by `serialize_single_proof`.
+const ENCODED_SINGLE_PROOF_SIZE: usize = 33;
+
+/// Restores the proof encoded by `serialize_single_proof`.
+/// `n` is the size of the circuit domain the proof was created for, it's not included into the encoding.
+pub fn deserialize_single_proof(
+    encoded: &EncodedSingleProof,
+    n: usize,
+) -> Result<OldProof<Engine, PlonkCsWidth4WithNextStepParams>, ConversionError> {
+    const STATE_WIDTH: usize = 4;
+
+    fn next_fe(values: &mut impl Iterator<Item = U256>) -> Result<Fr, ConversionError> {
+        EthereumSerializer::deserialize_fe(values.next().expect("proof size is checked"))
+    }
+
+    fn next_g1(values: &mut impl Iterator<Item = U256>) -> Result<G1Affine, ConversionError> {
+        let x = values.next().expect("proof size is checked");
+        let y = values.next().expect("proof size is checked");
+        EthereumSerializer::deserialize_g1((x, y))
+    }
+
+    if encoded.proof.len() != ENCODED_SINGLE_PROOF_SIZE {
+        return Err(ConversionError::IncorrectInputSize {
+            size: encoded.proof.len(),
+            expected_size: ENCODED_SINGLE_PROOF_SIZE,
+        });
+    }
+    let values = &mut encoded.proof.iter().copied();
+
+    let mut proof = OldProof::empty();
+    proof.n = n;
+    proof.num_inputs = encoded.inputs.len();
+    for input in encoded.inputs.iter() {
+        proof
+            .input_values
+            .push(EthereumSerializer::deserialize_fe(*input)?);
+    }
+
+    for _ in 0..STATE_WIDTH {
+        proof.wire_commitments.push(next_g1(values)?);
+    }
+    proof.grand_product_commitment = next_g1(values)?;
+    for _ in 0..STATE_WIDTH {
+        proof.quotient_poly_commitments.push(next_g1(values)?);
+    }
+
+    for _ in 0..STATE_WIDTH {
+        proof.wire_values_at_z.push(next_fe(values)?);
+    }
+    proof.wire_values_at_z_omega.push(next_fe(values)?);
+    proof.grand_product_at_z_omega = next_fe(values)?;
+    proof.quotient_polynomial_at_z = next_fe(values)?;
+    proof.linearization_polynomial_at_z = next_fe(values)?;
+    for _ in 0..STATE_WIDTH - 1 {
+        proof.permutation_polynomials_at_z.push(next_fe(values)?);
+    }
+
+    proof.opening_at_z_proof = next_g1(values)?;
+    proof.opening_at_z_omega_proof = next_g1(values)?;
+
+    Ok(proof)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -459,4 +524,41 @@ mod tests {
 
         assert_eq!(serialized_fr, expected.to_string());
     }
+
+    #[test]
+    fn test_single_proof_deserialize() {
+        use crate::bellman::pairing::{ff::PrimeField, CurveAffine};
+
+        let fe = |value: u64| Fr::from_str(&value.to_string()).unwrap();
+        let mut proof = OldProof::<Engine, PlonkCsWidth4WithNextStepParams>::empty();
+        proof.n = 1024;
+        proof.num_inputs = 1;
+        proof.input_values = vec![fe(42)];
+        proof.wire_commitments = vec![
+            G1Affine::one(),
+            G1Affine::zero(),
+            G1Affine::one(),
+            G1Affine::one(),
+        ];
+        proof.grand_product_commitment = G1Affine::one();
+        proof.quotient_poly_commitments = vec![G1Affine::zero(); 4];
+        proof.wire_values_at_z = (1..=4).map(fe).collect();
+        proof.wire_values_at_z_omega = vec![fe(5)];
+        proof.grand_product_at_z_omega = fe(6);
+        proof.quotient_polynomial_at_z = fe(7);
+        proof.linearization_polynomial_at_z = fe(8);
+        proof.permutation_polynomials_at_z = (9..=11).map(fe).collect();
+        proof.opening_at_z_proof = G1Affine::one();
+        proof.opening_at_z_omega_proof = G1Affine::zero();
+
+        let encoded = serialize_single_proof(&proof);
+        let decoded = deserialize_single_proof(&encoded, proof.n).expect("Deserialization failed");
+        assert_eq!(decoded.n, proof.n);
+        assert_eq!(decoded.num_inputs, proof.num_inputs);
+        assert_eq!(serialize_single_proof(&decoded), encoded);
+
+        let mut truncated = encoded;
+        truncated.proof.pop();
+        assert!(deserialize_single_proof(&truncated, 1024).is_err());
+    }
 }
@@ -1,9 +1,10 @@
 use matter_most_notifier::MatterMostNotifier;
 use reqwest::Url;
-use zksync_types::tokens::Token;
+use zksync_types::{prover::FailedProverJob, tokens::Token};
 
 mod matter_most_notifier;
 
+#[derive(Debug)]
 pub struct Notifier {
     matter_most_notifier: MatterMostNotifier,
 }
@@ -26,4 +27,21 @@ impl Notifier {
 
         Ok(())
     }
+
+    pub async fn send_failed_prover_job_notify(&self, job: &FailedProverJob) -> anyhow::Result<()> {
+        let job_info_msg = format!(
+            "Prover job failed: id = {}, type = {}, blocks = [{}, {}], attempts = {}, last error = {}",
+            job.job_id,
+            job.job_type,
+            job.first_block,
+            job.last_block,
+            job.attempts,
+            job.last_error.as_deref().unwrap_or("unknown"),
+        );
+        self.matter_most_notifier
+            .send_notify("prover_server_bot", &job_info_msg)
+            .await?;
+
+        Ok(())
+    }
 }
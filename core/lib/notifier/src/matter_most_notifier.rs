@@ -1,5 +1,6 @@
 use reqwest::{Client, Url};
 
+#[derive(Debug)]
 pub struct MatterMostNotifier {
     webhook_url: Url,
     client: Client,
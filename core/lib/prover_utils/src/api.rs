@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
-use zksync_circuit::serialization::ProverData;
+use zksync_circuit::serialization::{ProverData, WitnessFormat};
 use zksync_crypto::proof::{AggregatedProof, SingleProof};
+use zksync_types::prover::{ProverCapabilities, ProverJobType};
 use zksync_types::BlockNumber;
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -11,8 +12,29 @@ pub struct ProverInputRequest {
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct ProverInputRequestAuxData {
+    /// Whether the prover prefers aggregated (`true`) or single (`false`) proof jobs.
     pub prefer_aggregated_proof: Option<bool>,
+    /// The largest block chunks size the prover is able to prove.
     pub preferred_block_size: Option<usize>,
+    /// Amount of jobs the prover instance proves in parallel, each of them is requested separately.
+    pub parallel_jobs: Option<usize>,
+}
+
+impl ProverInputRequestAuxData {
+    pub fn capabilities(&self) -> ProverCapabilities {
+        let preferred_job_type = self.prefer_aggregated_proof.map(|aggregated| {
+            if aggregated {
+                ProverJobType::AggregatedProof
+            } else {
+                ProverJobType::SingleProof
+            }
+        });
+
+        ProverCapabilities {
+            preferred_job_type,
+            max_block_size: self.preferred_block_size,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -33,6 +55,26 @@ pub enum JobRequestData {
     AggregatedBlockProof(Vec<(SingleProof, usize)>),
 }
 
+impl JobRequestData {
+    /// Returns the chunks size of the block for the single proof job.
+    pub fn block_size(&self) -> Option<usize> {
+        match self {
+            JobRequestData::BlockProof(_, block_size) => Some(*block_size),
+            JobRequestData::AggregatedBlockProof(_) => None,
+        }
+    }
+
+    /// Encodes the job in the `WitnessFormat::CURRENT` format.
+    pub fn encode(&self) -> anyhow::Result<Vec<u8>> {
+        WitnessFormat::encode(self)
+    }
+
+    /// Decodes the job encoded in any of the supported formats.
+    pub fn decode(encoded: &[u8]) -> anyhow::Result<Self> {
+        WitnessFormat::decode(encoded)
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ProverOutputRequest {
     pub job_id: i32,
@@ -58,3 +100,11 @@ pub struct WorkingOn {
 pub struct ProverStopped {
     pub prover_name: String,
 }
+
+/// Failure of the job reported by the prover, e.g. the proof generation error.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ProverJobFailure {
+    pub prover_name: String,
+    pub job_id: i32,
+    pub error: String,
+}
@@ -0,0 +1,109 @@
+//! Circuits with no witness used to prepare the setups and the verification keys.
+
+use zksync_circuit::account::AccountWitness;
+use zksync_circuit::circuit::ZkSyncCircuit;
+use zksync_circuit::operation::{
+    Operation, OperationArguments, OperationBranch, OperationBranchWitness, SignatureData,
+};
+use zksync_crypto::params;
+use zksync_crypto::Engine;
+
+/// Creates the block circuit with no witness for the given block chunks size.
+///
+/// The circuit is only suitable for the key generation and the setup preparation,
+/// since these depend on the circuit structure only.
+pub fn empty_block_circuit(block_chunks: usize) -> ZkSyncCircuit<'static, Engine> {
+    let empty_operation = Operation {
+        new_root: None,
+        tx_type: None,
+        chunk: None,
+        pubdata_chunk: None,
+        signer_pub_key_packed: vec![None; params::FR_BIT_WIDTH_PADDED],
+        first_sig_msg: None,
+        second_sig_msg: None,
+        third_sig_msg: None,
+        signature_data: SignatureData::init_empty(),
+        args: OperationArguments {
+            a: None,
+            b: None,
+            amount_packed: None,
+            second_amount_packed: None,
+            special_amounts: vec![None; 2],
+            special_prices: vec![None; 4],
+            special_nonces: vec![None; 3],
+            special_tokens: vec![None; 3],
+            special_accounts: vec![None; 5],
+            special_eth_addresses: vec![None; 2],
+            full_amount: None,
+            fee: None,
+            pub_nonce: None,
+            new_pub_key_hash: None,
+            eth_address: None,
+            valid_from: None,
+            valid_until: None,
+            second_valid_from: None,
+            second_valid_until: None,
+            special_content_hash: vec![None; params::CONTENT_HASH_WIDTH],
+            special_serial_id: None,
+        },
+        lhs: OperationBranch {
+            address: None,
+            token: None,
+            witness: OperationBranchWitness {
+                account_witness: AccountWitness {
+                    nonce: None,
+                    pub_key_hash: None,
+                    address: None,
+                },
+                account_path: vec![None; params::account_tree_depth()],
+                balance_value: None,
+                balance_subtree_path: vec![None; params::balance_tree_depth()],
+            },
+        },
+        rhs: OperationBranch {
+            address: None,
+            token: None,
+            witness: OperationBranchWitness {
+                account_witness: AccountWitness {
+                    nonce: None,
+                    pub_key_hash: None,
+                    address: None,
+                },
+                account_path: vec![None; params::account_tree_depth()],
+                balance_value: None,
+                balance_subtree_path: vec![None; params::balance_tree_depth()],
+            },
+        },
+    };
+
+    ZkSyncCircuit {
+        rescue_params: &params::RESCUE_PARAMS,
+        jubjub_params: &params::JUBJUB_PARAMS,
+        old_root: None,
+        initial_used_subtree_root: None,
+        validator_address: None,
+        block_number: None,
+        block_timestamp: None,
+        pub_data_commitment: None,
+        validator_balances: vec![None; params::number_of_processable_tokens()],
+        validator_audit_path: vec![None; params::account_tree_depth()],
+        validator_non_processable_tokens_audit_before_fees: vec![
+            None;
+            params::balance_tree_depth()
+                - params::PROCESSABLE_TOKENS_DEPTH
+                    as usize
+        ],
+        validator_non_processable_tokens_audit_after_fees: vec![
+            None;
+            params::balance_tree_depth()
+                - params::PROCESSABLE_TOKENS_DEPTH
+                    as usize
+        ],
+        operations: vec![empty_operation; block_chunks],
+        validator_account: AccountWitness {
+            nonce: None,
+            pub_key_hash: None,
+            address: None,
+        },
+    }
+}
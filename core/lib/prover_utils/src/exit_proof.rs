@@ -10,8 +10,23 @@ use zksync_crypto::circuit::CircuitAccountTree;
 use zksync_crypto::proof::EncodedSingleProof;
 use zksync_types::{AccountId, AccountMap, Address, TokenId, H256};
 
-fn create_exit_proof(
-    accounts: AccountMap,
+/// Builds the account tree used by the exit circuit.
+/// Exit proofs don't change the tree, so it can be reused to prove the exits for several accounts.
+pub fn exit_proof_account_tree(accounts: &AccountMap) -> CircuitAccountTree {
+    let mut circuit_account_tree =
+        CircuitAccountTree::new(zksync_crypto::params::account_tree_depth());
+    for (id, account) in accounts.iter() {
+        circuit_account_tree.insert(**id, CircuitAccount::from(account.clone()));
+    }
+    circuit_account_tree
+}
+
+/// Creates the exit proof with the account tree built from `accounts` by `exit_proof_account_tree`.
+/// Returns the proof along with the amount to be withdrawn.
+#[allow(clippy::too_many_arguments)]
+pub fn create_exit_proof_with_tree(
+    circuit_account_tree: &mut CircuitAccountTree,
+    accounts: &AccountMap,
     account_id: AccountId,
     owner: Address,
     token_id: TokenId,
@@ -20,18 +35,8 @@ fn create_exit_proof(
     nft_content_hash: H256,
 ) -> Result<(EncodedSingleProof, BigUint), anyhow::Error> {
     let timer = Instant::now();
-    let mut circuit_account_tree =
-        CircuitAccountTree::new(zksync_crypto::params::account_tree_depth());
-
-    let mut target_account = None;
-    for (id, account) in accounts {
-        if id == account_id {
-            target_account = Some(account.clone());
-        }
-        circuit_account_tree.insert(*id, CircuitAccount::from(account));
-    }
-
-    let balance = target_account
+    let balance = accounts
+        .get(&account_id)
         .map(|acc| acc.get_balance(token_id))
         .ok_or_else(|| {
             format_err!(
@@ -42,7 +47,7 @@ fn create_exit_proof(
         })?;
 
     let zksync_exit_circuit = create_exit_circuit_with_public_input(
-        &mut circuit_account_tree,
+        circuit_account_tree,
         account_id,
         token_id,
         nft_creator_id,
@@ -61,6 +66,28 @@ fn create_exit_proof(
     Ok((proof.serialize_single_proof(), balance))
 }
 
+fn create_exit_proof(
+    accounts: AccountMap,
+    account_id: AccountId,
+    owner: Address,
+    token_id: TokenId,
+    nft_creator_id: AccountId,
+    nft_serial_id: u32,
+    nft_content_hash: H256,
+) -> Result<(EncodedSingleProof, BigUint), anyhow::Error> {
+    let mut circuit_account_tree = exit_proof_account_tree(&accounts);
+    create_exit_proof_with_tree(
+        &mut circuit_account_tree,
+        &accounts,
+        account_id,
+        owner,
+        token_id,
+        nft_creator_id,
+        nft_serial_id,
+        nft_content_hash,
+    )
+}
+
 pub fn create_exit_proof_fungible(
     accounts: AccountMap,
     account_id: AccountId,
@@ -27,6 +27,7 @@ use crate::fs_utils::{get_block_verification_key_path, get_exodus_verification_k
 
 pub mod aggregated_proofs;
 pub mod api;
+pub mod empty_circuit;
 pub mod exit_proof;
 pub mod fs_utils;
 pub mod network_utils;
@@ -0,0 +1 @@
+DROP TABLE data_restore_eth_logs_checkpoints;
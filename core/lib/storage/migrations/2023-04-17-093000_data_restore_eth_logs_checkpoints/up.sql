@@ -0,0 +1,8 @@
+-- Ethereum log ranges that were already fetched by the data restore,
+-- but not yet processed into the events state.
+CREATE TABLE data_restore_eth_logs_checkpoints
+(
+    from_block BIGINT PRIMARY KEY,
+    to_block BIGINT NOT NULL,
+    logs JSONB NOT NULL
+);
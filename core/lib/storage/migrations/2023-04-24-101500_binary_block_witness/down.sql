@@ -0,0 +1,4 @@
+-- Binary witnesses can't be represented as text, so they have to be regenerated.
+DELETE FROM block_witness WHERE get_byte(witness, 0) != ascii('{');
+ALTER TABLE block_witness
+    ALTER COLUMN witness TYPE TEXT USING convert_from(witness, 'UTF8');
@@ -0,0 +1,4 @@
+-- Witnesses are stored as tagged binary blobs, existing JSON witnesses are kept as is
+-- and remain readable since the JSON format is recognized by the opening brace.
+ALTER TABLE block_witness
+    ALTER COLUMN witness TYPE BYTEA USING convert_to(witness, 'UTF8');
@@ -0,0 +1,10 @@
+-- References to the witnesses and proofs kept in the blob store.
+CREATE TABLE blob_refs (
+    blob_key TEXT PRIMARY KEY,
+    blob_type TEXT NOT NULL,
+    first_block BIGINT NOT NULL,
+    last_block BIGINT NOT NULL,
+    checksum BYTEA NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+CREATE INDEX blob_refs_last_block_idx ON blob_refs (last_block);
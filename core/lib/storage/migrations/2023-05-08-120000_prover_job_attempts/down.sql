@@ -0,0 +1,5 @@
+-- Failed jobs are returned to the queue, since the `Failed` status is unknown before the migration.
+UPDATE prover_job_queue SET job_status = 0 WHERE job_status = 3;
+ALTER TABLE prover_job_queue
+    DROP COLUMN attempts,
+    DROP COLUMN last_error;
@@ -0,0 +1,4 @@
+-- Amount of times the job was given to provers and the last reported failure.
+ALTER TABLE prover_job_queue
+    ADD COLUMN attempts INT NOT NULL DEFAULT 0,
+    ADD COLUMN last_error TEXT;
@@ -0,0 +1,2 @@
+-- Time when the job was given to the prover, used to measure the proof generation duration.
+ALTER TABLE prover_job_queue ADD COLUMN started_at TIMESTAMP WITH TIME ZONE;
@@ -0,0 +1,2 @@
+-- Name of the prover that worked on the job last time, kept after the job is completed.
+ALTER TABLE prover_job_queue ADD COLUMN prover_name TEXT;
@@ -0,0 +1,13 @@
+-- Operator-managed corrections of the token metadata registered from the contract events.
+-- `NULL` values mean that the original metadata is used.
+CREATE TABLE token_overrides (
+    token_id INTEGER PRIMARY KEY REFERENCES tokens(id) ON UPDATE CASCADE ON DELETE CASCADE,
+    symbol TEXT,
+    decimals SMALLINT,
+    hidden BOOLEAN NOT NULL DEFAULT false,
+    -- Overrides the fee token validator: `true` allows paying fees with the token, `false` forbids it.
+    fee_token BOOLEAN,
+    logo_uri TEXT,
+    metadata_uri TEXT,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
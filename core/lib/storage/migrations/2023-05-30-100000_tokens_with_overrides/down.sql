@@ -0,0 +1 @@
+DROP VIEW IF EXISTS tokens_with_overrides;
@@ -0,0 +1,13 @@
+-- Tokens with the metadata corrected by the operator in `token_overrides`,
+-- so the overrides are applied in a single place for all the token queries.
+CREATE VIEW tokens_with_overrides AS
+SELECT
+    tokens.id,
+    tokens.address,
+    COALESCE(token_overrides.symbol, tokens.symbol) AS symbol,
+    COALESCE(token_overrides.decimals, tokens.decimals) AS decimals,
+    tokens.kind,
+    COALESCE(token_overrides.hidden, false) AS hidden,
+    token_overrides.fee_token
+FROM tokens
+LEFT JOIN token_overrides ON tokens.id = token_overrides.token_id;
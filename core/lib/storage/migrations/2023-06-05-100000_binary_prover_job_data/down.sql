@@ -0,0 +1,5 @@
+-- Binary job data can't be represented as JSON, so these jobs have to be recreated.
+DELETE FROM prover_job_queue WHERE get_byte(job_data, 0) != ascii('{');
+ALTER TABLE prover_job_queue
+    ALTER COLUMN job_data TYPE JSONB USING convert_from(job_data, 'UTF8')::jsonb;
+ALTER TABLE prover_job_queue DROP COLUMN block_size;
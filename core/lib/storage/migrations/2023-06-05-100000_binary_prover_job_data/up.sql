@@ -0,0 +1,8 @@
+-- Block size of the single proof job, used to dispatch jobs without decoding their data.
+ALTER TABLE prover_job_queue ADD COLUMN block_size BIGINT;
+UPDATE prover_job_queue SET block_size = (job_data->'BlockProof'->>1)::bigint
+    WHERE job_type = 'SINGLE_PROOF';
+-- Job data is stored in the same tagged binary format as the block witnesses,
+-- existing JSON job data remains readable since it starts with the opening brace.
+ALTER TABLE prover_job_queue
+    ALTER COLUMN job_data TYPE BYTEA USING convert_to(job_data::text, 'UTF8');
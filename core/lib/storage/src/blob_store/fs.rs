@@ -0,0 +1,67 @@
+// Built-in deps
+use std::io::ErrorKind;
+use std::path::{Component, Path, PathBuf};
+// External imports
+use anyhow::{ensure, format_err};
+use async_trait::async_trait;
+// Local imports
+use super::BlobStore;
+
+/// Blob store which keeps the blobs as files in the local directory.
+#[derive(Debug, Clone)]
+pub struct FsBlobStore {
+    root: PathBuf,
+}
+
+impl FsBlobStore {
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    fn path(&self, key: &str) -> anyhow::Result<PathBuf> {
+        let key = Path::new(key);
+        ensure!(
+            key.components()
+                .all(|component| matches!(component, Component::Normal(_))),
+            "Invalid blob key {}",
+            key.display()
+        );
+        Ok(self.root.join(key))
+    }
+}
+
+#[async_trait]
+impl BlobStore for FsBlobStore {
+    async fn put(&self, key: &str, data: &[u8]) -> anyhow::Result<()> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        // The blob is written to the temporary file first, so readers never observe a partially written blob.
+        let tmp_path = path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, data)
+            .await
+            .map_err(|err| format_err!("Failed to write blob {}: {}", key, err))?;
+        tokio::fs::rename(&tmp_path, &path).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        match tokio::fs::read(self.path(key)?).await {
+            Ok(data) => Ok(Some(data)),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(format_err!("Failed to read blob {}: {}", key, err)),
+        }
+    }
+
+    async fn remove(&self, key: &str) -> anyhow::Result<()> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Err(err) if err.kind() != ErrorKind::NotFound => {
+                Err(format_err!("Failed to remove blob {}: {}", key, err))
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! Storage for the large binary objects, such as block witnesses and proofs.
+//!
+//! Blobs are kept outside of the database to not bloat it and its backups:
+//! the database only stores references to the blobs along with their checksums
+//! (see `ProverSchema`), while the data itself is stored by one of the `BlobStore`
+//! implementations.
+
+// Built-in deps
+use std::fmt;
+use std::sync::Arc;
+// External imports
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+// Workspace imports
+use zksync_config::configs::blob_store::{BlobStoreBackend, BlobStoreConfig};
+// Local imports
+pub use self::{fs::FsBlobStore, s3::S3BlobStore};
+
+mod fs;
+mod s3;
+
+/// Key-value storage for the binary objects.
+///
+/// Keys are relative paths like `witness/42`, implementations must treat them as opaque strings.
+#[async_trait]
+pub trait BlobStore: fmt::Debug + Send + Sync {
+    /// Stores the blob, replacing the existing one with the same key.
+    async fn put(&self, key: &str, data: &[u8]) -> anyhow::Result<()>;
+
+    /// Loads the blob, returns `None` if there is no blob with such key.
+    async fn get(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>>;
+
+    /// Removes the blob. Removing the missing blob is not an error.
+    async fn remove(&self, key: &str) -> anyhow::Result<()>;
+}
+
+/// Creates the blob store for the given configuration.
+/// Returns `None` for the `postgres` backend, since the data is stored in the database tables then.
+pub fn from_config(config: &BlobStoreConfig) -> Option<Arc<dyn BlobStore>> {
+    match config.backend {
+        BlobStoreBackend::Postgres => None,
+        BlobStoreBackend::Fs => Some(Arc::new(FsBlobStore::new(&config.fs_path))),
+        BlobStoreBackend::S3 => Some(Arc::new(S3BlobStore::new(
+            &config.s3_endpoint,
+            &config.s3_bucket,
+            &config.s3_region,
+            &config.s3_access_key,
+            &config.s3_secret_key,
+        ))),
+    }
+}
+
+/// Creates the blob store configured via the environment variables.
+/// Returns `None` if the blob store is not configured, so the data stays in the database tables.
+pub fn from_env() -> Option<Arc<dyn BlobStore>> {
+    BlobStoreConfig::from_env_opt()
+        .as_ref()
+        .and_then(from_config)
+}
+
+/// Checksum of the blob stored in the database along with the reference.
+pub fn checksum(data: &[u8]) -> Vec<u8> {
+    Sha256::digest(data).to_vec()
+}
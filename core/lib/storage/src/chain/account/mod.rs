@@ -575,6 +575,67 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
         Ok(balance)
     }
 
+    /// Returns IDs of the NFTs owned by the account in the last verified state.
+    pub async fn get_account_nfts(&mut self, address: Address) -> QueryResult<Vec<TokenId>> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let account_id = transaction
+            .chain()
+            .account_schema()
+            .account_id_by_address(address)
+            .await?;
+        let account_id = match account_id {
+            Some(id) if id != NFT_STORAGE_ACCOUNT_ID => id,
+            // Special NFT storage account doesn't own the NFTs it stores.
+            _ => {
+                return Ok(Vec::new());
+            }
+        };
+
+        let nfts = sqlx::query!(
+            r#"
+                SELECT coin_id FROM balances
+                WHERE account_id = $1 AND coin_id >= $2 AND coin_id < $3 AND balance = 1
+                ORDER BY coin_id
+            "#,
+            i64::from(account_id.0),
+            MIN_NFT_TOKEN_ID as i32,
+            NFT_TOKEN_ID.0 as i32
+        )
+        .fetch_all(transaction.conn())
+        .await?
+        .into_iter()
+        .map(|record| TokenId(record.coin_id as u32))
+        .collect();
+
+        transaction.commit().await?;
+        metrics::histogram!("sql.chain.account.get_account_nfts", start.elapsed());
+
+        Ok(nfts)
+    }
+
+    /// Returns the sum of the token balances of all the accounts in the last verified state.
+    pub async fn get_token_total_supply(&mut self, token_id: TokenId) -> QueryResult<BigUint> {
+        let start = Instant::now();
+
+        let total_supply = sqlx::query!(
+            r#"
+                SELECT SUM(balance) as total_supply FROM balances
+                WHERE coin_id = $1
+            "#,
+            token_id.0 as i32
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .total_supply
+        .map(|supply| supply.to_bigint().unwrap().to_biguint().unwrap())
+        .unwrap_or_else(BigUint::zero);
+
+        metrics::histogram!("sql.chain.account.get_token_total_supply", start.elapsed());
+        Ok(total_supply)
+    }
+
     pub async fn get_nft_owner(&mut self, token_id: TokenId) -> QueryResult<Option<AccountId>> {
         let start = Instant::now();
 
@@ -462,7 +462,7 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         };
 
         let withdrawal_hash = EthereumSchema(self.0)
-            .aggregated_op_final_hash(block_number)
+            .aggregated_op_final_hash(AggregatedActionType::ExecuteBlocks, block_number)
             .await?;
 
         metrics::histogram!(
@@ -1,5 +1,5 @@
 // Built-in deps
-use std::{fmt, time::Duration, time::Instant};
+use std::{fmt, sync::Arc, time::Duration, time::Instant};
 // External imports
 use async_trait::async_trait;
 use deadpool::managed::{Manager, PoolConfig, RecycleResult, Timeouts};
@@ -8,7 +8,7 @@ use sqlx::{Connection, Error as SqlxError, PgConnection};
 use tokio::time;
 // Local imports
 // use self::recoverable_connection::RecoverableConnection;
-use crate::{get_database_replica_url, get_database_url, StorageProcessor};
+use crate::{blob_store::BlobStore, get_database_replica_url, get_database_url, StorageProcessor};
 use zksync_utils::parse_env;
 
 pub mod holder;
@@ -55,6 +55,7 @@ impl Manager for DbPool {
 #[derive(Clone)]
 pub struct ConnectionPool {
     pool: Pool,
+    blob_store: Option<Arc<dyn BlobStore>>,
 }
 
 impl fmt::Debug for ConnectionPool {
@@ -73,7 +74,10 @@ impl ConnectionPool {
 
         let pool = DbPool::create(database_url, max_size as usize);
 
-        Self { pool }
+        Self {
+            pool,
+            blob_store: None,
+        }
     }
 
     /// Establishes a pool of the connections to the replica of database and
@@ -86,8 +90,19 @@ impl ConnectionPool {
 
         let pool = DbPool::create(database_url, max_size as usize);
 
-        Self { pool }
+        Self {
+            pool,
+            blob_store: None,
+        }
     }
+
+    /// Sets the store for witnesses and proofs used by the storage processors of this pool.
+    /// If the store is `None`, witnesses and proofs are stored in the database tables.
+    pub fn with_blob_store(mut self, blob_store: Option<Arc<dyn BlobStore>>) -> Self {
+        self.blob_store = blob_store;
+        self
+    }
+
     /// Creates a `StorageProcessor` entity over a recoverable connection.
     /// Upon a database outage connection will block the thread until
     /// it will be able to recover the connection (or, if connection cannot
@@ -101,7 +116,9 @@ impl ConnectionPool {
         let connection = self.get_pooled_connection().await;
         metrics::histogram!("sql.connection_acquire", start.elapsed());
 
-        Ok(StorageProcessor::from_pool(connection))
+        let mut storage = StorageProcessor::from_pool(connection);
+        storage.blob_store = self.blob_store.clone();
+        Ok(storage)
     }
 
     async fn get_pooled_connection(&self) -> PooledConnection {
@@ -11,8 +11,8 @@ use zksync_types::{
 // Local imports
 use self::records::{
     NewBlockEvent, NewRollupOpsBlock, NewStorageState, NewTokenEvent, StoredBlockEvent,
-    StoredLastWatchedEthBlockNumber, StoredPriorityOpData, StoredRollupOpsBlock,
-    StoredStorageState,
+    StoredEthLogsCheckpoint, StoredLastWatchedEthBlockNumber, StoredPriorityOpData,
+    StoredRollupOpsBlock, StoredStorageState,
 };
 
 use crate::chain::operations::OperationsSchema;
@@ -171,6 +171,67 @@ impl<'a, 'c> DataRestoreSchema<'a, 'c> {
         Ok(stored)
     }
 
+    /// Stores the logs fetched for the Ethereum blocks range `[from_block, to_block]`,
+    /// so the range won't be requested again if the restore process is interrupted.
+    pub async fn save_eth_logs_checkpoint(
+        &mut self,
+        from_block: u64,
+        to_block: u64,
+        logs: serde_json::Value,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "INSERT INTO data_restore_eth_logs_checkpoints (from_block, to_block, logs)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (from_block) DO UPDATE SET to_block = $2, logs = $3",
+            from_block as i64,
+            to_block as i64,
+            logs
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.data_restore.save_eth_logs_checkpoint", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads all the stored logs checkpoints ordered by the Ethereum block number.
+    pub async fn load_eth_logs_checkpoints(&mut self) -> QueryResult<Vec<StoredEthLogsCheckpoint>> {
+        let start = Instant::now();
+        let checkpoints = sqlx::query_as!(
+            StoredEthLogsCheckpoint,
+            "SELECT * FROM data_restore_eth_logs_checkpoints ORDER BY from_block ASC",
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.data_restore.load_eth_logs_checkpoints",
+            start.elapsed()
+        );
+        Ok(checkpoints)
+    }
+
+    /// Removes the checkpoints that are fully covered by the last watched Ethereum block.
+    pub async fn remove_eth_logs_checkpoints(
+        &mut self,
+        last_watched_block: u64,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "DELETE FROM data_restore_eth_logs_checkpoints WHERE to_block <= $1",
+            last_watched_block as i64
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.data_restore.remove_eth_logs_checkpoints",
+            start.elapsed()
+        );
+        Ok(())
+    }
+
     fn new_storage_state(&self, state: impl ToString) -> NewStorageState {
         NewStorageState {
             storage_state: state.to_string(),
@@ -218,6 +279,12 @@ impl<'a, 'c> DataRestoreSchema<'a, 'c> {
         DataRestoreSchema(&mut transaction)
             .save_priority_op_data(priority_op_data)
             .await?;
+        // Logs up to the last watched block are processed now, so their checkpoints are not needed anymore.
+        if let Ok(last_watched_block) = last_watched_eth_number.parse() {
+            DataRestoreSchema(&mut transaction)
+                .remove_eth_logs_checkpoints(last_watched_block)
+                .await?;
+        }
 
         transaction.commit().await?;
 
@@ -71,3 +71,10 @@ pub struct StoredBlockEvent {
     pub block_num: i64,
     pub contract_version: i32,
 }
+
+#[derive(Debug, Clone, FromRow)]
+pub struct StoredEthLogsCheckpoint {
+    pub from_block: i64,
+    pub to_block: i64,
+    pub logs: Value,
+}
@@ -673,8 +673,11 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         Ok(created_at)
     }
 
+    /// Returns the hash of the confirmed Ethereum transaction that performed the aggregated operation
+    /// of the given type for the block.
     pub async fn aggregated_op_final_hash(
         &mut self,
+        action_type: AggregatedActionType,
         block_number: BlockNumber,
     ) -> QueryResult<Option<H256>> {
         let eth_operation = sqlx::query_as!(
@@ -686,7 +689,7 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
                 ($1 BETWEEN from_block AND to_block) AND action_type = $2 AND eth_operations.confirmed = true 
             LIMIT 1",
             i64::from(*block_number),
-            AggregatedActionType::ExecuteBlocks.to_string(),
+            action_type.to_string(),
         )
         .fetch_optional(self.0.conn())
         .await?;
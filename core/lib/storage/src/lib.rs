@@ -77,17 +77,20 @@
 
 // Built-in deps
 use std::env;
+use std::sync::Arc;
 // External imports
 use sqlx::{postgres::Postgres, Connection, PgConnection, Transaction};
 // Workspace imports
 use zksync_types::{ActionType, BlockNumber};
 // Local imports
+use crate::blob_store::BlobStore;
 use crate::connection::{holder::ConnectionHolder, PooledConnection};
 
 // mod schema;
 #[cfg(test)]
 mod tests;
 
+pub mod blob_store;
 pub mod chain;
 pub mod config;
 pub mod connection;
@@ -132,6 +135,8 @@ pub fn get_database_url() -> String {
 pub struct StorageProcessor<'a> {
     conn: ConnectionHolder<'a>,
     in_transaction: bool,
+    /// Store for witnesses and proofs, `None` means that they're stored in the database tables.
+    blob_store: Option<Arc<dyn BlobStore>>,
 }
 
 #[derive(sqlx::Type, Debug, Clone, PartialEq, Eq)]
@@ -158,6 +163,7 @@ impl<'a> StorageProcessor<'a> {
         Ok(StorageProcessor {
             conn: ConnectionHolder::Direct(connection),
             in_transaction: false,
+            blob_store: None,
         })
     }
 
@@ -168,6 +174,7 @@ impl<'a> StorageProcessor<'a> {
 
         let mut processor = StorageProcessor::from_transaction(transaction);
         processor.in_transaction = true;
+        processor.blob_store = self.blob_store.clone();
 
         Ok(processor)
     }
@@ -181,6 +188,7 @@ impl<'a> StorageProcessor<'a> {
         StorageProcessor {
             conn: ConnectionHolder::Transaction(conn),
             in_transaction: true,
+            blob_store: None,
         }
     }
 
@@ -200,9 +208,20 @@ impl<'a> StorageProcessor<'a> {
         Self {
             conn: ConnectionHolder::Pooled(conn),
             in_transaction: false,
+            blob_store: None,
         }
     }
 
+    /// Returns the store for witnesses and proofs, `None` means that they're stored in the database tables.
+    pub fn blob_store(&self) -> Option<Arc<dyn BlobStore>> {
+        self.blob_store.clone()
+    }
+
+    /// Sets the store for witnesses and proofs for this processor and transactions started from it.
+    pub fn set_blob_store(&mut self, blob_store: Arc<dyn BlobStore>) {
+        self.blob_store = Some(blob_store);
+    }
+
     /// Gains access to the `Chain` schemas.
     pub fn chain(&mut self) -> chain::ChainIntermediator<'_, 'a> {
         chain::ChainIntermediator(self)
@@ -1,34 +1,84 @@
 // Built-in deps
-use std::time::Instant;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 // External imports
-use anyhow::format_err;
+use anyhow::{ensure, format_err};
 // Workspace imports
 use zksync_types::BlockNumber;
 // Local imports
-use self::records::{StorageProverJobQueue, StoredAggregatedProof, StoredProof};
+use self::records::{
+    StorageBlobRef, StorageProverJobFailure, StorageProverJobInfo, StorageProverJobQueue,
+    StorageProverJobStats, StoredAggregatedProof, StoredProof,
+};
+use crate::blob_store::{self, BlobStore};
 use crate::chain::operations::OperationsSchema;
 use crate::prover::records::StorageBlockWitness;
 use crate::{QueryResult, StorageProcessor};
-use chrono::{TimeZone, Utc};
+use chrono::{DateTime, TimeZone, Utc};
 use zksync_crypto::proof::{AggregatedProof, SingleProof};
+use zksync_prover_utils::api::JobRequestData;
 use zksync_types::aggregated_operations::AggregatedActionType;
-use zksync_types::prover::{ProverJob, ProverJobStatus, ProverJobType};
+use zksync_types::prover::{
+    FailedProverJob, ProverCapabilities, ProverJob, ProverJobFailureOutcome, ProverJobInfo,
+    ProverJobStats, ProverJobStatus, ProverJobType,
+};
 
 pub mod records;
 
+const WITNESS_BLOB: &str = "witness";
+const PROOF_BLOB: &str = "proof";
+const AGGREGATED_PROOF_BLOB: &str = "aggregated_proof";
+
+/// Returns the key of the blob in the blob store, e.g. `witness/42` or `aggregated_proof/1-10`.
+fn blob_key(blob_type: &str, first_block: BlockNumber, last_block: BlockNumber) -> String {
+    if first_block == last_block {
+        format!("{}/{}", blob_type, *first_block)
+    } else {
+        format!("{}/{}-{}", blob_type, *first_block, *last_block)
+    }
+}
+
+fn prover_job_type(job_type: &str) -> ProverJobType {
+    if job_type == ProverJobType::SingleProof.to_string() {
+        ProverJobType::SingleProof
+    } else {
+        ProverJobType::AggregatedProof
+    }
+}
+
+/// Converts the job into `FailedProverJob` if it's in the `Failed` status.
+fn failed_prover_job(job: StorageProverJobFailure) -> Option<FailedProverJob> {
+    if job.job_status != ProverJobStatus::Failed.to_number() {
+        return None;
+    }
+    Some(FailedProverJob {
+        job_id: job.id,
+        job_type: job.job_type,
+        first_block: BlockNumber(job.first_block as u32),
+        last_block: BlockNumber(job.last_block as u32),
+        attempts: job.attempts,
+        last_error: job.last_error,
+    })
+}
+
 /// Prover schema is capable of handling the prover-related informations,
 /// such as started prover jobs, registered provers and proofs for blocks.
+///
+/// Witnesses and proofs are put into the blob store if it's configured, in that case
+/// only the references to them are stored in the database. Data stored in the database
+/// tables before the blob store was configured remains readable.
 #[derive(Debug)]
 pub struct ProverSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
 
 impl<'a, 'c> ProverSchema<'a, 'c> {
     /// Returns the amount of blocks which await for proof, but have
-    /// no assigned prover run.
+    /// no assigned prover run. Failed jobs are not counted.
     pub async fn pending_jobs_count(&mut self) -> QueryResult<u32> {
         let start = Instant::now();
         let pending_jobs_count = sqlx::query!(
-            "SELECT COUNT(*) FROM prover_job_queue WHERE job_status != $1",
-            ProverJobStatus::Done.to_number()
+            "SELECT COUNT(*) FROM prover_job_queue WHERE job_status != $1 AND job_status != $2",
+            ProverJobStatus::Done.to_number(),
+            ProverJobStatus::Failed.to_number(),
         )
         .fetch_one(self.0.conn())
         .await?
@@ -38,22 +88,125 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(pending_jobs_count)
     }
 
+    /// Loads the statistics of the pending jobs and the jobs created or proven since the given time,
+    /// grouped by the job type and size.
+    pub async fn load_prover_job_stats(
+        &mut self,
+        since: DateTime<Utc>,
+    ) -> QueryResult<Vec<ProverJobStats>> {
+        let start = Instant::now();
+        let stats = sqlx::query_as!(
+            StorageProverJobStats,
+            r#"
+                SELECT
+                    job_type,
+                    CASE WHEN job_type = $1
+                        THEN block_size
+                        ELSE last_block - first_block + 1
+                    END AS job_size,
+                    COUNT(*) FILTER (WHERE job_status = $2 OR job_status = $3) AS "pending_jobs!",
+                    COUNT(*) FILTER (WHERE created_at >= $5) AS "created_jobs!",
+                    COUNT(*) FILTER (WHERE job_status = $4 AND started_at >= $5) AS "proven_jobs!",
+                    (AVG(EXTRACT(EPOCH FROM updated_at - started_at))
+                        FILTER (WHERE job_status = $4 AND started_at >= $5))::float8 AS avg_proof_duration
+                FROM prover_job_queue
+                WHERE job_status = $2 OR job_status = $3 OR created_at >= $5 OR started_at >= $5
+                GROUP BY 1, 2
+                ORDER BY 1, 2
+            "#,
+            ProverJobType::SingleProof.to_string(),
+            ProverJobStatus::Idle.to_number(),
+            ProverJobStatus::InProgress.to_number(),
+            ProverJobStatus::Done.to_number(),
+            since,
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|stats| ProverJobStats {
+            job_type: prover_job_type(&stats.job_type),
+            job_size: stats.job_size.map(|size| size as u64),
+            pending_jobs: stats.pending_jobs as u32,
+            created_jobs: stats.created_jobs as u32,
+            proven_jobs: stats.proven_jobs as u32,
+            avg_proof_duration: stats
+                .avg_proof_duration
+                .map(|duration| Duration::from_secs_f64(duration.max(0.0))),
+        })
+        .collect();
+        metrics::histogram!("sql", start.elapsed(), "prover" => "load_prover_job_stats");
+        Ok(stats)
+    }
+
+    /// Loads the jobs in progress along with the jobs completed or failed since the given time,
+    /// ordered by the time of the last update.
+    pub async fn load_prover_activity(
+        &mut self,
+        since: DateTime<Utc>,
+    ) -> QueryResult<Vec<ProverJobInfo>> {
+        let start = Instant::now();
+        let jobs = sqlx::query_as!(
+            StorageProverJobInfo,
+            r#"
+                SELECT
+                    id, job_status, job_type,
+                    CASE WHEN job_type = $1
+                        THEN block_size
+                        ELSE last_block - first_block + 1
+                    END AS job_size,
+                    first_block, last_block, prover_name, attempts, created_at, started_at, updated_at
+                FROM prover_job_queue
+                WHERE job_status = $2 OR ((job_status = $3 OR job_status = $4) AND updated_at > $5)
+                ORDER BY updated_at, id
+            "#,
+            ProverJobType::SingleProof.to_string(),
+            ProverJobStatus::InProgress.to_number(),
+            ProverJobStatus::Done.to_number(),
+            ProverJobStatus::Failed.to_number(),
+            since,
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|job| ProverJobInfo {
+            job_id: job.id,
+            job_type: prover_job_type(&job.job_type),
+            job_size: job.job_size.map(|size| size as u64),
+            first_block: BlockNumber(job.first_block as u32),
+            last_block: BlockNumber(job.last_block as u32),
+            status: ProverJobStatus::from_number(job.job_status)
+                .expect("Incorrect prover job status in the database"),
+            prover_name: job.prover_name,
+            attempts: job.attempts,
+            created_at: job.created_at,
+            started_at: job.started_at,
+            updated_at: job.updated_at,
+        })
+        .collect();
+        metrics::histogram!("sql", start.elapsed(), "prover" => "load_prover_activity");
+        Ok(jobs)
+    }
+
+    /// Adds the job to the queue unless there is a job of the same type for the same blocks.
+    /// Job data is stored encoded in the `WitnessFormat::CURRENT` format.
     pub async fn add_prover_job_to_job_queue(
         &mut self,
         first_block: BlockNumber,
         last_block: BlockNumber,
-        job_data: serde_json::Value,
+        job_data: &JobRequestData,
         job_priority: i32,
         job_type: ProverJobType,
     ) -> QueryResult<()> {
         let start = Instant::now();
+        let block_size = job_data.block_size().map(|size| size as i64);
+        let job_data = job_data.encode()?;
         sqlx::query!(
         "
           WITH job_values as (
-            SELECT $1::int4, $2::int4, $3::text, 'server_add_job', $4::int8, $5::int8, $6::jsonb
+            SELECT $1::int4, $2::int4, $3::text, 'server_add_job', $4::int8, $5::int8, $6::bytea, $7::int8
             WHERE NOT EXISTS (SELECT * FROM prover_job_queue WHERE first_block = $4 and last_block = $5 and job_type = $3 LIMIT 1)
           )
-          INSERT INTO prover_job_queue (job_status, job_priority, job_type, updated_by, first_block, last_block, job_data)
+          INSERT INTO prover_job_queue (job_status, job_priority, job_type, updated_by, first_block, last_block, job_data, block_size)
           SELECT * from job_values
         ",
             ProverJobStatus::Idle.to_number(),
@@ -62,29 +215,145 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
             i64::from(*first_block),
             i64::from(*last_block),
             job_data,
+            block_size,
         ).execute(self.0.conn()).await?;
 
         metrics::histogram!("sql", start.elapsed(), "prover" => "add_prover_job_to_job_queue");
         Ok(())
     }
 
-    pub async fn mark_stale_jobs_as_idle(&mut self) -> QueryResult<()> {
+    /// Returns the jobs which provers stopped sending heartbeats for back to the queue.
+    /// Jobs that were given to provers `max_attempts` times are marked as failed instead,
+    /// and returned from the method.
+    pub async fn mark_stale_jobs_as_idle(
+        &mut self,
+        max_attempts: u32,
+    ) -> QueryResult<Vec<FailedProverJob>> {
         let start = Instant::now();
-        let result = sqlx::query!(
-            "UPDATE prover_job_queue SET (job_status, updated_at, updated_by) = ($1, now(), 'server_clean_idle')
-            WHERE job_status = $2 AND (now() - INTERVAL '120 seconds') >= updated_at RETURNING id",
+        let result = sqlx::query_as!(
+            StorageProverJobFailure,
+            "UPDATE prover_job_queue
+            SET (job_status, updated_at, updated_by, last_error) = (
+                CASE WHEN attempts >= $3 THEN $4::int4 ELSE $1::int4 END, now(), 'server_clean_idle', 'Job timed out'
+            )
+            WHERE job_status = $2 AND (now() - INTERVAL '120 seconds') >= updated_at
+            RETURNING id, job_status, job_type, first_block, last_block, attempts, last_error",
             ProverJobStatus::Idle.to_number(),
             ProverJobStatus::InProgress.to_number(),
+            max_attempts as i32,
+            ProverJobStatus::Failed.to_number(),
         )
         .fetch_all(self.0.conn())
         .await?;
         metrics::counter!("stale_jobs", result.len() as u64);
+
+        let failed_jobs: Vec<_> = result.into_iter().filter_map(failed_prover_job).collect();
+        metrics::counter!("failed_prover_jobs", failed_jobs.len() as u64);
         metrics::histogram!("sql", start.elapsed(), "prover" => "mark_stale_jobs_as_idle");
-        Ok(())
+        Ok(failed_jobs)
+    }
+
+    /// Records the failure reported by the prover and returns the job to the queue.
+    /// If the job was given to provers `max_attempts` times, it's marked as failed instead.
+    /// Returns `None` if the job is not in progress or is held by another prover.
+    pub async fn record_prover_job_failure(
+        &mut self,
+        job_id: i32,
+        prover_name: &str,
+        error: &str,
+        max_attempts: u32,
+    ) -> QueryResult<Option<ProverJobFailureOutcome>> {
+        let start = Instant::now();
+        let result = sqlx::query_as!(
+            StorageProverJobFailure,
+            "UPDATE prover_job_queue
+            SET (job_status, updated_at, updated_by, prover_name, last_error) = (
+                CASE WHEN attempts >= $3 THEN $4::int4 ELSE $1::int4 END, now(), $5, $5, $6
+            )
+            WHERE id = $7 AND job_status = $2 AND updated_by = $5
+            RETURNING id, job_status, job_type, first_block, last_block, attempts, last_error",
+            ProverJobStatus::Idle.to_number(),
+            ProverJobStatus::InProgress.to_number(),
+            max_attempts as i32,
+            ProverJobStatus::Failed.to_number(),
+            prover_name,
+            error,
+            job_id,
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        let outcome = result.map(|job| match failed_prover_job(job) {
+            Some(failed_job) => {
+                metrics::counter!("failed_prover_jobs", 1);
+                ProverJobFailureOutcome::Failed(failed_job)
+            }
+            None => ProverJobFailureOutcome::Requeued,
+        });
+        metrics::histogram!("sql", start.elapsed(), "prover" => "record_prover_job_failure");
+        Ok(outcome)
+    }
+
+    /// Loads the jobs in the `Failed` status.
+    pub async fn load_failed_prover_jobs(&mut self) -> QueryResult<Vec<FailedProverJob>> {
+        let start = Instant::now();
+        let failed_jobs = sqlx::query_as!(
+            StorageProverJobFailure,
+            "SELECT id, job_status, job_type, first_block, last_block, attempts, last_error
+            FROM prover_job_queue WHERE job_status = $1 ORDER BY id",
+            ProverJobStatus::Failed.to_number(),
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .filter_map(failed_prover_job)
+        .collect();
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "load_failed_prover_jobs");
+        Ok(failed_jobs)
+    }
+
+    /// Returns the failed job to the queue with the attempts counter reset.
+    /// Returns `false` if there is no failed job with such ID.
+    pub async fn requeue_failed_prover_job(&mut self, job_id: i32) -> QueryResult<bool> {
+        let start = Instant::now();
+        let updated_rows = sqlx::query!(
+            "UPDATE prover_job_queue
+            SET (job_status, updated_at, updated_by, attempts) = ($1, now(), 'admin_requeue', 0)
+            WHERE id = $2 AND job_status = $3",
+            ProverJobStatus::Idle.to_number(),
+            job_id,
+            ProverJobStatus::Failed.to_number(),
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected();
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "requeue_failed_prover_job");
+        Ok(updated_rows == 1)
     }
 
     pub async fn get_idle_prover_job_from_job_queue(&mut self) -> QueryResult<Option<ProverJob>> {
+        self.get_idle_prover_job_for_capabilities(&ProverCapabilities::default(), Duration::ZERO)
+            .await
+    }
+
+    /// Selects the job that fits the capabilities of the prover.
+    ///
+    /// Jobs the prover is able to handle go first, with the jobs of the preferred type
+    /// in the first place. Single proof jobs for the blocks larger than the prover can
+    /// handle are given only if they have been waiting for longer than `starvation_timeout`,
+    /// so jobs are not starved if there are no capable provers.
+    pub async fn get_idle_prover_job_for_capabilities(
+        &mut self,
+        capabilities: &ProverCapabilities,
+        starvation_timeout: Duration,
+    ) -> QueryResult<Option<ProverJob>> {
         let start = Instant::now();
+        let starved_since = chrono::Duration::from_std(starvation_timeout)
+            .ok()
+            .and_then(|timeout| Utc::now().checked_sub_signed(timeout))
+            .unwrap_or(chrono::MIN_DATETIME);
         // Select the block to prove.
         let mut transaction = self.0.start_transaction().await?;
 
@@ -92,12 +361,26 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
             StorageProverJobQueue,
             r#"
                 SELECT * FROM prover_job_queue
-                WHERE job_status = $1
-                ORDER BY (job_priority, id, first_block)
+                WHERE job_status = $1 AND (
+                    job_type != $2 OR $3::bigint IS NULL
+                    OR block_size <= $3
+                    OR created_at <= $4
+                )
+                ORDER BY
+                    (job_type != $2 OR $3::bigint IS NULL OR block_size <= $3) DESC,
+                    (job_type = $5) DESC,
+                    (job_priority, id, first_block)
                 FOR UPDATE SKIP LOCKED
                 LIMIT 1
             "#,
-            ProverJobStatus::Idle.to_number()
+            ProverJobStatus::Idle.to_number(),
+            ProverJobType::SingleProof.to_string(),
+            capabilities.max_block_size.map(|size| size as i64),
+            starved_since,
+            capabilities
+                .preferred_job_type
+                .as_ref()
+                .map(ProverJobType::to_string),
         )
         .fetch_optional(transaction.conn())
         .await?;
@@ -106,7 +389,8 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
             sqlx::query!(
                 r#"
                 UPDATE prover_job_queue
-                SET (job_status, updated_at, updated_by) = ($1, now(), 'server_give_job')
+                SET (job_status, updated_at, updated_by, started_at) = ($1, now(), 'server_give_job', now()),
+                    attempts = attempts + 1
                 WHERE id = $2;
             "#,
                 ProverJobStatus::InProgress.to_number(),
@@ -115,17 +399,24 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
             .execute(transaction.conn())
             .await?;
 
+            let job_data = JobRequestData::decode(&job.job_data).map_err(|err| {
+                format_err!(
+                    "Failed to decode data of the prover job {}: {}",
+                    job.id,
+                    err
+                )
+            })?;
             Some(ProverJob::new(
                 job.id,
                 BlockNumber(job.first_block as u32),
                 BlockNumber(job.last_block as u32),
-                job.job_data,
+                serde_json::to_value(job_data)?,
             ))
         } else {
             None
         };
         transaction.commit().await?;
-        metrics::histogram!("sql", start.elapsed(), "prover" => "get_idle_prover_job_from_job_queue");
+        metrics::histogram!("sql", start.elapsed(), "prover" => "get_idle_prover_job_for_capabilities");
         Ok(prover_job)
     }
 
@@ -138,7 +429,7 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         let start = Instant::now();
         sqlx::query!(
             "UPDATE prover_job_queue
-            SET (updated_at, updated_by) = (now(), $1)
+            SET (updated_at, updated_by, prover_name) = (now(), $1, $1)
             WHERE id = $2",
             prover_name.to_string(),
             job_id,
@@ -150,12 +441,13 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(())
     }
 
-    /// Marks the prover as stopped.
+    /// Marks the prover as stopped. Jobs of the stopped prover are returned to the queue,
+    /// and the attempts spent on them are not counted.
     pub async fn record_prover_stop(&mut self, prover_name: &str) -> QueryResult<()> {
         let start = Instant::now();
         sqlx::query!(
             "UPDATE prover_job_queue
-            SET (updated_at, job_status) = (now(), $1)
+            SET (updated_at, job_status) = (now(), $1), attempts = GREATEST(attempts - 1, 0)
             WHERE updated_by = $2 and job_status = $3",
             ProverJobStatus::Idle.to_number(),
             prover_name,
@@ -193,14 +485,28 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
             return Err(format_err!("Missing job for stored proof"));
         }
 
-        sqlx::query!(
-            "INSERT INTO proofs (block_number, proof)
-            VALUES ($1, $2)",
-            i64::from(*block_number),
-            serde_json::to_value(proof).unwrap()
-        )
-        .execute(transaction.conn())
-        .await?;
+        let proof = serde_json::to_value(proof).unwrap();
+        if let Some(blob_store) = transaction.blob_store() {
+            transaction
+                .prover_schema()
+                .store_blob(
+                    blob_store.as_ref(),
+                    PROOF_BLOB,
+                    block_number,
+                    block_number,
+                    &serde_json::to_vec(&proof)?,
+                )
+                .await?;
+        } else {
+            sqlx::query!(
+                "INSERT INTO proofs (block_number, proof)
+                VALUES ($1, $2)",
+                i64::from(*block_number),
+                proof
+            )
+            .execute(transaction.conn())
+            .await?;
+        }
 
         transaction
             .prover_schema()
@@ -263,15 +569,29 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
             return Err(format_err!("Missing job for stored aggregated proof"));
         }
 
-        sqlx::query!(
-            "INSERT INTO aggregated_proofs (first_block, last_block, proof)
-            VALUES ($1, $2, $3)",
-            i64::from(*first_block),
-            i64::from(*last_block),
-            serde_json::to_value(proof).unwrap()
-        )
-        .execute(transaction.conn())
-        .await?;
+        let proof = serde_json::to_value(proof).unwrap();
+        if let Some(blob_store) = transaction.blob_store() {
+            transaction
+                .prover_schema()
+                .store_blob(
+                    blob_store.as_ref(),
+                    AGGREGATED_PROOF_BLOB,
+                    first_block,
+                    last_block,
+                    &serde_json::to_vec(&proof)?,
+                )
+                .await?;
+        } else {
+            sqlx::query!(
+                "INSERT INTO aggregated_proofs (first_block, last_block, proof)
+                VALUES ($1, $2, $3)",
+                i64::from(*first_block),
+                i64::from(*last_block),
+                proof
+            )
+            .execute(transaction.conn())
+            .await?;
+        }
         transaction
             .prover_schema()
             .set_block_processing_metrics(first_block, last_block, "aggregated_proof".to_string())
@@ -288,14 +608,20 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         block_number: BlockNumber,
     ) -> QueryResult<Option<SingleProof>> {
         let start = Instant::now();
-        let proof = sqlx::query_as!(
-            StoredProof,
-            "SELECT * FROM proofs WHERE block_number = $1",
-            i64::from(*block_number),
-        )
-        .fetch_optional(self.0.conn())
-        .await?
-        .map(|stored| serde_json::from_value(stored.proof).unwrap());
+        let proof = match self
+            .load_blob(PROOF_BLOB, block_number, block_number)
+            .await?
+        {
+            Some(blob) => Some(serde_json::from_slice(&blob)?),
+            None => sqlx::query_as!(
+                StoredProof,
+                "SELECT * FROM proofs WHERE block_number = $1",
+                i64::from(*block_number),
+            )
+            .fetch_optional(self.0.conn())
+            .await?
+            .map(|stored| serde_json::from_value(stored.proof).unwrap()),
+        };
 
         metrics::histogram!("sql", start.elapsed(), "prover" => "load_proof");
         Ok(proof)
@@ -308,60 +634,111 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         last_block: BlockNumber,
     ) -> QueryResult<Option<AggregatedProof>> {
         let start = Instant::now();
-        let proof = sqlx::query_as!(
-            StoredAggregatedProof,
-            "SELECT * FROM aggregated_proofs WHERE first_block = $1 and last_block = $2",
-            i64::from(*first_block),
-            i64::from(*last_block)
-        )
-        .fetch_optional(self.0.conn())
-        .await?
-        .map(|stored| serde_json::from_value(stored.proof).unwrap());
+        let proof = match self
+            .load_blob(AGGREGATED_PROOF_BLOB, first_block, last_block)
+            .await?
+        {
+            Some(blob) => Some(serde_json::from_slice(&blob)?),
+            None => sqlx::query_as!(
+                StoredAggregatedProof,
+                "SELECT * FROM aggregated_proofs WHERE first_block = $1 and last_block = $2",
+                i64::from(*first_block),
+                i64::from(*last_block)
+            )
+            .fetch_optional(self.0.conn())
+            .await?
+            .map(|stored| serde_json::from_value(stored.proof).unwrap()),
+        };
 
         metrics::histogram!("sql", start.elapsed(), "prover" => "load_aggregated_proof");
         Ok(proof)
     }
 
-    /// Stores witness for a block
-    pub async fn store_witness(
-        &mut self,
-        block: BlockNumber,
-        witness: serde_json::Value,
-    ) -> QueryResult<()> {
+    /// Stores encoded witness for a block
+    pub async fn store_witness(&mut self, block: BlockNumber, witness: &[u8]) -> QueryResult<()> {
         let start = Instant::now();
-        let witness_str = serde_json::to_string(&witness).expect("Failed to serialize witness");
-        sqlx::query!(
-            "INSERT INTO block_witness (block, witness)
-            VALUES ($1, $2)
-            ON CONFLICT (block)
-            DO NOTHING",
-            i64::from(*block),
-            witness_str
-        )
-        .execute(self.0.conn())
-        .await?;
+        if let Some(blob_store) = self.0.blob_store() {
+            // Same as for the database table, the stored witness is never replaced.
+            let key = blob_key(WITNESS_BLOB, block, block);
+            if self.load_blob_ref(&key).await?.is_none() {
+                self.store_blob(blob_store.as_ref(), WITNESS_BLOB, block, block, witness)
+                    .await?;
+            }
+        } else {
+            sqlx::query!(
+                "INSERT INTO block_witness (block, witness)
+                VALUES ($1, $2)
+                ON CONFLICT (block)
+                DO NOTHING",
+                i64::from(*block),
+                witness
+            )
+            .execute(self.0.conn())
+            .await?;
+        }
 
         metrics::histogram!("sql", start.elapsed(), "prover" => "store_witness");
         Ok(())
     }
 
-    /// Gets stored witness for a block.
-    pub async fn get_witness(
+    /// Gets stored encoded witness for a block.
+    pub async fn get_witness(&mut self, block_number: BlockNumber) -> QueryResult<Option<Vec<u8>>> {
+        let start = Instant::now();
+        let witness = match self
+            .load_blob(WITNESS_BLOB, block_number, block_number)
+            .await?
+        {
+            Some(witness) => Some(witness),
+            None => sqlx::query_as!(
+                StorageBlockWitness,
+                "SELECT * FROM block_witness WHERE block = $1",
+                i64::from(*block_number),
+            )
+            .fetch_optional(self.0.conn())
+            .await?
+            .map(|w| w.witness),
+        };
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "get_witness");
+        Ok(witness)
+    }
+
+    /// Loads up to `limit` witnesses stored in the database table for blocks with numbers greater than `after_block`.
+    pub async fn load_witnesses_batch(
         &mut self,
-        block_number: BlockNumber,
-    ) -> QueryResult<Option<serde_json::Value>> {
+        after_block: BlockNumber,
+        limit: u32,
+    ) -> QueryResult<Vec<(BlockNumber, Vec<u8>)>> {
         let start = Instant::now();
-        let block_witness = sqlx::query_as!(
+        let witnesses = sqlx::query_as!(
             StorageBlockWitness,
-            "SELECT * FROM block_witness WHERE block = $1",
-            i64::from(*block_number),
+            "SELECT * FROM block_witness WHERE block > $1 ORDER BY block LIMIT $2",
+            i64::from(*after_block),
+            i64::from(limit),
         )
-        .fetch_optional(self.0.conn())
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|w| (BlockNumber(w.block as u32), w.witness))
+        .collect();
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "load_witnesses_batch");
+        Ok(witnesses)
+    }
+
+    /// Replaces the witness stored in the database table for a block, e.g. to re-encode it in a different format.
+    pub async fn update_witness(&mut self, block: BlockNumber, witness: &[u8]) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "UPDATE block_witness SET witness = $2 WHERE block = $1",
+            i64::from(*block),
+            witness
+        )
+        .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql", start.elapsed(), "prover" => "get_witness");
-        Ok(block_witness
-            .map(|w| serde_json::from_str(&w.witness).expect("Failed to deserialize witness")))
+        metrics::histogram!("sql", start.elapsed(), "prover" => "update_witness");
+        Ok(())
     }
 
     pub async fn get_last_block_prover_job_queue(
@@ -415,6 +792,7 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         )
         .execute(self.0.conn())
         .await?;
+        self.remove_blobs_after(WITNESS_BLOB, last_block).await?;
 
         metrics::histogram!("sql", start.elapsed(), "prover" => "remove_witnesses");
         Ok(())
@@ -429,6 +807,7 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         )
         .execute(self.0.conn())
         .await?;
+        self.remove_blobs_after(PROOF_BLOB, last_block).await?;
 
         metrics::histogram!("sql", start.elapsed(), "prover" => "remove_proofs");
         Ok(())
@@ -443,6 +822,8 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         )
         .execute(self.0.conn())
         .await?;
+        self.remove_blobs_after(AGGREGATED_PROOF_BLOB, last_block)
+            .await?;
 
         metrics::histogram!("sql", start.elapsed(), "prover" => "remove_aggregated_proofs");
         Ok(())
@@ -470,4 +851,146 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         metrics::histogram!("sql", start.elapsed(), "prover" => "remove_prover_jobs");
         Ok(())
     }
+
+    /// Removes the witnesses and proofs for the blocks executed on L1 from the blob store,
+    /// since they are not needed anymore. At most `limit` blobs are removed at once.
+    /// Returns the amount of the removed blobs.
+    pub async fn remove_executed_blobs(&mut self, limit: u32) -> QueryResult<usize> {
+        let start = Instant::now();
+        let last_executed_block = self
+            .0
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await?;
+
+        let keys: Vec<String> = sqlx::query!(
+            "SELECT blob_key FROM blob_refs WHERE last_block <= $1 ORDER BY last_block LIMIT $2",
+            i64::from(*last_executed_block),
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|row| row.blob_key)
+        .collect();
+
+        if !keys.is_empty() {
+            let blob_store = self.referenced_blob_store()?;
+            // The reference is deleted only after the blob is removed, so that failing in between
+            // never leaks a blob without a reference. A reference left to the removed blob is
+            // retried on the next call, which is fine since removal of a missing blob succeeds.
+            for key in &keys {
+                blob_store.remove(key).await?;
+                sqlx::query!("DELETE FROM blob_refs WHERE blob_key = $1", key)
+                    .execute(self.0.conn())
+                    .await?;
+                metrics::increment_counter!("blob_store.removed_blobs");
+            }
+        }
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "remove_executed_blobs");
+        Ok(keys.len())
+    }
+
+    async fn load_blob_ref(&mut self, key: &str) -> QueryResult<Option<StorageBlobRef>> {
+        let blob_ref = sqlx::query_as!(
+            StorageBlobRef,
+            "SELECT * FROM blob_refs WHERE blob_key = $1",
+            key
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+        Ok(blob_ref)
+    }
+
+    /// Puts the blob into the blob store and records the reference to it along with the checksum.
+    async fn store_blob(
+        &mut self,
+        blob_store: &dyn BlobStore,
+        blob_type: &str,
+        first_block: BlockNumber,
+        last_block: BlockNumber,
+        data: &[u8],
+    ) -> QueryResult<()> {
+        let key = blob_key(blob_type, first_block, last_block);
+        blob_store.put(&key, data).await?;
+        sqlx::query!(
+            "INSERT INTO blob_refs (blob_key, blob_type, first_block, last_block, checksum)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (blob_key)
+            DO UPDATE SET checksum = $5, created_at = now()",
+            key,
+            blob_type,
+            i64::from(*first_block),
+            i64::from(*last_block),
+            blob_store::checksum(data)
+        )
+        .execute(self.0.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Loads the referenced blob and verifies its checksum.
+    /// Returns `None` if there is no reference, i.e. the data is stored in the database table.
+    async fn load_blob(
+        &mut self,
+        blob_type: &str,
+        first_block: BlockNumber,
+        last_block: BlockNumber,
+    ) -> QueryResult<Option<Vec<u8>>> {
+        let key = blob_key(blob_type, first_block, last_block);
+        let blob_ref = match self.load_blob_ref(&key).await? {
+            Some(blob_ref) => blob_ref,
+            None => return Ok(None),
+        };
+        let data = self
+            .referenced_blob_store()?
+            .get(&key)
+            .await?
+            .ok_or_else(|| format_err!("Blob {} is missing in the blob store", key))?;
+        ensure!(
+            blob_store::checksum(&data) == blob_ref.checksum,
+            "Checksum mismatch for the blob {}",
+            key
+        );
+        Ok(Some(data))
+    }
+
+    /// Removes the blobs of the given type for blocks with number greater than `last_block`.
+    async fn remove_blobs_after(
+        &mut self,
+        blob_type: &str,
+        last_block: BlockNumber,
+    ) -> QueryResult<()> {
+        let keys: Vec<String> = sqlx::query!(
+            "DELETE FROM blob_refs WHERE blob_type = $1 AND last_block > $2 RETURNING blob_key",
+            blob_type,
+            i64::from(*last_block)
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|row| row.blob_key)
+        .collect();
+        self.remove_blobs(&keys).await
+    }
+
+    /// Removes the blobs which references are already deleted.
+    async fn remove_blobs(&mut self, keys: &[String]) -> QueryResult<()> {
+        if keys.is_empty() {
+            return Ok(());
+        }
+        let blob_store = self.referenced_blob_store()?;
+        for key in keys {
+            blob_store.remove(key).await?;
+        }
+        Ok(())
+    }
+
+    fn referenced_blob_store(&self) -> QueryResult<Arc<dyn BlobStore>> {
+        self.0.blob_store().ok_or_else(|| {
+            format_err!("Blobs are referenced, but the blob store is not configured")
+        })
+    }
 }
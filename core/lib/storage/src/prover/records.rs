@@ -53,7 +53,8 @@ pub struct IntegerNumber {
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct StorageBlockWitness {
     pub block: i64,
-    pub witness: String,
+    /// Witness encoded in one of the `zksync_circuit::serialization::WitnessFormat` formats.
+    pub witness: Vec<u8>,
 }
 
 #[derive(Debug, FromRow)]
@@ -67,5 +68,68 @@ pub struct StorageProverJobQueue {
     pub updated_at: DateTime<Utc>,
     pub first_block: i64,
     pub last_block: i64,
-    pub job_data: serde_json::Value,
+    /// Job data encoded in one of the `zksync_circuit::serialization::WitnessFormat` formats.
+    pub job_data: Vec<u8>,
+    /// How many times the job was given to provers.
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    /// Time when the job was given to the prover last time.
+    pub started_at: Option<DateTime<Utc>>,
+    /// Prover that worked on the job last time.
+    pub prover_name: Option<String>,
+    /// Chunks size of the block for the single proof job.
+    pub block_size: Option<i64>,
+}
+
+/// Prover job which status was changed after the failure.
+#[derive(Debug, FromRow)]
+pub struct StorageProverJobFailure {
+    pub id: i32,
+    pub job_status: i32,
+    pub job_type: String,
+    pub first_block: i64,
+    pub last_block: i64,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+}
+
+/// Statistics of the prover jobs grouped by the job type and size.
+#[derive(Debug, FromRow)]
+pub struct StorageProverJobStats {
+    pub job_type: String,
+    pub job_size: Option<i64>,
+    pub pending_jobs: i64,
+    pub created_jobs: i64,
+    pub proven_jobs: i64,
+    /// Average proof generation duration in seconds.
+    pub avg_proof_duration: Option<f64>,
+}
+
+/// Prover job along with the prover working on it.
+#[derive(Debug, FromRow)]
+pub struct StorageProverJobInfo {
+    pub id: i32,
+    pub job_status: i32,
+    pub job_type: String,
+    pub job_size: Option<i64>,
+    pub first_block: i64,
+    pub last_block: i64,
+    pub prover_name: Option<String>,
+    pub attempts: i32,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Reference to the witness or proof kept in the blob store.
+#[derive(Debug, Clone, FromRow)]
+pub struct StorageBlobRef {
+    pub blob_key: String,
+    /// One of `witness`, `proof` or `aggregated_proof`.
+    pub blob_type: String,
+    pub first_block: i64,
+    pub last_block: i64,
+    /// SHA-256 of the blob.
+    pub checksum: Vec<u8>,
+    pub created_at: DateTime<Utc>,
 }
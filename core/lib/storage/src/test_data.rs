@@ -8,9 +8,16 @@ use num::{BigUint, Zero};
 use once_cell::sync::Lazy;
 use parity_crypto::publickey::{Generator, Random};
 // Workspace imports
+use zksync_circuit::account::AccountWitness;
+use zksync_circuit::serialization::ProverData;
 use zksync_crypto::params::{max_account_id, MIN_NFT_TOKEN_ID};
 use zksync_crypto::proof::{AggregatedProof, PrecomputedSampleProofs, SingleProof};
-use zksync_crypto::{ff::PrimeField, rand::Rng, Fr};
+use zksync_crypto::{
+    ff::{Field, PrimeField},
+    rand::Rng,
+    Fr,
+};
+use zksync_prover_utils::api::JobRequestData;
 use zksync_prover_utils::fs_utils::load_precomputed_proofs;
 use zksync_types::block::{IncompleteBlock, PendingBlock};
 use zksync_types::{
@@ -39,6 +46,35 @@ pub fn get_sample_aggregated_proof() -> AggregatedProof {
     SAMPLE_PROOF.aggregated_proof.clone()
 }
 
+/// Creates the single proof job data with an empty witness for the block of the given size.
+pub fn get_sample_single_proof_job_data(block_size: usize) -> JobRequestData {
+    let prover_data = ProverData {
+        block_number: Fr::zero(),
+        public_data_commitment: Fr::zero(),
+        old_root: Fr::zero(),
+        initial_used_subtree_root: Fr::zero(),
+        new_root: Fr::zero(),
+        block_timestamp: Fr::zero(),
+        validator_address: Fr::zero(),
+        validator_balances: Vec::new(),
+        validator_audit_path: Vec::new(),
+        operations: Vec::new(),
+        validator_account: AccountWitness {
+            nonce: None,
+            pub_key_hash: None,
+            address: None,
+        },
+        validator_non_processable_tokens_audit_before_fees: Vec::new(),
+        validator_non_processable_tokens_audit_after_fees: Vec::new(),
+    };
+    JobRequestData::BlockProof(prover_data, block_size)
+}
+
+/// Creates the aggregated proof job data without proofs.
+pub fn get_sample_aggregated_proof_job_data() -> JobRequestData {
+    JobRequestData::AggregatedBlockProof(Vec::new())
+}
+
 /// Block size used for tests
 pub const BLOCK_SIZE_CHUNKS: usize = 100;
 
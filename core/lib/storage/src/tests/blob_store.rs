@@ -0,0 +1,148 @@
+// Built-in imports
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+// External imports
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use sha2::{Digest, Sha256};
+// Local imports
+use crate::blob_store::{BlobStore, FsBlobStore, S3BlobStore};
+
+type Objects = Arc<Mutex<HashMap<String, Vec<u8>>>>;
+
+/// Minimal in-memory stand-in for the S3-compatible storage (like MinIO).
+/// Checks that requests are signed and the payload hash matches the body.
+async fn handle_s3_request(
+    objects: Objects,
+    request: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    let (parts, body) = request.into_parts();
+    let body = hyper::body::to_bytes(body).await.unwrap().to_vec();
+    let header = |name: &str| {
+        parts
+            .headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    let response = |status: StatusCode, body: Vec<u8>| {
+        Ok(Response::builder()
+            .status(status)
+            .body(Body::from(body))
+            .unwrap())
+    };
+    if !header("authorization").starts_with("AWS4-HMAC-SHA256 Credential=minio/")
+        || header("x-amz-content-sha256") != hex::encode(Sha256::digest(&body))
+        || header("x-amz-date").is_empty()
+    {
+        return response(StatusCode::FORBIDDEN, Vec::new());
+    }
+
+    let key = parts.uri.path().to_string();
+    let mut objects = objects.lock().unwrap();
+    match parts.method {
+        Method::PUT => {
+            objects.insert(key, body);
+            response(StatusCode::OK, Vec::new())
+        }
+        Method::GET => match objects.get(&key) {
+            Some(object) => response(StatusCode::OK, object.clone()),
+            None => response(StatusCode::NOT_FOUND, b"NoSuchKey".to_vec()),
+        },
+        Method::DELETE => {
+            objects.remove(&key);
+            response(StatusCode::NO_CONTENT, Vec::new())
+        }
+        _ => response(StatusCode::METHOD_NOT_ALLOWED, Vec::new()),
+    }
+}
+
+/// Spawns the S3 stand-in and returns its address along with the stored objects.
+fn spawn_s3_server() -> (SocketAddr, Objects) {
+    let objects = Objects::default();
+    let service_objects = objects.clone();
+    let make_service = make_service_fn(move |_| {
+        let objects = service_objects.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                handle_s3_request(objects.clone(), request)
+            }))
+        }
+    });
+    let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service);
+    let address = server.local_addr();
+    tokio::spawn(server);
+    (address, objects)
+}
+
+/// Checks the basic operations that every blob store must support.
+async fn check_blob_store(store: &dyn BlobStore) -> anyhow::Result<()> {
+    assert_eq!(store.get("witness/1").await?, None);
+
+    store.put("witness/1", b"first").await?;
+    store.put("aggregated_proof/1-10", b"second").await?;
+    assert_eq!(store.get("witness/1").await?, Some(b"first".to_vec()));
+    assert_eq!(
+        store.get("aggregated_proof/1-10").await?,
+        Some(b"second".to_vec())
+    );
+
+    // Blobs are overwritten.
+    store.put("witness/1", b"updated").await?;
+    assert_eq!(store.get("witness/1").await?, Some(b"updated".to_vec()));
+
+    store.remove("witness/1").await?;
+    assert_eq!(store.get("witness/1").await?, None);
+    // Removing the missing blob is fine.
+    store.remove("witness/1").await?;
+    assert_eq!(
+        store.get("aggregated_proof/1-10").await?,
+        Some(b"second".to_vec())
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn fs_blob_store() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let store = FsBlobStore::new(dir.path());
+    check_blob_store(&store).await?;
+
+    // Keys must not escape the root directory.
+    assert!(store.put("../witness", b"data").await.is_err());
+    assert!(store.get("/etc/passwd").await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn s3_blob_store() -> anyhow::Result<()> {
+    let (address, objects) = spawn_s3_server();
+    let store = S3BlobStore::new(
+        &format!("http://{}", address),
+        "zksync",
+        "us-east-1",
+        "minio",
+        "minio-secret",
+    );
+    check_blob_store(&store).await?;
+    assert!(objects
+        .lock()
+        .unwrap()
+        .contains_key("/zksync/aggregated_proof/1-10"));
+
+    // Requests signed with the wrong credentials are rejected.
+    let store = S3BlobStore::new(
+        &format!("http://{}", address),
+        "zksync",
+        "us-east-1",
+        "unknown",
+        "minio-secret",
+    );
+    assert!(store.put("witness/2", b"data").await.is_err());
+    assert!(store.get("aggregated_proof/1-10").await.is_err());
+    Ok(())
+}
@@ -422,6 +422,73 @@ async fn test_get_account_nft_balance(mut storage: StorageProcessor<'_>) -> Quer
         .await?;
     assert_eq!(nft_balance2, 1u32);
 
+    // Checks that the special token is not listed among the owned NFTs.
+    let nfts = storage
+        .chain()
+        .account_schema()
+        .get_account_nfts(address)
+        .await?;
+    assert_eq!(nfts, vec![nft_id]);
+
+    Ok(())
+}
+
+#[db_test]
+async fn test_get_token_total_supply(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let token_id = TokenId(0);
+
+    // Checks that there is no supply for the token without balances.
+    let total_supply0 = storage
+        .chain()
+        .account_schema()
+        .get_token_total_supply(token_id)
+        .await?;
+    assert_eq!(total_supply0, BigUint::zero());
+
+    let mut updates = Vec::new();
+    for (id, balance) in [(1u32, 100u32), (2, 250)].iter() {
+        updates.push((
+            AccountId(*id),
+            AccountUpdate::Create {
+                address: Address::random(),
+                nonce: Nonce(0),
+            },
+        ));
+        updates.push((
+            AccountId(*id),
+            AccountUpdate::UpdateBalance {
+                old_nonce: Nonce(0),
+                new_nonce: Nonce(0),
+                balance_update: (token_id, BigUint::zero(), BigUint::from(*balance)),
+            },
+        ));
+    }
+    storage
+        .chain()
+        .state_schema()
+        .commit_state_update(BlockNumber(1), &updates, 0)
+        .await?;
+
+    // Checks that only verified balances are taken into account.
+    let total_supply1 = storage
+        .chain()
+        .account_schema()
+        .get_token_total_supply(token_id)
+        .await?;
+    assert_eq!(total_supply1, BigUint::zero());
+
+    storage
+        .chain()
+        .state_schema()
+        .apply_state_update(BlockNumber(1))
+        .await?;
+    let total_supply2 = storage
+        .chain()
+        .account_schema()
+        .get_token_total_supply(token_id)
+        .await?;
+    assert_eq!(total_supply2, BigUint::from(350u32));
+
     Ok(())
 }
 
@@ -40,3 +40,58 @@ async fn last_watched_block(mut storage: StorageProcessor<'_>) -> QueryResult<()
 
     Ok(())
 }
+
+/// Checks that the Ethereum logs checkpoints can be stored, loaded and
+/// are removed once the last watched block passes them.
+#[db_test]
+async fn eth_logs_checkpoints(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let checkpoints = DataRestoreSchema(&mut storage)
+        .load_eth_logs_checkpoints()
+        .await?;
+    assert!(checkpoints.is_empty());
+
+    DataRestoreSchema(&mut storage)
+        .save_eth_logs_checkpoint(11, 20, serde_json::json!([2]))
+        .await?;
+    DataRestoreSchema(&mut storage)
+        .save_eth_logs_checkpoint(1, 10, serde_json::json!([1]))
+        .await?;
+    // Saving the same range again overwrites it.
+    DataRestoreSchema(&mut storage)
+        .save_eth_logs_checkpoint(11, 20, serde_json::json!([3]))
+        .await?;
+
+    let checkpoints = DataRestoreSchema(&mut storage)
+        .load_eth_logs_checkpoints()
+        .await?;
+    assert_eq!(checkpoints.len(), 2);
+    assert_eq!(
+        (checkpoints[0].from_block, checkpoints[0].to_block),
+        (1, 10)
+    );
+    assert_eq!(
+        (checkpoints[1].from_block, checkpoints[1].to_block),
+        (11, 20)
+    );
+    assert_eq!(checkpoints[1].logs, serde_json::json!([3]));
+
+    // Processing the events state up to the block 15 must only drop the first range.
+    DataRestoreSchema(&mut storage)
+        .save_events_state(&[], &[], &[], "15")
+        .await?;
+    let checkpoints = DataRestoreSchema(&mut storage)
+        .load_eth_logs_checkpoints()
+        .await?;
+    assert_eq!(checkpoints.len(), 1);
+    assert_eq!(checkpoints[0].from_block, 11);
+
+    DataRestoreSchema(&mut storage)
+        .remove_eth_logs_checkpoints(20)
+        .await?;
+    let checkpoints = DataRestoreSchema(&mut storage)
+        .load_eth_logs_checkpoints()
+        .await?;
+    assert!(checkpoints.is_empty());
+
+    Ok(())
+}
@@ -23,6 +23,7 @@ use tokio::sync::Mutex;
 // Workspace imports
 use zksync_crypto::rand::{SeedableRng, XorShiftRng};
 
+mod blob_store;
 pub(crate) mod chain;
 mod config;
 mod data_restore;
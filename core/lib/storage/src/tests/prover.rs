@@ -1,14 +1,24 @@
+// Built-in imports
+use std::sync::Arc;
+use std::time::Duration;
 // External imports
 use anyhow::format_err;
 use once_cell::sync::Lazy;
 use tokio::sync::Mutex;
 // Workspace imports
 use zksync_types::{
-    prover::{ProverJob, ProverJobType},
+    prover::{
+        ProverCapabilities, ProverJob, ProverJobFailureOutcome, ProverJobStats, ProverJobStatus,
+        ProverJobType, AGGREGATED_PROOF_JOB_PRIORITY, SINGLE_PROOF_JOB_PRIORITY,
+    },
     BlockNumber,
 };
 // Local imports
-use crate::test_data::{gen_sample_block, get_sample_aggregated_proof, get_sample_single_proof};
+use crate::blob_store::FsBlobStore;
+use crate::test_data::{
+    gen_sample_block, get_sample_aggregated_proof, get_sample_aggregated_proof_job_data,
+    get_sample_single_proof, get_sample_single_proof_job_data,
+};
 use crate::tests::db_test;
 use crate::{prover::ProverSchema, QueryResult, StorageProcessor};
 
@@ -73,12 +83,11 @@ async fn test_store_proof(storage: &mut StorageProcessor<'_>) -> QueryResult<()>
         .contains("Missing job for stored aggregated proof"));
 
     // Add jobs to `job_prover_queue`.
-    let job_data = serde_json::Value::default();
     let stored_job = ProverSchema(storage)
         .add_prover_job_to_job_queue(
             BlockNumber(1),
             BlockNumber(1),
-            job_data.clone(),
+            &get_sample_single_proof_job_data(10),
             0,
             ProverJobType::SingleProof,
         )
@@ -87,7 +96,7 @@ async fn test_store_proof(storage: &mut StorageProcessor<'_>) -> QueryResult<()>
         .add_prover_job_to_job_queue(
             BlockNumber(1),
             BlockNumber(1),
-            job_data,
+            &get_sample_aggregated_proof_job_data(),
             1,
             ProverJobType::AggregatedProof,
         )
@@ -140,7 +149,7 @@ async fn pending_jobs_count(storage: &mut StorageProcessor<'_>) -> QueryResult<(
         .add_prover_job_to_job_queue(
             BlockNumber(2),
             BlockNumber(2),
-            Default::default(),
+            &get_sample_single_proof_job_data(10),
             1,
             ProverJobType::SingleProof,
         )
@@ -149,7 +158,7 @@ async fn pending_jobs_count(storage: &mut StorageProcessor<'_>) -> QueryResult<(
         .add_prover_job_to_job_queue(
             BlockNumber(3),
             BlockNumber(3),
-            Default::default(),
+            &get_sample_single_proof_job_data(10),
             1,
             ProverJobType::SingleProof,
         )
@@ -158,7 +167,7 @@ async fn pending_jobs_count(storage: &mut StorageProcessor<'_>) -> QueryResult<(
         .add_prover_job_to_job_queue(
             BlockNumber(2),
             BlockNumber(3),
-            Default::default(),
+            &get_sample_aggregated_proof_job_data(),
             0,
             ProverJobType::AggregatedProof,
         )
@@ -237,36 +246,34 @@ async fn test_store_witness(mut storage: StorageProcessor<'_>) -> QueryResult<()
         .await?;
 
     // Store the witness.
-    let expected = String::from("test");
-    let witness = serde_json::to_value(expected.clone()).unwrap();
+    let expected = vec![1u8, 2, 3];
     storage
         .prover_schema()
-        .store_witness(BLOCK_NUMBER, witness)
+        .store_witness(BLOCK_NUMBER, &expected)
         .await?;
 
     // Now load it.
-    let loaded = storage
-        .prover_schema()
-        .get_witness(BLOCK_NUMBER)
-        .await?
-        .map(|value| serde_json::from_value(value).unwrap());
+    let loaded = storage.prover_schema().get_witness(BLOCK_NUMBER).await?;
     assert_eq!(loaded.as_ref(), Some(&expected));
 
     // Do nothing on conflict.
-    let not_expected = String::from("__test");
-    let witness = serde_json::to_value(expected.clone()).unwrap();
+    let not_expected = vec![1u8, 2, 3, 4];
     storage
         .prover_schema()
-        .store_witness(BLOCK_NUMBER, witness)
+        .store_witness(BLOCK_NUMBER, &not_expected)
         .await?;
 
-    let loaded = storage
+    let loaded = storage.prover_schema().get_witness(BLOCK_NUMBER).await?;
+    assert_ne!(loaded.as_ref(), Some(&not_expected));
+    assert_eq!(loaded.as_ref(), Some(&expected));
+
+    // Witness can be replaced explicitly.
+    storage
         .prover_schema()
-        .get_witness(BLOCK_NUMBER)
-        .await?
-        .map(|value| serde_json::from_value(value).unwrap());
-    assert_ne!(loaded, Some(not_expected));
-    assert_eq!(loaded, Some(expected));
+        .update_witness(BLOCK_NUMBER, &not_expected)
+        .await?;
+    let loaded = storage.prover_schema().get_witness(BLOCK_NUMBER).await?;
+    assert_eq!(loaded, Some(not_expected));
 
     Ok(())
 }
@@ -285,12 +292,24 @@ async fn test_remove_witnesses(mut storage: StorageProcessor<'_>) -> QueryResult
                 Default::default(),
             ))
             .await?;
-        let witness = serde_json::to_value(String::from("test")).unwrap();
         storage
             .prover_schema()
-            .store_witness(BlockNumber(block_number), witness)
+            .store_witness(BlockNumber(block_number), b"test")
             .await?;
     }
+    // Witnesses are loaded in batches ordered by the block number.
+    let batch = storage
+        .prover_schema()
+        .load_witnesses_batch(BlockNumber(1), 2)
+        .await?;
+    assert_eq!(
+        batch,
+        vec![
+            (BlockNumber(2), b"test".to_vec()),
+            (BlockNumber(3), b"test".to_vec())
+        ]
+    );
+
     // Remove witnesses for the 4th and 5th blocks.
     storage
         .prover_schema()
@@ -319,15 +338,13 @@ async fn test_remove_proofs(mut storage: StorageProcessor<'_>) -> QueryResult<()
     let _lock = MUTEX.lock().await;
 
     let proof = get_sample_single_proof();
-    let job_data = serde_json::Value::default();
-
     // Insert proofs for 5 blocks.
     for block_number in 1..=5 {
         ProverSchema(&mut storage)
             .add_prover_job_to_job_queue(
                 BlockNumber(block_number),
                 BlockNumber(block_number),
-                job_data.clone(),
+                &get_sample_single_proof_job_data(10),
                 0,
                 ProverJobType::SingleProof,
             )
@@ -360,7 +377,7 @@ async fn test_remove_proofs(mut storage: StorageProcessor<'_>) -> QueryResult<()
         .add_prover_job_to_job_queue(
             BlockNumber(1),
             BlockNumber(2),
-            job_data.clone(),
+            &get_sample_aggregated_proof_job_data(),
             1,
             ProverJobType::AggregatedProof,
         )
@@ -374,7 +391,7 @@ async fn test_remove_proofs(mut storage: StorageProcessor<'_>) -> QueryResult<()
         .add_prover_job_to_job_queue(
             BlockNumber(3),
             BlockNumber(5),
-            job_data.clone(),
+            &get_sample_aggregated_proof_job_data(),
             1,
             ProverJobType::AggregatedProof,
         )
@@ -405,14 +422,12 @@ async fn test_remove_proofs(mut storage: StorageProcessor<'_>) -> QueryResult<()
 /// Checks that prover jobs are removed correctly.
 #[db_test]
 async fn test_remove_prover_jobs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
-    let job_data = serde_json::Value::default();
-
     // Insert jobs for blocks 1-3 and 4-5.
     ProverSchema(&mut storage)
         .add_prover_job_to_job_queue(
             BlockNumber(1),
             BlockNumber(3),
-            job_data.clone(),
+            &get_sample_aggregated_proof_job_data(),
             1,
             ProverJobType::AggregatedProof,
         )
@@ -421,7 +436,7 @@ async fn test_remove_prover_jobs(mut storage: StorageProcessor<'_>) -> QueryResu
         .add_prover_job_to_job_queue(
             BlockNumber(4),
             BlockNumber(5),
-            job_data.clone(),
+            &get_sample_aggregated_proof_job_data(),
             1,
             ProverJobType::AggregatedProof,
         )
@@ -441,3 +456,449 @@ async fn test_remove_prover_jobs(mut storage: StorageProcessor<'_>) -> QueryResu
 
     Ok(())
 }
+
+/// Checks that prover jobs are given according to the prover capabilities.
+#[db_test]
+async fn test_prover_job_capabilities(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Lock to prevent database deadlock
+    let _lock = MUTEX.lock().await;
+
+    ProverSchema(&mut storage)
+        .add_prover_job_to_job_queue(
+            BlockNumber(1),
+            BlockNumber(1),
+            &get_sample_single_proof_job_data(654),
+            SINGLE_PROOF_JOB_PRIORITY,
+            ProverJobType::SingleProof,
+        )
+        .await?;
+    ProverSchema(&mut storage)
+        .add_prover_job_to_job_queue(
+            BlockNumber(2),
+            BlockNumber(2),
+            &get_sample_single_proof_job_data(10),
+            SINGLE_PROOF_JOB_PRIORITY,
+            ProverJobType::SingleProof,
+        )
+        .await?;
+    ProverSchema(&mut storage)
+        .add_prover_job_to_job_queue(
+            BlockNumber(1),
+            BlockNumber(2),
+            &get_sample_aggregated_proof_job_data(),
+            AGGREGATED_PROOF_JOB_PRIORITY,
+            ProverJobType::AggregatedProof,
+        )
+        .await?;
+
+    let capabilities = ProverCapabilities {
+        preferred_job_type: Some(ProverJobType::SingleProof),
+        max_block_size: Some(32),
+    };
+    let starvation_timeout = Duration::from_secs(3600);
+
+    // Preferred job type goes first. Job data is served decoded.
+    let job = ProverSchema(&mut storage)
+        .get_idle_prover_job_for_capabilities(&capabilities, starvation_timeout)
+        .await?
+        .expect("expect idle job from job queue");
+    assert_eq!(
+        (job.first_block, job.last_block),
+        (BlockNumber(2), BlockNumber(2))
+    );
+    assert_eq!(
+        job.job_data,
+        serde_json::to_value(get_sample_single_proof_job_data(10))?
+    );
+
+    // Then any other job the prover is able to handle.
+    let job = ProverSchema(&mut storage)
+        .get_idle_prover_job_for_capabilities(&capabilities, starvation_timeout)
+        .await?
+        .expect("expect idle job from job queue");
+    assert_eq!(
+        (job.first_block, job.last_block),
+        (BlockNumber(1), BlockNumber(2))
+    );
+
+    // The block is too large for the prover and the job is not starved yet.
+    assert!(ProverSchema(&mut storage)
+        .get_idle_prover_job_for_capabilities(&capabilities, starvation_timeout)
+        .await?
+        .is_none());
+
+    // Starved job is given to any prover.
+    let job = ProverSchema(&mut storage)
+        .get_idle_prover_job_for_capabilities(&capabilities, Duration::ZERO)
+        .await?
+        .expect("expect idle job from job queue");
+    assert_eq!(
+        (job.first_block, job.last_block),
+        (BlockNumber(1), BlockNumber(1))
+    );
+
+    Ok(())
+}
+
+/// Checks that witnesses and proofs are kept in the blob store if it's set,
+/// and the database stores only the references to them.
+#[db_test]
+async fn test_prover_data_in_blob_store(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Lock to prevent database deadlock
+    let _lock = MUTEX.lock().await;
+
+    let blob_dir = tempfile::tempdir()?;
+    storage.set_blob_store(Arc::new(FsBlobStore::new(blob_dir.path())));
+
+    // Witness is put into the blob store and is never replaced.
+    storage
+        .prover_schema()
+        .store_witness(BlockNumber(1), b"witness")
+        .await?;
+    storage
+        .prover_schema()
+        .store_witness(BlockNumber(1), b"another witness")
+        .await?;
+    assert_eq!(
+        storage.prover_schema().get_witness(BlockNumber(1)).await?,
+        Some(b"witness".to_vec())
+    );
+    assert_eq!(
+        std::fs::read(blob_dir.path().join("witness/1"))?,
+        b"witness"
+    );
+    assert!(storage
+        .prover_schema()
+        .load_witnesses_batch(BlockNumber(0), 10)
+        .await?
+        .is_empty());
+
+    // Proofs are put into the blob store as well.
+    let proof = get_sample_single_proof();
+    ProverSchema(&mut storage)
+        .add_prover_job_to_job_queue(
+            BlockNumber(1),
+            BlockNumber(1),
+            &get_sample_single_proof_job_data(10),
+            0,
+            ProverJobType::SingleProof,
+        )
+        .await?;
+    let job_id = get_idle_job_from_queue(&mut storage).await?.job_id;
+    ProverSchema(&mut storage)
+        .store_proof(job_id, BlockNumber(1), &proof)
+        .await?;
+    let loaded_proof = ProverSchema(&mut storage)
+        .load_proof(BlockNumber(1))
+        .await?
+        .expect("Proof must be stored");
+    assert_eq!(
+        serde_json::to_value(loaded_proof)?,
+        serde_json::to_value(proof)?
+    );
+    assert!(blob_dir.path().join("proof/1").exists());
+
+    // Blob modified outside of the server is detected.
+    std::fs::write(blob_dir.path().join("witness/1"), b"corrupted")?;
+    let err = storage
+        .prover_schema()
+        .get_witness(BlockNumber(1))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Checksum mismatch"));
+
+    // Blobs of the blocks that are not executed on L1 are not collected.
+    assert_eq!(storage.prover_schema().remove_executed_blobs(10).await?, 0);
+
+    // Reverted blocks data is removed from the blob store.
+    storage
+        .prover_schema()
+        .remove_witnesses(BlockNumber(0))
+        .await?;
+    ProverSchema(&mut storage)
+        .remove_proofs(BlockNumber(0))
+        .await?;
+    assert!(storage
+        .prover_schema()
+        .get_witness(BlockNumber(1))
+        .await?
+        .is_none());
+    assert!(ProverSchema(&mut storage)
+        .load_proof(BlockNumber(1))
+        .await?
+        .is_none());
+    assert!(!blob_dir.path().join("witness/1").exists());
+    assert!(!blob_dir.path().join("proof/1").exists());
+
+    Ok(())
+}
+
+/// Checks that the job is marked as failed after exhausting its attempts and can be requeued.
+#[db_test]
+async fn test_prover_job_failures(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Lock to prevent database deadlock
+    let _lock = MUTEX.lock().await;
+    const MAX_ATTEMPTS: u32 = 2;
+
+    ProverSchema(&mut storage)
+        .add_prover_job_to_job_queue(
+            BlockNumber(1),
+            BlockNumber(1),
+            &get_sample_single_proof_job_data(10),
+            SINGLE_PROOF_JOB_PRIORITY,
+            ProverJobType::SingleProof,
+        )
+        .await?;
+
+    // The first failure returns the job to the queue.
+    let job_id = get_idle_job_from_queue(&mut storage).await?.job_id;
+    ProverSchema(&mut storage)
+        .record_prover_is_working(job_id, "prover_1")
+        .await?;
+    let outcome = ProverSchema(&mut storage)
+        .record_prover_job_failure(job_id, "prover_1", "out of memory", MAX_ATTEMPTS)
+        .await?;
+    assert_eq!(outcome, Some(ProverJobFailureOutcome::Requeued));
+    assert_eq!(ProverSchema(&mut storage).pending_jobs_count().await?, 1);
+
+    // Failure on the last attempt moves the job to the `Failed` status.
+    let job = get_idle_job_from_queue(&mut storage).await?;
+    assert_eq!(job.job_id, job_id);
+    ProverSchema(&mut storage)
+        .record_prover_is_working(job_id, "prover_2")
+        .await?;
+    let outcome = ProverSchema(&mut storage)
+        .record_prover_job_failure(job_id, "prover_2", "invalid witness", MAX_ATTEMPTS)
+        .await?;
+    let failed_job = match outcome {
+        Some(ProverJobFailureOutcome::Failed(failed_job)) => failed_job,
+        outcome => panic!("Job must be failed, got {:?}", outcome),
+    };
+    assert_eq!(failed_job.job_id, job_id);
+    assert_eq!(failed_job.attempts, 2);
+    assert_eq!(failed_job.last_error.as_deref(), Some("invalid witness"));
+
+    // Failed job is not given to provers and is not considered pending.
+    assert!(ProverSchema(&mut storage)
+        .get_idle_prover_job_from_job_queue()
+        .await?
+        .is_none());
+    assert_eq!(ProverSchema(&mut storage).pending_jobs_count().await?, 0);
+    assert_eq!(
+        ProverSchema(&mut storage).load_failed_prover_jobs().await?,
+        vec![failed_job]
+    );
+
+    // Requeued job is given to provers again.
+    assert!(
+        ProverSchema(&mut storage)
+            .requeue_failed_prover_job(job_id)
+            .await?
+    );
+    assert!(
+        !ProverSchema(&mut storage)
+            .requeue_failed_prover_job(job_id)
+            .await?
+    );
+    assert!(ProverSchema(&mut storage)
+        .load_failed_prover_jobs()
+        .await?
+        .is_empty());
+    assert_eq!(get_idle_job_from_queue(&mut storage).await?.job_id, job_id);
+
+    Ok(())
+}
+
+/// Checks that the failure is recorded only if it's reported by the prover holding the job.
+#[db_test]
+async fn test_prover_job_failure_ownership(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Lock to prevent database deadlock
+    let _lock = MUTEX.lock().await;
+    const MAX_ATTEMPTS: u32 = 2;
+
+    ProverSchema(&mut storage)
+        .add_prover_job_to_job_queue(
+            BlockNumber(1),
+            BlockNumber(1),
+            &get_sample_single_proof_job_data(10),
+            SINGLE_PROOF_JOB_PRIORITY,
+            ProverJobType::SingleProof,
+        )
+        .await?;
+
+    let job_id = get_idle_job_from_queue(&mut storage).await?.job_id;
+    ProverSchema(&mut storage)
+        .record_prover_is_working(job_id, "prover_1")
+        .await?;
+
+    // Another prover can't fail the job.
+    assert_eq!(
+        ProverSchema(&mut storage)
+            .record_prover_job_failure(job_id, "prover_2", "out of memory", MAX_ATTEMPTS)
+            .await?,
+        None
+    );
+    assert!(ProverSchema(&mut storage)
+        .get_idle_prover_job_from_job_queue()
+        .await?
+        .is_none());
+
+    // The prover holding the job can.
+    assert_eq!(
+        ProverSchema(&mut storage)
+            .record_prover_job_failure(job_id, "prover_1", "out of memory", MAX_ATTEMPTS)
+            .await?,
+        Some(ProverJobFailureOutcome::Requeued)
+    );
+
+    // The job returned to the queue is not in progress anymore.
+    assert_eq!(
+        ProverSchema(&mut storage)
+            .record_prover_job_failure(job_id, "prover_1", "out of memory", MAX_ATTEMPTS)
+            .await?,
+        None
+    );
+    assert_eq!(get_idle_job_from_queue(&mut storage).await?.job_id, job_id);
+
+    Ok(())
+}
+
+/// Checks that the job statistics used by the prover scaler are grouped by the job type and size.
+#[db_test]
+async fn test_prover_job_stats(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Lock to prevent database deadlock
+    let _lock = MUTEX.lock().await;
+
+    for block in 1..=2 {
+        ProverSchema(&mut storage)
+            .add_prover_job_to_job_queue(
+                BlockNumber(block),
+                BlockNumber(block),
+                &get_sample_single_proof_job_data(10),
+                SINGLE_PROOF_JOB_PRIORITY,
+                ProverJobType::SingleProof,
+            )
+            .await?;
+    }
+    ProverSchema(&mut storage)
+        .add_prover_job_to_job_queue(
+            BlockNumber(1),
+            BlockNumber(2),
+            &get_sample_aggregated_proof_job_data(),
+            AGGREGATED_PROOF_JOB_PRIORITY,
+            ProverJobType::AggregatedProof,
+        )
+        .await?;
+
+    // Prove the single block, the aggregated proof job goes first, so it stays in progress.
+    get_idle_job_from_queue(&mut storage).await?;
+    let job = get_idle_job_from_queue(&mut storage).await?;
+    ProverSchema(&mut storage)
+        .store_proof(job.job_id, job.first_block, &get_sample_single_proof())
+        .await?;
+
+    let since = chrono::Utc::now() - chrono::Duration::hours(1);
+    let mut stats = ProverSchema(&mut storage)
+        .load_prover_job_stats(since)
+        .await?;
+    assert_eq!(stats.len(), 2);
+    assert!(stats[1].avg_proof_duration.unwrap() < Duration::from_secs(60));
+    stats[1].avg_proof_duration = None;
+    assert_eq!(
+        stats,
+        vec![
+            ProverJobStats {
+                job_type: ProverJobType::AggregatedProof,
+                job_size: Some(2),
+                pending_jobs: 1,
+                created_jobs: 1,
+                proven_jobs: 0,
+                avg_proof_duration: None,
+            },
+            ProverJobStats {
+                job_type: ProverJobType::SingleProof,
+                job_size: Some(10),
+                pending_jobs: 1,
+                created_jobs: 2,
+                proven_jobs: 1,
+                avg_proof_duration: None,
+            },
+        ]
+    );
+
+    // Jobs created and proven before the observed period are not taken into account.
+    let stats = ProverSchema(&mut storage)
+        .load_prover_job_stats(chrono::Utc::now() + chrono::Duration::hours(1))
+        .await?;
+    assert_eq!(
+        stats
+            .iter()
+            .map(|stats| (stats.pending_jobs, stats.created_jobs, stats.proven_jobs))
+            .collect::<Vec<_>>(),
+        vec![(1, 0, 0), (1, 0, 0)]
+    );
+    Ok(())
+}
+
+/// Checks that the jobs in progress and the recently completed jobs are loaded along with the provers.
+#[db_test]
+async fn test_prover_activity(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Lock to prevent database deadlock
+    let _lock = MUTEX.lock().await;
+
+    for block in 1..=3 {
+        ProverSchema(&mut storage)
+            .add_prover_job_to_job_queue(
+                BlockNumber(block),
+                BlockNumber(block),
+                &get_sample_single_proof_job_data(10),
+                SINGLE_PROOF_JOB_PRIORITY,
+                ProverJobType::SingleProof,
+            )
+            .await?;
+    }
+    let since = chrono::Utc::now() - chrono::Duration::hours(1);
+
+    // Idle jobs are not loaded.
+    assert!(ProverSchema(&mut storage)
+        .load_prover_activity(since)
+        .await?
+        .is_empty());
+
+    let first_job = get_idle_job_from_queue(&mut storage).await?;
+    ProverSchema(&mut storage)
+        .record_prover_is_working(first_job.job_id, "prover_1")
+        .await?;
+    ProverSchema(&mut storage)
+        .store_proof(
+            first_job.job_id,
+            first_job.first_block,
+            &get_sample_single_proof(),
+        )
+        .await?;
+    let second_job = get_idle_job_from_queue(&mut storage).await?;
+    ProverSchema(&mut storage)
+        .record_prover_is_working(second_job.job_id, "prover_2")
+        .await?;
+
+    let jobs = ProverSchema(&mut storage)
+        .load_prover_activity(since)
+        .await?;
+    assert_eq!(jobs.len(), 2);
+    assert_eq!(jobs[0].job_id, first_job.job_id);
+    assert_eq!(jobs[0].status, ProverJobStatus::Done);
+    assert_eq!(jobs[0].prover_name.as_deref(), Some("prover_1"));
+    assert_eq!(jobs[0].job_size, Some(10));
+    assert!(jobs[0].started_at.is_some());
+    assert_eq!(jobs[1].job_id, second_job.job_id);
+    assert_eq!(jobs[1].status, ProverJobStatus::InProgress);
+    assert_eq!(jobs[1].prover_name.as_deref(), Some("prover_2"));
+
+    // Jobs completed before the given time are skipped, jobs in progress are always loaded.
+    let jobs = ProverSchema(&mut storage)
+        .load_prover_activity(chrono::Utc::now() + chrono::Duration::hours(1))
+        .await?;
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].job_id, second_job.job_id);
+    Ok(())
+}
@@ -4,10 +4,12 @@ use std::str::FromStr;
 use chrono::Utc;
 use num::{rational::Ratio, BigUint};
 // Workspace imports
+use zksync_api_types::v02::pagination::{PaginationDirection, PaginationQuery};
 use zksync_test_account::ZkSyncAccount;
 use zksync_types::{
-    tokens::TokenMarketVolume, AccountId, Address, BlockNumber, ExecutedOperations, ExecutedTx,
-    Token, TokenId, TokenKind, TokenLike, TokenPrice, WithdrawNFTOp, ZkSyncOp, H256,
+    tokens::{TokenMarketVolume, TokenOverride},
+    AccountId, Address, BlockNumber, ExecutedOperations, ExecutedTx, Token, TokenId, TokenKind,
+    TokenLike, TokenPrice, WithdrawNFTOp, ZkSyncOp, H256,
 };
 use zksync_utils::{big_decimal_to_ratio, ratio_to_big_decimal};
 // Local imports
@@ -255,6 +257,176 @@ async fn test_market_volume(mut storage: StorageProcessor<'_>) -> QueryResult<()
     Ok(())
 }
 
+/// Checks that the operator overrides are applied to the loaded tokens.
+#[db_test]
+async fn test_token_overrides(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let token = Token {
+        id: TokenId(1),
+        address: Address::random(),
+        symbol: "SCAM".into(),
+        decimals: 6,
+        kind: TokenKind::ERC20,
+        is_nft: false,
+    };
+    let min_market_volume = Ratio::new(BigUint::from(2u32), BigUint::from(5u32));
+    storage
+        .tokens_schema()
+        .store_or_update_token(token.clone())
+        .await?;
+    assert!(storage
+        .tokens_schema()
+        .get_token_override(token.id)
+        .await?
+        .is_none());
+
+    // Correct the symbol and decimals, hide the token and allow it for fees.
+    let token_override = TokenOverride {
+        token_id: token.id,
+        symbol: Some("OK".into()),
+        decimals: Some(18),
+        hidden: true,
+        fee_token: Some(true),
+        logo_uri: Some("https://example.com/ok.png".into()),
+        metadata_uri: None,
+    };
+    storage
+        .tokens_schema()
+        .store_token_override(&token_override)
+        .await?;
+    assert_eq!(
+        storage.tokens_schema().get_token_override(token.id).await?,
+        Some(token_override.clone())
+    );
+    assert_eq!(
+        storage.tokens_schema().load_token_overrides().await?.len(),
+        1
+    );
+
+    let loaded = storage
+        .tokens_schema()
+        .get_token(TokenLike::Symbol("ok".into()))
+        .await?
+        .expect("token by overridden symbol not found");
+    assert_eq!(loaded.id, token.id);
+    assert_eq!(loaded.symbol, "OK");
+    assert_eq!(loaded.decimals, 18);
+    assert!(storage
+        .tokens_schema()
+        .get_token(TokenLike::Symbol(token.symbol.clone()))
+        .await?
+        .is_none());
+
+    // The hidden token is still listed, so that clients can resolve it.
+    assert!(storage
+        .tokens_schema()
+        .load_tokens()
+        .await?
+        .contains_key(&token.id));
+    assert!(storage
+        .tokens_schema()
+        .load_token_page(&PaginationQuery {
+            from: TokenId(0),
+            limit: 10,
+            direction: PaginationDirection::Newer,
+        })
+        .await?
+        .iter()
+        .any(|listed| listed.id == token.id));
+    assert_eq!(storage.tokens_schema().get_count().await?, 2);
+
+    // The token can't be used to pay fees until it has a price, even if it's allowed by the operator.
+    assert!(!storage
+        .tokens_schema()
+        .load_tokens_by_market_volume(min_market_volume.clone())
+        .await?
+        .contains_key(&token.id));
+    assert!(storage
+        .tokens_schema()
+        .filter_tokens_by_market_volume(vec![token.id], &min_market_volume)
+        .await?
+        .is_empty());
+
+    // Once the price is known, the token is allowed for fees without any market volume.
+    storage
+        .tokens_schema()
+        .update_historical_ticker_price(
+            token.id,
+            TokenPrice {
+                usd_price: Ratio::new(BigUint::from(4u32), BigUint::from(9u32)),
+                last_updated: Utc::now(),
+            },
+        )
+        .await?;
+    assert!(storage
+        .tokens_schema()
+        .load_tokens_by_market_volume(min_market_volume.clone())
+        .await?
+        .contains_key(&token.id));
+    assert!(storage
+        .tokens_schema()
+        .filter_tokens_by_market_volume(vec![token.id], &min_market_volume)
+        .await?
+        .contains(&token.id));
+
+    // Deny the token for fees even though its market volume is enough.
+    storage
+        .tokens_schema()
+        .update_token_market_volume(
+            token.id,
+            TokenMarketVolume {
+                market_volume: min_market_volume.clone(),
+                last_updated: Utc::now(),
+            },
+        )
+        .await?;
+    storage
+        .tokens_schema()
+        .store_token_override(&TokenOverride {
+            fee_token: Some(false),
+            ..token_override
+        })
+        .await?;
+    assert!(!storage
+        .tokens_schema()
+        .load_tokens_by_market_volume(min_market_volume.clone())
+        .await?
+        .contains_key(&token.id));
+    assert!(storage
+        .tokens_schema()
+        .filter_tokens_by_market_volume(vec![token.id], &min_market_volume)
+        .await?
+        .is_empty());
+
+    // Once the override is removed, the original token is back.
+    assert!(
+        storage
+            .tokens_schema()
+            .remove_token_override(token.id)
+            .await?
+    );
+    assert!(
+        !storage
+            .tokens_schema()
+            .remove_token_override(token.id)
+            .await?
+    );
+    assert_eq!(
+        storage
+            .tokens_schema()
+            .get_token(TokenLike::Id(token.id))
+            .await?,
+        Some(token.clone())
+    );
+    assert_eq!(storage.tokens_schema().get_count().await?, 2);
+    assert!(storage
+        .tokens_schema()
+        .load_tokens_by_market_volume(min_market_volume)
+        .await?
+        .contains_key(&token.id));
+
+    Ok(())
+}
+
 /// Checks the store/load factories for nft
 #[db_test]
 async fn test_nfts_with_factories(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
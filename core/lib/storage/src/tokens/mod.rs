@@ -10,10 +10,14 @@ use zksync_api_types::v02::{
     pagination::{PaginationDirection, PaginationQuery},
     token::ApiNFT,
 };
-use zksync_types::{AccountId, Address, Token, TokenId, TokenLike, TokenPrice, NFT};
+use zksync_types::{
+    tokens::TokenOverride, AccountId, Address, Token, TokenId, TokenLike, TokenPrice, NFT,
+};
 use zksync_utils::ratio_to_big_decimal;
 // Local imports
-use self::records::{DBMarketVolume, DbTickerPrice, DbToken, StorageApiNFT, StorageNFT, TokenKind};
+use self::records::{
+    DBMarketVolume, DbTickerPrice, DbToken, DbTokenOverride, StorageApiNFT, StorageNFT, TokenKind,
+};
 
 use crate::utils::address_to_stored_string;
 use crate::{QueryResult, StorageProcessor};
@@ -26,6 +30,9 @@ pub(crate) const STORED_USD_PRICE_PRECISION: usize = 6;
 
 /// Tokens schema handles the `tokens` table, providing methods to
 /// get and store new tokens.
+///
+/// Tokens are loaded from the `tokens_with_overrides` view, so their symbol and decimals
+/// are taken from the `token_overrides` table if the operator has corrected them.
 #[derive(Debug)]
 pub struct TokensSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
 
@@ -136,7 +143,9 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         let tokens = sqlx::query_as!(
             DbToken,
             r#"
-            SELECT id, address, decimals, kind as "kind: _", symbol FROM tokens
+            SELECT id as "id!", address as "address!", decimals as "decimals!",
+                kind as "kind!: _", symbol as "symbol!"
+            FROM tokens_with_overrides
             WHERE id >= $1 AND kind = 'ERC20'::token_kind
             ORDER BY id ASC
             LIMIT $2
@@ -163,7 +172,9 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         let tokens = sqlx::query_as!(
             DbToken,
             r#"
-            SELECT id, address, decimals, kind as "kind: _", symbol FROM tokens
+            SELECT id as "id!", address as "address!", decimals as "decimals!",
+                kind as "kind!: _", symbol as "symbol!"
+            FROM tokens_with_overrides
             WHERE id <= $1 AND kind = 'ERC20'::token_kind
             ORDER BY id DESC
             LIMIT $2
@@ -182,12 +193,14 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
     /// Loads all the stored tokens from the database.
     /// Alongside with the tokens added via `store_token` method, the default `ETH` token
     /// is returned.
+    ///
+    /// Tokens hidden by the operator are returned as well, since clients still have to resolve them.
     pub async fn load_tokens(&mut self) -> QueryResult<HashMap<TokenId, Token>> {
         let tokens = self.load_tokens_asc(TokenId(0), None).await?;
         Ok(tokens.into_iter().map(|token| (token.id, token)).collect())
     }
 
-    /// Loads tokens for the given pagination query
+    /// Loads tokens for the given pagination query, including the ones hidden by the operator.
     pub async fn load_token_page(
         &mut self,
         query: &PaginationQuery<TokenId>,
@@ -233,6 +246,8 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
     /// Loads all the stored tokens, which have market_volume (ticker_market_volume table)
     /// not less than parameter (min_market_volume)
     /// And have not null price in ticker_price
+    /// The `fee_token` override of the operator takes precedence over the market volume,
+    /// but the token must have a price anyway.
     pub async fn load_tokens_by_market_volume(
         &mut self,
         min_market_volume: Ratio<BigUint>,
@@ -241,15 +256,19 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         let tokens = sqlx::query_as!(
             DbToken,
             r#"
-            SELECT id, address, decimals, kind as "kind: _", symbol
-            FROM tokens
-            INNER JOIN ticker_market_volume
-            ON tokens.id = ticker_market_volume.token_id
-            INNER JOIN ticker_price 
-            ON tokens.id = ticker_price.token_id
-            WHERE ticker_market_volume.market_volume >= $1
-            AND ticker_price.usd_price > 0
+            SELECT id as "id!", address as "address!", decimals as "decimals!",
+                kind as "kind!: _", symbol as "symbol!"
+            FROM tokens_with_overrides
+            INNER JOIN ticker_price
+            ON tokens_with_overrides.id = ticker_price.token_id
+            LEFT JOIN ticker_market_volume
+            ON tokens_with_overrides.id = ticker_market_volume.token_id
+            WHERE ticker_price.usd_price > 0
             AND kind = 'ERC20'::token_kind
+            AND (
+                fee_token
+                OR (fee_token IS NULL AND ticker_market_volume.market_volume >= $1)
+            )
             ORDER BY id ASC
             "#,
             ratio_to_big_decimal(&min_market_volume, STORED_USD_PRICE_PRECISION)
@@ -270,6 +289,8 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
     }
 
     /// Filters out tokens whose market volume is less than the specified limit (min_market_volume).
+    /// The `fee_token` override of the operator takes precedence over the market volume,
+    /// but the token must have a price anyway.
     pub async fn filter_tokens_by_market_volume(
         &mut self,
         tokens_to_check: Vec<TokenId>,
@@ -279,12 +300,18 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         let tokens_to_check: Vec<i32> = tokens_to_check.into_iter().map(|id| *id as i32).collect();
         let tokens = sqlx::query!(
             r#"
-            SELECT ticker_market_volume.token_id
-            FROM ticker_market_volume
-            INNER JOIN ticker_price 
-            ON ticker_market_volume.token_id = ticker_price.token_id
-            WHERE ticker_market_volume.token_id = ANY($1) AND market_volume >= $2
+            SELECT id as "token_id!"
+            FROM tokens_with_overrides
+            INNER JOIN ticker_price
+            ON tokens_with_overrides.id = ticker_price.token_id
+            LEFT JOIN ticker_market_volume
+            ON tokens_with_overrides.id = ticker_market_volume.token_id
+            WHERE id = ANY($1)
             AND ticker_price.usd_price > 0
+            AND (
+                fee_token
+                OR (fee_token IS NULL AND ticker_market_volume.market_volume >= $2)
+            )
             "#,
             &tokens_to_check,
             ratio_to_big_decimal(min_market_volume, STORED_USD_PRICE_PRECISION)
@@ -314,7 +341,8 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         let start = Instant::now();
         let count = sqlx::query!(
             r#"
-            SELECT COUNT(*) as "count!" FROM tokens WHERE kind = 'ERC20'::token_kind
+            SELECT COUNT(*) as "count!" FROM tokens_with_overrides
+            WHERE kind = 'ERC20'::token_kind
             "#,
         )
         .fetch_one(self.0.conn())
@@ -417,7 +445,9 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
                 sqlx::query_as!(
                     DbToken,
                     r#"
-                    SELECT id, address, decimals, kind as "kind: _", symbol FROM tokens
+                    SELECT id as "id!", address as "address!", decimals as "decimals!",
+                        kind as "kind!: _", symbol as "symbol!"
+                    FROM tokens_with_overrides
                     WHERE id = $1
                     LIMIT 1
                     "#,
@@ -430,7 +460,9 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
                 sqlx::query_as!(
                     DbToken,
                     r#"
-                    SELECT id, address, decimals, kind as "kind: _", symbol FROM tokens
+                    SELECT id as "id!", address as "address!", decimals as "decimals!",
+                        kind as "kind!: _", symbol as "symbol!"
+                    FROM tokens_with_overrides
                     WHERE address = $1
                     LIMIT 1
                     "#,
@@ -449,7 +481,9 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
                 sqlx::query_as!(
                     DbToken,
                     r#"
-                    SELECT id, address, decimals, kind as "kind: _", symbol FROM tokens
+                    SELECT id as "id!", address as "address!", decimals as "decimals!",
+                        kind as "kind!: _", symbol as "symbol!"
+                    FROM tokens_with_overrides
                     WHERE lower(symbol) = lower($1)
                     LIMIT 1
                     "#,
@@ -592,4 +626,93 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         metrics::histogram!("sql.token.store_nft_factory", start.elapsed());
         Ok(())
     }
+
+    /// Loads all the token overrides set by the operator.
+    pub async fn load_token_overrides(&mut self) -> QueryResult<HashMap<TokenId, TokenOverride>> {
+        let start = Instant::now();
+        let overrides = sqlx::query_as!(
+            DbTokenOverride,
+            r#"
+            SELECT token_id, symbol, decimals, hidden, fee_token, logo_uri, metadata_uri
+            FROM token_overrides
+            ORDER BY token_id ASC
+            "#,
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.token.load_token_overrides", start.elapsed());
+        Ok(overrides
+            .into_iter()
+            .map(|token_override| {
+                let token_override = TokenOverride::from(token_override);
+                (token_override.token_id, token_override)
+            })
+            .collect())
+    }
+
+    /// Given token id, returns the override set by the operator, if any.
+    pub async fn get_token_override(
+        &mut self,
+        token_id: TokenId,
+    ) -> QueryResult<Option<TokenOverride>> {
+        let start = Instant::now();
+        let token_override = sqlx::query_as!(
+            DbTokenOverride,
+            r#"
+            SELECT token_id, symbol, decimals, hidden, fee_token, logo_uri, metadata_uri
+            FROM token_overrides
+            WHERE token_id = $1
+            "#,
+            *token_id as i32
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.token.get_token_override", start.elapsed());
+        Ok(token_override.map(|o| o.into()))
+    }
+
+    /// Stores the override for the token, replacing the previous one.
+    pub async fn store_token_override(
+        &mut self,
+        token_override: &TokenOverride,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO token_overrides ( token_id, symbol, decimals, hidden, fee_token, logo_uri, metadata_uri )
+            VALUES ( $1, $2, $3, $4, $5, $6, $7 )
+            ON CONFLICT ( token_id )
+            DO UPDATE
+            SET symbol = $2, decimals = $3, hidden = $4, fee_token = $5, logo_uri = $6, metadata_uri = $7, updated_at = now()
+            "#,
+            *token_override.token_id as i32,
+            token_override.symbol,
+            token_override.decimals.map(i16::from),
+            token_override.hidden,
+            token_override.fee_token,
+            token_override.logo_uri,
+            token_override.metadata_uri,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.token.store_token_override", start.elapsed());
+        Ok(())
+    }
+
+    /// Removes the override for the token. Returns `false` if there was none.
+    pub async fn remove_token_override(&mut self, token_id: TokenId) -> QueryResult<bool> {
+        let start = Instant::now();
+        let result = sqlx::query!(
+            "DELETE FROM token_overrides WHERE token_id = $1",
+            *token_id as i32
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.token.remove_token_override", start.elapsed());
+        Ok(result.rows_affected() > 0)
+    }
 }
@@ -9,7 +9,7 @@ use crate::utils::{address_to_stored_string, stored_str_address_to_address};
 use chrono::{DateTime, Utc};
 use zksync_api_types::v02::token::ApiNFT;
 use zksync_types::{
-    tokens::{TokenMarketVolume, TokenPrice},
+    tokens::{TokenMarketVolume, TokenOverride, TokenPrice},
     AccountId, Address, Token, TokenId, H256, NFT,
 };
 use zksync_utils::big_decimal_to_ratio;
@@ -168,3 +168,28 @@ impl From<DBMarketVolume> for TokenMarketVolume {
         }
     }
 }
+
+#[derive(Debug, Clone, FromRow)]
+pub struct DbTokenOverride {
+    pub token_id: i32,
+    pub symbol: Option<String>,
+    pub decimals: Option<i16>,
+    pub hidden: bool,
+    pub fee_token: Option<bool>,
+    pub logo_uri: Option<String>,
+    pub metadata_uri: Option<String>,
+}
+
+impl From<DbTokenOverride> for TokenOverride {
+    fn from(val: DbTokenOverride) -> Self {
+        Self {
+            token_id: TokenId(val.token_id as u32),
+            symbol: val.symbol,
+            decimals: val.decimals.map(|decimals| decimals as u8),
+            hidden: val.hidden,
+            fee_token: val.fee_token,
+            logo_uri: val.logo_uri,
+            metadata_uri: val.metadata_uri,
+        }
+    }
+}
@@ -4,7 +4,7 @@ use std::{collections::HashMap, sync::Arc};
 use tokio::sync::RwLock;
 
 use zksync_storage::StorageProcessor;
-use zksync_types::tokens::TokenMarketVolume;
+use zksync_types::tokens::{TokenMarketVolume, TokenPrice};
 use zksync_types::{Token, TokenId, TokenLike, NFT};
 
 #[derive(Debug, Clone, Default)]
@@ -141,6 +141,26 @@ impl TokenDBCache {
         Ok(volume)
     }
 
+    /// Returns the fee token allow/deny override set by the operator, if any.
+    pub async fn get_fee_token_override(
+        storage: &mut StorageProcessor<'_>,
+        token: TokenId,
+    ) -> anyhow::Result<Option<bool>> {
+        let token_override = storage.tokens_schema().get_token_override(token).await?;
+        Ok(token_override.and_then(|token_override| token_override.fee_token))
+    }
+
+    pub async fn get_token_price(
+        storage: &mut StorageProcessor<'_>,
+        token: TokenId,
+    ) -> anyhow::Result<Option<TokenPrice>> {
+        let price = storage
+            .tokens_schema()
+            .get_historical_ticker_price(token)
+            .await?;
+        Ok(price)
+    }
+
     pub async fn update_token_market_volume(
         storage: &mut StorageProcessor<'_>,
         token: TokenId,
@@ -446,7 +446,8 @@ impl Block {
         res
     }
 
-    fn get_commitment(
+    /// Calculates the block commitment the same way the zkSync contract does it on the block commit.
+    pub fn get_commitment(
         block_number: BlockNumber,
         fee_account: AccountId,
         old_state_hash: H256,
@@ -1,11 +1,16 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
 use thiserror::Error;
 use zksync_basic_types::BlockNumber;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ProverJobStatus {
     Idle = 0,
     InProgress = 1,
     Done = 2,
+    /// The job has exhausted its attempts and won't be given to provers until requeued.
+    Failed = 3,
 }
 
 impl ProverJobStatus {
@@ -14,6 +19,7 @@ impl ProverJobStatus {
             ProverJobStatus::Idle => 0,
             ProverJobStatus::InProgress => 1,
             ProverJobStatus::Done => 2,
+            ProverJobStatus::Failed => 3,
         }
     }
 
@@ -22,6 +28,7 @@ impl ProverJobStatus {
             0 => Self::Idle,
             1 => Self::InProgress,
             2 => Self::Done,
+            3 => Self::Failed,
             _ => return Err(IncorrectProverJobStatus(num)),
         })
     }
@@ -54,7 +61,29 @@ impl ProverJob {
     }
 }
 
-#[derive(Debug, Clone)]
+/// Prover job that moved to the `Failed` status.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailedProverJob {
+    pub job_id: i32,
+    pub job_type: String,
+    pub first_block: BlockNumber,
+    pub last_block: BlockNumber,
+    /// How many times the job was given to provers.
+    pub attempts: i32,
+    /// Failure reported by the prover on the last attempt, or the timeout reason.
+    pub last_error: Option<String>,
+}
+
+/// Result of recording the failure reported by the prover.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProverJobFailureOutcome {
+    /// The job is returned to the queue to be retried.
+    Requeued,
+    /// The job was given to provers the maximum amount of times and moved to the `Failed` status.
+    Failed(FailedProverJob),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ProverJobType {
     SingleProof,
     AggregatedProof,
@@ -69,6 +98,80 @@ impl ToString for ProverJobType {
     }
 }
 
+/// Statistics of the prover jobs of the same type and size.
+///
+/// Used by the prover scaler to estimate the amount of provers required to keep up with the load.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProverJobStats {
+    pub job_type: ProverJobType,
+    /// Chunks size of the block for the single proof jobs and amount of the aggregated blocks
+    /// for the aggregated proof jobs. `None` if the job data doesn't contain the block size.
+    pub job_size: Option<u64>,
+    /// Jobs that are either waiting for a prover or being proven right now.
+    pub pending_jobs: u32,
+    /// Jobs created during the observed period.
+    pub created_jobs: u32,
+    /// Jobs started and completed during the observed period.
+    pub proven_jobs: u32,
+    /// Average time between giving the job to the prover and receiving the proof
+    /// for the jobs completed during the observed period.
+    pub avg_proof_duration: Option<Duration>,
+}
+
+/// State of the prover job, used to monitor the provers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProverJobInfo {
+    pub job_id: i32,
+    pub job_type: ProverJobType,
+    /// Same as `ProverJobStats::job_size`.
+    pub job_size: Option<u64>,
+    pub first_block: BlockNumber,
+    pub last_block: BlockNumber,
+    pub status: ProverJobStatus,
+    /// Prover that worked on the job last time, `None` if no prover reported about it yet.
+    pub prover_name: Option<String>,
+    pub attempts: i32,
+    pub created_at: DateTime<Utc>,
+    /// Time when the job was given to the prover last time.
+    pub started_at: Option<DateTime<Utc>>,
+    /// Time of the last heartbeat for the jobs in progress and the completion time for the finished ones.
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Capabilities declared by the prover, used to choose the job it's able to handle.
+#[derive(Debug, Clone, Default)]
+pub struct ProverCapabilities {
+    /// Type of the job the prover prefers, jobs of other types are given
+    /// only if there are no jobs of the preferred type.
+    pub preferred_job_type: Option<ProverJobType>,
+    /// The largest block chunks size the prover is able to prove.
+    /// Single proof jobs for the larger blocks are given to the prover
+    /// only if they weren't taken by anyone else for too long.
+    pub max_block_size: Option<usize>,
+}
+
+impl ProverCapabilities {
+    /// Checks whether the prover is able to handle the job without a fallback.
+    ///
+    /// `block_size` is the chunks size of the block for single proof jobs.
+    pub fn can_handle(&self, job_type: &ProverJobType, block_size: Option<usize>) -> bool {
+        match (job_type, self.max_block_size, block_size) {
+            (ProverJobType::SingleProof, Some(max_block_size), Some(block_size)) => {
+                block_size <= max_block_size
+            }
+            _ => true,
+        }
+    }
+
+    /// Checks whether the job has the type preferred by the prover.
+    pub fn prefers(&self, job_type: &ProverJobType) -> bool {
+        self.preferred_job_type
+            .as_ref()
+            .map(|preferred| preferred.to_string() == job_type.to_string())
+            .unwrap_or(false)
+    }
+}
+
 #[derive(Debug, Error, PartialEq)]
 #[error("Incorrect ProverJobStatus number: {0}")]
 pub struct IncorrectProverJobStatus(pub i32);
@@ -160,6 +160,18 @@ impl Token {
     }
 }
 
+/// Token as it is listed by the API.
+///
+/// Tokens hidden by the operator are still listed, so that clients can resolve them
+/// (e.g. to withdraw the funds), but they are not supposed to be displayed to the users.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ListedToken {
+    #[serde(flatten)]
+    pub token: Token,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub hidden: bool,
+}
+
 /// ERC-20 standard token.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenInfo {
@@ -241,6 +253,25 @@ pub struct TokenMarketVolume {
     pub last_updated: DateTime<Utc>,
 }
 
+/// Operator-managed correction of the token metadata registered from the contract events.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenOverride {
+    pub token_id: TokenId,
+    /// Corrected token symbol.
+    pub symbol: Option<String>,
+    /// Corrected token precision.
+    pub decimals: Option<u8>,
+    /// Hidden tokens are still listed by the API, but marked with the `hidden` flag,
+    /// so that clients can resolve them without displaying them to the users.
+    pub hidden: bool,
+    /// Overrides the fee token validator: `Some(true)` allows paying fees with the token
+    /// regardless of its market volume, `Some(false)` forbids it.
+    pub fee_token: Option<bool>,
+    pub logo_uri: Option<String>,
+    pub metadata_uri: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Hash, Eq)]
 #[serde(untagged)]
 pub enum ChangePubKeyFeeTypeArg {
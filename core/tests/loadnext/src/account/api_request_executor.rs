@@ -0,0 +1,174 @@
+use std::time::Instant;
+
+use rand::Rng;
+
+use zksync::{error::ClientError, provider::Provider, RpcProvider};
+use zksync_api_client::rest::client::{Client as RestClient, ClientError as RestClientError};
+use zksync_api_types::v02::{
+    pagination::{ApiEither, PaginationDirection, PaginationQuery, MAX_LIMIT},
+    Response, ResultStatus,
+};
+use zksync_types::{tx::TxHash, BlockNumber, TokenId};
+
+use crate::{
+    account::AccountLifespan,
+    command::{ApiRequestCommand, ApiType},
+    recorder::RecordedRequest,
+    report::ReportLabel,
+};
+
+impl AccountLifespan {
+    pub(super) async fn execute_api_request(
+        &mut self,
+        command: &ApiRequestCommand,
+    ) -> Result<ReportLabel, ClientError> {
+        let command = match command {
+            ApiRequestCommand::TxStatus { api, tx_hash: None } => {
+                // Transactions that are not known to the server are still looked up,
+                // so the status is requested even if the account has not sent anything yet.
+                let tx_hash = match self.last_tx_hash {
+                    Some(tx_hash) => tx_hash,
+                    None => TxHash::from_slice(&self.rng.gen::<[u8; 32]>()).unwrap(),
+                };
+                ApiRequestCommand::TxStatus {
+                    api: *api,
+                    tx_hash: Some(tx_hash),
+                }
+            }
+            command => command.clone(),
+        };
+
+        let sent_at = Instant::now();
+        let result = send_api_request(
+            &self.wallet.provider,
+            &self.rest_client,
+            &self.config.main_token,
+            &command,
+        )
+        .await;
+
+        if let Some(recorder) = &self.recorder {
+            let error = match &result {
+                Ok(ReportLabel::ActionFailed { error }) => Some(error.clone()),
+                Ok(_) => None,
+                Err(err) => Some(err.to_string()),
+            };
+            let request = RecordedRequest::Api { request: command };
+            recorder.record(self.wallet.address(), sent_at, request, error);
+        }
+        result
+    }
+}
+
+/// Sends the API request to the server and checks the response.
+/// Transaction status requests without the transaction hash are sent for the default hash.
+pub(crate) async fn send_api_request(
+    provider: &RpcProvider,
+    rest_client: &RestClient,
+    main_token: &str,
+    command: &ApiRequestCommand,
+) -> Result<ReportLabel, ClientError> {
+    match command {
+        ApiRequestCommand::AccountInfo { api, address } => match api {
+            ApiType::JsonRpc => {
+                provider.account_info(*address).await?;
+                Ok(ReportLabel::done())
+            }
+            ApiType::RestV02 => {
+                let response = rest_client
+                    .account_info(&format!("{:?}", address), "committed")
+                    .await
+                    .map_err(rest_error)?;
+                Ok(rest_response_label(response, false))
+            }
+        },
+        ApiRequestCommand::TxStatus { api, tx_hash } => {
+            let tx_hash = tx_hash.unwrap_or_default();
+            match api {
+                ApiType::JsonRpc => {
+                    provider.tx_info(tx_hash).await?;
+                    Ok(ReportLabel::done())
+                }
+                ApiType::RestV02 => {
+                    let response = rest_client.tx_status(tx_hash).await.map_err(rest_error)?;
+                    Ok(rest_response_label(response, true))
+                }
+            }
+        }
+        ApiRequestCommand::BlocksPagination { limit } => {
+            let query = PaginationQuery {
+                from: "latest".parse::<ApiEither<BlockNumber>>().unwrap(),
+                limit: *limit,
+                direction: PaginationDirection::Older,
+            };
+            let response = rest_client
+                .block_pagination(&query)
+                .await
+                .map_err(rest_error)?;
+            Ok(rest_response_label(response, false))
+        }
+        ApiRequestCommand::Tokens { api } => match api {
+            ApiType::JsonRpc => {
+                provider.tokens().await?;
+                Ok(ReportLabel::done())
+            }
+            ApiType::RestV02 => {
+                let query = PaginationQuery {
+                    from: ApiEither::from(TokenId(0)),
+                    limit: MAX_LIMIT,
+                    direction: PaginationDirection::Newer,
+                };
+                let response = rest_client
+                    .token_pagination(&query)
+                    .await
+                    .map_err(rest_error)?;
+                Ok(rest_response_label(response, false))
+            }
+        },
+        ApiRequestCommand::TxFee {
+            api,
+            tx_type,
+            address,
+        } => match api {
+            ApiType::JsonRpc => {
+                provider
+                    .get_tx_fee(tx_type.clone().into(), *address, main_token)
+                    .await?;
+                Ok(ReportLabel::done())
+            }
+            ApiType::RestV02 => {
+                let response = rest_client
+                    .get_txs_fee(tx_type.clone(), *address, main_token.into())
+                    .await
+                    .map_err(rest_error)?;
+                Ok(rest_response_label(response, false))
+            }
+        },
+        ApiRequestCommand::NftInfo { token_id } => {
+            let response = rest_client.nft_by_id(*token_id).await.map_err(rest_error)?;
+            Ok(rest_response_label(response, true))
+        }
+    }
+}
+
+/// Converts the REST client error, so the transport errors are retried the same way as the JSON RPC ones.
+fn rest_error(err: RestClientError) -> ClientError {
+    match err {
+        RestClientError::Other(err) => ClientError::NetworkError(err.to_string()),
+        err => ClientError::MalformedResponse(err.to_string()),
+    }
+}
+
+/// Checks the status of the REST API response.
+/// Lookups of the entities that may not exist (random transactions or NFTs) are considered
+/// successful even if the server responds with an error.
+fn rest_response_label(response: Response, missing_allowed: bool) -> ReportLabel {
+    match response.status {
+        ResultStatus::Success => ReportLabel::done(),
+        ResultStatus::Error if missing_allowed => ReportLabel::done(),
+        ResultStatus::Error => {
+            let error = format!("API request failed: {:?}", response.error);
+            ReportLabel::failed(&error)
+        }
+    }
+}
@@ -26,6 +26,7 @@ impl AccountLifespan {
                     self.build_withdraw(command, Some(nonce)).await?
                 }
                 TxType::ChangePubKey => self.build_change_pubkey(command, Some(nonce)).await?,
+                TxType::MintNFT => self.build_mint_nft(command, Some(nonce)).await?,
                 _ => unreachable!("Other tx types are not suitable for batches"),
             };
 
@@ -58,10 +59,10 @@ impl AccountLifespan {
                     .unwrap_or(IncorrectnessModifier::None)
             });
 
-        let provider = self.wallet.provider.clone();
+        let provider = self.recording_provider();
         self.submit(modifier, || async {
-            self.wallet.provider.send_txs_batch(batch, None).await?;
-            Ok(SyncTransactionHandle::new(main_hash, provider))
+            provider.send_txs_batch(batch, None).await?;
+            Ok(SyncTransactionHandle::new(main_hash, provider.provider()))
         })
         .await
     }
@@ -6,19 +6,28 @@ use std::{
 use futures::{channel::mpsc::Sender, SinkExt};
 
 use zksync::{error::ClientError, operations::SyncTransactionHandle, RpcProvider, Wallet};
+use zksync_api_client::rest::client::Client as RestClient;
 use zksync_eth_signer::PrivateKeySigner;
-use zksync_types::{Token, H256};
+use zksync_types::{tx::TxHash, Address, Token, H256};
 
 use crate::{
     account_pool::{AddressPool, TestWallet},
     command::{Command, ExpectedOutcome, IncorrectnessModifier, TxCommand},
     config::LoadtestConfig,
     constants::{COMMIT_TIMEOUT, POLLING_INTERVAL},
+    open_loop::CommandSchedule,
+    recorder::{Recorder, RecordingProvider},
     report::{Report, ReportBuilder, ReportLabel},
     rng::LoadtestRng,
 };
 
+use self::swap_executor::SwapPartner;
+
+pub(crate) use self::api_request_executor::send_api_request;
+
+mod api_request_executor;
 mod batch_command_executor;
+mod swap_executor;
 mod tx_command_executor;
 
 /// Account lifespan represents a flow of a single account:
@@ -41,6 +50,16 @@ pub struct AccountLifespan {
     addresses: AddressPool,
     /// ERC-20 token used in the test.
     main_token: Token,
+    /// Client of the REST API, used by the API requests.
+    rest_client: RestClient,
+    /// Hash of the latest transaction sent by the account, used by the API requests.
+    last_tx_hash: Option<TxHash>,
+    /// Accounts created by the transfers of the account, used as the forced exit targets.
+    created_accounts: Vec<Address>,
+    /// Counterparty of the swaps, created on the first swap.
+    swap_partner: Option<SwapPartner>,
+    /// Recording of the sent requests, if the run is recorded.
+    recorder: Option<Recorder>,
     /// Channel for sending reports about performed operations.
     report_sink: Sender<Report>,
 }
@@ -51,6 +70,7 @@ impl AccountLifespan {
         addresses: AddressPool,
         test_account: TestWallet,
         report_sink: Sender<Report>,
+        recorder: Option<Recorder>,
     ) -> Self {
         let main_token = test_account
             .wallet
@@ -65,12 +85,48 @@ impl AccountLifespan {
             config: config.clone(),
             addresses,
             main_token,
+            rest_client: RestClient::new(config.zksync_rest_addr.clone()),
+            last_tx_hash: None,
+            created_accounts: Vec::new(),
+            swap_partner: None,
+            recorder,
 
             report_sink,
         }
     }
 
     pub async fn run(mut self) {
+        if !self.init_account_id().await {
+            return;
+        }
+
+        let command_sequence = self.generate_commands();
+        for command in command_sequence {
+            self.execute_command(command, None).await;
+        }
+    }
+
+    /// Runs the account in the open-loop mode: the account unlocks itself and then executes
+    /// a random command every time the scheduler hands one to it. The scheduler only hands
+    /// the commands to the free accounts, so the command is sent right at the moment it's scheduled at.
+    pub async fn run_open_loop(mut self, mut schedule: CommandSchedule) {
+        if !self.init_account_id().await {
+            return;
+        }
+
+        let change_pubkey = TxCommand::change_pubkey(self.wallet.address());
+        self.execute_command(Command::SingleTx(change_pubkey), None)
+            .await;
+        schedule.mark_ready();
+
+        while let Some(scheduled_at) = schedule.next().await {
+            let command = self.random_command();
+            self.execute_command(command, Some(scheduled_at)).await;
+        }
+    }
+
+    /// Obtains the account ID, returns `false` if it wasn't obtained.
+    async fn init_account_id(&mut self) -> bool {
         // We assume that account is initialized after the transfer to it is executed,
         // thus we can start from obtaining the account ID.
         let retry_attempts = 3;
@@ -88,25 +144,23 @@ impl AccountLifespan {
                             "Unable to set account ID for account {}",
                             self.wallet.address()
                         );
-                        return;
+                        return false;
                     }
                     // We will wait and try again.
                     tokio::time::sleep(Duration::from_secs(1)).await;
                 }
             }
         }
-
-        let command_sequence = self.generate_commands();
-        for command in command_sequence {
-            self.execute_command(command).await;
-        }
+        true
     }
 
     /// Executes a command with support of retries:
     /// If command fails due to the network/API error, it will be retried multiple times
     /// before considering it completely failed. Such an approach makes us a bit more resilient to
     /// volatile errors such as random connection drop or insufficient fee error.
-    async fn execute_command(&mut self, command: Command) {
+    ///
+    /// If the command was scheduled, the reported time is counted from the moment it was scheduled at.
+    async fn execute_command(&mut self, command: Command, scheduled_at: Option<Instant>) {
         // We consider API errors to be somewhat likely, thus we will retry the operation if it fails
         // due to connection issues.
         const MAX_RETRIES: usize = 3;
@@ -119,9 +173,7 @@ impl AccountLifespan {
                 Command::Batch(tx_commands) => {
                     self.execute_batch_command(tx_commands.as_ref()).await
                 }
-                Command::ApiRequest(_) => {
-                    todo!()
-                }
+                Command::ApiRequest(api_request) => self.execute_api_request(api_request).await,
             };
 
             let label = match result {
@@ -147,7 +199,8 @@ impl AccountLifespan {
             };
 
             // We won't continue the loop unless `continue` was manually called.
-            self.report(label, start.elapsed(), attempt, command).await;
+            let time = scheduled_at.unwrap_or(start).elapsed();
+            self.report(label, time, attempt, command).await;
             break;
         }
     }
@@ -178,13 +231,22 @@ impl AccountLifespan {
         };
     }
 
+    /// Returns the provider to send the transactions through, so they get into the recording.
+    fn recording_provider(&self) -> RecordingProvider {
+        RecordingProvider::new(
+            self.wallet.provider.clone(),
+            self.recorder.clone(),
+            self.wallet.address(),
+        )
+    }
+
     /// Generic sumbitter for zkSync network: it can operate both individual transactions and
     /// batches, as long as we can provide a `SyncTransactionHandle` to wait for the commitment and the
     /// execution result.
     /// Once result is obtained, it's compared to the expected operation outcome in order to check whether
     /// command was completed as planned.
     async fn submit<F, Fut>(
-        &self,
+        &mut self,
         modifier: IncorrectnessModifier,
         send: F,
     ) -> Result<ReportLabel, ClientError>
@@ -203,6 +265,7 @@ impl AccountLifespan {
             }
             (_, Ok(handle)) => {
                 // Transaction should have been accepted by API and it was; now wait for the commitment.
+                self.last_tx_hash = Some(handle.hash());
                 handle
             }
             (ExpectedOutcome::ApiRequestFailed, Err(_error)) => {
@@ -250,10 +313,19 @@ impl AccountLifespan {
         ))];
 
         for _ in 0..self.config.operations_per_account {
-            let command = Command::random(&mut self.rng, self.wallet.address(), &self.addresses);
+            let command = self.random_command();
             commands.push(command)
         }
 
         commands
     }
+
+    fn random_command(&mut self) -> Command {
+        Command::random(
+            &mut self.rng,
+            self.wallet.address(),
+            &self.addresses,
+            &self.config,
+        )
+    }
 }
@@ -0,0 +1,257 @@
+use num::BigUint;
+
+use zksync::{
+    error::ClientError,
+    operations::SyncTransactionHandle,
+    provider::Provider,
+    types::BlockStatus,
+    utils::{closest_packable_token_amount, private_key_from_seed},
+    RpcProvider, Wallet, WalletCredentials,
+};
+use zksync_eth_signer::PrivateKeySigner;
+use zksync_types::{
+    tokens::TxFeeTypes,
+    tx::{PackedEthSignature, TimeRange, TxHash},
+    AccountId, Address, Nonce, Order, Swap, TokenId, ZkSyncTx, H256,
+};
+
+use crate::{
+    account::AccountLifespan,
+    account_pool::AccountCredentials,
+    command::TxCommand,
+    constants::{COMMIT_TIMEOUT, POLLING_INTERVAL},
+    recorder::RecordingProvider,
+    report::ReportLabel,
+    rng::Random,
+};
+
+/// Share of the account balance transferred to the swap partner, so it's able to pay
+/// for its `ChangePubKey` and buy the NFTs.
+const SWAP_PARTNER_FUNDS_DIVISOR: u32 = 4;
+
+/// Counterparty of the swaps made by the test account.
+///
+/// Both orders of the swap must be signed by their owners and have the current nonces of their accounts,
+/// thus the partner is created by the test account itself and is not used by anyone else.
+#[derive(Debug)]
+pub(super) struct SwapPartner {
+    wallet: Wallet<PrivateKeySigner, RpcProvider>,
+    eth_pk: H256,
+}
+
+/// Side of the swap: the signing data of the order owner and the tokens it exchanges.
+struct SwapSide<'a> {
+    eth_pk: H256,
+    account_id: AccountId,
+    address: Address,
+    nonce: Nonce,
+    token_sell: (TokenId, &'a str, u8),
+    token_buy: TokenId,
+    amount: BigUint,
+}
+
+impl SwapSide<'_> {
+    /// Creates the order exchanging exactly the `amount` of the sold token for `amount_buy` of the bought one.
+    fn signed_order(
+        &self,
+        amount_buy: &BigUint,
+        buy_symbol: &str,
+    ) -> Result<(Order, Option<PackedEthSignature>), ClientError> {
+        let private_key = private_key_from_seed(self.eth_pk.as_bytes())?;
+        let (token_sell, sell_symbol, sell_decimals) = self.token_sell;
+        let order = Order::new_signed(
+            self.account_id,
+            self.address,
+            self.nonce,
+            token_sell,
+            self.token_buy,
+            (self.amount.clone(), amount_buy.clone()),
+            self.amount.clone(),
+            TimeRange::default(),
+            &private_key,
+        )
+        .expect("Order should be correct");
+
+        let message = order.get_ethereum_sign_message(sell_symbol, buy_symbol, sell_decimals);
+        let eth_signature = PackedEthSignature::sign(&self.eth_pk, message.as_bytes())
+            .expect("Signing the order unexpectedly failed");
+        Ok((order, Some(eth_signature)))
+    }
+}
+
+impl AccountLifespan {
+    /// Swaps an NFT for the main token with the swap partner.
+    /// The NFT is sold by the account if it owns one, otherwise the NFT of the partner is bought.
+    pub(super) async fn execute_swap(
+        &mut self,
+        command: &TxCommand,
+    ) -> Result<ReportLabel, ClientError> {
+        let account_id = match self.wallet.account_id() {
+            Some(id) => id,
+            None => return Ok(ReportLabel::skipped("L2 account was not initialized yet")),
+        };
+        if self.swap_partner.is_none() {
+            match self.create_swap_partner().await? {
+                Some(partner) => self.swap_partner = Some(partner),
+                None => return Ok(ReportLabel::skipped("Unable to create the swap partner")),
+            }
+        }
+        let partner = self.swap_partner.as_ref().unwrap();
+
+        let own_nft = self.owned_nft().await?;
+        let partner_nfts = partner.wallet.account_info().await?.committed.nfts;
+        let partner_nft = partner_nfts.values().min_by_key(|nft| nft.id).cloned();
+        let (nft, sells_nft) = match (own_nft, partner_nft) {
+            (Some(nft), _) => (nft, true),
+            (None, Some(nft)) => (nft, false),
+            (None, None) => return Ok(ReportLabel::skipped("No NFTs to swap")),
+        };
+
+        let main_token = (
+            self.main_token.id,
+            self.main_token.symbol.as_str(),
+            self.main_token.decimals,
+        );
+        let nft_token = (nft.id, nft.symbol.as_str(), 0);
+        let price = closest_packable_token_amount(&command.amount.clone().max(1u32.into()));
+        let ((own_token, own_amount), (partner_token, partner_amount)) = if sells_nft {
+            ((nft_token, 1u32.into()), (main_token, price))
+        } else {
+            ((main_token, price), (nft_token, 1u32.into()))
+        };
+
+        let nonce = self.wallet.account_info().await?.committed.nonce;
+        let partner_nonce = partner.wallet.account_info().await?.committed.nonce;
+        let own_side = SwapSide {
+            eth_pk: self.eth_pk,
+            account_id,
+            address: self.wallet.address(),
+            nonce,
+            token_sell: own_token,
+            token_buy: partner_token.0,
+            amount: own_amount,
+        };
+        let partner_side = SwapSide {
+            eth_pk: partner.eth_pk,
+            account_id: partner.wallet.account_id().unwrap(),
+            address: partner.wallet.address(),
+            nonce: partner_nonce,
+            token_sell: partner_token,
+            token_buy: own_token.0,
+            amount: partner_amount,
+        };
+        let (own_order, own_order_signature) =
+            own_side.signed_order(&partner_side.amount, partner_token.1)?;
+        let (partner_order, partner_order_signature) =
+            partner_side.signed_order(&own_side.amount, own_token.1)?;
+
+        let fee = self
+            .wallet
+            .provider
+            .get_tx_fee(TxFeeTypes::Swap, self.wallet.address(), self.main_token.id)
+            .await?
+            .total_fee;
+        let private_key = private_key_from_seed(self.eth_pk.as_bytes())?;
+        let swap = Swap::new_signed(
+            account_id,
+            self.wallet.address(),
+            nonce,
+            (own_order, partner_order),
+            (own_side.amount, partner_side.amount),
+            fee,
+            self.main_token.id,
+            &private_key,
+        )
+        .expect("Swap should be correct");
+        let message =
+            swap.get_ethereum_sign_message(&self.main_token.symbol, self.main_token.decimals);
+        let eth_signature = PackedEthSignature::sign(&self.eth_pk, message.as_bytes())
+            .expect("Signing the swap unexpectedly failed");
+
+        let (tx, eth_signature) =
+            self.apply_modifier(ZkSyncTx::from(swap), Some(eth_signature), command.modifier);
+        let swap = match tx {
+            ZkSyncTx::Swap(swap) => *swap,
+            _ => unreachable!("Modifier doesn't change the transaction type"),
+        };
+
+        let provider = self.recording_provider();
+        self.submit(command.modifier, || async {
+            let tx_hash = provider
+                .send_swap(
+                    swap,
+                    eth_signature,
+                    (own_order_signature, partner_order_signature),
+                )
+                .await?;
+            Ok(SyncTransactionHandle::new(tx_hash, provider.provider()))
+        })
+        .await
+    }
+
+    /// Creates a new account, funds it and sets its signing key, so it can sign the orders.
+    /// Returns `None` if any of the required transactions fails.
+    async fn create_swap_partner(&mut self) -> Result<Option<SwapPartner>, ClientError> {
+        let credentials = AccountCredentials::random(&mut self.rng);
+        let zksync_pk = private_key_from_seed(credentials.eth_pk.as_bytes())?;
+        let wallet_credentials = WalletCredentials::<PrivateKeySigner>::from_pk(
+            credentials.address,
+            zksync_pk,
+            Some(credentials.eth_pk),
+        );
+        let mut wallet = Wallet::new(self.wallet.provider.clone(), wallet_credentials).await?;
+
+        let balance = self
+            .wallet
+            .get_balance(BlockStatus::Committed, self.main_token.id)
+            .await?;
+        let amount =
+            closest_packable_token_amount(&(balance / BigUint::from(SWAP_PARTNER_FUNDS_DIVISOR)));
+        let (transfer, eth_signature) = self
+            .wallet
+            .start_transfer()
+            .to(wallet.address())
+            .token(self.main_token.id)?
+            .amount(amount)
+            .tx()
+            .await?;
+        let provider = self.recording_provider();
+        let tx_hash = provider.send_tx(transfer, eth_signature).await?;
+        if !Self::wait_for_success(tx_hash, provider.provider()).await? {
+            return Ok(None);
+        }
+
+        // The partner transactions are sent through the recording as well, so the swaps can be replayed.
+        wallet.update_account_id().await?;
+        let change_pubkey = wallet
+            .start_change_pubkey()
+            .fee_token(self.main_token.id)?
+            .tx()
+            .await?;
+        let partner_provider = RecordingProvider::new(
+            wallet.provider.clone(),
+            self.recorder.clone(),
+            wallet.address(),
+        );
+        let tx_hash = partner_provider.send_tx(change_pubkey, None).await?;
+        if !Self::wait_for_success(tx_hash, partner_provider.provider()).await? {
+            return Ok(None);
+        }
+
+        Ok(Some(SwapPartner {
+            wallet,
+            eth_pk: credentials.eth_pk,
+        }))
+    }
+
+    /// Waits for the commitment of the transaction, returns `false` if it failed.
+    async fn wait_for_success(tx_hash: TxHash, provider: RpcProvider) -> Result<bool, ClientError> {
+        let mut handle = SyncTransactionHandle::new(tx_hash, provider);
+        handle.polling_interval(POLLING_INTERVAL).unwrap();
+        let receipt = handle
+            .commit_timeout(COMMIT_TIMEOUT)
+            .wait_for_commit()
+            .await?;
+        Ok(receipt.fail_reason.is_none())
+    }
+}
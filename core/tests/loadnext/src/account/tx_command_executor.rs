@@ -1,11 +1,16 @@
 use std::convert::TryInto;
 
 use num::{BigUint, Zero};
+use rand::Rng;
 use zksync::{
     error::ClientError, ethereum::PriorityOpHolder, operations::SyncTransactionHandle,
     provider::Provider,
 };
-use zksync_types::{tokens::ETH_TOKEN_ID, tx::PackedEthSignature, Nonce, ZkSyncTx, H256};
+use zksync_types::{
+    tokens::{TxFeeTypes, ETH_TOKEN_ID},
+    tx::{PackedEthSignature, TimeRange},
+    Nonce, ZkSyncTx, H256, NFT,
+};
 
 use crate::{
     account::AccountLifespan,
@@ -30,6 +35,11 @@ impl AccountLifespan {
             }
             TxType::Deposit => self.execute_deposit(command).await,
             TxType::FullExit => self.execute_full_exit().await,
+            TxType::ForcedExit => self.execute_forced_exit(command).await,
+            TxType::MintNFT => self.execute_mint_nft(command).await,
+            TxType::TransferNFT => self.execute_transfer_nft(command).await,
+            TxType::WithdrawNFT => self.execute_withdraw_nft(command).await,
+            TxType::Swap => self.execute_swap(command).await,
         }
     }
 
@@ -183,13 +193,16 @@ impl AccountLifespan {
         Ok(ReportLabel::done())
     }
 
-    async fn execute_change_pubkey(&self, command: &TxCommand) -> Result<ReportLabel, ClientError> {
+    async fn execute_change_pubkey(
+        &mut self,
+        command: &TxCommand,
+    ) -> Result<ReportLabel, ClientError> {
         let (tx, eth_signature) = self.build_change_pubkey(command, None).await?;
 
-        let provider = self.wallet.provider.clone();
+        let provider = self.recording_provider();
         self.submit(command.modifier, || async {
             let tx_hash = provider.send_tx(tx, eth_signature).await?;
-            Ok(SyncTransactionHandle::new(tx_hash, provider))
+            Ok(SyncTransactionHandle::new(tx_hash, provider.provider()))
         })
         .await
     }
@@ -214,15 +227,25 @@ impl AccountLifespan {
         Ok(self.apply_modifier(tx, None, command.modifier))
     }
 
-    async fn execute_transfer(&self, command: &TxCommand) -> Result<ReportLabel, ClientError> {
+    async fn execute_transfer(&mut self, command: &TxCommand) -> Result<ReportLabel, ClientError> {
         let (tx, eth_signature) = self.build_transfer(command, None).await?;
 
-        let provider = self.wallet.provider.clone();
-        self.submit(command.modifier, || async {
-            let tx_hash = provider.send_tx(tx, eth_signature).await?;
-            Ok(SyncTransactionHandle::new(tx_hash, provider))
-        })
-        .await
+        let provider = self.recording_provider();
+        let label = self
+            .submit(command.modifier, || async {
+                let tx_hash = provider.send_tx(tx, eth_signature).await?;
+                Ok(SyncTransactionHandle::new(tx_hash, provider.provider()))
+            })
+            .await?;
+
+        // Accounts created by the correct transfers have no signing key set, thus they can be forced to exit.
+        if command.command_type == TxType::TransferToNew
+            && command.modifier == IncorrectnessModifier::None
+            && matches!(label, ReportLabel::ActionDone)
+        {
+            self.created_accounts.push(command.to);
+        }
+        Ok(label)
     }
 
     pub(super) async fn build_transfer(
@@ -247,13 +270,13 @@ impl AccountLifespan {
         Ok(self.apply_modifier(tx, eth_signature, command.modifier))
     }
 
-    async fn execute_withdraw(&self, command: &TxCommand) -> Result<ReportLabel, ClientError> {
+    async fn execute_withdraw(&mut self, command: &TxCommand) -> Result<ReportLabel, ClientError> {
         let (tx, eth_signature) = self.build_withdraw(command, None).await?;
 
-        let provider = self.wallet.provider.clone();
+        let provider = self.recording_provider();
         self.submit(command.modifier, || async {
             let tx_hash = provider.send_tx(tx, eth_signature).await?;
-            Ok(SyncTransactionHandle::new(tx_hash, provider))
+            Ok(SyncTransactionHandle::new(tx_hash, provider.provider()))
         })
         .await
     }
@@ -278,4 +301,163 @@ impl AccountLifespan {
 
         Ok(self.apply_modifier(tx, eth_signature, command.modifier))
     }
+
+    /// Returns one of the NFTs owned by the wallet, if there are any.
+    pub(super) async fn owned_nft(&self) -> Result<Option<NFT>, ClientError> {
+        let account_info = self.wallet.account_info().await?;
+        Ok(account_info
+            .committed
+            .nfts
+            .values()
+            .min_by_key(|nft| nft.id)
+            .cloned())
+    }
+
+    async fn execute_forced_exit(
+        &mut self,
+        command: &TxCommand,
+    ) -> Result<ReportLabel, ClientError> {
+        if self.created_accounts.is_empty() {
+            return Ok(ReportLabel::skipped("No accounts to force exit"));
+        }
+        let target_index = self.rng.gen_range(0..self.created_accounts.len());
+        let target = self.created_accounts[target_index];
+
+        let fee = self
+            .wallet
+            .provider
+            .get_tx_fee(TxFeeTypes::Withdraw, target, self.main_token.id)
+            .await?
+            .total_fee;
+        let nonce = self.wallet.account_info().await?.committed.nonce;
+        let (tx, eth_signature) = self
+            .wallet
+            .signer
+            .sign_forced_exit(
+                target,
+                self.main_token.clone(),
+                fee,
+                nonce,
+                TimeRange::default(),
+            )
+            .await
+            .map(|(tx, signature)| (ZkSyncTx::ForcedExit(Box::new(tx)), signature))
+            .map_err(ClientError::SigningError)
+            .map_err(Self::tx_creation_error)?;
+        let (tx, eth_signature) = self.apply_modifier(tx, eth_signature, command.modifier);
+
+        let provider = self.recording_provider();
+        let label = self
+            .submit(command.modifier, || async {
+                let tx_hash = provider.send_tx(tx, eth_signature).await?;
+                Ok(SyncTransactionHandle::new(tx_hash, provider.provider()))
+            })
+            .await?;
+
+        // Forced exit drains the target, so the next forced exit against it would fail.
+        if command.modifier == IncorrectnessModifier::None
+            && matches!(label, ReportLabel::ActionDone)
+        {
+            self.created_accounts.swap_remove(target_index);
+        }
+        Ok(label)
+    }
+
+    async fn execute_mint_nft(&mut self, command: &TxCommand) -> Result<ReportLabel, ClientError> {
+        let (tx, eth_signature) = self.build_mint_nft(command, None).await?;
+
+        let provider = self.recording_provider();
+        self.submit(command.modifier, || async {
+            let tx_hash = provider.send_tx(tx, eth_signature).await?;
+            Ok(SyncTransactionHandle::new(tx_hash, provider.provider()))
+        })
+        .await
+    }
+
+    pub(super) async fn build_mint_nft(
+        &mut self,
+        command: &TxCommand,
+        nonce: Option<Nonce>,
+    ) -> Result<(ZkSyncTx, Option<PackedEthSignature>), ClientError> {
+        let content_hash = H256::random_using(&mut self.rng);
+        let mut builder = self
+            .wallet
+            .start_mint_nft()
+            .recipient(command.to)
+            .content_hash(content_hash)
+            .fee_token(self.config.main_token.as_str())
+            .unwrap();
+        if let Some(nonce) = nonce {
+            builder = builder.nonce(nonce);
+        }
+
+        let (tx, eth_signature) = builder.tx().await.map_err(Self::tx_creation_error)?;
+
+        Ok(self.apply_modifier(tx, eth_signature, command.modifier))
+    }
+
+    async fn execute_transfer_nft(
+        &mut self,
+        command: &TxCommand,
+    ) -> Result<ReportLabel, ClientError> {
+        let nft = match self.owned_nft().await? {
+            Some(nft) => nft,
+            None => return Ok(ReportLabel::skipped("Account doesn't own any NFT")),
+        };
+
+        let (nft_transfer, (fee_tx, fee_eth_signature)) = self
+            .wallet
+            .start_transfer_nft()
+            .to(command.to)
+            .nft(nft)
+            .fee_token(self.config.main_token.as_str())
+            .unwrap()
+            .tx()
+            .await
+            .map_err(Self::tx_creation_error)?;
+        // NFT transfer is sent as a batch with the transfer paying the fee, which is corrupted instead:
+        // the transactions of the batch share the outcome, and it's the one signed in the main token.
+        let fee_transfer = self.apply_modifier(fee_tx, fee_eth_signature, command.modifier);
+
+        // Batch result can be identified by a hash of a single transaction from this batch.
+        let main_hash = nft_transfer.0.hash();
+        let provider = self.recording_provider();
+        self.submit(command.modifier, || async {
+            provider
+                .send_txs_batch(vec![nft_transfer, fee_transfer], None)
+                .await?;
+            Ok(SyncTransactionHandle::new(main_hash, provider.provider()))
+        })
+        .await
+    }
+
+    async fn execute_withdraw_nft(
+        &mut self,
+        command: &TxCommand,
+    ) -> Result<ReportLabel, ClientError> {
+        let nft = match self.owned_nft().await? {
+            Some(nft) => nft,
+            None => return Ok(ReportLabel::skipped("Account doesn't own any NFT")),
+        };
+
+        let (tx, eth_signature) = self
+            .wallet
+            .start_withdraw_nft()
+            .to(command.to)
+            .token(nft.id)
+            .unwrap()
+            .fee_token(self.config.main_token.as_str())
+            .unwrap()
+            .tx()
+            .await
+            .map_err(Self::tx_creation_error)?;
+        let (tx, eth_signature) = self.apply_modifier(tx, eth_signature, command.modifier);
+
+        let provider = self.recording_provider();
+        self.submit(command.modifier, || async {
+            let tx_hash = provider.send_tx(tx, eth_signature).await?;
+            Ok(SyncTransactionHandle::new(tx_hash, provider.provider()))
+        })
+        .await
+    }
 }
@@ -1,12 +1,220 @@
-use zksync_types::Address;
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
 
-use crate::account_pool::AddressPool;
+use zksync_api_types::v02::{fee::ApiTxFeeTypes, pagination::MAX_LIMIT};
+use zksync_types::{tx::TxHash, Address, TokenId};
 
-#[derive(Debug, Clone)]
-pub enum ApiRequestCommand {}
+use crate::{
+    account_pool::AddressPool,
+    all::All,
+    config::LoadtestConfig,
+    constants::MIN_NFT_TOKEN_ID,
+    rng::{LoadtestRng, Random},
+};
+
+/// Amount of NFT ids starting from the first one, that are used in the NFT lookups.
+/// Lookups of the NFTs that weren't minted are still useful, since they go through the same code path.
+const NFT_LOOKUP_RANGE: u32 = 1000;
+
+/// API used to perform the request.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ApiType {
+    /// JSON RPC API, also used by the wallets to send transactions.
+    JsonRpc,
+    /// REST API v0.2.
+    RestV02,
+}
+
+impl All for ApiType {
+    fn all() -> &'static [Self] {
+        &[Self::JsonRpc, Self::RestV02]
+    }
+}
+
+impl Random for ApiType {
+    fn random(rng: &mut LoadtestRng) -> Self {
+        *Self::all().choose(rng).unwrap()
+    }
+}
+
+/// Type of the API request, the weight of each type is configured in the `LoadtestConfig`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ApiRequestType {
+    AccountInfo,
+    TxStatus,
+    BlocksPagination,
+    Tokens,
+    TxFee,
+    NftInfo,
+}
+
+impl All for ApiRequestType {
+    fn all() -> &'static [Self] {
+        &[
+            Self::AccountInfo,
+            Self::TxStatus,
+            Self::BlocksPagination,
+            Self::Tokens,
+            Self::TxFee,
+            Self::NftInfo,
+        ]
+    }
+}
+
+impl ApiRequestType {
+    /// Returns the configured weight of the request type.
+    fn weight(self, config: &LoadtestConfig) -> f32 {
+        match self {
+            Self::AccountInfo => config.api_account_info_weight,
+            Self::TxStatus => config.api_tx_status_weight,
+            Self::BlocksPagination => config.api_blocks_weight,
+            Self::Tokens => config.api_tokens_weight,
+            Self::TxFee => config.api_tx_fee_weight,
+            Self::NftInfo => config.api_nft_weight,
+        }
+    }
+
+    /// Chooses the request type according to the weights configured in the `LoadtestConfig`.
+    /// Weights are checked by `LoadtestConfig::validate` before the test is started.
+    pub fn random(rng: &mut LoadtestRng, config: &LoadtestConfig) -> Self {
+        *Self::all()
+            .choose_weighted(rng, |request_type| request_type.weight(config))
+            .expect("API request weights are validated when the config is loaded")
+    }
+}
+
+/// Request to the read-only API methods. Such requests don't change the server state, but are the most common
+/// kind of the load the server has to handle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ApiRequestCommand {
+    /// Request of the account state.
+    AccountInfo { api: ApiType, address: Address },
+    /// Request of the status of the transaction. If the hash is not set, the latest transaction sent by the account
+    /// is requested (or a random one, if nothing was sent).
+    TxStatus {
+        api: ApiType,
+        tx_hash: Option<TxHash>,
+    },
+    /// Request of the page of the latest blocks. Only available in the REST API.
+    BlocksPagination { limit: u32 },
+    /// Request of the list of the supported tokens.
+    Tokens { api: ApiType },
+    /// Request of the fee for the transaction in the main token.
+    TxFee {
+        api: ApiType,
+        tx_type: ApiTxFeeTypes,
+        address: Address,
+    },
+    /// Request of the NFT info. Only available in the REST API.
+    NftInfo { token_id: TokenId },
+}
 
 impl ApiRequestCommand {
-    pub fn random(_own_address: Address, _addresses: &AddressPool) -> Self {
-        todo!()
+    pub fn random(
+        rng: &mut LoadtestRng,
+        own_address: Address,
+        addresses: &AddressPool,
+        config: &LoadtestConfig,
+    ) -> Self {
+        match ApiRequestType::random(rng, config) {
+            ApiRequestType::AccountInfo => {
+                // Accounts mostly check their own state, but the other accounts are requested as well.
+                let address = if rng.gen_bool(0.5) {
+                    own_address
+                } else {
+                    addresses.random_address(rng)
+                };
+                Self::AccountInfo {
+                    api: ApiType::random(rng),
+                    address,
+                }
+            }
+            ApiRequestType::TxStatus => Self::TxStatus {
+                api: ApiType::random(rng),
+                tx_hash: None,
+            },
+            ApiRequestType::BlocksPagination => Self::BlocksPagination {
+                limit: rng.gen_range(1..=MAX_LIMIT),
+            },
+            ApiRequestType::Tokens => Self::Tokens {
+                api: ApiType::random(rng),
+            },
+            ApiRequestType::TxFee => {
+                let tx_type = if rng.gen_bool(0.5) {
+                    ApiTxFeeTypes::Transfer
+                } else {
+                    ApiTxFeeTypes::Withdraw
+                };
+                Self::TxFee {
+                    api: ApiType::random(rng),
+                    tx_type,
+                    address: addresses.random_address(rng),
+                }
+            }
+            ApiRequestType::NftInfo => Self::NftInfo {
+                token_id: TokenId(
+                    rng.gen_range(MIN_NFT_TOKEN_ID..MIN_NFT_TOKEN_ID + NFT_LOOKUP_RANGE),
+                ),
+            },
+        }
+    }
+
+    /// Returns the type of the request.
+    pub fn request_type(&self) -> ApiRequestType {
+        match self {
+            Self::AccountInfo { .. } => ApiRequestType::AccountInfo,
+            Self::TxStatus { .. } => ApiRequestType::TxStatus,
+            Self::BlocksPagination { .. } => ApiRequestType::BlocksPagination,
+            Self::Tokens { .. } => ApiRequestType::Tokens,
+            Self::TxFee { .. } => ApiRequestType::TxFee,
+            Self::NftInfo { .. } => ApiRequestType::NftInfo,
+        }
+    }
+
+    /// Returns the API used to perform the request.
+    pub fn api(&self) -> ApiType {
+        match self {
+            Self::AccountInfo { api, .. }
+            | Self::TxStatus { api, .. }
+            | Self::Tokens { api }
+            | Self::TxFee { api, .. } => *api,
+            Self::BlocksPagination { .. } | Self::NftInfo { .. } => ApiType::RestV02,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_request_weights() {
+        let mut rng = LoadtestRng::new_generic(None);
+        let addresses = AddressPool::new(vec![Address::random()]);
+        let config = LoadtestConfig {
+            api_account_info_weight: 0.0,
+            api_tx_status_weight: 0.0,
+            api_blocks_weight: 1.0,
+            api_tokens_weight: 0.0,
+            api_tx_fee_weight: 1.0,
+            api_nft_weight: 0.0,
+            ..Default::default()
+        };
+
+        for _ in 0..100 {
+            let command =
+                ApiRequestCommand::random(&mut rng, Address::random(), &addresses, &config);
+            assert!(
+                matches!(
+                    command.request_type(),
+                    ApiRequestType::BlocksPagination | ApiRequestType::TxFee
+                ),
+                "Request with zero weight was generated: {:?}",
+                command
+            );
+            if let ApiRequestCommand::BlocksPagination { limit } = command {
+                assert!((1..=MAX_LIMIT).contains(&limit));
+            }
+        }
     }
 }
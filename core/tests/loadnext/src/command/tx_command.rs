@@ -20,6 +20,11 @@ pub enum TxType {
     WithdrawToOther,
     FullExit,
     ChangePubKey,
+    ForcedExit,
+    MintNFT,
+    TransferNFT,
+    WithdrawNFT,
+    Swap,
 }
 
 impl All for TxType {
@@ -32,6 +37,11 @@ impl All for TxType {
             Self::WithdrawToOther,
             Self::FullExit,
             Self::ChangePubKey,
+            Self::ForcedExit,
+            Self::MintNFT,
+            Self::TransferNFT,
+            Self::WithdrawNFT,
+            Self::Swap,
         ]
     }
 }
@@ -50,6 +60,11 @@ impl AllWeighted for TxType {
             (Self::WithdrawToOther, DEFAULT_WEIGHT),
             (Self::FullExit, DEFAULT_WEIGHT),
             (Self::ChangePubKey, DEFAULT_WEIGHT),
+            (Self::ForcedExit, DEFAULT_WEIGHT),
+            (Self::MintNFT, DEFAULT_WEIGHT),
+            (Self::TransferNFT, DEFAULT_WEIGHT),
+            (Self::WithdrawNFT, DEFAULT_WEIGHT),
+            (Self::Swap, DEFAULT_WEIGHT),
         ]
     }
 }
@@ -69,15 +84,34 @@ impl TxType {
 
     /// Checks whether `TxType` can be used as a part of the batch.
     fn is_batchable(self) -> bool {
-        !matches!(self, Self::Deposit | Self::FullExit)
+        // Operations that are not inserted into the batch:
+        // - `Deposit` and `FullExit` are priority operations.
+        // - `ForcedExit` depends on the state of the target account at the moment of execution.
+        // - `TransferNFT` is a batch itself.
+        // - `WithdrawNFT` depends on the NFTs owned by the account at the moment of execution.
+        // - `Swap` requires the signatures of the orders.
+        !matches!(
+            self,
+            Self::Deposit
+                | Self::FullExit
+                | Self::ForcedExit
+                | Self::TransferNFT
+                | Self::WithdrawNFT
+                | Self::Swap
+        )
     }
 
     fn is_withdrawal(self) -> bool {
         matches!(self, Self::WithdrawToOther | Self::WithdrawToSelf)
     }
 
-    fn is_change_pubkey(self) -> bool {
-        matches!(self, Self::ChangePubKey)
+    /// Checks whether the transaction has no amount field that can be corrupted.
+    /// NFT transfer is corrupted via the transfer that pays the fee, so it's not listed here.
+    fn has_no_amount(self) -> bool {
+        matches!(
+            self,
+            Self::ChangePubKey | Self::ForcedExit | Self::MintNFT | Self::WithdrawNFT
+        )
     }
 
     fn is_priority(self) -> bool {
@@ -199,6 +233,7 @@ pub struct TxCommand {
     /// Whether and how transaction should be corrupted.
     pub modifier: IncorrectnessModifier,
     /// Recipient address.
+    /// For the forced exit it's ignored: the target is chosen among the accounts created by the wallet.
     pub to: Address,
     /// Transaction amount (0 if not applicable).
     pub amount: BigUint,
@@ -257,7 +292,7 @@ impl TxCommand {
 
         // Transactions that have no amount field.
         let no_amount_field =
-            command.command_type.is_change_pubkey() && command.modifier.affects_amount();
+            command.command_type.has_no_amount() && command.modifier.affects_amount();
         // It doesn't make sense to fail contract-based functions.
         let incorrect_priority_op = command.command_type.is_priority();
         // Amount doesn't have to be packable for withdrawals.
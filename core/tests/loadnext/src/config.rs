@@ -12,6 +12,9 @@ use serde::Deserialize;
 pub struct LoadtestConfig {
     /// Address of the zkSync node.
     pub zksync_rpc_addr: String,
+    /// Address of the zkSync REST API.
+    #[serde(default = "default_zksync_rest_addr")]
+    pub zksync_rest_addr: String,
     /// Address of the Ethereum web3 API.
     pub web3_url: String,
     /// Used Ethereum network (e.g. `rinkeby` or `localhost`).
@@ -45,12 +48,150 @@ pub struct LoadtestConfig {
     pub seed: Option<String>,
     /// Allowed percent of failed transactions
     pub allowed_percent: u8,
+
+    /// Share of the API requests among the commands executed by each account, from 0 to 1.
+    /// The rest of the commands are transactions and batches. API requests are disabled by default,
+    /// so the load of the existing test configurations stays the same.
+    #[serde(default = "default_api_requests_share")]
+    pub api_requests_share: f32,
+    /// Relative weights of the API request types: the probability of the request type is
+    /// its weight divided by the sum of all the weights. Zero weight disables the request type.
+    #[serde(default = "default_api_request_weight")]
+    pub api_account_info_weight: f32,
+    #[serde(default = "default_api_request_weight")]
+    pub api_tx_status_weight: f32,
+    #[serde(default = "default_api_request_weight")]
+    pub api_blocks_weight: f32,
+    #[serde(default = "default_api_request_weight")]
+    pub api_tokens_weight: f32,
+    #[serde(default = "default_api_request_weight")]
+    pub api_tx_fee_weight: f32,
+    #[serde(default = "default_api_request_weight")]
+    pub api_nft_weight: f32,
+
+    /// Whether the commands are sent at the configured rate rather than by each account one after another.
+    /// In the open-loop mode, the commands are sent regardless of the completion of the previous ones,
+    /// and `operations_per_account` is ignored: the test lasts for `open_loop_duration_secs`.
+    #[serde(default)]
+    pub open_loop: bool,
+    /// Rate of the commands (per second) at the start of the open-loop test.
+    #[serde(default = "default_open_loop_start_tps")]
+    pub open_loop_start_tps: f64,
+    /// Rate of the commands (per second) at the end of the open-loop test.
+    /// The rate grows linearly from the start one to the end one.
+    #[serde(default = "default_open_loop_end_tps")]
+    pub open_loop_end_tps: f64,
+    /// Duration of the open-loop test.
+    #[serde(default = "default_open_loop_duration_secs")]
+    pub open_loop_duration_secs: u64,
+
+    /// Size of the time windows, for which the latencies and the throughput are reported.
+    #[serde(default = "default_report_window_secs")]
+    pub report_window_secs: u64,
+    /// Path of the JSON report. The report is not written if the path is not set.
+    pub report_path: Option<String>,
+
+    /// Latency percentile checked against the latency thresholds.
+    #[serde(default = "default_slo_latency_percentile")]
+    pub slo_latency_percentile: u8,
+    /// Maximum allowed latency of the transactions and batches, in milliseconds.
+    pub slo_tx_latency_ms: Option<u64>,
+    /// Maximum allowed latency of the API requests, in milliseconds.
+    pub slo_api_latency_ms: Option<u64>,
+    /// Minimum throughput of the successful actions (per second) that must be sustained during the test.
+    pub slo_min_tps: Option<f64>,
+
+    /// Path of the file to record the sent transactions and API requests to.
+    /// The run is not recorded if the path is not set.
+    pub record_path: Option<String>,
+    /// Path of the recording to replay. If the path is set, the recorded requests are re-submitted
+    /// instead of running the test.
+    pub replay_path: Option<String>,
+    /// Speed of the replay relative to the recorded run, e.g. `2.0` re-submits the requests twice as fast.
+    #[serde(default = "default_replay_speed")]
+    pub replay_speed: f64,
+    /// Whether the master account is funded in L2 before the replay, which is required
+    /// if the recording is replayed against a fresh server.
+    #[serde(default)]
+    pub replay_prepare_master: bool,
+}
+
+fn default_zksync_rest_addr() -> String {
+    "http://127.0.0.1:3001".into()
+}
+
+fn default_api_requests_share() -> f32 {
+    0.0
+}
+
+fn default_api_request_weight() -> f32 {
+    1.0
+}
+
+fn default_open_loop_start_tps() -> f64 {
+    1.0
+}
+
+fn default_open_loop_end_tps() -> f64 {
+    10.0
+}
+
+fn default_open_loop_duration_secs() -> u64 {
+    300
+}
+
+fn default_report_window_secs() -> u64 {
+    10
+}
+
+fn default_slo_latency_percentile() -> u8 {
+    99
+}
+
+fn default_replay_speed() -> f64 {
+    1.0
 }
 
 impl LoadtestConfig {
     pub fn from_env() -> envy::Result<Self> {
         envy::from_env()
     }
+
+    /// Checks the values that can't be validated by the deserialization, so the misconfigured test fails
+    /// before it's started rather than in the middle of the run.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            (0.0..=1.0).contains(&self.api_requests_share),
+            "API requests share must be in range from 0 to 1, got {}",
+            self.api_requests_share
+        );
+        if self.api_requests_share > 0.0 {
+            let weights = self.api_request_weights();
+            anyhow::ensure!(
+                weights
+                    .iter()
+                    .all(|weight| weight.is_finite() && *weight >= 0.0),
+                "API request weights must be non-negative numbers, got {:?}",
+                weights
+            );
+            anyhow::ensure!(
+                weights.iter().any(|weight| *weight > 0.0),
+                "API requests are enabled, but all the API request weights are zero"
+            );
+        }
+        Ok(())
+    }
+
+    fn api_request_weights(&self) -> [f32; 6] {
+        [
+            self.api_account_info_weight,
+            self.api_tx_status_weight,
+            self.api_blocks_weight,
+            self.api_tokens_weight,
+            self.api_tx_fee_weight,
+            self.api_nft_weight,
+        ]
+    }
 }
 
 impl Default for LoadtestConfig {
@@ -60,6 +201,7 @@ impl Default for LoadtestConfig {
         // dependency on the `zk` tool and TOML config files.
         Self {
             zksync_rpc_addr: "http://127.0.0.1:3030".into(),
+            zksync_rest_addr: default_zksync_rest_addr(),
             web3_url: "http://127.0.0.1:8545".into(),
             eth_network: "localhost".into(),
             master_wallet_pk: "74d8b3a188f7260f67698eb44da07397a298df5427df681ef68c45b34b61f998"
@@ -69,6 +211,75 @@ impl Default for LoadtestConfig {
             main_token: "DAI".into(),
             seed: None,
             allowed_percent: 10,
+            api_requests_share: default_api_requests_share(),
+            api_account_info_weight: default_api_request_weight(),
+            api_tx_status_weight: default_api_request_weight(),
+            api_blocks_weight: default_api_request_weight(),
+            api_tokens_weight: default_api_request_weight(),
+            api_tx_fee_weight: default_api_request_weight(),
+            api_nft_weight: default_api_request_weight(),
+            open_loop: false,
+            open_loop_start_tps: default_open_loop_start_tps(),
+            open_loop_end_tps: default_open_loop_end_tps(),
+            open_loop_duration_secs: default_open_loop_duration_secs(),
+            report_window_secs: default_report_window_secs(),
+            report_path: None,
+            slo_latency_percentile: default_slo_latency_percentile(),
+            slo_tx_latency_ms: None,
+            slo_api_latency_ms: None,
+            slo_min_tps: None,
+            record_path: None,
+            replay_path: None,
+            replay_speed: default_replay_speed(),
+            replay_prepare_master: false,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_config_is_valid() {
+        LoadtestConfig::default().validate().unwrap();
+    }
+
+    #[test]
+    fn zero_api_request_weights_are_rejected() {
+        let config = LoadtestConfig {
+            api_requests_share: 0.5,
+            api_account_info_weight: 0.0,
+            api_tx_status_weight: 0.0,
+            api_blocks_weight: 0.0,
+            api_tokens_weight: 0.0,
+            api_tx_fee_weight: 0.0,
+            api_nft_weight: 0.0,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        // Weights don't matter if API requests are disabled.
+        let config = LoadtestConfig {
+            api_requests_share: 0.0,
+            ..config
+        };
+        config.validate().unwrap();
+    }
+
+    #[test]
+    fn invalid_api_request_values_are_rejected() {
+        let config = LoadtestConfig {
+            api_requests_share: 0.5,
+            api_tokens_weight: -1.0,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = LoadtestConfig {
+            api_requests_share: 1.5,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
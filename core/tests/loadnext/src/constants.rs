@@ -14,3 +14,6 @@ pub const POLLING_INTERVAL: Duration = Duration::from_secs(3);
 // TODO (ZKS-623): This value is not the greatest batch size zkSync supports.
 // However, choosing the bigger value (e.g. 40) causes server to fail with error "Error communicating core server".
 pub const MAX_BATCH_SIZE: usize = 20;
+
+/// First id of the NFT tokens in zkSync.
+pub const MIN_NFT_TOKEN_ID: u32 = 65536;
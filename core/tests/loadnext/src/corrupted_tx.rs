@@ -98,7 +98,8 @@ impl Corrupted for (ZkSyncTx, Option<PackedEthSignature>) {
             }
             ZkSyncTx::Close(_tx) => unreachable!(),
             ZkSyncTx::Swap(tx) => {
-                tx.signature = TxSignature::sign_musig(&zksync_pk, &tx.get_bytes());
+                // Swap signature covers the hashes of the orders rather than the orders themselves.
+                tx.signature = TxSignature::sign_musig(&zksync_pk, &tx.get_sign_bytes());
                 tx.get_ethereum_sign_message(token_symbol, decimals)
                     .as_bytes()
                     .to_vec()
@@ -325,6 +326,8 @@ mod tests {
     use zksync_test_account::ZkSyncAccount;
     use zksync_types::{AccountId, Address, Nonce, PubKeyHash, Transfer};
 
+    use crate::constants::MIN_NFT_TOKEN_ID;
+
     const AMOUNT: u64 = 100;
     const FEE: u64 = 100;
 
@@ -352,6 +355,103 @@ mod tests {
         }
     }
 
+    /// Creates the correct transactions of the types other than transfer.
+    fn create_other_txs(account: &ZkSyncAccount) -> Vec<(ZkSyncTx, Option<PackedEthSignature>)> {
+        let recipient = Address::repeat_byte(0x7e);
+
+        let (mint_nft, mint_nft_signature) = account.sign_mint_nft(
+            TokenId(0),
+            "ETH",
+            H256::repeat_byte(0x11),
+            FEE.into(),
+            &recipient,
+            Some(Nonce(1)),
+            false,
+        );
+        let (withdraw_nft, withdraw_nft_signature) = account.sign_withdraw_nft(
+            TokenId(MIN_NFT_TOKEN_ID),
+            TokenId(0),
+            "ETH",
+            FEE.into(),
+            &recipient,
+            Some(Nonce(1)),
+            false,
+            Default::default(),
+        );
+        let forced_exit = account.sign_forced_exit(
+            TokenId(0),
+            FEE.into(),
+            &recipient,
+            Some(Nonce(1)),
+            false,
+            Default::default(),
+        );
+
+        let counterparty = create_counterparty();
+        let order = account.sign_order(
+            TokenId(MIN_NFT_TOKEN_ID),
+            TokenId(0),
+            AMOUNT.into(),
+            1u64.into(),
+            1u64.into(),
+            &account.address,
+            Some(Nonce(1)),
+            false,
+            Default::default(),
+        );
+        let counter_order = counterparty.sign_order(
+            TokenId(0),
+            TokenId(MIN_NFT_TOKEN_ID),
+            1u64.into(),
+            AMOUNT.into(),
+            AMOUNT.into(),
+            &counterparty.address,
+            Some(Nonce(1)),
+            false,
+            Default::default(),
+        );
+        let (swap, swap_signature) = account.sign_swap(
+            (order, counter_order),
+            (1u64.into(), AMOUNT.into()),
+            Some(Nonce(1)),
+            false,
+            TokenId(0),
+            "ETH",
+            FEE.into(),
+        );
+
+        vec![
+            (ZkSyncTx::from(mint_nft), mint_nft_signature),
+            (ZkSyncTx::from(withdraw_nft), withdraw_nft_signature),
+            (ZkSyncTx::from(forced_exit), None),
+            (ZkSyncTx::from(swap), swap_signature),
+        ]
+    }
+
+    /// Returns the signer of the transaction, ignoring the cached value.
+    fn tx_signer(tx: ZkSyncTx) -> Option<PubKeyHash> {
+        let signer = match tx {
+            ZkSyncTx::MintNFT(mut tx) => {
+                tx.wipe_signer_cache();
+                tx.verify_signature()
+            }
+            ZkSyncTx::WithdrawNFT(mut tx) => {
+                tx.wipe_signer_cache();
+                tx.verify_signature()
+            }
+            ZkSyncTx::ForcedExit(mut tx) => {
+                tx.wipe_signer_cache();
+                tx.verify_signature()
+            }
+            ZkSyncTx::Swap(mut tx) => {
+                tx.wipe_signer_cache();
+                tx.verify_signature()
+            }
+            _ => unreachable!("Transfers are checked separately"),
+        };
+        signer.map(|(pub_key_hash, _)| pub_key_hash)
+    }
+
     fn create_account() -> ZkSyncAccount {
         let mut account = ZkSyncAccount::rand();
         account.set_account_id(Some(AccountId(1)));
@@ -362,6 +462,13 @@ mod tests {
         account
     }
 
+    /// Creates another account, e.g. to sign the second order of the swap.
+    fn create_counterparty() -> ZkSyncAccount {
+        let mut account = create_account();
+        account.set_account_id(Some(AccountId(2)));
+        account
+    }
+
     #[test]
     fn zero_fee() {
         let account = create_account();
@@ -460,4 +567,54 @@ mod tests {
                 .unwrap()
         );
     }
+
+    /// Checks that the modifications that keep the transaction signed by the account
+    /// produce a correct zkSync signature for every transaction type.
+    #[test]
+    fn resigned_other_txs() {
+        let account = create_account();
+        let eth_pk = account.eth_account_data.unwrap_eoa_pk();
+
+        for tx in create_other_txs(&account) {
+            let modified_txs = vec![
+                tx.clone().zero_fee(eth_pk, "ETH", 18),
+                tx.clone().not_packable_fee(eth_pk, "ETH", 18),
+                tx.clone().nonexistent_token(eth_pk, "ETH", 18),
+                tx.clone().bad_eth_signature(eth_pk, "ETH", 18),
+            ];
+            for (modified_tx, _eth_signature) in modified_txs {
+                assert_eq!(
+                    tx_signer(modified_tx.clone()),
+                    Some(account.pubkey_hash),
+                    "Incorrect signature of the modified transaction: {:?}",
+                    modified_tx
+                );
+            }
+
+            let (modified_tx, _eth_signature) = tx.bad_zksync_signature();
+            assert_ne!(tx_signer(modified_tx), Some(account.pubkey_hash));
+        }
+    }
+
+    #[test]
+    fn swap_amounts() {
+        let account = create_account();
+        let eth_pk = account.eth_account_data.unwrap_eoa_pk();
+        let swap = create_other_txs(&account).pop().unwrap();
+
+        let (modified_swap, _eth_signature) = swap.clone().too_big_amount(eth_pk, "ETH", 18);
+        if let ZkSyncTx::Swap(modified_swap) = &modified_swap {
+            assert!(modified_swap.amounts.1 > AMOUNT.into());
+        } else {
+            panic!("Not a swap");
+        }
+        assert_eq!(tx_signer(modified_swap), Some(account.pubkey_hash));
+
+        let (modified_swap, _eth_signature) = swap.not_packable_amount(eth_pk, "ETH", 18);
+        if let ZkSyncTx::Swap(modified_swap) = modified_swap {
+            assert!(!is_token_amount_packable(&modified_swap.amounts.0));
+        } else {
+            panic!("Not a swap");
+        }
+    }
 }
@@ -1,4 +1,9 @@
-use futures::{channel::mpsc, future::join_all};
+use std::time::Instant;
+
+use futures::{
+    channel::{mpsc, oneshot},
+    future::join_all,
+};
 
 use tokio::task::JoinHandle;
 use zksync::{
@@ -8,7 +13,12 @@ use zksync::{
 use zksync_types::{tx::TxHash, TransactionReceipt, TxFeeTypes, U256};
 
 use crate::{
-    account::AccountLifespan, account_pool::AccountPool, config::LoadtestConfig,
+    account::AccountLifespan,
+    account_pool::AccountPool,
+    config::LoadtestConfig,
+    open_loop::{CommandSchedule, TpsRamp},
+    recorder::{Recorder, RecordingProvider},
+    report::Report,
     report_collector::LoadtestResult,
 };
 use crate::{constants::*, report_collector::ReportCollector};
@@ -22,20 +32,40 @@ use crate::{constants::*, report_collector::ReportCollector};
 /// - Spawning the report collector.
 /// - Distributing the funds among the test wallets.
 /// - Spawning account lifespan futures.
+/// - Issuing the commands at the configured rate in the open-loop mode.
 /// - Awaiting for all the account futures to complete.
 /// - Getting the final test resolution from the report collector.
+/// - Completing the recording of the sent requests, if the run is recorded.
 #[derive(Debug)]
 pub struct Executor {
     config: LoadtestConfig,
     pool: AccountPool,
+    /// Rate of the commands in the open-loop mode.
+    ramp: Option<TpsRamp>,
+    /// Recording of the sent requests along with the task writing it.
+    recorder: Option<(Recorder, JoinHandle<()>)>,
 }
 
 impl Executor {
     /// Creates a new Executor entity.
     pub async fn new(config: LoadtestConfig) -> anyhow::Result<Self> {
+        let ramp = TpsRamp::from_config(&config)?;
         let pool = AccountPool::new(&config).await?;
+        let recorder = config.record_path.as_ref().map(Recorder::new).transpose()?;
+
+        Ok(Self {
+            config,
+            pool,
+            ramp,
+            recorder,
+        })
+    }
 
-        Ok(Self { config, pool })
+    /// Provides the master account with the funds in L2, so it's able to fund the test accounts.
+    pub async fn prepare_master(&mut self) -> anyhow::Result<()> {
+        self.check_onchain_balance().await?;
+        self.mint().await?;
+        self.deposit_to_master().await
     }
 
     /// Runs the loadtest until the completion.
@@ -50,15 +80,61 @@ impl Executor {
     /// Inner representation of `start` function which returns a `Result`, so it can conveniently use `?`.
     async fn start_inner(&mut self) -> anyhow::Result<LoadtestResult> {
         vlog::info!("Initializing accounts");
-        self.check_onchain_balance().await?;
-        self.mint().await?;
-        self.deposit_to_master().await?;
+        self.prepare_master().await?;
         self.set_signing_key().await?;
-        let (executor_future, account_futures) = self.send_initial_transfers().await?;
+
+        // Prepare channels for the report collector.
+        let (report_sender, report_receiver) = mpsc::channel(256);
+        let (load_start_sender, load_start_receiver) = oneshot::channel();
+        let report_collector = ReportCollector::new(
+            report_receiver,
+            &self.config,
+            self.ramp,
+            load_start_receiver,
+        );
+        let executor_future = tokio::spawn(report_collector.run());
+
+        let (scheduler, schedule) = match self.ramp {
+            Some(ramp) => {
+                let (scheduler, schedule) = ramp.scheduler();
+                (Some(scheduler), Some(schedule))
+            }
+            None => (None, None),
+        };
+        let account_futures = self
+            .send_initial_transfers(schedule, report_sender.clone())
+            .await?;
+
+        match scheduler {
+            Some(mut scheduler) => {
+                let ready_accounts = scheduler.wait_for_accounts().await;
+                vlog::info!(
+                    "Starting the open-loop load with {} ready accounts: {:?}",
+                    ready_accounts,
+                    self.ramp.unwrap()
+                );
+                let load_start = Instant::now();
+                // The report collector is alive until all the accounts are done.
+                load_start_sender.send(load_start).ok();
+                tokio::spawn(scheduler.run(load_start, report_sender));
+            }
+            // The report collector is completed once all the senders are dropped.
+            None => drop(report_sender),
+        }
         self.wait_account_routines(account_futures).await;
 
         let final_resultion = executor_future.await.unwrap_or(LoadtestResult::TestFailed);
 
+        if let Some((recorder, writer_task)) = self.recorder.take() {
+            // The recording is completed once the last handle is dropped.
+            drop(recorder);
+            writer_task.await?;
+            vlog::info!(
+                "Recording is written to {}",
+                self.config.record_path.as_deref().unwrap_or_default()
+            );
+        }
+
         Ok(final_resultion)
     }
 
@@ -181,19 +257,16 @@ impl Executor {
     /// Invokes `ChangePubKey` for the main wallet in L2.
     async fn set_signing_key(&mut self) -> anyhow::Result<()> {
         vlog::info!("Master Account: Setting the signing key");
-        let mut handle = self
+        let change_pubkey = self
             .pool
             .master_wallet
             .start_change_pubkey()
             .fee_token(self.config.main_token.as_str())
             .unwrap()
-            .send()
-            .await?;
-        handle.polling_interval(POLLING_INTERVAL).unwrap();
-        let result = handle
-            .commit_timeout(COMMIT_TIMEOUT)
-            .wait_for_commit()
+            .tx()
             .await?;
+        let tx_hash = self.master_provider().send_tx(change_pubkey, None).await?;
+        let result = self.wait_for_sync_tx(tx_hash).await?;
 
         assert!(
             result.fail_reason.is_none(),
@@ -285,11 +358,20 @@ impl Executor {
         *nonce += 1;
         batch.push((fee_tx, fee_tx_signature));
 
-        master_wallet.provider.send_txs_batch(batch, None).await?;
+        self.master_provider().send_txs_batch(batch, None).await?;
 
         Ok(batch_tx_hash)
     }
 
+    /// Returns the provider to send the transactions of the master account through, so they get into the recording.
+    fn master_provider(&self) -> RecordingProvider {
+        RecordingProvider::new(
+            self.pool.master_wallet.provider.clone(),
+            self.recorder.as_ref().map(|(recorder, _)| recorder.clone()),
+            self.pool.master_wallet.address(),
+        )
+    }
+
     /// Returns the amount sufficient for wallets to perform many operations.
     fn transfer_amount(&self) -> u128 {
         let accounts_amount = self.config.accounts_amount;
@@ -313,27 +395,23 @@ impl Executor {
 
     /// Initializes the loadtest by doing the following:
     ///
-    /// - Spawning the `ReportCollector`.
     /// - Distributing ERC-20 token in L2 among test wallets via `Transfer` operation.
     /// - Distributing ETH in L1 among test wallets in order to make them able to perform priority operations.
-    /// - Spawning test account routine futures.
+    /// - Spawning test account routine futures. In the open-loop mode, accounts take the commands from the `schedule`.
     /// - Collecting all the spawned tasks and returning them to the caller.
     async fn send_initial_transfers(
         &mut self,
-    ) -> anyhow::Result<(JoinHandle<LoadtestResult>, Vec<JoinHandle<()>>)> {
+        schedule: Option<CommandSchedule>,
+        report_sender: mpsc::Sender<Report>,
+    ) -> anyhow::Result<Vec<JoinHandle<()>>> {
         vlog::info!("Master Account: Sending initial transfers");
         // How many times we will resend a batch.
         const MAX_RETRIES: usize = 3;
 
-        // Prepare channels for the report collector.
-        let (report_sender, report_receiver) = mpsc::channel(256);
-
-        let report_collector = ReportCollector::new(report_receiver, self.config.allowed_percent);
-        let report_collector_future = tokio::spawn(report_collector.run());
-
         let config = &self.config;
         let accounts_amount = config.accounts_amount;
         let addresses = self.pool.addresses.clone();
+        let recorder = self.recorder.as_ref().map(|(recorder, _)| recorder.clone());
 
         let mut retry_counter = 0;
         let mut accounts_processed = 0;
@@ -398,8 +476,12 @@ impl Executor {
                             addresses.clone(),
                             wallet,
                             report_sender.clone(),
+                            recorder.clone(),
                         );
-                        tokio::spawn(account.run())
+                        match &schedule {
+                            Some(schedule) => tokio::spawn(account.run_open_loop(schedule.clone())),
+                            None => tokio::spawn(account.run()),
+                        }
                     });
 
             account_futures.extend(new_account_futures);
@@ -411,7 +493,7 @@ impl Executor {
         );
         vlog::info!("All the initial transfers are completed");
 
-        Ok((report_collector_future, account_futures))
+        Ok(account_futures)
     }
 
     /// Calculates amount of ETH to be distributed per account in order to make them
@@ -432,11 +514,19 @@ impl Executor {
 
         // Amount of priority operations expected to be made by account.
         // We assume that 10% of operations made by account will be priority operations.
-        let priority_ops_per_account = self.config.operations_per_account / 10;
+        let priority_ops_per_account = self.operations_per_account() / 10;
 
         Ok(average_gas_price * gas_per_priority_op * priority_ops_per_account)
     }
 
+    /// Returns the amount of operations expected to be made by each account.
+    fn operations_per_account(&self) -> usize {
+        match &self.ramp {
+            Some(ramp) => ramp.expected_commands() / self.config.accounts_amount + 1,
+            None => self.config.operations_per_account,
+        }
+    }
+
     /// Waits for all the test account futures to be completed.
     async fn wait_account_routines(&self, account_futures: Vec<JoinHandle<()>>) {
         vlog::info!("Waiting for the account futures to be completed...");
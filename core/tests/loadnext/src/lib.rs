@@ -7,6 +7,9 @@ pub mod config;
 pub mod constants;
 pub mod corrupted_tx;
 pub mod executor;
+pub mod open_loop;
+pub mod recorder;
+pub mod replay;
 pub mod report;
 pub mod report_collector;
 pub mod rng;
@@ -3,7 +3,9 @@
 //! In order to launch it, you must provide required environmental variables, for details see `README.md`.
 //! Without required variables provided, test is launched in the localhost/development mode with some hard-coded
 //! values to check the local zkSync deployment.
-use loadnext::{config::LoadtestConfig, executor::Executor, report_collector::LoadtestResult};
+use loadnext::{
+    config::LoadtestConfig, executor::Executor, replay::Replayer, report_collector::LoadtestResult,
+};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -17,9 +19,21 @@ async fn main() -> anyhow::Result<()> {
         vlog::warn!("Using the hard-coded config, assuming it's the development run");
         LoadtestConfig::default()
     });
+    config.validate()?;
 
-    let mut executor = Executor::new(config).await?;
-    let final_resolution = executor.start().await;
+    let final_resolution = match config.replay_path.clone() {
+        Some(replay_path) => {
+            if config.replay_prepare_master {
+                let mut executor = Executor::new(config.clone()).await?;
+                executor.prepare_master().await?;
+            }
+            Replayer::new(config, &replay_path)?.run().await
+        }
+        None => {
+            let mut executor = Executor::new(config).await?;
+            executor.start().await
+        }
+    };
 
     match final_resolution {
         LoadtestResult::TestPassed => {
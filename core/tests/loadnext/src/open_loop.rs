@@ -0,0 +1,332 @@
+//! Open-loop traffic: commands are scheduled at the configured rate regardless of the completion
+//! of the previous ones, so the latency growth under the increasing load is not hidden by the accounts
+//! waiting for their own transactions. Each command is sent by a free account right away, and the commands
+//! scheduled while all the accounts are busy are dropped.
+
+use std::time::{Duration, Instant};
+
+use futures::{channel::mpsc::Sender, SinkExt};
+use tokio::sync::{
+    mpsc::{self, error::TryRecvError},
+    oneshot,
+};
+
+use crate::{
+    config::LoadtestConfig,
+    report::{ActionType, Report, ReportBuilder, ReportLabel},
+};
+
+/// Rate of the commands growing linearly from the start rate to the end rate during the test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TpsRamp {
+    start_tps: f64,
+    end_tps: f64,
+    duration: Duration,
+}
+
+impl TpsRamp {
+    pub fn new(start_tps: f64, end_tps: f64, duration: Duration) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            start_tps > 0.0 && end_tps > 0.0,
+            "Open-loop rate must be positive, got {} -> {} TPS",
+            start_tps,
+            end_tps
+        );
+        anyhow::ensure!(
+            duration > Duration::from_secs(0),
+            "Open-loop test duration must be positive"
+        );
+
+        Ok(Self {
+            start_tps,
+            end_tps,
+            duration,
+        })
+    }
+
+    /// Returns the ramp configured for the open-loop mode, or `None` if the mode is disabled.
+    pub fn from_config(config: &LoadtestConfig) -> anyhow::Result<Option<Self>> {
+        if !config.open_loop {
+            return Ok(None);
+        }
+
+        Self::new(
+            config.open_loop_start_tps,
+            config.open_loop_end_tps,
+            Duration::from_secs(config.open_loop_duration_secs),
+        )
+        .map(Some)
+    }
+
+    pub fn start_tps(&self) -> f64 {
+        self.start_tps
+    }
+
+    pub fn end_tps(&self) -> f64 {
+        self.end_tps
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Returns the target rate at the given moment of the test.
+    pub fn rate_at(&self, elapsed: Duration) -> f64 {
+        let progress = (elapsed.as_secs_f64() / self.duration.as_secs_f64()).min(1.0);
+        self.start_tps + (self.end_tps - self.start_tps) * progress
+    }
+
+    /// Returns the offsets from the start of the test at which the commands are sent.
+    pub fn send_offsets(&self) -> impl Iterator<Item = Duration> + '_ {
+        let mut next = Duration::from_secs(0);
+        std::iter::from_fn(move || {
+            if next >= self.duration {
+                return None;
+            }
+            let current = next;
+            next += Duration::from_secs_f64(1.0 / self.rate_at(current));
+            Some(current)
+        })
+    }
+
+    /// Returns the approximate amount of commands sent during the test.
+    pub fn expected_commands(&self) -> usize {
+        ((self.start_tps + self.end_tps) / 2.0 * self.duration.as_secs_f64()).ceil() as usize
+    }
+
+    /// Creates the scheduler for the ramp and the schedule that is consumed by the accounts.
+    pub fn scheduler(self) -> (CommandScheduler, CommandSchedule) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let (ready_sender, ready_receiver) = mpsc::unbounded_channel();
+        let scheduler = CommandScheduler {
+            ramp: self,
+            idle_accounts: receiver,
+            ready_accounts: ready_receiver,
+        };
+        let schedule = CommandSchedule {
+            idle_accounts: sender,
+            ready: Some(ready_sender),
+        };
+        (scheduler, schedule)
+    }
+}
+
+/// Amounts of the commands issued by the scheduler.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScheduleSummary {
+    /// Commands handed to the accounts.
+    pub sent: usize,
+    /// Commands dropped since all the accounts were busy at the moment they were scheduled at.
+    pub dropped: usize,
+}
+
+/// Entity that issues the commands at the rate of the ramp.
+#[derive(Debug)]
+pub struct CommandScheduler {
+    ramp: TpsRamp,
+    /// Accounts waiting for the next command, each one is notified with the moment its command is scheduled at.
+    idle_accounts: mpsc::UnboundedReceiver<oneshot::Sender<Instant>>,
+    /// Notifications from the accounts that have completed their preparation.
+    ready_accounts: mpsc::UnboundedReceiver<()>,
+}
+
+impl CommandScheduler {
+    /// Waits until every account either is ready to take the commands or has given up,
+    /// and returns the amount of the ready accounts.
+    ///
+    /// The ramp must not start earlier: commands scheduled while the accounts are still changing
+    /// their public keys would be dropped and inflate the failure rate of the test.
+    pub async fn wait_for_accounts(&mut self) -> usize {
+        let mut ready = 0;
+        // The channel is closed once every copy of the schedule has been either marked as ready or dropped.
+        while self.ready_accounts.recv().await.is_some() {
+            ready += 1;
+        }
+        ready
+    }
+
+    /// Issues the commands until the end of the ramp.
+    ///
+    /// Every command is handed to a free account right at the moment it's scheduled at, so the account sends it
+    /// immediately and waits for its execution on its own. Commands are never queued: if all the accounts are
+    /// busy, the command is dropped and reported as failed, so the saturation of the server is visible in the report
+    /// instead of silently lowering the rate.
+    pub async fn run(mut self, start: Instant, mut report_sink: Sender<Report>) -> ScheduleSummary {
+        let mut summary = ScheduleSummary::default();
+        for offset in self.ramp.send_offsets() {
+            let scheduled_at = start + offset;
+            tokio::time::sleep_until(scheduled_at.into()).await;
+
+            match self.next_idle_account(scheduled_at) {
+                Some(true) => summary.sent += 1,
+                Some(false) => {
+                    summary.dropped += 1;
+                    let report = ReportBuilder::new()
+                        .label(ReportLabel::failed("No free account to send the command"))
+                        .action(ActionType::Dropped)
+                        .finish();
+                    if let Err(_err) = report_sink.send(report).await {
+                        vlog::trace!("Failed to send report to the sink");
+                    }
+                }
+                // All the accounts are gone, there is no one to send the commands.
+                None => break,
+            }
+        }
+
+        vlog::info!(
+            "Open-loop schedule is completed, {} commands were sent, {} dropped",
+            summary.sent,
+            summary.dropped
+        );
+        summary
+    }
+
+    /// Hands the command to the first free account.
+    /// Returns `Some(false)` if all the accounts are busy and `None` if there are no accounts left.
+    fn next_idle_account(&mut self, scheduled_at: Instant) -> Option<bool> {
+        loop {
+            match self.idle_accounts.try_recv() {
+                // The account may have finished while waiting, then the next one is taken.
+                Ok(account) => {
+                    if account.send(scheduled_at).is_ok() {
+                        return Some(true);
+                    }
+                }
+                Err(TryRecvError::Empty) => return Some(false),
+                Err(TryRecvError::Disconnected) => return None,
+            }
+        }
+    }
+}
+
+/// Handle of the schedule used by the accounts to take the commands.
+#[derive(Debug, Clone)]
+pub struct CommandSchedule {
+    idle_accounts: mpsc::UnboundedSender<oneshot::Sender<Instant>>,
+    /// Dropped once the account is ready, so the scheduler knows when all the accounts are prepared.
+    ready: Option<mpsc::UnboundedSender<()>>,
+}
+
+impl CommandSchedule {
+    /// Notifies the scheduler that the account is prepared to take the commands.
+    pub fn mark_ready(&mut self) {
+        if let Some(ready) = self.ready.take() {
+            ready.send(()).ok();
+        }
+    }
+
+    /// Marks the account as free and waits for the next command assigned to it.
+    /// Returns the moment the command was scheduled at, or `None` once the schedule is completed.
+    pub async fn next(&self) -> Option<Instant> {
+        let (sender, receiver) = oneshot::channel();
+        self.idle_accounts.send(sender).ok()?;
+        receiver.await.ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+
+    use super::*;
+
+    #[test]
+    fn ramp_rate() {
+        let ramp = TpsRamp::new(10.0, 20.0, Duration::from_secs(100)).unwrap();
+
+        assert_eq!(ramp.rate_at(Duration::from_secs(0)), 10.0);
+        assert_eq!(ramp.rate_at(Duration::from_secs(50)), 15.0);
+        assert_eq!(ramp.rate_at(Duration::from_secs(100)), 20.0);
+        assert_eq!(ramp.rate_at(Duration::from_secs(200)), 20.0);
+    }
+
+    #[test]
+    fn ramp_send_offsets() {
+        let constant = TpsRamp::new(4.0, 4.0, Duration::from_secs(10)).unwrap();
+        let offsets: Vec<_> = constant.send_offsets().collect();
+        assert_eq!(offsets.len(), constant.expected_commands());
+        assert_eq!(offsets[1], Duration::from_millis(250));
+        assert!(offsets.last().unwrap() < &constant.duration());
+
+        let ramp = TpsRamp::new(1.0, 100.0, Duration::from_secs(60)).unwrap();
+        let offsets: Vec<_> = ramp.send_offsets().collect();
+        // The discrete schedule follows the rate with a small error.
+        let expected = ramp.expected_commands() as f64;
+        assert!((offsets.len() as f64 - expected).abs() / expected < 0.05);
+        // Intervals between the commands only decrease with the growing rate.
+        let intervals: Vec<_> = offsets.windows(2).map(|pair| pair[1] - pair[0]).collect();
+        assert!(intervals.windows(2).all(|pair| pair[1] <= pair[0]));
+    }
+
+    #[test]
+    fn ramp_validation() {
+        assert!(TpsRamp::new(0.0, 10.0, Duration::from_secs(10)).is_err());
+        assert!(TpsRamp::new(10.0, -1.0, Duration::from_secs(10)).is_err());
+        assert!(TpsRamp::new(10.0, 10.0, Duration::from_secs(0)).is_err());
+    }
+
+    #[tokio::test]
+    async fn commands_are_dropped_when_accounts_are_busy() {
+        let ramp = TpsRamp::new(1000.0, 1000.0, Duration::from_millis(20)).unwrap();
+        let (scheduler, schedule) = ramp.scheduler();
+        let (report_sender, report_receiver) = futures::channel::mpsc::channel(256);
+
+        // The account exists, but never becomes free.
+        let summary = scheduler.run(Instant::now(), report_sender).await;
+        assert_eq!(summary.sent, 0);
+        assert_eq!(summary.dropped, ramp.expected_commands());
+
+        let reports: Vec<_> = report_receiver.collect().await;
+        assert_eq!(reports.len(), summary.dropped);
+        assert!(reports
+            .iter()
+            .all(|report| report.action == ActionType::Dropped
+                && matches!(report.label, ReportLabel::ActionFailed { .. })));
+        assert!(schedule.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn scheduler_waits_for_accounts() {
+        let ramp = TpsRamp::new(100.0, 100.0, Duration::from_millis(100)).unwrap();
+        let (mut scheduler, schedule) = ramp.scheduler();
+        let preparation = Duration::from_millis(50);
+
+        let mut preparing = schedule.clone();
+        let account = tokio::spawn(async move {
+            tokio::time::sleep(preparation).await;
+            preparing.mark_ready();
+            // The account stays alive after it's ready.
+            preparing
+        });
+        // The account that gives up without being ready doesn't block the start.
+        let failed = schedule.clone();
+        drop(failed);
+        drop(schedule);
+
+        let start = Instant::now();
+        assert_eq!(scheduler.wait_for_accounts().await, 1);
+        assert!(start.elapsed() >= preparation);
+        account.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn commands_are_sent_by_free_accounts() {
+        let ramp = TpsRamp::new(100.0, 100.0, Duration::from_millis(100)).unwrap();
+        let (scheduler, schedule) = ramp.scheduler();
+        let (report_sender, _report_receiver) = futures::channel::mpsc::channel(256);
+
+        let account = tokio::spawn(async move {
+            let mut received = 0;
+            while schedule.next().await.is_some() {
+                received += 1;
+            }
+            received
+        });
+        let summary = scheduler.run(Instant::now(), report_sender).await;
+
+        assert!(summary.sent > 0);
+        assert_eq!(summary.sent + summary.dropped, ramp.expected_commands());
+        assert_eq!(account.await.unwrap(), summary.sent);
+    }
+}
@@ -0,0 +1,278 @@
+//! Recording of the requests sent to the server, so the run can be replayed later.
+//!
+//! Requests are recorded exactly as they were sent: transactions with their signatures (including the corrupted ones)
+//! and the API requests with their parameters, along with the time they were sent at and whether the server accepted
+//! them. Priority operations are sent to Ethereum rather than to the server, so they are not recorded.
+
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
+    time::Instant,
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::{sync::mpsc, task::JoinHandle};
+
+use zksync::{error::ClientError, provider::Provider, RpcProvider};
+use zksync_types::{
+    tx::{PackedEthSignature, TxHash},
+    Address, Swap, ZkSyncTx,
+};
+
+use crate::command::ApiRequestCommand;
+
+/// Request sent to the server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum RecordedRequest {
+    Tx {
+        tx: ZkSyncTx,
+        eth_signature: Option<PackedEthSignature>,
+    },
+    Batch {
+        txs: Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
+        eth_signature: Option<PackedEthSignature>,
+    },
+    Swap {
+        swap: Box<Swap>,
+        eth_signature: Option<PackedEthSignature>,
+        orders_eth_signatures: (Option<PackedEthSignature>, Option<PackedEthSignature>),
+    },
+    Api {
+        request: ApiRequestCommand,
+    },
+}
+
+/// Single line of the recording.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedEntry {
+    /// Time the request was sent at, counted from the start of the recording.
+    pub offset_ms: u64,
+    /// Address of the account that sent the request.
+    pub sender: Address,
+    pub request: RecordedRequest,
+    /// Error returned by the server, if the request was not accepted.
+    pub error: Option<String>,
+}
+
+/// Handle of the recording, shared by all the accounts.
+///
+/// Entries are sent to the writer task through a channel, so recording doesn't block the accounts on the file IO.
+/// The recording is completed once all the handles are dropped.
+#[derive(Debug, Clone)]
+pub struct Recorder {
+    start: Instant,
+    sender: mpsc::UnboundedSender<RecordedEntry>,
+}
+
+impl Recorder {
+    /// Creates the recording file and spawns the task writing the entries into it.
+    pub fn new(path: impl AsRef<Path>) -> anyhow::Result<(Self, JoinHandle<()>)> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        let (sender, mut receiver) = mpsc::unbounded_channel::<RecordedEntry>();
+
+        let writer_task = tokio::spawn(async move {
+            while let Some(entry) = receiver.recv().await {
+                let result = serde_json::to_writer(&mut writer, &entry)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|_| writer.write_all(b"\n").map_err(anyhow::Error::from));
+                if let Err(err) = result {
+                    vlog::error!("Failed to record the request: {}", err);
+                }
+            }
+            if let Err(err) = writer.flush() {
+                vlog::error!("Failed to flush the recording: {}", err);
+            }
+        });
+
+        let recorder = Self {
+            start: Instant::now(),
+            sender,
+        };
+        Ok((recorder, writer_task))
+    }
+
+    /// Records the request sent at `sent_at`.
+    pub fn record(
+        &self,
+        sender: Address,
+        sent_at: Instant,
+        request: RecordedRequest,
+        error: Option<String>,
+    ) {
+        let entry = RecordedEntry {
+            offset_ms: sent_at.saturating_duration_since(self.start).as_millis() as u64,
+            sender,
+            request,
+            error,
+        };
+        if self.sender.send(entry).is_err() {
+            vlog::warn!("Request was sent after the recording was completed");
+        }
+    }
+}
+
+/// Loads the recorded entries, ordered by the time they were sent at.
+pub fn load_recording(path: impl AsRef<Path>) -> anyhow::Result<Vec<RecordedEntry>> {
+    let mut entries = Vec::new();
+    for (idx, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry = serde_json::from_str(&line).map_err(|err| {
+            anyhow::anyhow!("Invalid recording entry on line {}: {}", idx + 1, err)
+        })?;
+        entries.push(entry);
+    }
+    // Entries are written once the server responds, so the ones sent concurrently may be out of order.
+    entries.sort_by_key(|entry: &RecordedEntry| entry.offset_ms);
+    Ok(entries)
+}
+
+/// Provider that records the transactions sent through it.
+#[derive(Debug, Clone)]
+pub struct RecordingProvider {
+    provider: RpcProvider,
+    recorder: Option<Recorder>,
+    address: Address,
+}
+
+impl RecordingProvider {
+    pub fn new(provider: RpcProvider, recorder: Option<Recorder>, address: Address) -> Self {
+        Self {
+            provider,
+            recorder,
+            address,
+        }
+    }
+
+    /// Returns the underlying provider, e.g. to wait for the sent transaction.
+    pub fn provider(&self) -> RpcProvider {
+        self.provider.clone()
+    }
+
+    pub async fn send_tx(
+        &self,
+        tx: ZkSyncTx,
+        eth_signature: Option<PackedEthSignature>,
+    ) -> Result<TxHash, ClientError> {
+        let request = self.recorder.as_ref().map(|_| RecordedRequest::Tx {
+            tx: tx.clone(),
+            eth_signature: eth_signature.clone(),
+        });
+        let sent_at = Instant::now();
+        let result = self.provider.send_tx(tx, eth_signature).await;
+        self.record(request, sent_at, &result);
+        result
+    }
+
+    pub async fn send_txs_batch(
+        &self,
+        txs: Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
+        eth_signature: Option<PackedEthSignature>,
+    ) -> Result<Vec<TxHash>, ClientError> {
+        let request = self.recorder.as_ref().map(|_| RecordedRequest::Batch {
+            txs: txs.clone(),
+            eth_signature: eth_signature.clone(),
+        });
+        let sent_at = Instant::now();
+        let result = self.provider.send_txs_batch(txs, eth_signature).await;
+        self.record(request, sent_at, &result);
+        result
+    }
+
+    pub async fn send_swap(
+        &self,
+        swap: Swap,
+        eth_signature: Option<PackedEthSignature>,
+        orders_eth_signatures: (Option<PackedEthSignature>, Option<PackedEthSignature>),
+    ) -> Result<TxHash, ClientError> {
+        let request = self.recorder.as_ref().map(|_| RecordedRequest::Swap {
+            swap: Box::new(swap.clone()),
+            eth_signature: eth_signature.clone(),
+            orders_eth_signatures: orders_eth_signatures.clone(),
+        });
+        let sent_at = Instant::now();
+        let result = self
+            .provider
+            .send_swap(swap, eth_signature, orders_eth_signatures)
+            .await;
+        self.record(request, sent_at, &result);
+        result
+    }
+
+    fn record<T>(
+        &self,
+        request: Option<RecordedRequest>,
+        sent_at: Instant,
+        result: &Result<T, ClientError>,
+    ) {
+        if let (Some(recorder), Some(request)) = (&self.recorder, request) {
+            let error = result.as_ref().err().map(ToString::to_string);
+            recorder.record(self.address, sent_at, request, error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::ApiType;
+
+    #[tokio::test]
+    async fn recording_roundtrip() {
+        let path =
+            std::env::temp_dir().join(format!("loadnext-recording-{}", rand::random::<u64>()));
+        let (recorder, writer_task) = Recorder::new(&path).unwrap();
+
+        let requests = [
+            ApiRequestCommand::Tokens {
+                api: ApiType::JsonRpc,
+            },
+            ApiRequestCommand::TxStatus {
+                api: ApiType::RestV02,
+                tx_hash: Some(TxHash::default()),
+            },
+        ];
+        let start = Instant::now();
+        // The second request is sent earlier, but recorded later.
+        recorder.record(
+            Address::repeat_byte(1),
+            start + std::time::Duration::from_millis(20),
+            RecordedRequest::Api {
+                request: requests[0].clone(),
+            },
+            None,
+        );
+        recorder.record(
+            Address::repeat_byte(2),
+            start,
+            RecordedRequest::Api {
+                request: requests[1].clone(),
+            },
+            Some("error".into()),
+        );
+        drop(recorder);
+        writer_task.await.unwrap();
+
+        let entries = load_recording(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].offset_ms < entries[1].offset_ms);
+        assert_eq!(entries[0].sender, Address::repeat_byte(2));
+        assert_eq!(entries[0].error.as_deref(), Some("error"));
+        assert!(matches!(
+            &entries[0].request,
+            RecordedRequest::Api {
+                request: ApiRequestCommand::TxStatus {
+                    tx_hash: Some(_),
+                    ..
+                }
+            }
+        ));
+        assert!(entries[1].error.is_none());
+    }
+}
@@ -0,0 +1,277 @@
+//! Replay of the recorded run: the recorded requests are re-submitted to the server at the recorded
+//! (or scaled) timing, and the acceptance of each request is compared with the recorded one.
+//!
+//! Transactions are replayed with their original signatures and nonces, thus the server state must match the one
+//! the run was recorded against, e.g. the replay must be made against a fresh server with the same master account.
+//! Requests are sent without waiting for the previous ones to be committed, so a replay that is too fast may have
+//! the transactions rejected due to the nonces that are not yet committed.
+
+use std::{
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use futures::{
+    channel::{
+        mpsc::{self, Sender},
+        oneshot,
+    },
+    future::join_all,
+    SinkExt,
+};
+
+use zksync::RpcProvider;
+use zksync_api_client::rest::client::Client as RestClient;
+use zksync_types::ZkSyncTx;
+
+use crate::{
+    account::send_api_request,
+    config::LoadtestConfig,
+    recorder::{load_recording, RecordedEntry, RecordedRequest, RecordingProvider},
+    report::{ActionType, ApiActionType, Report, ReportBuilder, ReportLabel, TxActionType},
+    report_collector::{LoadtestResult, ReportCollector},
+};
+
+/// Returns the type of the action the request is reported as.
+pub fn action_type(request: &RecordedRequest) -> ActionType {
+    match request {
+        RecordedRequest::Tx { tx, .. } => {
+            let action = match tx {
+                ZkSyncTx::Transfer(_) => TxActionType::Transfer,
+                ZkSyncTx::Withdraw(_) => TxActionType::Withdraw,
+                ZkSyncTx::Close(_) => unreachable!("Close operations are not sent by the loadtest"),
+                ZkSyncTx::ChangePubKey(_) => TxActionType::ChangePubKey,
+                ZkSyncTx::ForcedExit(_) => TxActionType::ForcedExit,
+                ZkSyncTx::MintNFT(_) => TxActionType::MintNFT,
+                ZkSyncTx::Swap(_) => TxActionType::Swap,
+                ZkSyncTx::WithdrawNFT(_) => TxActionType::WithdrawNFT,
+            };
+            action.into()
+        }
+        RecordedRequest::Batch { txs, .. } => ActionType::Batch {
+            batch_size: txs.len(),
+        },
+        RecordedRequest::Swap { .. } => TxActionType::Swap.into(),
+        RecordedRequest::Api { request } => ApiActionType::from(request.clone()).into(),
+    }
+}
+
+/// Returns the offsets from the start of the replay at which the entries are re-submitted.
+/// The first entry is sent right away, since the recording includes the preparation of the master account.
+pub fn replay_offsets(entries: &[RecordedEntry], speed: f64) -> Vec<Duration> {
+    let first_offset = entries
+        .first()
+        .map(|entry| entry.offset_ms)
+        .unwrap_or_default();
+    entries
+        .iter()
+        .map(|entry| {
+            let offset = Duration::from_millis(entry.offset_ms - first_offset);
+            offset.div_f64(speed)
+        })
+        .collect()
+}
+
+/// Entity that re-submits the recorded requests and reports whether the server handles them as in the recorded run.
+///
+/// Each request is reported as done if it's accepted or rejected the same way it was in the recorded run,
+/// and as failed otherwise. The time of the report is the latency of the request.
+#[derive(Debug)]
+pub struct Replayer {
+    config: LoadtestConfig,
+    entries: Vec<RecordedEntry>,
+    provider: RpcProvider,
+    rest_client: RestClient,
+}
+
+impl Replayer {
+    pub fn new(config: LoadtestConfig, path: &str) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            config.replay_speed > 0.0,
+            "Replay speed must be positive, got {}",
+            config.replay_speed
+        );
+        let entries = load_recording(path)?;
+        vlog::info!("Loaded {} recorded requests from {}", entries.len(), path);
+
+        let provider = RpcProvider::from_addr_and_network(
+            &config.zksync_rpc_addr,
+            zksync::Network::from_str(&config.eth_network).expect("Invalid network name"),
+        );
+        let rest_client = RestClient::new(config.zksync_rest_addr.clone());
+
+        Ok(Self {
+            config,
+            entries,
+            provider,
+            rest_client,
+        })
+    }
+
+    /// Re-submits all the recorded requests and returns the resolution of the report collector.
+    pub async fn run(self) -> LoadtestResult {
+        let (report_sender, report_receiver) = mpsc::channel(256);
+        let (load_start_sender, load_start_receiver) = oneshot::channel();
+        let report_collector =
+            ReportCollector::new(report_receiver, &self.config, None, load_start_receiver);
+        let report_collector_future = tokio::spawn(report_collector.run());
+
+        vlog::info!(
+            "Replaying the recorded requests at {}x speed",
+            self.config.replay_speed
+        );
+        let offsets = replay_offsets(&self.entries, self.config.replay_speed);
+        let start = Instant::now();
+        load_start_sender.send(start).ok();
+
+        let mut request_futures = Vec::with_capacity(self.entries.len());
+        for (entry, offset) in self.entries.into_iter().zip(offsets) {
+            tokio::time::sleep_until((start + offset).into()).await;
+            request_futures.push(tokio::spawn(Self::replay_entry(
+                self.provider.clone(),
+                self.rest_client.clone(),
+                self.config.main_token.clone(),
+                entry,
+                report_sender.clone(),
+            )));
+        }
+        drop(report_sender);
+
+        join_all(request_futures).await;
+        vlog::info!("All the recorded requests are replayed");
+
+        report_collector_future
+            .await
+            .unwrap_or(LoadtestResult::TestFailed)
+    }
+
+    async fn replay_entry(
+        provider: RpcProvider,
+        rest_client: RestClient,
+        main_token: String,
+        entry: RecordedEntry,
+        mut report_sink: Sender<Report>,
+    ) {
+        let action = action_type(&entry.request);
+        // Requests are not recorded once again.
+        let provider = RecordingProvider::new(provider, None, entry.sender);
+
+        let sent_at = Instant::now();
+        let error = match entry.request {
+            RecordedRequest::Tx { tx, eth_signature } => provider
+                .send_tx(tx, eth_signature)
+                .await
+                .err()
+                .map(|err| err.to_string()),
+            RecordedRequest::Batch { txs, eth_signature } => provider
+                .send_txs_batch(txs, eth_signature)
+                .await
+                .err()
+                .map(|err| err.to_string()),
+            RecordedRequest::Swap {
+                swap,
+                eth_signature,
+                orders_eth_signatures,
+            } => provider
+                .send_swap(*swap, eth_signature, orders_eth_signatures)
+                .await
+                .err()
+                .map(|err| err.to_string()),
+            RecordedRequest::Api { request } => {
+                let provider = provider.provider();
+                match send_api_request(&provider, &rest_client, &main_token, &request).await {
+                    Ok(ReportLabel::ActionFailed { error }) => Some(error),
+                    Ok(_) => None,
+                    Err(err) => Some(err.to_string()),
+                }
+            }
+        };
+        let time = sent_at.elapsed();
+
+        let label = match (entry.error, error) {
+            (None, None) | (Some(_), Some(_)) => ReportLabel::done(),
+            (None, Some(error)) => ReportLabel::failed(&format!(
+                "Request was accepted in the recorded run, but got rejected: {}",
+                error
+            )),
+            (Some(error), None) => ReportLabel::failed(&format!(
+                "Request was rejected in the recorded run ({}), but got accepted",
+                error
+            )),
+        };
+        let report = ReportBuilder::new()
+            .reporter(entry.sender)
+            .label(label)
+            .action(action)
+            .time(time)
+            .finish();
+
+        if let Err(_err) = report_sink.send(report).await {
+            // It's not that important if report will be skipped.
+            vlog::trace!("Failed to send report to the sink");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{tx::TxHash, Address};
+
+    use super::*;
+    use crate::command::{ApiRequestCommand, ApiType};
+
+    fn api_entry(offset_ms: u64, request: ApiRequestCommand) -> RecordedEntry {
+        RecordedEntry {
+            offset_ms,
+            sender: Address::zero(),
+            request: RecordedRequest::Api { request },
+            error: None,
+        }
+    }
+
+    #[test]
+    fn scaled_offsets() {
+        let request = ApiRequestCommand::Tokens {
+            api: ApiType::JsonRpc,
+        };
+        let entries: Vec<_> = [5000, 5000, 6000, 9000]
+            .iter()
+            .map(|&offset_ms| api_entry(offset_ms, request.clone()))
+            .collect();
+
+        let offsets: Vec<_> = replay_offsets(&entries, 1.0)
+            .into_iter()
+            .map(|offset| offset.as_millis())
+            .collect();
+        assert_eq!(offsets, [0, 0, 1000, 4000]);
+
+        let offsets: Vec<_> = replay_offsets(&entries, 4.0)
+            .into_iter()
+            .map(|offset| offset.as_millis())
+            .collect();
+        assert_eq!(offsets, [0, 0, 250, 1000]);
+
+        assert!(replay_offsets(&[], 2.0).is_empty());
+    }
+
+    #[test]
+    fn api_action_types() {
+        let entry = api_entry(
+            0,
+            ApiRequestCommand::TxStatus {
+                api: ApiType::RestV02,
+                tx_hash: Some(TxHash::default()),
+            },
+        );
+        assert_eq!(
+            action_type(&entry.request),
+            ActionType::Api(ApiActionType::RestTxStatus)
+        );
+
+        let batch = RecordedRequest::Batch {
+            txs: Vec::new(),
+            eth_signature: None,
+        };
+        assert_eq!(action_type(&batch), ActionType::Batch { batch_size: 0 });
+    }
+}
@@ -4,7 +4,7 @@ use zksync_types::Address;
 
 use crate::{
     all::All,
-    command::{ApiRequestCommand, Command, TxType},
+    command::{ApiRequestCommand, ApiRequestType, ApiType, Command, TxType},
     constants::MAX_BATCH_SIZE,
 };
 
@@ -26,6 +26,7 @@ pub struct Report {
     /// Amount of retries that it took the wallet to finish the action.
     pub retries: usize,
     /// Duration of the latest execution attempt.
+    /// In the open-loop mode, it's counted from the moment the action was scheduled at.
     pub time: Duration,
 }
 
@@ -119,6 +120,10 @@ pub enum TxActionType {
     ChangePubKey,
     FullExit,
     Deposit,
+    MintNFT,
+    TransferNFT,
+    WithdrawNFT,
+    Swap,
 }
 
 impl All for TxActionType {
@@ -130,6 +135,10 @@ impl All for TxActionType {
             TxActionType::ChangePubKey,
             TxActionType::FullExit,
             TxActionType::Deposit,
+            TxActionType::MintNFT,
+            TxActionType::TransferNFT,
+            TxActionType::WithdrawNFT,
+            TxActionType::Swap,
         ];
 
         ALL
@@ -144,36 +153,75 @@ impl From<TxType> for TxActionType {
             TxType::WithdrawToSelf | TxType::WithdrawToOther => Self::Withdraw,
             TxType::FullExit => Self::FullExit,
             TxType::ChangePubKey => Self::ChangePubKey,
+            TxType::ForcedExit => Self::ForcedExit,
+            TxType::MintNFT => Self::MintNFT,
+            TxType::TransferNFT => Self::TransferNFT,
+            TxType::WithdrawNFT => Self::WithdrawNFT,
+            TxType::Swap => Self::Swap,
         }
     }
 }
 
 /// Denotes the type of the performed API action.
-/// Currently loadtest doesn't do any API actions (only as part of the transactions flow), thus enum exists
-/// for the future.
+/// Every endpoint is a separate action, so the metrics are collected for each API separately.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum ApiActionType {}
+pub enum ApiActionType {
+    RpcAccountInfo,
+    RestAccountInfo,
+    RpcTxInfo,
+    RestTxStatus,
+    RestBlocks,
+    RpcTokens,
+    RestTokens,
+    RpcTxFee,
+    RestTxFee,
+    RestNftInfo,
+}
 
 impl All for ApiActionType {
     fn all() -> &'static [Self] {
-        const ALL: &[ApiActionType] = &[];
+        const ALL: &[ApiActionType] = &[
+            ApiActionType::RpcAccountInfo,
+            ApiActionType::RestAccountInfo,
+            ApiActionType::RpcTxInfo,
+            ApiActionType::RestTxStatus,
+            ApiActionType::RestBlocks,
+            ApiActionType::RpcTokens,
+            ApiActionType::RestTokens,
+            ApiActionType::RpcTxFee,
+            ApiActionType::RestTxFee,
+            ApiActionType::RestNftInfo,
+        ];
 
         ALL
     }
 }
 
 impl From<ApiRequestCommand> for ApiActionType {
-    fn from(_: ApiRequestCommand) -> Self {
-        todo!()
+    fn from(command: ApiRequestCommand) -> Self {
+        match (command.request_type(), command.api()) {
+            (ApiRequestType::AccountInfo, ApiType::JsonRpc) => Self::RpcAccountInfo,
+            (ApiRequestType::AccountInfo, ApiType::RestV02) => Self::RestAccountInfo,
+            (ApiRequestType::TxStatus, ApiType::JsonRpc) => Self::RpcTxInfo,
+            (ApiRequestType::TxStatus, ApiType::RestV02) => Self::RestTxStatus,
+            (ApiRequestType::BlocksPagination, _) => Self::RestBlocks,
+            (ApiRequestType::Tokens, ApiType::JsonRpc) => Self::RpcTokens,
+            (ApiRequestType::Tokens, ApiType::RestV02) => Self::RestTokens,
+            (ApiRequestType::TxFee, ApiType::JsonRpc) => Self::RpcTxFee,
+            (ApiRequestType::TxFee, ApiType::RestV02) => Self::RestTxFee,
+            (ApiRequestType::NftInfo, _) => Self::RestNftInfo,
+        }
     }
 }
 
 /// Generic wrapper of all the actions that can be done in loadtest.
+/// `Dropped` is the command that was not sent in the open-loop mode, since all the accounts were busy.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ActionType {
     Tx(TxActionType),
     Api(ApiActionType),
     Batch { batch_size: usize },
+    Dropped,
 }
 
 impl From<TxActionType> for ActionType {
@@ -1,21 +1,58 @@
-use futures::{channel::mpsc::Receiver, StreamExt};
+use std::{
+    fs::File,
+    time::{Duration, Instant},
+};
+
+use futures::{
+    channel::{mpsc::Receiver, oneshot},
+    StreamExt,
+};
 use operation_results_collector::OperationResultsCollector;
+use serde::Serialize;
 
 use crate::{
+    config::LoadtestConfig,
+    open_loop::TpsRamp,
     report::{Report, ReportLabel},
-    report_collector::metrics_collector::MetricsCollector,
+    report_collector::{
+        metrics_collector::MetricsCollector,
+        slo_checker::{SloCheck, SloChecker},
+        timeline_collector::{Timeline, TimelineCollector},
+    },
 };
 
 mod metrics_collector;
 mod operation_results_collector;
+mod slo_checker;
+mod timeline_collector;
 
 /// Decision on whether loadtest considered passed or failed.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize)]
 pub enum LoadtestResult {
     TestPassed,
     TestFailed,
 }
 
+/// Parameters of the open-loop load.
+#[derive(Debug, Serialize)]
+struct OpenLoopReport {
+    start_tps: f64,
+    end_tps: f64,
+    duration_secs: u64,
+}
+
+/// Machine-readable report of the loadtest, written to the configured `report_path`.
+#[derive(Debug, Serialize)]
+struct JsonReport<'a> {
+    result: LoadtestResult,
+    open_loop: Option<OpenLoopReport>,
+    successes: u64,
+    skipped: u64,
+    failures: u64,
+    slo: &'a [SloCheck],
+    timeline: &'a Timeline,
+}
+
 /// ReportCollector is an entity capable of analyzing everything that happens in the loadtest.
 ///
 /// It is designed to be separated from the actual execution, so that logic of the execution does not
@@ -25,13 +62,14 @@ pub enum LoadtestResult {
 /// Currently, only the following collectors are used:
 ///
 /// - MetricsCollector, which builds time distribution histograms for each kind of performed action.
-/// - OperationResultsCollector, a primitive collector that counts the amount of failures and decides whether
-///   test is passed.
+/// - OperationResultsCollector, a primitive collector that counts the amount of failures.
+/// - TimelineCollector, which splits the load into time windows and reports the latencies and the throughput
+///   for each of them.
+///
+/// Whether the test is passed is decided by the `SloChecker`.
 ///
 /// Other possible collectors that can be implemented:
 ///
-/// - ScriptCollector, which records all the actions (including wallet private keys and signatures), which makes it
-///   possible to reproduce test once again.
 /// - RetryCollector, which analyzes the average amount of retries that have to be made in order to make operation
 ///   succeed.
 /// - PrometheusCollector, which exposes the ongoing loadtest results to grafana via prometheus.
@@ -43,24 +81,49 @@ pub enum LoadtestResult {
 /// queue uncontrollable growth.
 #[derive(Debug)]
 pub struct ReportCollector {
-    allowed_percent: u8,
     reports_stream: Receiver<Report>,
+    /// Start of the open-loop load: actions completed before it are not included into the timeline.
+    /// If the sender is dropped, the load is considered started along with the collector.
+    load_start: oneshot::Receiver<Instant>,
+    ramp: Option<TpsRamp>,
+    report_window: Duration,
+    report_path: Option<String>,
     metrics_collector: MetricsCollector,
     operations_results_collector: OperationResultsCollector,
+    timeline_collector: TimelineCollector,
+    slo_checker: SloChecker,
 }
 
 impl ReportCollector {
-    pub fn new(reports_stream: Receiver<Report>, allowed_percent: u8) -> Self {
-        assert!(allowed_percent < 100, "Allowed percent more than 100");
+    pub fn new(
+        reports_stream: Receiver<Report>,
+        config: &LoadtestConfig,
+        ramp: Option<TpsRamp>,
+        load_start: oneshot::Receiver<Instant>,
+    ) -> Self {
+        assert!(
+            config.allowed_percent < 100,
+            "Allowed percent more than 100"
+        );
+        assert!(
+            config.report_window_secs > 0,
+            "Report window must be at least 1 second"
+        );
         Self {
-            allowed_percent,
             reports_stream,
+            load_start,
+            ramp,
+            report_window: Duration::from_secs(config.report_window_secs),
+            report_path: config.report_path.clone(),
             metrics_collector: MetricsCollector::new(),
             operations_results_collector: OperationResultsCollector::new(),
+            timeline_collector: TimelineCollector::new(),
+            slo_checker: SloChecker::new(config),
         }
     }
 
     pub async fn run(mut self) -> LoadtestResult {
+        let collector_start = Instant::now();
         while let Some(report) = self.reports_stream.next().await {
             vlog::trace!("Report: {:?}", &report);
 
@@ -71,6 +134,7 @@ impl ReportCollector {
             }
 
             self.operations_results_collector.add_status(&report.label);
+            self.timeline_collector.add_report(&report);
 
             // Report failure, if it exists.
             if let ReportLabel::ActionFailed { error } = &report.label {
@@ -80,20 +144,70 @@ impl ReportCollector {
 
         // All the receivers are gone, it's likely the end of the test.
         // Now we can output the statistics.
+        let load_end = Instant::now();
+        let load_start = match self.load_start.try_recv() {
+            Ok(Some(load_start)) => load_start,
+            _ => collector_start,
+        };
+
         self.metrics_collector.report();
         self.operations_results_collector.report();
+        let timeline = self.timeline_collector.timeline(
+            load_start,
+            load_end,
+            self.report_window,
+            self.ramp.as_ref(),
+        );
+        timeline.report();
+
+        let slo_checks = self.slo_checker.check(
+            &self.operations_results_collector,
+            &self.timeline_collector,
+            load_start,
+            &timeline,
+        );
+        SloChecker::report(&slo_checks);
 
-        self.final_resolution()
+        let result = Self::final_resolution(&slo_checks);
+        if let Some(path) = &self.report_path {
+            if let Err(err) = self.write_json_report(path, result, &slo_checks, &timeline) {
+                vlog::error!("Failed to write the JSON report to {}: {}", path, err);
+            }
+        }
+
+        result
     }
 
-    fn final_resolution(&self) -> LoadtestResult {
-        let failure_percent = (self.operations_results_collector.failures() as f64
-            / self.operations_results_collector.total() as f64)
-            * 100.0;
-        if failure_percent > self.allowed_percent as f64 {
-            LoadtestResult::TestFailed
-        } else {
+    fn final_resolution(slo_checks: &[SloCheck]) -> LoadtestResult {
+        if slo_checks.iter().all(|check| check.passed) {
             LoadtestResult::TestPassed
+        } else {
+            LoadtestResult::TestFailed
         }
     }
+
+    fn write_json_report(
+        &self,
+        path: &str,
+        result: LoadtestResult,
+        slo_checks: &[SloCheck],
+        timeline: &Timeline,
+    ) -> anyhow::Result<()> {
+        let report = JsonReport {
+            result,
+            open_loop: self.ramp.as_ref().map(|ramp| OpenLoopReport {
+                start_tps: ramp.start_tps(),
+                end_tps: ramp.end_tps(),
+                duration_secs: ramp.duration().as_secs(),
+            }),
+            successes: self.operations_results_collector.successes(),
+            skipped: self.operations_results_collector.skipped(),
+            failures: self.operations_results_collector.failures(),
+            slo: slo_checks,
+            timeline,
+        };
+        serde_json::to_writer_pretty(File::create(path)?, &report)?;
+        vlog::info!("JSON report is written to {}", path);
+        Ok(())
+    }
 }
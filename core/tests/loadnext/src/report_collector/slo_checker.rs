@@ -0,0 +1,184 @@
+use std::time::Instant;
+
+use serde::Serialize;
+
+use crate::{
+    config::LoadtestConfig,
+    report::ActionType,
+    report_collector::{
+        operation_results_collector::OperationResultsCollector,
+        timeline_collector::{Timeline, TimelineCollector},
+    },
+};
+
+/// Result of a single SLO check.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SloCheck {
+    pub name: String,
+    pub threshold: f64,
+    pub actual: f64,
+    pub passed: bool,
+}
+
+impl SloCheck {
+    fn upper_bound(name: String, threshold: f64, actual: f64) -> Self {
+        Self {
+            name,
+            threshold,
+            actual,
+            passed: actual <= threshold,
+        }
+    }
+
+    fn lower_bound(name: String, threshold: f64, actual: f64) -> Self {
+        Self {
+            name,
+            threshold,
+            actual,
+            passed: actual >= threshold,
+        }
+    }
+}
+
+/// Checker of the service level objectives, which decide whether the test is passed.
+///
+/// The share of the failed actions is always checked, while the latency and throughput
+/// thresholds are only checked if they are configured.
+#[derive(Debug, Clone)]
+pub struct SloChecker {
+    allowed_failures_percent: u8,
+    latency_percentile: u8,
+    tx_latency_ms: Option<u64>,
+    api_latency_ms: Option<u64>,
+    min_tps: Option<f64>,
+}
+
+impl SloChecker {
+    pub fn new(config: &LoadtestConfig) -> Self {
+        assert!(
+            config.slo_latency_percentile <= 100,
+            "SLO latency percentile more than 100"
+        );
+        Self {
+            allowed_failures_percent: config.allowed_percent,
+            latency_percentile: config.slo_latency_percentile,
+            tx_latency_ms: config.slo_tx_latency_ms,
+            api_latency_ms: config.slo_api_latency_ms,
+            min_tps: config.slo_min_tps,
+        }
+    }
+
+    /// Checks the outcome of the actions completed after the load `start`.
+    pub fn check(
+        &self,
+        results: &OperationResultsCollector,
+        timeline_collector: &TimelineCollector,
+        start: Instant,
+        timeline: &Timeline,
+    ) -> Vec<SloCheck> {
+        let failures_percent = if results.total() > 0 {
+            results.failures() as f64 / results.total() as f64 * 100.0
+        } else {
+            0.0
+        };
+        let mut checks = vec![SloCheck::upper_bound(
+            "Failed actions, %".into(),
+            self.allowed_failures_percent.into(),
+            failures_percent,
+        )];
+
+        let latency_thresholds = [(self.tx_latency_ms, false), (self.api_latency_ms, true)];
+        for &(threshold, is_api) in &latency_thresholds {
+            let threshold = match threshold {
+                Some(threshold) => threshold,
+                None => continue,
+            };
+            let latencies =
+                timeline_collector.latency_percentiles(start, self.latency_percentile, |action| {
+                    matches!(action, ActionType::Api(_)) == is_api
+                });
+            for (action, latency) in latencies {
+                checks.push(SloCheck::upper_bound(
+                    format!("{}: p{} latency, ms", action, self.latency_percentile),
+                    threshold as f64,
+                    latency as f64,
+                ));
+            }
+        }
+
+        if let Some(min_tps) = self.min_tps {
+            checks.push(SloCheck::lower_bound(
+                "Sustained throughput, TPS".into(),
+                min_tps,
+                timeline.sustained_tps,
+            ));
+        }
+
+        checks
+    }
+
+    pub fn report(checks: &[SloCheck]) {
+        for check in checks {
+            let status = if check.passed { "passed" } else { "FAILED" };
+            vlog::info!(
+                "SLO {}: {} (threshold {}) {}",
+                check.name,
+                check.actual,
+                check.threshold,
+                status
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::report::{ApiActionType, ReportBuilder, ReportLabel, TxActionType};
+
+    #[test]
+    fn slo_checks() {
+        let config = LoadtestConfig {
+            slo_tx_latency_ms: Some(1000),
+            slo_api_latency_ms: Some(100),
+            slo_min_tps: Some(1.0),
+            ..LoadtestConfig::default()
+        };
+        let checker = SloChecker::new(&config);
+
+        let start = Instant::now();
+        let mut results = OperationResultsCollector::new();
+        let mut timeline_collector = TimelineCollector::new();
+        let reports = [
+            (ActionType::from(TxActionType::Transfer), 900),
+            (ActionType::from(ApiActionType::RpcTokens), 200),
+            (ActionType::Batch { batch_size: 2 }, 800),
+        ];
+        for &(action, latency_ms) in &reports {
+            let report = ReportBuilder::new()
+                .label(ReportLabel::done())
+                .action(action)
+                .time(Duration::from_millis(latency_ms))
+                .finish();
+            results.add_status(&report.label);
+            timeline_collector.add_report(&report);
+        }
+        let timeline = timeline_collector.timeline(
+            start,
+            Instant::now() + Duration::from_secs(1),
+            Duration::from_secs(1),
+            None,
+        );
+
+        let checks = checker.check(&results, &timeline_collector, start, &timeline);
+        let failed: Vec<_> = checks
+            .iter()
+            .filter(|check| !check.passed)
+            .map(|check| check.name.as_str())
+            .collect();
+        assert_eq!(checks.len(), 5);
+        assert_eq!(failed, ["Api(RpcTokens): p99 latency, ms"]);
+    }
+}
@@ -0,0 +1,377 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+use crate::{
+    open_loop::TpsRamp,
+    report::{ActionType, Report, ReportLabel},
+};
+
+/// Outcome of the action, as it's counted in the timeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Done,
+    Skipped,
+    Failed,
+}
+
+impl From<&ReportLabel> for Outcome {
+    fn from(label: &ReportLabel) -> Self {
+        match label {
+            ReportLabel::ActionDone => Self::Done,
+            ReportLabel::ActionSkipped { .. } => Self::Skipped,
+            ReportLabel::ActionFailed { .. } => Self::Failed,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TimelineEntry {
+    completed_at: Instant,
+    action: ActionType,
+    outcome: Outcome,
+    latency: Duration,
+}
+
+/// Latency distribution of the successfully completed actions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LatencyStats {
+    pub count: usize,
+    pub p50_ms: u64,
+    pub p90_ms: u64,
+    pub p99_ms: u64,
+    pub max_ms: u64,
+}
+
+impl LatencyStats {
+    /// Builds the stats from the latencies sorted in ascending order.
+    fn from_sorted(latencies: &[u64]) -> Self {
+        Self {
+            count: latencies.len(),
+            p50_ms: percentile(latencies, 50),
+            p90_ms: percentile(latencies, 90),
+            p99_ms: percentile(latencies, 99),
+            max_ms: latencies.last().copied().unwrap_or_default(),
+        }
+    }
+}
+
+/// Outcomes of the actions completed within a single time window.
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowSummary {
+    /// Offset of the window start from the start of the load, in seconds.
+    pub start_secs: f64,
+    pub end_secs: f64,
+    pub done: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    /// Rate of the commands scheduled in the middle of the window, in the open-loop mode.
+    pub target_tps: Option<f64>,
+    /// Successfully completed actions per second.
+    pub throughput_tps: f64,
+    /// Latencies of each type of the action completed within the window.
+    pub actions: BTreeMap<String, LatencyStats>,
+}
+
+/// Summary of the load split into the time windows.
+#[derive(Debug, Clone, Serialize)]
+pub struct Timeline {
+    pub duration_secs: f64,
+    /// Successfully completed actions per second over the whole load.
+    pub sustained_tps: f64,
+    /// Throughput of the best window.
+    pub peak_tps: f64,
+    pub windows: Vec<WindowSummary>,
+    /// Latencies of each type of the action over the whole load.
+    pub actions: BTreeMap<String, LatencyStats>,
+}
+
+impl Timeline {
+    pub fn report(&self) {
+        vlog::info!(
+            "Throughput: {:.2} TPS sustained, {:.2} TPS peak over {:.0} seconds",
+            self.sustained_tps,
+            self.peak_tps,
+            self.duration_secs
+        );
+        for window in &self.windows {
+            let target = window
+                .target_tps
+                .map(|tps| format!(" (target {:.2} TPS)", tps))
+                .unwrap_or_default();
+            vlog::info!(
+                "[{:.0}s..{:.0}s] {:.2} TPS{}, {} done, {} skipped, {} failed",
+                window.start_secs,
+                window.end_secs,
+                window.throughput_tps,
+                target,
+                window.done,
+                window.skipped,
+                window.failed
+            );
+            for (action, stats) in &window.actions {
+                vlog::info!(
+                    "    {}: {} actions, p50 {}ms, p90 {}ms, p99 {}ms, max {}ms",
+                    action,
+                    stats.count,
+                    stats.p50_ms,
+                    stats.p90_ms,
+                    stats.p99_ms,
+                    stats.max_ms
+                );
+            }
+        }
+    }
+}
+
+/// Returns the latency at the percentile of the latencies sorted in ascending order, using the nearest-rank method.
+fn percentile(sorted_latencies: &[u64], percentile: u8) -> u64 {
+    if sorted_latencies.is_empty() {
+        return 0;
+    }
+    let rank = (sorted_latencies.len() as f64 * f64::from(percentile) / 100.0).ceil() as usize;
+    sorted_latencies[rank.clamp(1, sorted_latencies.len()) - 1]
+}
+
+/// Returns the sorted latencies of each type of the successfully completed action among the entries.
+fn sorted_latencies<'a>(
+    entries: impl Iterator<Item = &'a TimelineEntry>,
+) -> HashMap<ActionType, Vec<u64>> {
+    let mut latencies: HashMap<ActionType, Vec<u64>> = HashMap::new();
+    for entry in entries.filter(|entry| entry.outcome == Outcome::Done) {
+        latencies
+            .entry(entry.action)
+            .or_default()
+            .push(entry.latency.as_millis() as u64);
+    }
+    for action_latencies in latencies.values_mut() {
+        action_latencies.sort_unstable();
+    }
+    latencies
+}
+
+/// Builds the latency stats for each type of the action among the entries.
+fn latency_stats<'a>(
+    entries: impl Iterator<Item = &'a TimelineEntry>,
+) -> BTreeMap<String, LatencyStats> {
+    sorted_latencies(entries)
+        .into_iter()
+        .map(|(action, latencies)| {
+            (
+                format!("{:?}", action),
+                LatencyStats::from_sorted(&latencies),
+            )
+        })
+        .collect()
+}
+
+/// Collector of the action outcomes over time.
+///
+/// Unlike `MetricsCollector`, it keeps every completed action, so the exact latency percentiles
+/// can be calculated for any part of the test, e.g. to find the moment the latency starts to grow.
+#[derive(Debug, Clone, Default)]
+pub struct TimelineCollector {
+    entries: Vec<TimelineEntry>,
+}
+
+impl TimelineCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_report(&mut self, report: &Report) {
+        self.add_entry(Instant::now(), report);
+    }
+
+    fn add_entry(&mut self, completed_at: Instant, report: &Report) {
+        self.entries.push(TimelineEntry {
+            completed_at,
+            action: report.action,
+            outcome: Outcome::from(&report.label),
+            latency: report.time,
+        });
+    }
+
+    /// Returns the latency percentile of each type of the successfully completed actions that match the filter.
+    /// Only the actions completed after the `start` are taken into account.
+    pub fn latency_percentiles(
+        &self,
+        start: Instant,
+        percentile_value: u8,
+        filter: impl Fn(ActionType) -> bool,
+    ) -> BTreeMap<String, u64> {
+        let load_entries = self
+            .entries
+            .iter()
+            .filter(|entry| entry.completed_at >= start && filter(entry.action));
+        sorted_latencies(load_entries)
+            .into_iter()
+            .map(|(action, latencies)| {
+                (
+                    format!("{:?}", action),
+                    percentile(&latencies, percentile_value),
+                )
+            })
+            .collect()
+    }
+
+    /// Splits the actions completed between `start` and `end` into windows of the given size.
+    /// Actions completed before the `start` (e.g. during the preparation of the accounts) are not included.
+    /// If the load follows the rate ramp, the target rate is reported for each window.
+    pub fn timeline(
+        &self,
+        start: Instant,
+        end: Instant,
+        window: Duration,
+        ramp: Option<&TpsRamp>,
+    ) -> Timeline {
+        assert!(
+            window.as_secs() > 0,
+            "Report window must be at least 1 second"
+        );
+        let load_entries: Vec<_> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.completed_at >= start)
+            .collect();
+        let duration = end.saturating_duration_since(start);
+        let windows_count = (duration.as_secs_f64() / window.as_secs_f64()).ceil() as u64;
+
+        let windows: Vec<_> = (0..windows_count)
+            .map(|idx| {
+                let window_start = window * idx as u32;
+                let window_end = std::cmp::min(window_start + window, duration);
+                let window_entries: Vec<_> = load_entries
+                    .iter()
+                    .copied()
+                    .filter(|entry| {
+                        let offset = entry.completed_at - start;
+                        offset >= window_start && offset < window_end
+                    })
+                    .collect();
+                let count = |outcome| {
+                    window_entries
+                        .iter()
+                        .filter(|entry| entry.outcome == outcome)
+                        .count()
+                };
+                let done = count(Outcome::Done);
+
+                WindowSummary {
+                    start_secs: window_start.as_secs_f64(),
+                    end_secs: window_end.as_secs_f64(),
+                    done,
+                    skipped: count(Outcome::Skipped),
+                    failed: count(Outcome::Failed),
+                    target_tps: ramp.map(|ramp| ramp.rate_at((window_start + window_end) / 2)),
+                    throughput_tps: done as f64 / (window_end - window_start).as_secs_f64(),
+                    actions: latency_stats(window_entries.into_iter()),
+                }
+            })
+            .collect();
+
+        let done = load_entries
+            .iter()
+            .filter(|entry| entry.outcome == Outcome::Done)
+            .count();
+        let sustained_tps = if duration.as_secs_f64() > 0.0 {
+            done as f64 / duration.as_secs_f64()
+        } else {
+            0.0
+        };
+        let peak_tps = windows
+            .iter()
+            .map(|window| window.throughput_tps)
+            .fold(0.0, f64::max);
+
+        Timeline {
+            duration_secs: duration.as_secs_f64(),
+            sustained_tps,
+            peak_tps,
+            windows,
+            actions: latency_stats(load_entries.into_iter()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{ReportBuilder, TxActionType};
+
+    fn report(label: ReportLabel, latency_ms: u64) -> Report {
+        ReportBuilder::new()
+            .label(label)
+            .action(TxActionType::Transfer)
+            .time(Duration::from_millis(latency_ms))
+            .finish()
+    }
+
+    #[test]
+    fn nearest_rank_percentile() {
+        let latencies: Vec<u64> = (1..=100).collect();
+        assert_eq!(percentile(&latencies, 0), 1);
+        assert_eq!(percentile(&latencies, 50), 50);
+        assert_eq!(percentile(&latencies, 99), 99);
+        assert_eq!(percentile(&latencies, 100), 100);
+
+        assert_eq!(percentile(&[7], 50), 7);
+        assert_eq!(percentile(&[], 50), 0);
+    }
+
+    #[test]
+    fn timeline_windows() {
+        let start = Instant::now();
+        let mut collector = TimelineCollector::new();
+        // Action completed before the load is not counted.
+        collector.add_entry(
+            start - Duration::from_secs(1),
+            &report(ReportLabel::done(), 1),
+        );
+        for idx in 0..10 {
+            let completed_at = start + Duration::from_millis(idx * 100);
+            collector.add_entry(completed_at, &report(ReportLabel::done(), 100 * (idx + 1)));
+        }
+        for idx in 0..4 {
+            let completed_at = start + Duration::from_millis(1000 + idx * 100);
+            collector.add_entry(completed_at, &report(ReportLabel::done(), 2000));
+        }
+        collector.add_entry(
+            start + Duration::from_millis(1500),
+            &report(ReportLabel::failed("error"), 5000),
+        );
+
+        let ramp = TpsRamp::new(10.0, 20.0, Duration::from_secs(5)).unwrap();
+        let timeline = collector.timeline(
+            start,
+            start + Duration::from_millis(2500),
+            Duration::from_secs(1),
+            Some(&ramp),
+        );
+        assert_eq!(timeline.windows.len(), 3);
+        assert_eq!(timeline.sustained_tps, 14.0 / 2.5);
+        assert_eq!(timeline.peak_tps, 10.0);
+
+        let first = &timeline.windows[0];
+        assert_eq!((first.done, first.failed), (10, 0));
+        assert_eq!(first.target_tps, Some(11.0));
+        let transfers = &first.actions["Tx(Transfer)"];
+        assert_eq!(transfers.p50_ms, 500);
+        assert_eq!(transfers.p99_ms, 1000);
+
+        let second = &timeline.windows[1];
+        assert_eq!((second.done, second.failed), (4, 1));
+        assert_eq!(second.actions["Tx(Transfer)"].max_ms, 2000);
+
+        // The last window is shorter than the others.
+        let last = &timeline.windows[2];
+        assert_eq!((last.start_secs, last.end_secs), (2.0, 2.5));
+        assert_eq!(last.done, 0);
+        assert!(last.actions.is_empty());
+
+        assert_eq!(timeline.actions["Tx(Transfer)"].count, 14);
+    }
+}
@@ -3,7 +3,7 @@ use web3::{transports::Http, types::Address, Web3};
 use zksync_crypto::Fr;
 use zksync_data_restore::{
     data_restore_driver::DataRestoreDriver, inmemory_storage_interactor::InMemoryStorageInteractor,
-    ETH_BLOCKS_STEP,
+    ETH_BLOCKS_STEP, PARALLEL_ETH_LOGS_RANGES,
 };
 use zksync_types::{Account, AccountId, TokenId};
 
@@ -29,6 +29,7 @@ pub async fn verify_restore(
         testkit_config.contract_upgrade_eth_blocks.clone(),
         testkit_config.init_contract_version,
         ETH_BLOCKS_STEP,
+        PARALLEL_ETH_LOGS_RANGES,
         0,
         true,
         Default::default(),
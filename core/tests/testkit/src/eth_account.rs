@@ -1,4 +1,5 @@
 use crate::external_commands::js_revert_reason;
+use crate::sim_ethereum::SimulatedEthereum;
 use std::collections::HashMap;
 
 use anyhow::{bail, ensure, format_err};
@@ -42,12 +43,21 @@ pub fn parse_ether(eth_value: &str) -> Result<BigUint, anyhow::Error> {
     Ok(BigUint::from_str(&string_wei_value)?)
 }
 
+/// Ethereum network the transactions of `EthereumAccount` are sent to.
+#[derive(Debug, Clone)]
+pub enum EthereumBackend {
+    /// Ethereum node with the deployed zkSync contracts.
+    Node(ETHDirectClient<PrivateKeySigner>),
+    /// In-process simulation of the Ethereum network and the zkSync contracts.
+    Simulated(SimulatedEthereum),
+}
+
 /// Used to sign and post ETH transactions for the zkSync contracts.
 #[derive(Debug, Clone)]
 pub struct EthereumAccount {
     pub private_key: H256,
     pub address: Address,
-    pub main_contract_eth_client: ETHDirectClient<PrivateKeySigner>,
+    pub backend: EthereumBackend,
 }
 
 fn big_dec_to_u256(bd: BigUint) -> U256 {
@@ -88,12 +98,40 @@ impl EthereumAccount {
         Self {
             private_key,
             address,
-            main_contract_eth_client,
+            backend: EthereumBackend::Node(main_contract_eth_client),
+        }
+    }
+
+    /// Creates the account that sends transactions to the simulated Ethereum network.
+    pub fn simulated(private_key: H256, address: Address, l1: SimulatedEthereum) -> Self {
+        Self {
+            private_key,
+            address,
+            backend: EthereumBackend::Simulated(l1),
+        }
+    }
+
+    pub fn is_simulated(&self) -> bool {
+        matches!(self.backend, EthereumBackend::Simulated(_))
+    }
+
+    /// Get fee paid in wei for tx execution, transactions are free in the simulated network.
+    pub async fn executed_tx_fee(
+        &self,
+        receipt: &TransactionReceipt,
+    ) -> Result<BigUint, anyhow::Error> {
+        match &self.backend {
+            EthereumBackend::Node(client) => get_executed_tx_fee(client, receipt).await,
+            EthereumBackend::Simulated(_) => Ok(BigUint::from(0u32)),
         }
     }
 
     pub async fn total_blocks_committed(&self) -> Result<u64, anyhow::Error> {
-        let contract = self.main_contract_eth_client.main_contract();
+        let client = match &self.backend {
+            EthereumBackend::Node(client) => client,
+            EthereumBackend::Simulated(l1) => return Ok(l1.total_blocks_committed().await),
+        };
+        let contract = client.main_contract();
         contract
             .query("totalBlocksCommitted", (), None, default_tx_options(), None)
             .await
@@ -101,7 +139,11 @@ impl EthereumAccount {
     }
 
     pub async fn total_blocks_verified(&self) -> Result<u64, anyhow::Error> {
-        let contract = self.main_contract_eth_client.main_contract();
+        let client = match &self.backend {
+            EthereumBackend::Node(client) => client,
+            EthereumBackend::Simulated(l1) => return Ok(l1.total_blocks_proven().await),
+        };
+        let contract = client.main_contract();
 
         contract
             .query("totalBlocksVerified", (), None, default_tx_options(), None)
@@ -110,7 +152,11 @@ impl EthereumAccount {
     }
 
     pub async fn is_exodus(&self) -> Result<bool, anyhow::Error> {
-        let contract = self.main_contract_eth_client.main_contract();
+        let client = match &self.backend {
+            EthereumBackend::Node(client) => client,
+            EthereumBackend::Simulated(l1) => return Ok(l1.is_exodus().await),
+        };
+        let contract = client.main_contract();
 
         contract
             .query("exodusMode", (), None, default_tx_options(), None)
@@ -123,17 +169,23 @@ impl EthereumAccount {
         account_id: AccountId,
         token_address: Address,
     ) -> Result<(TransactionReceipt, PriorityOp), anyhow::Error> {
-        let data = self
-            .main_contract_eth_client
-            .encode_tx_data("requestFullExit", (u64::from(*account_id), token_address));
+        let client = match &self.backend {
+            EthereumBackend::Node(client) => client,
+            EthereumBackend::Simulated(l1) => {
+                let (receipt, result) = l1.full_exit(self.address, account_id, token_address).await;
+                let priority_op =
+                    result.map_err(|e| format_err!("Full exit submit fail: {}", e))?;
+                return Ok((receipt, priority_op));
+            }
+        };
+        let data =
+            client.encode_tx_data("requestFullExit", (u64::from(*account_id), token_address));
 
-        let signed_tx = self
-            .main_contract_eth_client
+        let signed_tx = client
             .sign_prepared_tx(data, default_tx_options())
             .await
             .map_err(|e| format_err!("Full exit send err: {}", e))?;
-        let receipt =
-            send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await?;
+        let receipt = send_raw_tx_wait_confirmation(client, signed_tx.raw_tx).await?;
         ensure!(
             receipt.status == Some(U64::from(1)),
             "Full exit submit fail"
@@ -153,6 +205,22 @@ impl EthereumAccount {
         zero_account_address: Address,
         proof: EncodedSingleProof,
     ) -> Result<ETHExecResult, anyhow::Error> {
+        let client = match &self.backend {
+            EthereumBackend::Node(client) => client,
+            EthereumBackend::Simulated(l1) => {
+                let (receipt, result) = l1
+                    .perform_exodus(
+                        self.address,
+                        last_block,
+                        account_id,
+                        token_id,
+                        amount,
+                        &proof.proof,
+                    )
+                    .await;
+                return Ok(ETHExecResult::simulated(receipt, result));
+            }
+        };
         let options = Options {
             gas: Some(3_000_000.into()),
             // `exit` function requires more gas to operate.
@@ -160,7 +228,7 @@ impl EthereumAccount {
         };
 
         let stored_block_info = stored_block_info(last_block);
-        let data = self.main_contract_eth_client.encode_tx_data(
+        let data = client.encode_tx_data(
             "performExodus",
             (
                 stored_block_info,
@@ -175,16 +243,14 @@ impl EthereumAccount {
                 proof.proof,
             ),
         );
-        let signed_tx = self
-            .main_contract_eth_client
+        let signed_tx = client
             .sign_prepared_tx(data, options)
             .await
             .map_err(|e| format_err!("Exit send err: {}", e))?;
 
-        let receipt =
-            send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await?;
+        let receipt = send_raw_tx_wait_confirmation(client, signed_tx.raw_tx).await?;
 
-        Ok(ETHExecResult::new(receipt, &self.main_contract_eth_client).await)
+        Ok(ETHExecResult::new(receipt, client).await)
     }
 
     pub async fn cancel_outstanding_deposits_for_exodus_mode(
@@ -192,36 +258,45 @@ impl EthereumAccount {
         number: u64,
         priority_op_data: Vec<Vec<u8>>,
     ) -> Result<ETHExecResult, anyhow::Error> {
-        let data = self.main_contract_eth_client.encode_tx_data(
+        let client = match &self.backend {
+            EthereumBackend::Node(client) => client,
+            EthereumBackend::Simulated(l1) => {
+                let (receipt, result) = l1
+                    .cancel_outstanding_deposits(self.address, number, &priority_op_data)
+                    .await;
+                return Ok(ETHExecResult::simulated(receipt, result));
+            }
+        };
+        let data = client.encode_tx_data(
             "cancelOutstandingDepositsForExodusMode",
             (number, priority_op_data),
         );
-        let signed_tx = self
-            .main_contract_eth_client
+        let signed_tx = client
             .sign_prepared_tx(data, default_tx_options())
             .await
             .map_err(|e| format_err!("cancelOutstandingDepositsForExodusMode send err: {}", e))?;
 
-        let receipt =
-            send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await?;
+        let receipt = send_raw_tx_wait_confirmation(client, signed_tx.raw_tx).await?;
 
-        Ok(ETHExecResult::new(receipt, &self.main_contract_eth_client).await)
+        Ok(ETHExecResult::new(receipt, client).await)
     }
 
     pub async fn change_pubkey_priority_op(
         &self,
         new_pubkey_hash: &PubKeyHash,
     ) -> Result<PriorityOp, anyhow::Error> {
-        let data = self
-            .main_contract_eth_client
-            .encode_tx_data("changePubKeyHash", (new_pubkey_hash.data.to_vec(),));
-        let signed_tx = self
-            .main_contract_eth_client
+        let client = match &self.backend {
+            EthereumBackend::Node(client) => client,
+            EthereumBackend::Simulated(_) => {
+                bail!("ChangePubKeyHash priority operation is not supported by the simulated contract")
+            }
+        };
+        let data = client.encode_tx_data("changePubKeyHash", (new_pubkey_hash.data.to_vec(),));
+        let signed_tx = client
             .sign_prepared_tx(data, default_tx_options())
             .await
             .map_err(|e| format_err!("ChangePubKeyHash send err: {}", e))?;
-        let receipt =
-            send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await?;
+        let receipt = send_raw_tx_wait_confirmation(client, signed_tx.raw_tx).await?;
         ensure!(
             receipt.status == Some(U64::from(1)),
             "ChangePubKeyHash transaction failed"
@@ -237,11 +312,17 @@ impl EthereumAccount {
         to: &Address,
         nonce: Option<U256>,
     ) -> Result<(Vec<TransactionReceipt>, PriorityOp), anyhow::Error> {
-        let data = self
-            .main_contract_eth_client
-            .encode_tx_data("depositETH", *to);
-        let signed_tx = self
-            .main_contract_eth_client
+        let client = match &self.backend {
+            EthereumBackend::Node(client) => client,
+            EthereumBackend::Simulated(l1) => {
+                let (receipt, result) =
+                    l1.deposit(self.address, Address::zero(), amount, *to).await;
+                let priority_op = result.map_err(|e| format_err!("eth deposit fail: {}", e))?;
+                return Ok((vec![receipt], priority_op));
+            }
+        };
+        let data = client.encode_tx_data("depositETH", *to);
+        let signed_tx = client
             .sign_prepared_tx(
                 data,
                 Options::with(|opt| {
@@ -252,8 +333,7 @@ impl EthereumAccount {
             )
             .await
             .map_err(|e| format_err!("Deposit eth send err: {}", e))?;
-        let receipt =
-            send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await?;
+        let receipt = send_raw_tx_wait_confirmation(client, signed_tx.raw_tx).await?;
         ensure!(receipt.status == Some(U64::from(1)), "eth deposit fail");
         let priority_op =
             priority_op_from_tx_logs(&receipt).expect("no priority op log in deposit");
@@ -261,15 +341,23 @@ impl EthereumAccount {
     }
 
     pub async fn eth_balance(&self) -> Result<BigUint, anyhow::Error> {
-        Ok(u256_to_big_dec(
-            self.main_contract_eth_client
-                .eth_balance(self.address)
-                .await?,
-        ))
+        let client = match &self.backend {
+            EthereumBackend::Node(client) => client,
+            EthereumBackend::Simulated(l1) => {
+                return Ok(l1.balance(self.address, Address::zero()).await)
+            }
+        };
+        Ok(u256_to_big_dec(client.eth_balance(self.address).await?))
     }
 
     pub async fn erc20_balance(&self, token_contract: &Address) -> Result<BigUint, anyhow::Error> {
-        self.main_contract_eth_client
+        let client = match &self.backend {
+            EthereumBackend::Node(client) => client,
+            EthereumBackend::Simulated(l1) => {
+                return Ok(l1.balance(self.address, *token_contract).await)
+            }
+        };
+        client
             .call_contract_function(
                 "balanceOf",
                 self.address,
@@ -285,7 +373,13 @@ impl EthereumAccount {
     }
 
     pub async fn balances_to_withdraw(&self, token: Address) -> Result<BigUint, anyhow::Error> {
-        let contract = self.main_contract_eth_client.main_contract();
+        let client = match &self.backend {
+            EthereumBackend::Node(client) => client,
+            EthereumBackend::Simulated(l1) => {
+                return Ok(l1.pending_balance(self.address, token).await)
+            }
+        };
+        let contract = client.main_contract();
 
         contract
             .query(
@@ -305,30 +399,35 @@ impl EthereumAccount {
         token_contract: Address,
         amount: BigUint,
     ) -> Result<TransactionReceipt, anyhow::Error> {
+        let client = match &self.backend {
+            EthereumBackend::Node(client) => client,
+            EthereumBackend::Simulated(l1) => {
+                let (receipt, result) =
+                    l1.approve_erc20(self.address, token_contract, amount).await;
+                result.map_err(|e| format_err!("erc20 approve fail: {}", e))?;
+                return Ok(receipt);
+            }
+        };
         let eth_signer = PrivateKeySigner::new(self.private_key);
         let erc20_client = ETHDirectClient::new(
-            self.main_contract_eth_client.get_web3_transport().clone(),
+            client.get_web3_transport().clone(),
             erc20_contract(),
             self.address,
             eth_signer,
             token_contract,
-            self.main_contract_eth_client.chain_id(),
-            self.main_contract_eth_client.gas_price_factor(),
+            client.chain_id(),
+            client.gas_price_factor(),
         );
         let data = erc20_client.encode_tx_data(
             "approve",
-            (
-                self.main_contract_eth_client.contract_addr(),
-                big_dec_to_u256(amount.clone()),
-            ),
+            (client.contract_addr(), big_dec_to_u256(amount.clone())),
         );
 
         let signed_tx = erc20_client
             .sign_prepared_tx(data, default_tx_options())
             .await
             .map_err(|e| format_err!("Approve send err: {}", e))?;
-        let receipt =
-            send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await?;
+        let receipt = send_raw_tx_wait_confirmation(client, signed_tx.raw_tx).await?;
 
         ensure!(receipt.status == Some(U64::from(1)), "erc20 approve fail");
 
@@ -343,19 +442,25 @@ impl EthereumAccount {
         to: &Address,
     ) -> Result<(Vec<TransactionReceipt>, PriorityOp), anyhow::Error> {
         let approve_receipt = self.approve_erc20(token_contract, amount.clone()).await?;
+        let client = match &self.backend {
+            EthereumBackend::Node(client) => client,
+            EthereumBackend::Simulated(l1) => {
+                let (receipt, result) = l1.deposit(self.address, token_contract, amount, *to).await;
+                let priority_op = result.map_err(|e| format_err!("erc20 deposit fail: {}", e))?;
+                return Ok((vec![approve_receipt, receipt], priority_op));
+            }
+        };
 
-        let data = self.main_contract_eth_client.encode_tx_data(
+        let data = client.encode_tx_data(
             "depositERC20",
             (token_contract, big_dec_to_u256(amount.clone()), *to),
         );
-        let signed_tx = self
-            .main_contract_eth_client
+        let signed_tx = client
             .sign_prepared_tx(data, default_tx_options())
             .await
             .map_err(|e| format_err!("Deposit erc20 send err: {}", e))?;
-        let receipt =
-            send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await?;
-        let exec_result = ETHExecResult::new(receipt, &self.main_contract_eth_client).await;
+        let receipt = send_raw_tx_wait_confirmation(client, signed_tx.raw_tx).await?;
+        let exec_result = ETHExecResult::new(receipt, client).await;
         let receipt = exec_result.success_result()?;
         let priority_op =
             priority_op_from_tx_logs(&receipt).expect("no priority op log in deposit erc20");
@@ -366,12 +471,24 @@ impl EthereumAccount {
         &self,
         commit_operation: &BlocksCommitOperation,
     ) -> Result<ETHExecResult, anyhow::Error> {
-        let data = self.main_contract_eth_client.encode_tx_data(
+        let client = match &self.backend {
+            EthereumBackend::Node(client) => client,
+            EthereumBackend::Simulated(l1) => {
+                let (receipt, result) = l1
+                    .commit_blocks(
+                        self.address,
+                        &commit_operation.last_committed_block,
+                        &commit_operation.blocks,
+                    )
+                    .await;
+                return Ok(ETHExecResult::simulated(receipt, result));
+            }
+        };
+        let data = client.encode_tx_data(
             "commitBlocks",
             commit_operation.get_eth_tx_args().as_slice(),
         );
-        let signed_tx = self
-            .main_contract_eth_client
+        let signed_tx = client
             .sign_prepared_tx(
                 data,
                 Options::with(|f| f.gas = Some(U256::from(9 * 10u64.pow(6)))),
@@ -379,10 +496,9 @@ impl EthereumAccount {
             .await
             .map_err(|e| format_err!("Commit block send err: {}", e))?;
 
-        let receipt =
-            send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await?;
+        let receipt = send_raw_tx_wait_confirmation(client, signed_tx.raw_tx).await?;
 
-        Ok(ETHExecResult::new(receipt, &self.main_contract_eth_client).await)
+        Ok(ETHExecResult::new(receipt, client).await)
     }
 
     // Verifies block using provided proof or empty proof if None is provided. (`DUMMY_VERIFIER` should be enabled on the contract).
@@ -390,20 +506,30 @@ impl EthereumAccount {
         &self,
         proof_operation: &BlocksProofOperation,
     ) -> Result<ETHExecResult, anyhow::Error> {
-        let data = self
-            .main_contract_eth_client
-            .encode_tx_data("proveBlocks", proof_operation.get_eth_tx_args().as_slice());
-        let signed_tx = self
-            .main_contract_eth_client
+        let client = match &self.backend {
+            EthereumBackend::Node(client) => client,
+            EthereumBackend::Simulated(l1) => {
+                let (receipt, result) = l1
+                    .prove_blocks(
+                        self.address,
+                        &proof_operation.blocks,
+                        &proof_operation.proof,
+                    )
+                    .await;
+                return Ok(ETHExecResult::simulated(receipt, result));
+            }
+        };
+        let data =
+            client.encode_tx_data("proveBlocks", proof_operation.get_eth_tx_args().as_slice());
+        let signed_tx = client
             .sign_prepared_tx(
                 data,
                 Options::with(|f| f.gas = Some(U256::from(10 * 10u64.pow(6)))),
             )
             .await
             .map_err(|e| format_err!("Verify block send err: {}", e))?;
-        let receipt =
-            send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await?;
-        Ok(ETHExecResult::new(receipt, &self.main_contract_eth_client).await)
+        let receipt = send_raw_tx_wait_confirmation(client, signed_tx.raw_tx).await?;
+        Ok(ETHExecResult::new(receipt, client).await)
     }
 
     // Completes pending withdrawals.
@@ -411,23 +537,30 @@ impl EthereumAccount {
         &self,
         execute_operation: &BlocksExecuteOperation,
     ) -> Result<ETHExecResult, anyhow::Error> {
-        let data = self.main_contract_eth_client.encode_tx_data(
+        let client = match &self.backend {
+            EthereumBackend::Node(client) => client,
+            EthereumBackend::Simulated(l1) => {
+                let (receipt, result) = l1
+                    .execute_blocks(self.address, &execute_operation.blocks)
+                    .await;
+                return Ok(ETHExecResult::simulated(receipt, result));
+            }
+        };
+        let data = client.encode_tx_data(
             "executeBlocks",
             execute_operation.get_eth_tx_args().as_slice(),
         );
 
-        let signed_tx = self
-            .main_contract_eth_client
+        let signed_tx = client
             .sign_prepared_tx(
                 data,
                 Options::with(|f| f.gas = Some(U256::from(9 * 10u64.pow(6)))),
             )
             .await
             .map_err(|e| format_err!("Complete withdrawals send err: {}", e))?;
-        let receipt =
-            send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await?;
+        let receipt = send_raw_tx_wait_confirmation(client, signed_tx.raw_tx).await?;
 
-        Ok(ETHExecResult::new(receipt, &self.main_contract_eth_client).await)
+        Ok(ETHExecResult::new(receipt, client).await)
     }
 
     // Completes pending withdrawals.
@@ -447,28 +580,50 @@ impl EthereumAccount {
                 })
             })
             .collect();
-        let mut ft_balances = vec![];
-        let mut nft_balances = vec![];
+        let mut ft_withdrawals = vec![];
+        let mut nft_withdrawals = vec![];
         for ex_op in ex_ops {
             let ex_op = ex_op.get_executed_tx().unwrap().signed_tx.clone().tx;
             match ex_op {
-                ZkSyncTx::Withdraw(tx) => ft_balances.push(Token::Tuple(vec![
-                    Token::Address(tx.to),
-                    Token::Address(*tokens.get(&tx.token).unwrap()),
-                    Token::Uint(Uint::from(200_000)),
-                ])),
-                ZkSyncTx::WithdrawNFT(tx) => nft_balances.push(Token::Tuple(vec![
-                    Token::Uint(Uint::from(tx.token.0)),
-                    Token::Uint(Uint::from(300_000)),
-                ])),
+                ZkSyncTx::Withdraw(tx) => ft_withdrawals.push((tx.to, tx.token)),
+                ZkSyncTx::WithdrawNFT(tx) => nft_withdrawals.push(tx.token),
                 _ => unreachable!(),
             };
         }
 
-        if ft_balances.is_empty() && nft_balances.is_empty() {
+        if ft_withdrawals.is_empty() && nft_withdrawals.is_empty() {
             return Ok(None);
         }
 
+        let client = match &self.backend {
+            EthereumBackend::Node(client) => client,
+            EthereumBackend::Simulated(l1) => {
+                let (receipt, result) = l1
+                    .withdraw_pending_balances(self.address, &ft_withdrawals, &nft_withdrawals)
+                    .await;
+                return Ok(Some(ETHExecResult::simulated(receipt, result)));
+            }
+        };
+        let ft_balances = ft_withdrawals
+            .into_iter()
+            .map(|(to, token)| {
+                Token::Tuple(vec![
+                    Token::Address(to),
+                    Token::Address(*tokens.get(&token).unwrap()),
+                    Token::Uint(Uint::from(200_000)),
+                ])
+            })
+            .collect();
+        let nft_balances = nft_withdrawals
+            .into_iter()
+            .map(|token| {
+                Token::Tuple(vec![
+                    Token::Uint(Uint::from(token.0)),
+                    Token::Uint(Uint::from(300_000)),
+                ])
+            })
+            .collect();
+
         let f = pending_withdrawer_contract
             .0
             .function("withdrawPendingBalances")
@@ -478,57 +633,62 @@ impl EthereumAccount {
         let data = f
             .encode_input(&tokens)
             .expect("failed to encode parameters");
-        let signed_tx = self
-            .main_contract_eth_client
+        let signed_tx = client
             .sign_prepared_tx_for_addr(data, pending_withdrawer_contract.1, Options::default())
             .await
             .map_err(|e| format_err!("Complete withdrawals send err: {}", e))?;
 
-        let receipt =
-            send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await?;
-        Ok(Some(
-            ETHExecResult::new(receipt, &self.main_contract_eth_client).await,
-        ))
+        let receipt = send_raw_tx_wait_confirmation(client, signed_tx.raw_tx).await?;
+        Ok(Some(ETHExecResult::new(receipt, client).await))
     }
 
     pub async fn revert_blocks(&self, blocks: &[Block]) -> Result<ETHExecResult, anyhow::Error> {
+        let client = match &self.backend {
+            EthereumBackend::Node(client) => client,
+            EthereumBackend::Simulated(l1) => {
+                let (receipt, result) = l1.revert_blocks(self.address, blocks).await;
+                return Ok(ETHExecResult::simulated(receipt, result));
+            }
+        };
         let tx_arg = Token::Array(blocks.iter().map(stored_block_info).collect());
 
-        let data = self
-            .main_contract_eth_client
-            .encode_tx_data("revertBlocks", tx_arg);
+        let data = client.encode_tx_data("revertBlocks", tx_arg);
 
-        let signed_tx = self
-            .main_contract_eth_client
+        let signed_tx = client
             .sign_prepared_tx(
                 data,
                 Options::with(|f| f.gas = Some(U256::from(9 * 10u64.pow(6)))),
             )
             .await
             .map_err(|e| format_err!("Revert blocks send err: {}", e))?;
-        let receipt =
-            send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await?;
+        let receipt = send_raw_tx_wait_confirmation(client, signed_tx.raw_tx).await?;
 
-        Ok(ETHExecResult::new(receipt, &self.main_contract_eth_client).await)
+        Ok(ETHExecResult::new(receipt, client).await)
     }
 
     pub async fn trigger_exodus_if_needed(&self) -> Result<ETHExecResult, anyhow::Error> {
-        let data = self
-            .main_contract_eth_client
-            .encode_tx_data("activateExodusMode", ());
-        let signed_tx = self
-            .main_contract_eth_client
+        let client = match &self.backend {
+            EthereumBackend::Node(client) => client,
+            EthereumBackend::Simulated(l1) => {
+                let (receipt, result) = l1.activate_exodus_mode(self.address).await;
+                return Ok(ETHExecResult::simulated(receipt, result.map(drop)));
+            }
+        };
+        let data = client.encode_tx_data("activateExodusMode", ());
+        let signed_tx = client
             .sign_prepared_tx(data, default_tx_options())
             .await
             .map_err(|e| format_err!("Trigger exodus if needed send err: {}", e))?;
-        let receipt =
-            send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await?;
+        let receipt = send_raw_tx_wait_confirmation(client, signed_tx.raw_tx).await?;
 
-        Ok(ETHExecResult::new(receipt, &self.main_contract_eth_client).await)
+        Ok(ETHExecResult::new(receipt, client).await)
     }
 
     pub async fn eth_block_number(&self) -> Result<u64, anyhow::Error> {
-        Ok(self.main_contract_eth_client.block_number().await?.as_u64())
+        match &self.backend {
+            EthereumBackend::Node(client) => Ok(client.block_number().await?.as_u64()),
+            EthereumBackend::Simulated(l1) => Ok(l1.block_number().await),
+        }
     }
 
     pub async fn auth_fact(
@@ -536,15 +696,19 @@ impl EthereumAccount {
         fact: &[u8],
         nonce: Nonce,
     ) -> Result<TransactionReceipt, anyhow::Error> {
-        let data = self
-            .main_contract_eth_client
-            .encode_tx_data("setAuthPubkeyHash", (fact.to_vec(), u64::from(*nonce)));
-        let signed_tx = self
-            .main_contract_eth_client
+        let client = match &self.backend {
+            EthereumBackend::Node(client) => client,
+            EthereumBackend::Simulated(l1) => {
+                let (receipt, _) = l1.set_auth_pubkey_hash(self.address, fact, nonce).await;
+                return Ok(receipt);
+            }
+        };
+        let data = client.encode_tx_data("setAuthPubkeyHash", (fact.to_vec(), u64::from(*nonce)));
+        let signed_tx = client
             .sign_prepared_tx(data, default_tx_options())
             .await
             .map_err(|e| format_err!("AuthFact send err: {}", e))?;
-        send_raw_tx_wait_confirmation(&self.main_contract_eth_client, signed_tx.raw_tx).await
+        send_raw_tx_wait_confirmation(client, signed_tx.raw_tx).await
     }
 }
 
@@ -553,6 +717,7 @@ pub struct ETHExecResult {
     success: bool,
     receipt: TransactionReceipt,
     revert_reason: String,
+    simulated: bool,
 }
 
 impl ETHExecResult {
@@ -576,6 +741,22 @@ impl ETHExecResult {
             success,
             receipt,
             revert_reason,
+            simulated: false,
+        }
+    }
+
+    /// Creates the result of the transaction sent to the simulated network, `result` holds the revert reason.
+    pub fn simulated<T>(receipt: TransactionReceipt, result: Result<T, String>) -> Self {
+        let (success, revert_reason) = match result {
+            Ok(_) => (true, String::new()),
+            Err(reason) => (false, reason),
+        };
+
+        Self {
+            success,
+            receipt,
+            revert_reason,
+            simulated: true,
         }
     }
 
@@ -593,8 +774,11 @@ impl ETHExecResult {
 
     pub fn expect_success(self) -> TransactionReceipt {
         let tx_hash = self.receipt.transaction_hash;
+        let simulated = self.simulated;
         self.success_result().unwrap_or_else(|e| {
-            eprintln!("js revert reason:\n{}", js_revert_reason(&tx_hash));
+            if !simulated {
+                eprintln!("js revert reason:\n{}", js_revert_reason(&tx_hash));
+            }
             panic!("Expected transaction success: {}", e)
         })
     }
@@ -0,0 +1,114 @@
+//! In-process testkit setup.
+//!
+//! Blocks are produced by the state keeper driven by `TestSetup`, and all the Ethereum transactions
+//! are sent to the simulated network from `sim_ethereum`, so the scenarios don't require
+//! an Ethereum node with the deployed contracts and can be run by `cargo test`.
+
+use futures::channel::oneshot;
+use num::BigUint;
+use std::thread::JoinHandle;
+
+use zksync_types::{block::Block, AccountId, BlockNumber, TokenId, H256, U256};
+
+use crate::{
+    eth_account::EthereumAccount,
+    sim_ethereum::SimulatedEthereum,
+    state_keeper_utils::spawn_state_keeper,
+    zksync_account::{ZkSyncAccount, ZkSyncETHAccountData},
+};
+
+use super::*;
+
+/// `TestSetup` connected to the simulated Ethereum network, along with the state keeper running for it.
+pub struct InProcessTestkit {
+    pub test_setup: TestSetup,
+    pub l1: SimulatedEthereum,
+    sk_thread_handle: JoinHandle<()>,
+    stop_state_keeper_sender: oneshot::Sender<()>,
+}
+
+impl InProcessTestkit {
+    /// Creates the setup with `eth_accounts_number` Ethereum accounts having `initial_balance`
+    /// of ETH and of the test ERC20 token.
+    ///
+    /// zkSync account 0 is the fee account, zkSync account `i + 1` has the same address as Ethereum account `i`.
+    pub async fn new(eth_accounts_number: usize, initial_balance: BigUint) -> Self {
+        let fee_account = ZkSyncAccount::rand();
+        let (sk_thread_handle, stop_state_keeper_sender, sk_channels) =
+            spawn_state_keeper(&fee_account.address, genesis_state(&fee_account.address));
+
+        let initial_root = genesis_state(&fee_account.address).state.root_hash();
+        let genesis_block = Block::new(
+            BlockNumber(0),
+            initial_root,
+            AccountId(0),
+            vec![],
+            (0, 0),
+            0,
+            U256::from(0),
+            U256::from(0),
+            H256::default(),
+            0,
+        );
+
+        let l1 = SimulatedEthereum::new(&genesis_block);
+        let contracts = l1.contracts().await;
+
+        let commit_account = simulated_account(ZkSyncAccount::rand(), &l1).0;
+        let mut eth_accounts = Vec::with_capacity(eth_accounts_number);
+        let mut zksync_accounts = vec![fee_account];
+        for _ in 0..eth_accounts_number {
+            let (eth_account, zksync_account) = simulated_account(ZkSyncAccount::rand(), &l1);
+            for token_id in &[TokenId(0), TokenId(1)] {
+                l1.set_balance(eth_account.address, *token_id, initial_balance.clone())
+                    .await;
+            }
+            eth_accounts.push(eth_account);
+            zksync_accounts.push(zksync_account);
+        }
+
+        let accounts = AccountSet {
+            eth_accounts,
+            zksync_accounts,
+            fee_account_id: ZKSyncAccountId(0),
+        };
+
+        let test_setup = TestSetup::new(
+            sk_channels,
+            accounts,
+            &contracts,
+            commit_account,
+            initial_root,
+            Some(genesis_block),
+        );
+
+        Self {
+            test_setup,
+            l1,
+            sk_thread_handle,
+            stop_state_keeper_sender,
+        }
+    }
+
+    /// Stops the state keeper.
+    pub fn stop(self) {
+        self.stop_state_keeper_sender
+            .send(())
+            .expect("sk stop send");
+        self.sk_thread_handle.join().expect("sk thread join");
+    }
+}
+
+/// Returns the Ethereum account connected to the simulated network along with the zkSync account having the same address.
+fn simulated_account(
+    zksync_account: ZkSyncAccount,
+    l1: &SimulatedEthereum,
+) -> (EthereumAccount, ZkSyncAccount) {
+    let eth_private_key = match &zksync_account.eth_account_data {
+        ZkSyncETHAccountData::EOA { eth_private_key } => *eth_private_key,
+        ZkSyncETHAccountData::Create2(_) => unreachable!("random accounts are EOA"),
+    };
+    let eth_account =
+        EthereumAccount::simulated(eth_private_key, zksync_account.address, l1.clone());
+    (eth_account, zksync_account)
+}
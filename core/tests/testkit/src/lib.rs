@@ -16,7 +16,9 @@ pub mod account_set;
 pub mod data_restore;
 pub mod eth_account;
 pub mod external_commands;
+pub mod in_process;
 pub mod scenarios;
+pub mod sim_ethereum;
 pub mod state_keeper_utils;
 pub mod test_setup;
 pub mod types;
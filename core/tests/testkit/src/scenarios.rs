@@ -4,14 +4,16 @@ use num::BigUint;
 use std::time::Instant;
 use web3::transports::Http;
 
+use zksync_crypto::{params::PRIORITY_EXPIRATION, proof::EncodedSingleProof};
 use zksync_test_account::ZkSyncETHAccountData;
 use zksync_types::block::Block;
-use zksync_types::{Nonce, TokenId};
+use zksync_types::{AccountId, Nonce, PriorityOp, TokenId};
 
 use crate::{
     data_restore::verify_restore,
     eth_account::{parse_ether, EthereumAccount},
     external_commands::{deploy_contracts, get_test_accounts},
+    in_process::InProcessTestkit,
     state_keeper_utils::spawn_state_keeper,
     zksync_account::ZkSyncAccount,
 };
@@ -142,6 +144,182 @@ pub async fn perform_basic_tests() {
     sk_thread_handle.join().expect("sk thread join");
 }
 
+/// Same as `perform_basic_tests`, but runs in-process against the simulated Ethereum network.
+/// Instead of restoring the state from the contract events, checks that all the blocks and priority operations
+/// are executed by the contract.
+pub async fn perform_basic_tests_in_process() {
+    let mut testkit = InProcessTestkit::new(2, parse_ether("100.0").unwrap()).await;
+    let test_setup = &mut testkit.test_setup;
+
+    let deposit_amount = parse_ether("1.0").unwrap();
+    let executed_blocks = perform_basic_operations(
+        TokenId(1),
+        test_setup,
+        deposit_amount,
+        BlockProcessing::CommitAndVerify,
+    )
+    .await;
+
+    // Verify queued transactions events.
+    let expected_operations_num: usize = executed_blocks
+        .iter()
+        .map(|block| block.block_transactions.len())
+        .sum();
+    let mut operations_num: usize = 0;
+    while let Ok(Some(message)) = test_setup.processed_tx_events_receiver.try_next() {
+        operations_num += message.executed_ops.len();
+    }
+    assert!(operations_num > 0);
+    assert_eq!(operations_num, expected_operations_num);
+
+    assert_eq!(
+        testkit.l1.total_blocks_executed().await,
+        executed_blocks.len() as u64
+    );
+    assert!(testkit.l1.priority_requests().await.is_empty());
+
+    testkit.stop();
+}
+
+/// Exodus mode scenario run in-process against the simulated Ethereum network:
+/// an unprocessed deposit expires, the exodus mode is activated, the outstanding deposit is cancelled
+/// and the funds are withdrawn with exit proofs. Exits with an incorrect sender, amount or proof,
+/// as well as the second exit for the same token, are rejected.
+pub async fn perform_exodus_tests_in_process() {
+    let mut testkit = InProcessTestkit::new(2, parse_ether("100.0").unwrap()).await;
+    let test_setup = &mut testkit.test_setup;
+    let token = Token(TokenId(0));
+    let deposit_amount = parse_ether("1.0").unwrap();
+
+    test_setup.start_block();
+    for account in &[ZKSyncAccountId(1), ZKSyncAccountId(2)] {
+        test_setup
+            .deposit(ETHAccountId(0), *account, token, deposit_amount.clone())
+            .await;
+    }
+    test_setup
+        .execute_commit_and_verify_block()
+        .await
+        .expect("Commit and verify initial block");
+    let verified_accounts_state = test_setup.get_accounts_state().await;
+
+    // The deposit is executed by the state keeper, but is never committed.
+    let expired_deposit_amount = parse_ether("0.3").unwrap();
+    let (_, expired_priority_op) = test_setup
+        .deposit(
+            ETHAccountId(1),
+            ZKSyncAccountId(2),
+            token,
+            expired_deposit_amount.clone(),
+        )
+        .await;
+
+    test_setup.trigger_exodus_if_needed(ETHAccountId(1)).await;
+    assert!(
+        !test_setup.is_exodus().await,
+        "Exodus is triggered too early"
+    );
+    testkit.l1.advance_blocks(PRIORITY_EXPIRATION).await;
+    test_setup.trigger_exodus_if_needed(ETHAccountId(1)).await;
+    assert!(test_setup.is_exodus().await, "Exodus is not triggered");
+
+    let token_address = test_setup.tokens[&token.0];
+    let balance_to_withdraw_before = test_setup
+        .get_balance_to_withdraw(ETHAccountId(1), token_address)
+        .await;
+    let (number, data) = PriorityOp::get_args_for_priority_queue_cancel(&[expired_priority_op]);
+    test_setup
+        .cancel_outstanding_deposits(ETHAccountId(1), number, data)
+        .await;
+    assert_eq!(
+        test_setup
+            .get_balance_to_withdraw(ETHAccountId(1), token_address)
+            .await,
+        balance_to_withdraw_before + &expired_deposit_amount,
+        "Outstanding deposit is not cancelled"
+    );
+
+    let zero_account_address = verified_accounts_state[&AccountId(0)].address;
+    let fund_owner = ZKSyncAccountId(1);
+    let account_id = test_setup
+        .get_zksync_account_id(fund_owner)
+        .await
+        .expect("Account should exist");
+    let (proof, exit_amount) =
+        test_setup.gen_exit_proof_fungible(verified_accounts_state, fund_owner, token);
+    assert_eq!(exit_amount, deposit_amount);
+
+    // Proof is bound to the owner of the account.
+    test_setup
+        .exit(
+            ETHAccountId(1),
+            account_id,
+            token,
+            &exit_amount,
+            zero_account_address,
+            proof.clone(),
+        )
+        .await
+        .expect_revert("x");
+    test_setup
+        .exit(
+            ETHAccountId(0),
+            account_id,
+            token,
+            &(&exit_amount * BigUint::from(2u32)),
+            zero_account_address,
+            proof.clone(),
+        )
+        .await
+        .expect_revert("x");
+    test_setup
+        .exit(
+            ETHAccountId(0),
+            account_id,
+            token,
+            &exit_amount,
+            zero_account_address,
+            EncodedSingleProof::default(),
+        )
+        .await
+        .expect_revert("x");
+
+    let balance_to_withdraw_before = test_setup
+        .get_balance_to_withdraw(ETHAccountId(0), token_address)
+        .await;
+    test_setup
+        .exit(
+            ETHAccountId(0),
+            account_id,
+            token,
+            &exit_amount,
+            zero_account_address,
+            proof.clone(),
+        )
+        .await
+        .expect_success();
+    assert_eq!(
+        test_setup
+            .get_balance_to_withdraw(ETHAccountId(0), token_address)
+            .await,
+        balance_to_withdraw_before + &exit_amount,
+        "Balance to withdraw is not incremented"
+    );
+    test_setup
+        .exit(
+            ETHAccountId(0),
+            account_id,
+            token,
+            &exit_amount,
+            zero_account_address,
+            proof,
+        )
+        .await
+        .expect_revert("t");
+
+    testkit.stop();
+}
+
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum BlockProcessing {
     CommitAndVerify,
@@ -330,3 +508,18 @@ pub async fn perform_basic_operations(
 
     executed_blocks
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn basic_operations_in_process() {
+        perform_basic_tests_in_process().await;
+    }
+
+    #[tokio::test]
+    async fn exodus_in_process() {
+        perform_exodus_tests_in_process().await;
+    }
+}
@@ -0,0 +1,876 @@
+//! Simulated Ethereum network with the zkSync contract deployed.
+//!
+//! Allows the testkit scenarios to run in-process, without an Ethereum node and deployed contracts.
+//! The simulated contract models the parts of the zkSync contract that the testkit interacts with:
+//! deposits and full exits through the priority queue, commitment, proving and execution of blocks,
+//! pending balances, onchain authentication of public keys and the exodus mode.
+//! Revert codes match the ones of the real contract.
+//!
+//! Transactions are sent through `MockEthereum`, which provides their hashes and the block numbers:
+//! every transaction is included into a new block. Gas is free, so ETH balances are only changed by
+//! deposits and withdrawals.
+//!
+//! Neither block proofs nor exit proofs are verified cryptographically. Block proofs are checked
+//! against the committed block commitments, and exit proofs produced by `simulated_exit_proof` are
+//! bound to the arguments of the exit, so proofs for another account, token or amount are rejected.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+use num::{BigUint, ToPrimitive, Zero};
+use tokio::sync::Mutex;
+use web3::{
+    contract::Options,
+    types::{TransactionReceipt, H256, U256, U64},
+};
+
+use zksync_crypto::{
+    params::{MIN_NFT_TOKEN_ID, NFT_STORAGE_ACCOUNT_ID, PRIORITY_EXPIRATION},
+    proof::{EncodedAggregatedProof, EncodedSingleProof},
+    Fr,
+};
+use zksync_eth_client::clients::mock::MockEthereum;
+use zksync_types::{
+    block::Block, tx::ChangePubKeyEthAuthData, AccountId, AccountMap, Address, BlockNumber,
+    Deposit, ExecutedOperations, FullExit, Nonce, PriorityOp, TokenId, ZkSyncOp, ZkSyncPriorityOp,
+};
+
+use crate::external_commands::Contracts;
+
+/// Mask applied to the block commitments in the proof, same as `INPUT_MASK` of the contract.
+fn input_mask() -> U256 {
+    U256::max_value() >> 3
+}
+
+/// Block data stored by the contract, same as `StoredBlockInfo` of the contract.
+#[derive(Debug, Clone, PartialEq)]
+struct StoredBlockInfo {
+    block_number: BlockNumber,
+    priority_operations: u64,
+    pending_onchain_operations_hash: H256,
+    timestamp: u64,
+    state_hash: H256,
+    commitment: H256,
+}
+
+impl From<&Block> for StoredBlockInfo {
+    fn from(block: &Block) -> Self {
+        Self {
+            block_number: block.block_number,
+            priority_operations: block.number_of_processed_prior_ops(),
+            pending_onchain_operations_hash: block.get_onchain_operations_block_info().1,
+            timestamp: block.timestamp,
+            state_hash: block.get_eth_encoded_root(),
+            commitment: block.block_commitment,
+        }
+    }
+}
+
+fn require(condition: bool, code: &str) -> Result<(), String> {
+    if condition {
+        Ok(())
+    } else {
+        Err(code.to_string())
+    }
+}
+
+/// Returns the commitment to the exit statement, which the proof of `performExodus` is checked against.
+fn exit_commitment(
+    state_hash: H256,
+    account_id: AccountId,
+    owner: Address,
+    token_id: TokenId,
+    amount: &BigUint,
+) -> U256 {
+    let mut data = state_hash.as_bytes().to_vec();
+    data.extend_from_slice(&account_id.to_be_bytes());
+    data.extend_from_slice(owner.as_bytes());
+    data.extend_from_slice(&token_id.to_be_bytes());
+    data.extend_from_slice(&amount.to_u128().unwrap_or(u128::MAX).to_be_bytes());
+    U256::from_big_endian(MockEthereum::fake_sha256(&data).as_bytes())
+}
+
+/// Creates the exit proof accepted by the simulated contract for the state of the last executed block.
+/// Returns the proof along with the amount to be withdrawn.
+pub fn simulated_exit_proof(
+    accounts: &AccountMap,
+    account_id: AccountId,
+    owner: Address,
+    token_id: TokenId,
+) -> Option<(EncodedSingleProof, BigUint)> {
+    let amount = accounts.get(&account_id)?.get_balance(token_id);
+    let root: Fr = zksync_prover_utils::exit_proof::exit_proof_account_tree(accounts).root_hash();
+    let commitment = exit_commitment(
+        Block::encode_fr_for_eth(root),
+        account_id,
+        owner,
+        token_id,
+        &amount,
+    );
+    let proof = EncodedSingleProof {
+        inputs: vec![commitment],
+        proof: vec![commitment],
+    };
+    Some((proof, amount))
+}
+
+/// State of the simulated zkSync contract and the ERC20 tokens.
+#[derive(Debug, Clone)]
+struct ContractState {
+    priority_expiration: u64,
+    tokens: HashMap<Address, TokenId>,
+    balances: HashMap<(Address, TokenId), BigUint>,
+    allowances: HashMap<(Address, TokenId), BigUint>,
+    pending_balances: HashMap<(Address, TokenId), BigUint>,
+    pending_nfts: HashMap<TokenId, Address>,
+    nft_owners: HashMap<TokenId, Address>,
+    auth_facts: HashMap<(Address, Nonce), Vec<u8>>,
+    /// Open priority requests, the first one has `first_priority_request_id`.
+    priority_requests: VecDeque<PriorityOp>,
+    first_priority_request_id: u64,
+    total_committed_priority_requests: u64,
+    /// Stored blocks starting from the genesis one, the last one is the last committed block.
+    stored_blocks: Vec<StoredBlockInfo>,
+    total_blocks_proven: u32,
+    total_blocks_executed: u32,
+    exodus_mode: bool,
+    performed_exodus: HashSet<(AccountId, TokenId)>,
+}
+
+impl ContractState {
+    fn total_blocks_committed(&self) -> u32 {
+        (self.stored_blocks.len() - 1) as u32
+    }
+
+    fn token_id(&self, token: Address) -> Result<TokenId, String> {
+        self.tokens
+            .get(&token)
+            .copied()
+            .ok_or_else(|| "1i".to_string()) // token not exist
+    }
+
+    fn require_active(&self) -> Result<(), String> {
+        require(!self.exodus_mode, "L") // exodus mode activated
+    }
+
+    fn add_priority_request(
+        &mut self,
+        data: ZkSyncPriorityOp,
+        tx_hash: H256,
+        block: u64,
+    ) -> PriorityOp {
+        let priority_op = PriorityOp {
+            serial_id: self.first_priority_request_id + self.priority_requests.len() as u64,
+            data,
+            deadline_block: block + self.priority_expiration,
+            eth_hash: tx_hash,
+            eth_block: block,
+            eth_block_index: Some(0),
+        };
+        self.priority_requests.push_back(priority_op.clone());
+        priority_op
+    }
+
+    fn increase_pending_balance(&mut self, owner: Address, token: TokenId, amount: &BigUint) {
+        *self.pending_balances.entry((owner, token)).or_default() += amount;
+    }
+
+    fn deposit(
+        &mut self,
+        from: Address,
+        token: Address,
+        amount: BigUint,
+        to: Address,
+        tx_hash: H256,
+        block: u64,
+    ) -> Result<PriorityOp, String> {
+        self.require_active()?;
+        let token = self.token_id(token)?;
+        require(!amount.is_zero(), "M")?; // zero-value deposits are forbidden
+        if token != TokenId(0) {
+            let allowance = self.allowances.entry((from, token)).or_default();
+            require(
+                *allowance >= amount,
+                "ERC20: transfer amount exceeds allowance",
+            )?;
+            *allowance -= &amount;
+        }
+        let balance = self.balances.entry((from, token)).or_default();
+        require(*balance >= amount, "insufficient funds for transfer")?;
+        *balance -= &amount;
+
+        let deposit = Deposit {
+            from,
+            token,
+            amount,
+            to,
+        };
+        Ok(self.add_priority_request(ZkSyncPriorityOp::Deposit(deposit), tx_hash, block))
+    }
+
+    fn full_exit(
+        &mut self,
+        from: Address,
+        account_id: AccountId,
+        token: Address,
+        tx_hash: H256,
+        block: u64,
+    ) -> Result<PriorityOp, String> {
+        self.require_active()?;
+        require(account_id != NFT_STORAGE_ACCOUNT_ID, "v")?; // full exit for the nft storage account
+        let token = self.token_id(token)?;
+
+        let full_exit = FullExit {
+            account_id,
+            eth_address: from,
+            token,
+            is_legacy: false,
+        };
+        Ok(self.add_priority_request(ZkSyncPriorityOp::FullExit(full_exit), tx_hash, block))
+    }
+
+    fn check_priority_operation(
+        &self,
+        executed: &PriorityOp,
+        priority_request_id: u64,
+    ) -> Result<(), String> {
+        let index = (priority_request_id - self.first_priority_request_id) as usize;
+        let request = self
+            .priority_requests
+            .get(index)
+            .ok_or_else(|| "j".to_string())?;
+
+        match (&executed.data, &request.data) {
+            (ZkSyncPriorityOp::Deposit(executed), ZkSyncPriorityOp::Deposit(request)) => require(
+                executed.from == request.from
+                    && executed.token == request.token
+                    && executed.amount == request.amount
+                    && executed.to == request.to,
+                "I",
+            ),
+            (ZkSyncPriorityOp::Deposit(_), _) => Err("H".to_string()),
+            (ZkSyncPriorityOp::FullExit(executed), ZkSyncPriorityOp::FullExit(request)) => require(
+                executed.account_id == request.account_id
+                    && executed.eth_address == request.eth_address
+                    && executed.token == request.token,
+                "K",
+            ),
+            (ZkSyncPriorityOp::FullExit(_), _) => Err("J".to_string()),
+        }
+    }
+
+    fn commit_blocks(
+        &mut self,
+        last_committed_block: &Block,
+        blocks: &[Block],
+    ) -> Result<(), String> {
+        self.require_active()?;
+        let last_stored = self.stored_blocks.last().expect("genesis block is stored");
+        // Incorrect previous block data.
+        require(
+            StoredBlockInfo::from(last_committed_block) == *last_stored,
+            "i",
+        )?;
+
+        for block in blocks {
+            let previous = self.stored_blocks.last().expect("genesis block is stored");
+            require(*block.block_number == *previous.block_number + 1, "f")?; // only commit next block
+            require(block.timestamp >= previous.timestamp, "g")?;
+
+            for operation in &block.block_transactions {
+                match operation {
+                    ExecutedOperations::PriorityOp(executed) => {
+                        let priority_request_id =
+                            self.first_priority_request_id + self.total_committed_priority_requests;
+                        self.check_priority_operation(&executed.priority_op, priority_request_id)?;
+                        self.total_committed_priority_requests += 1;
+                    }
+                    ExecutedOperations::Tx(executed) if executed.success => {
+                        if let Some(ZkSyncOp::ChangePubKeyOffchain(op)) = &executed.op {
+                            if let Some(ChangePubKeyEthAuthData::Onchain) = op.tx.eth_auth_data {
+                                // New pub key hash must be authenticated by the account owner.
+                                let fact = self.auth_facts.get(&(op.tx.account, op.tx.nonce));
+                                require(fact == Some(&op.tx.new_pk_hash.data.to_vec()), "E")?;
+                            }
+                        }
+                    }
+                    ExecutedOperations::Tx(_) => {}
+                }
+            }
+            self.stored_blocks.push(StoredBlockInfo::from(block));
+        }
+
+        require(
+            self.total_committed_priority_requests <= self.priority_requests.len() as u64,
+            "j",
+        )
+    }
+
+    fn prove_blocks(
+        &mut self,
+        blocks: &[Block],
+        proof: &EncodedAggregatedProof,
+    ) -> Result<(), String> {
+        let mut total_blocks_proven = self.total_blocks_proven;
+        for (i, block) in blocks.iter().enumerate() {
+            total_blocks_proven += 1;
+            let stored = self.stored_blocks.get(total_blocks_proven as usize);
+            require(stored == Some(&StoredBlockInfo::from(block)), "o1")?;
+
+            let commitment = U256::from_big_endian(block.block_commitment.as_bytes());
+            let proof_commitment = proof
+                .individual_vk_inputs
+                .get(i)
+                .copied()
+                .unwrap_or_default();
+            // Incorrect block commitment in proof.
+            require(
+                proof_commitment & input_mask() == commitment & input_mask(),
+                "o",
+            )?;
+        }
+        require(total_blocks_proven <= self.total_blocks_committed(), "q")?;
+        self.total_blocks_proven = total_blocks_proven;
+        Ok(())
+    }
+
+    fn execute_blocks(&mut self, blocks: &[Block]) -> Result<(), String> {
+        self.require_active()?;
+        for (i, block) in blocks.iter().enumerate() {
+            let block_number = self.total_blocks_executed + i as u32 + 1;
+            // Blocks are executed in order and must be committed.
+            require(*block.block_number == block_number, "k")?;
+            let stored = self.stored_blocks.get(block_number as usize);
+            require(stored == Some(&StoredBlockInfo::from(block)), "exe10")?;
+
+            for operation in block
+                .block_transactions
+                .iter()
+                .filter(|op| op.is_successful())
+            {
+                match operation.get_executed_op() {
+                    Some(ZkSyncOp::Withdraw(op)) => {
+                        self.increase_pending_balance(op.tx.to, op.tx.token, &op.tx.amount)
+                    }
+                    Some(ZkSyncOp::ForcedExit(op)) => {
+                        if let Some(amount) = &op.withdraw_amount {
+                            self.increase_pending_balance(op.tx.target, op.tx.token, &amount.0);
+                        }
+                    }
+                    Some(ZkSyncOp::FullExit(op)) => {
+                        let exit = &op.priority_op;
+                        match &op.withdraw_amount {
+                            Some(amount) if *exit.token >= MIN_NFT_TOKEN_ID => {
+                                if !amount.0.is_zero() {
+                                    self.pending_nfts.insert(exit.token, exit.eth_address);
+                                }
+                            }
+                            Some(amount) => self.increase_pending_balance(
+                                exit.eth_address,
+                                exit.token,
+                                &amount.0,
+                            ),
+                            None => {}
+                        }
+                    }
+                    Some(ZkSyncOp::WithdrawNFT(op)) => {
+                        self.pending_nfts.insert(op.tx.token, op.tx.to);
+                    }
+                    _ => {}
+                }
+            }
+
+            let priority_operations = block.number_of_processed_prior_ops();
+            for _ in 0..priority_operations {
+                self.priority_requests.pop_front();
+            }
+            self.first_priority_request_id += priority_operations;
+            self.total_committed_priority_requests -= priority_operations;
+        }
+
+        self.total_blocks_executed += blocks.len() as u32;
+        // Can't execute blocks more then committed and proven.
+        require(self.total_blocks_executed <= self.total_blocks_proven, "n")
+    }
+
+    fn withdraw_pending_balances(&mut self, balances: &[(Address, TokenId)], nfts: &[TokenId]) {
+        for key in balances {
+            if let Some(amount) = self.pending_balances.remove(key) {
+                *self.balances.entry(*key).or_default() += amount;
+            }
+        }
+        for token in nfts {
+            if let Some(owner) = self.pending_nfts.remove(token) {
+                self.nft_owners.insert(*token, owner);
+            }
+        }
+    }
+
+    fn revert_blocks(&mut self, blocks: &[Block]) -> Result<(), String> {
+        self.require_active()?;
+        let blocks_to_revert = blocks
+            .len()
+            .min((self.total_blocks_committed() - self.total_blocks_executed) as usize);
+
+        for block in &blocks[..blocks_to_revert] {
+            let stored = StoredBlockInfo::from(block);
+            require(self.stored_blocks.last() == Some(&stored), "r")?; // incorrect stored block info
+            self.stored_blocks.pop();
+            self.total_committed_priority_requests -= stored.priority_operations;
+        }
+        self.total_blocks_proven = self.total_blocks_proven.min(self.total_blocks_committed());
+        Ok(())
+    }
+
+    fn activate_exodus_mode(&mut self, block: u64) -> bool {
+        if self.exodus_mode {
+            return false;
+        }
+        let trigger = self
+            .priority_requests
+            .front()
+            .map(|request| block >= request.deadline_block)
+            .unwrap_or(false);
+        if trigger {
+            self.exodus_mode = true;
+        }
+        trigger
+    }
+
+    fn cancel_outstanding_deposits(
+        &mut self,
+        number: u64,
+        deposits_pubdata: &[Vec<u8>],
+    ) -> Result<(), String> {
+        require(self.exodus_mode, "8")?; // exodus mode not active
+        let to_process = number.min(self.priority_requests.len() as u64);
+        require(to_process > 0, "9")?; // no deposits to process
+
+        let mut current_deposit_idx = 0;
+        for _ in 0..to_process {
+            let request = self
+                .priority_requests
+                .pop_front()
+                .expect("open priority request");
+            if let ZkSyncPriorityOp::Deposit(deposit) = &request.data {
+                let (_, expected_pubdata) =
+                    PriorityOp::get_args_for_priority_queue_cancel(&[request.clone()]);
+                require(
+                    deposits_pubdata.get(current_deposit_idx) == expected_pubdata.first(),
+                    "a",
+                )?;
+                current_deposit_idx += 1;
+                self.increase_pending_balance(deposit.to, deposit.token, &deposit.amount);
+            }
+        }
+        self.first_priority_request_id += to_process;
+        self.total_committed_priority_requests = self
+            .total_committed_priority_requests
+            .min(self.priority_requests.len() as u64);
+        Ok(())
+    }
+
+    fn perform_exodus(
+        &mut self,
+        owner: Address,
+        stored_block: &Block,
+        account_id: AccountId,
+        token_id: TokenId,
+        amount: &BigUint,
+        proof: &[U256],
+    ) -> Result<(), String> {
+        require(account_id != NFT_STORAGE_ACCOUNT_ID, "v")?;
+        require(self.exodus_mode, "s")?; // must be in exodus mode
+        require(
+            !self.performed_exodus.contains(&(account_id, token_id)),
+            "t",
+        )?; // already exited
+        let stored = &self.stored_blocks[self.total_blocks_executed as usize];
+        require(StoredBlockInfo::from(stored_block) == *stored, "u")?; // incorrect stored block info
+
+        let commitment = exit_commitment(stored.state_hash, account_id, owner, token_id, amount);
+        require(proof.first() == Some(&commitment), "x")?;
+
+        self.performed_exodus.insert((account_id, token_id));
+        self.increase_pending_balance(owner, token_id, amount);
+        Ok(())
+    }
+}
+
+/// Simulated network: the state of the contract and `MockEthereum`, which provides transaction hashes and blocks.
+/// `MockEthereum` is never cloned, since it's required to be unique to advance the blocks.
+#[derive(Debug)]
+struct SimulatedNetwork {
+    eth: MockEthereum,
+    nonce: u64,
+    state: ContractState,
+}
+
+impl SimulatedNetwork {
+    async fn block_number(&self) -> u64 {
+        self.eth
+            .block_number()
+            .await
+            .expect("mock block number")
+            .as_u64()
+    }
+
+    /// Sends the transaction that calls `method` of the contract and includes it into a new block.
+    /// Changes made by `call` are reverted if it fails.
+    async fn transact<T>(
+        &mut self,
+        from: Address,
+        method: &str,
+        call: impl FnOnce(&mut ContractState, H256, u64) -> Result<T, String>,
+    ) -> (TransactionReceipt, Result<T, String>) {
+        let mut data = method.as_bytes().to_vec();
+        data.extend_from_slice(from.as_bytes());
+        let options = Options::with(|opt| opt.nonce = Some(self.nonce.into()));
+        self.nonce += 1;
+        let signed_tx = self
+            .eth
+            .sign_prepared_tx(data, options)
+            .await
+            .expect("mock sign tx");
+        let tx_hash = self
+            .eth
+            .send_raw_tx(signed_tx.raw_tx)
+            .await
+            .expect("mock send tx");
+
+        let block = self.block_number().await;
+        let snapshot = self.state.clone();
+        let result = call(&mut self.state, tx_hash, block);
+        if result.is_ok() {
+            self.eth.add_successfull_execution(tx_hash, 1).await;
+        } else {
+            self.state = snapshot;
+            self.eth.add_failed_execution(&tx_hash, 1).await;
+        }
+
+        let receipt = TransactionReceipt {
+            transaction_hash: tx_hash,
+            block_number: Some(U64::from(block)),
+            gas_used: Some(U256::zero()),
+            status: Some(U64::from(result.is_ok() as u64)),
+            ..Default::default()
+        };
+        (receipt, result)
+    }
+}
+
+/// Handle to the simulated Ethereum network, shared by all the simulated `EthereumAccount`s.
+#[derive(Debug, Clone)]
+pub struct SimulatedEthereum {
+    network: Arc<Mutex<SimulatedNetwork>>,
+}
+
+impl SimulatedEthereum {
+    /// Creates the network with the contract initialized with the genesis block
+    /// and the priority expiration of the real contract.
+    pub fn new(genesis_block: &Block) -> Self {
+        Self::with_priority_expiration(genesis_block, PRIORITY_EXPIRATION)
+    }
+
+    pub fn with_priority_expiration(genesis_block: &Block, priority_expiration: u64) -> Self {
+        let mut tokens = HashMap::new();
+        tokens.insert(Address::zero(), TokenId(0));
+        let state = ContractState {
+            priority_expiration,
+            tokens,
+            balances: HashMap::new(),
+            allowances: HashMap::new(),
+            pending_balances: HashMap::new(),
+            pending_nfts: HashMap::new(),
+            nft_owners: HashMap::new(),
+            auth_facts: HashMap::new(),
+            priority_requests: VecDeque::new(),
+            first_priority_request_id: 0,
+            total_committed_priority_requests: 0,
+            stored_blocks: vec![StoredBlockInfo::from(genesis_block)],
+            total_blocks_proven: 0,
+            total_blocks_executed: 0,
+            exodus_mode: false,
+            performed_exodus: HashSet::new(),
+        };
+        let network = SimulatedNetwork {
+            eth: MockEthereum::default(),
+            nonce: 0,
+            state,
+        };
+        Self {
+            network: Arc::new(Mutex::new(network)),
+        }
+    }
+
+    /// Returns the addresses of the simulated contracts, the test ERC20 token is registered as the token with id 1.
+    pub async fn contracts(&self) -> Contracts {
+        let contracts = Contracts {
+            governance: Address::repeat_byte(0x01),
+            verifier: Address::repeat_byte(0x02),
+            contract: Address::repeat_byte(0x03),
+            upgrade_gatekeeper: Address::repeat_byte(0x04),
+            test_erc20_address: Address::repeat_byte(0x05),
+            pending_withdrawer: (
+                serde_json::from_str("[]").expect("empty contract abi"),
+                Address::repeat_byte(0x06),
+            ),
+        };
+        self.add_token(TokenId(1), contracts.test_erc20_address)
+            .await;
+        contracts
+    }
+
+    pub async fn add_token(&self, token_id: TokenId, address: Address) {
+        let mut network = self.network.lock().await;
+        network.state.tokens.insert(address, token_id);
+    }
+
+    /// Sets the L1 balance of `owner`, e.g. to fund the test accounts.
+    pub async fn set_balance(&self, owner: Address, token_id: TokenId, amount: BigUint) {
+        let mut network = self.network.lock().await;
+        network.state.balances.insert((owner, token_id), amount);
+    }
+
+    pub async fn block_number(&self) -> u64 {
+        self.network.lock().await.block_number().await
+    }
+
+    /// Mines `count` empty blocks, e.g. to let the priority requests expire.
+    pub async fn advance_blocks(&self, count: u64) {
+        let mut network = self.network.lock().await;
+        let block_number = network.block_number().await + count;
+        network
+            .eth
+            .set_block_number(block_number.into())
+            .await
+            .expect("mock set block number");
+    }
+
+    pub async fn balance(&self, owner: Address, token: Address) -> BigUint {
+        let network = self.network.lock().await;
+        let state = &network.state;
+        state
+            .token_id(token)
+            .ok()
+            .and_then(|token_id| state.balances.get(&(owner, token_id)).cloned())
+            .unwrap_or_default()
+    }
+
+    pub async fn pending_balance(&self, owner: Address, token: Address) -> BigUint {
+        let network = self.network.lock().await;
+        let state = &network.state;
+        state
+            .token_id(token)
+            .ok()
+            .and_then(|token_id| state.pending_balances.get(&(owner, token_id)).cloned())
+            .unwrap_or_default()
+    }
+
+    /// Returns the L1 owner of the withdrawn NFT.
+    pub async fn nft_owner(&self, token_id: TokenId) -> Option<Address> {
+        let network = self.network.lock().await;
+        network.state.nft_owners.get(&token_id).copied()
+    }
+
+    pub async fn total_blocks_committed(&self) -> u64 {
+        let network = self.network.lock().await;
+        network.state.total_blocks_committed() as u64
+    }
+
+    pub async fn total_blocks_proven(&self) -> u64 {
+        let network = self.network.lock().await;
+        network.state.total_blocks_proven as u64
+    }
+
+    pub async fn total_blocks_executed(&self) -> u64 {
+        let network = self.network.lock().await;
+        network.state.total_blocks_executed as u64
+    }
+
+    pub async fn is_exodus(&self) -> bool {
+        self.network.lock().await.state.exodus_mode
+    }
+
+    /// Returns the open priority requests, i.e. the ones that are not executed yet.
+    pub async fn priority_requests(&self) -> Vec<PriorityOp> {
+        let network = self.network.lock().await;
+        network.state.priority_requests.iter().cloned().collect()
+    }
+
+    pub async fn approve_erc20(
+        &self,
+        from: Address,
+        token: Address,
+        amount: BigUint,
+    ) -> (TransactionReceipt, Result<(), String>) {
+        let mut network = self.network.lock().await;
+        network
+            .transact(from, "approve", |state, _, _| {
+                let token_id = state.token_id(token)?;
+                state.allowances.insert((from, token_id), amount);
+                Ok(())
+            })
+            .await
+    }
+
+    pub async fn deposit(
+        &self,
+        from: Address,
+        token: Address,
+        amount: BigUint,
+        to: Address,
+    ) -> (TransactionReceipt, Result<PriorityOp, String>) {
+        let mut network = self.network.lock().await;
+        network
+            .transact(from, "deposit", |state, tx_hash, block| {
+                state.deposit(from, token, amount, to, tx_hash, block)
+            })
+            .await
+    }
+
+    pub async fn full_exit(
+        &self,
+        from: Address,
+        account_id: AccountId,
+        token: Address,
+    ) -> (TransactionReceipt, Result<PriorityOp, String>) {
+        let mut network = self.network.lock().await;
+        network
+            .transact(from, "requestFullExit", |state, tx_hash, block| {
+                state.full_exit(from, account_id, token, tx_hash, block)
+            })
+            .await
+    }
+
+    pub async fn set_auth_pubkey_hash(
+        &self,
+        from: Address,
+        pubkey_hash: &[u8],
+        nonce: Nonce,
+    ) -> (TransactionReceipt, Result<(), String>) {
+        let mut network = self.network.lock().await;
+        network
+            .transact(from, "setAuthPubkeyHash", |state, _, _| {
+                require(pubkey_hash.len() == 20, "y")?; // PubKeyHash should be 20 bytes
+                state.auth_facts.insert((from, nonce), pubkey_hash.to_vec());
+                Ok(())
+            })
+            .await
+    }
+
+    pub async fn commit_blocks(
+        &self,
+        from: Address,
+        last_committed_block: &Block,
+        blocks: &[Block],
+    ) -> (TransactionReceipt, Result<(), String>) {
+        let mut network = self.network.lock().await;
+        network
+            .transact(from, "commitBlocks", |state, _, _| {
+                state.commit_blocks(last_committed_block, blocks)
+            })
+            .await
+    }
+
+    pub async fn prove_blocks(
+        &self,
+        from: Address,
+        blocks: &[Block],
+        proof: &EncodedAggregatedProof,
+    ) -> (TransactionReceipt, Result<(), String>) {
+        let mut network = self.network.lock().await;
+        network
+            .transact(from, "proveBlocks", |state, _, _| {
+                state.prove_blocks(blocks, proof)
+            })
+            .await
+    }
+
+    pub async fn execute_blocks(
+        &self,
+        from: Address,
+        blocks: &[Block],
+    ) -> (TransactionReceipt, Result<(), String>) {
+        let mut network = self.network.lock().await;
+        network
+            .transact(from, "executeBlocks", |state, _, _| {
+                state.execute_blocks(blocks)
+            })
+            .await
+    }
+
+    /// Moves the pending balances to the L1 balances of their owners, same as `PendingBalanceWithdrawer`.
+    pub async fn withdraw_pending_balances(
+        &self,
+        from: Address,
+        balances: &[(Address, TokenId)],
+        nfts: &[TokenId],
+    ) -> (TransactionReceipt, Result<(), String>) {
+        let mut network = self.network.lock().await;
+        network
+            .transact(from, "withdrawPendingBalances", |state, _, _| {
+                state.withdraw_pending_balances(balances, nfts);
+                Ok(())
+            })
+            .await
+    }
+
+    pub async fn revert_blocks(
+        &self,
+        from: Address,
+        blocks: &[Block],
+    ) -> (TransactionReceipt, Result<(), String>) {
+        let mut network = self.network.lock().await;
+        network
+            .transact(from, "revertBlocks", |state, _, _| {
+                state.revert_blocks(blocks)
+            })
+            .await
+    }
+
+    pub async fn activate_exodus_mode(
+        &self,
+        from: Address,
+    ) -> (TransactionReceipt, Result<bool, String>) {
+        let mut network = self.network.lock().await;
+        network
+            .transact(from, "activateExodusMode", |state, _, block| {
+                Ok(state.activate_exodus_mode(block))
+            })
+            .await
+    }
+
+    pub async fn cancel_outstanding_deposits(
+        &self,
+        from: Address,
+        number: u64,
+        deposits_pubdata: &[Vec<u8>],
+    ) -> (TransactionReceipt, Result<(), String>) {
+        let mut network = self.network.lock().await;
+        network
+            .transact(
+                from,
+                "cancelOutstandingDepositsForExodusMode",
+                |state, _, _| state.cancel_outstanding_deposits(number, deposits_pubdata),
+            )
+            .await
+    }
+
+    pub async fn perform_exodus(
+        &self,
+        from: Address,
+        stored_block: &Block,
+        account_id: AccountId,
+        token_id: TokenId,
+        amount: &BigUint,
+        proof: &[U256],
+    ) -> (TransactionReceipt, Result<(), String>) {
+        let mut network = self.network.lock().await;
+        network
+            .transact(from, "performExodus", |state, _, _| {
+                state.perform_exodus(from, stored_block, account_id, token_id, amount, proof)
+            })
+            .await
+    }
+}
@@ -1,4 +1,4 @@
-use crate::eth_account::{get_executed_tx_fee, ETHExecResult, EthereumAccount};
+use crate::eth_account::{ETHExecResult, EthereumAccount};
 use crate::external_commands::Contracts;
 use anyhow::bail;
 use futures::{
@@ -26,6 +26,7 @@ use zksync_crypto::proof::{EncodedAggregatedProof, EncodedSingleProof};
 use zksync_crypto::rand::Rng;
 
 use crate::account_set::AccountSet;
+use crate::sim_ethereum::simulated_exit_proof;
 use crate::state_keeper_utils::*;
 use crate::types::*;
 
@@ -299,7 +300,9 @@ impl TestSetup {
         let mut gas_fee = BigUint::from(0u32);
 
         for r in &receipts {
-            let current_fee = get_executed_tx_fee(&self.commit_account.main_contract_eth_client, r)
+            let current_fee = self
+                .commit_account
+                .executed_tx_fee(r)
                 .await
                 .expect("Failed to get transaction fee");
 
@@ -381,7 +384,9 @@ impl TestSetup {
         let mut gas_fee = BigUint::from(0u32);
 
         for r in &receipts {
-            let current_fee = get_executed_tx_fee(&self.commit_account.main_contract_eth_client, r)
+            let current_fee = self
+                .commit_account
+                .executed_tx_fee(r)
                 .await
                 .expect("Failed to get transaction fee");
 
@@ -492,7 +497,9 @@ impl TestSetup {
             .full_exit(post_by, token_address, account_id)
             .await;
 
-        let gas_fee = get_executed_tx_fee(&self.commit_account.main_contract_eth_client, &receipt)
+        let gas_fee = self
+            .commit_account
+            .executed_tx_fee(&receipt)
             .await
             .expect("Failed to get transaction fee");
 
@@ -1338,6 +1345,10 @@ impl TestSetup {
         let owner_id = owner
             .get_account_id()
             .expect("Account should have id to exit");
+        if self.commit_account.is_simulated() {
+            return simulated_exit_proof(&accounts, owner_id, owner.address, token.0)
+                .expect("Fund account not found");
+        }
         // restore account state
         zksync_prover_utils::exit_proof::create_exit_proof_fungible(
             accounts,
@@ -39,6 +39,17 @@ export async function check(expectedHash: string) {
     );
 }
 
+export async function exportArchive(output: string) {
+    await utils.spawn(`cargo run --bin zksync_data_restore --release -- export --output ${output}`);
+}
+
+export async function checkArchive(archive: string, expectedHash: string) {
+    await db.reset();
+    await utils.spawn(
+        `cargo run --bin zksync_data_restore --release -- --genesis --archive ${archive} --final_hash ${expectedHash}`
+    );
+}
+
 export async function checkExisting() {
     const expectedHash = await rootHash();
     await check(expectedHash);
@@ -55,6 +66,16 @@ command
     .description('wipe the database, run the data restore in finite mode and check the root hash')
     .action(check);
 
+command
+    .command('export <output>')
+    .description('export the contracts logs and commit transactions into the archive file')
+    .action(exportArchive);
+
+command
+    .command('check-archive <archive> <hash>')
+    .description('wipe the database, restore the state from the archive and check the root hash')
+    .action(checkArchive);
+
 command
     .command('check-existing')
     .description(`like "check", but instead hash is loaded from the database before wiping it`)
@@ -31,6 +31,11 @@ export async function gen(command: 'contract' | 'all' | 'circuit-size') {
     fs.copyFileSync(`${outputDir}/KeysWithPlonkVerifier.sol`, 'contracts/contracts/KeysWithPlonkVerifier.sol');
 }
 
+export async function constraints(update: boolean) {
+    const flag = update ? '--update' : '';
+    await utils.spawn(`cargo run --bin key_generator --release -- constraint-report ${flag}`);
+}
+
 export async function unpack() {
     const keysTarball = verfiyKeysTarball();
     if (!fs.existsSync(`keys/packed/${keysTarball}`)) {
@@ -64,3 +69,11 @@ command
         }
         await gen(command);
     });
+
+command
+    .command('constraints')
+    .description('compare circuit constraint counts with the committed baseline')
+    .option('--update', 'overwrite the baseline with the current constraint counts')
+    .action(async (cmd: Command) => {
+        await constraints(!!cmd.update);
+    });
@@ -13,7 +13,7 @@ use num::BigUint;
 use zksync_types::{
     network::Network,
     tx::{PackedEthSignature, TxHash, ZkSyncTx},
-    Address, TokenLike, TxFeeTypes,
+    Address, Swap, TokenLike, TxFeeTypes,
 };
 
 // Local uses
@@ -226,6 +226,18 @@ impl RpcProvider {
         self.send_and_deserialize(&msg).await
     }
 
+    /// Submits a swap to the zkSync network.
+    /// Swaps require the Ethereum signatures of both orders in addition to the signature of the swap itself.
+    pub async fn send_swap(
+        &self,
+        swap: Swap,
+        eth_signature: Option<PackedEthSignature>,
+        orders_eth_signatures: (Option<PackedEthSignature>, Option<PackedEthSignature>),
+    ) -> Result<TxHash, ClientError> {
+        let msg = JsonRpcRequest::submit_swap(swap, eth_signature, orders_eth_signatures);
+        self.send_and_deserialize(&msg).await
+    }
+
     /// Requests and returns information about an Ethereum operation given its `serial_id`.
     pub async fn ethop_info(&self, serial_id: u32) -> Result<EthOpInfo, ClientError> {
         let msg = JsonRpcRequest::ethop_info(serial_id);
@@ -323,8 +335,8 @@ impl RpcProvider {
 mod messages {
     use serde::Serialize;
     use zksync_types::{
-        tx::{PackedEthSignature, TxEthSignature, TxHash, ZkSyncTx},
-        Address, TokenLike, TxFeeTypes,
+        tx::{PackedEthSignature, TxEthSignature, TxEthSignatureVariant, TxHash, ZkSyncTx},
+        Address, Swap, TokenLike, TxFeeTypes,
     };
 
     #[derive(Debug, Serialize)]
@@ -372,6 +384,24 @@ mod messages {
             Self::create("tx_submit", params)
         }
 
+        pub fn submit_swap(
+            swap: Swap,
+            eth_signature: Option<PackedEthSignature>,
+            orders_eth_signatures: (Option<PackedEthSignature>, Option<PackedEthSignature>),
+        ) -> Self {
+            let signature = TxEthSignatureVariant::Triple(
+                eth_signature.map(TxEthSignature::EthereumSignature),
+                orders_eth_signatures
+                    .0
+                    .map(TxEthSignature::EthereumSignature),
+                orders_eth_signatures
+                    .1
+                    .map(TxEthSignature::EthereumSignature),
+            );
+            let params = json_values![ZkSyncTx::from(swap), signature];
+            Self::create("tx_submit", params)
+        }
+
         pub fn submit_tx_batch(
             txs_signed: Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
             eth_signature: Option<PackedEthSignature>,
@@ -330,6 +330,8 @@ export interface Tokens {
         id: number;
         symbol: string;
         decimals: number;
+        // Hidden tokens can be used, but should not be displayed to the users
+        hidden?: boolean;
     };
 }
 
@@ -482,6 +484,8 @@ export interface TokenInfo {
     symbol: string;
     decimals: number;
     enabledForFees: boolean;
+    // Hidden tokens can be used, but should not be displayed to the users
+    hidden?: boolean;
 }
 
 export interface TokenPriceInfo {